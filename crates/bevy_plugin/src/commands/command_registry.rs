@@ -6,6 +6,7 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use std::time::Duration;
 
+mod validation;
 pub(crate) mod wait;
 
 pub(crate) fn command_registry_plugin(app: &mut App) {
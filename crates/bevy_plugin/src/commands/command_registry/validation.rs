@@ -0,0 +1,179 @@
+use crate::prelude::*;
+use yarnspinner::compiler::{Diagnostic, DiagnosticCode, DiagnosticDetails, DiagnosticSeverity};
+use yarnspinner::core::{OpCode, Type};
+use yarnspinner::runtime::Command;
+
+impl YarnCommands {
+    /// Checks every command in the compiled Yarn files against the registered commands, i.e. their [`YarnCommand::In`] types.
+    /// Reports commands that are not registered as well as wrong argument counts and arguments that cannot be converted
+    /// to the type of their parameter, located via the [`DebugInfo`](yarnspinner::compiler::DebugInfo) of the compilation.
+    ///
+    /// All returned diagnostics are warnings. Arguments containing interpolated expressions, e.g. `{$speed}`,
+    /// are only known at runtime and thus not type checked.
+    ///
+    /// This can be run for you when building a [`DialogueRunner`], see [`DialogueRunnerBuilder::with_command_validation`].
+    pub fn validate(&self, compilation: &Compilation) -> Vec<Diagnostic> {
+        let Some(program) = compilation.program.as_ref() else {
+            return Vec::new();
+        };
+        let mut node_names: Vec<_> = program.nodes.keys().collect();
+        node_names.sort();
+
+        let mut diagnostics = Vec::new();
+        for node_name in node_names {
+            let instructions = &program.nodes[node_name].instructions;
+            let debug_info = compilation.debug_info.get(node_name);
+            for (index, instruction) in instructions.iter().enumerate() {
+                if instruction.opcode() != OpCode::RunCommand {
+                    continue;
+                }
+                let text: String = instruction.read_operand(0);
+                let Ok(command) = text.parse::<Command>() else {
+                    continue;
+                };
+                let line_info =
+                    debug_info.and_then(|debug_info| debug_info.try_get_line_info(index));
+                let file_name = line_info
+                    .as_ref()
+                    .map(|line_info| line_info.file_name.clone());
+                let range = line_info
+                    .and_then(|line_info| line_info.source)
+                    .map(|source| source.range);
+                diagnostics.extend(self.validate_command(&command).into_iter().map(
+                    |(code, message, details)| Diagnostic {
+                        code,
+                        file_name: file_name.clone(),
+                        start_line: range.as_ref().map_or(0, |range| range.start.line),
+                        range: range.clone(),
+                        message,
+                        context: None,
+                        severity: DiagnosticSeverity::Warning,
+                        details,
+                        fixes: Vec::new(),
+                    },
+                ));
+            }
+        }
+        diagnostics
+    }
+
+    fn validate_command(
+        &self,
+        command: &Command,
+    ) -> Vec<(DiagnosticCode, String, DiagnosticDetails)> {
+        let name = &command.name;
+        let Some(registered_command) = self.get(name) else {
+            let message = format!(
+                "Command \"{name}\" is not registered in the YarnCommands of the DialogueRunner"
+            );
+            return vec![(DiagnosticCode::UnknownCommand, message, Default::default())];
+        };
+        let parameters = registered_command.parameters();
+        let arguments: Vec<String> = command.parameters.iter().map(String::from).collect();
+
+        let minimum = parameters
+            .iter()
+            .filter(|parameter| !parameter.is_optional)
+            .count();
+        if arguments.len() < minimum || arguments.len() > parameters.len() {
+            let expected = if minimum == parameters.len() {
+                minimum.to_string()
+            } else {
+                format!("{minimum} to {}", parameters.len())
+            };
+            let message = format!(
+                "Command \"{name}\" expects {expected} argument(s), but received {}",
+                arguments.len()
+            );
+            let details = DiagnosticDetails {
+                expected_count: Some(minimum),
+                actual_count: Some(arguments.len()),
+                ..Default::default()
+            };
+            return vec![(DiagnosticCode::WrongParameterCount, message, details)];
+        }
+
+        parameters
+            .iter()
+            .zip(&arguments)
+            .enumerate()
+            .filter(|(_, (parameter, argument))| !accepts_argument(&parameter.r#type, argument))
+            .map(|(index, (parameter, argument))| {
+                let message = format!(
+                    "Argument {} of command \"{name}\" expects a {}, but received \"{argument}\"",
+                    index + 1,
+                    parameter.r#type
+                );
+                let details = DiagnosticDetails {
+                    expected_types: vec![parameter.r#type.to_string()],
+                    ..Default::default()
+                };
+                (DiagnosticCode::InvalidParameterType, message, details)
+            })
+            .collect()
+    }
+}
+
+/// Whether the argument can be converted to the given type when the command is executed.
+fn accepts_argument(r#type: &Type, argument: &str) -> bool {
+    // Placeholders for interpolated expressions, e.g. `{0}`
+    if argument.contains('{') {
+        return true;
+    }
+    match r#type {
+        Type::Number => argument.parse::<f32>().is_ok(),
+        Type::Boolean => argument.parse::<bool>().is_ok(),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::In;
+
+    #[test]
+    fn validates_commands_against_registered_signatures() {
+        let source = "title: Start
+---
+<<walk Mae 2>>
+<<walk \"Mae Borowski\" {1 + 1}>>
+<<walk Mae fast>>
+<<walk>>
+<<wlak Mae>>
+<<wait 1>>
+===";
+        let compilation = YarnCompiler::new()
+            .add_file(yarnspinner::compiler::File {
+                file_name: "test.yarn".to_owned(),
+                source: source.to_owned(),
+            })
+            .compile()
+            .unwrap();
+        let mut commands = YarnCommands::builtin_commands();
+        commands.add_command("walk", |_: In<(String, Option<f32>)>| {});
+
+        let diagnostics = commands.validate(&compilation);
+
+        let summary: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.code,
+                    diagnostic.range.clone().unwrap().start.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (DiagnosticCode::InvalidParameterType, 4),
+                (DiagnosticCode::WrongParameterCount, 5),
+                (DiagnosticCode::UnknownCommand, 6),
+            ],
+            summary
+        );
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.file_name.as_deref() == Some("test.yarn")));
+    }
+}
@@ -7,7 +7,7 @@ use std::fmt::{Debug, Display, Formatter};
 use std::marker::PhantomData;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
-use yarnspinner::core::{YarnFnParam, YarnFnParamItem, YarnValueWrapper};
+use yarnspinner::core::{YarnFnParam, YarnFnParamItem, YarnFnParameterInfo, YarnValueWrapper};
 
 pub(crate) fn command_wrapping_plugin(_app: &mut App) {}
 
@@ -160,6 +160,8 @@ pub trait UntypedYarnCommand: Debug + Send + Sync + 'static {
     fn call(&mut self, input: Vec<YarnValue>, world: &mut World) -> Box<dyn TaskFinishedIndicator>;
     #[doc(hidden)]
     fn clone_box(&self) -> Box<dyn UntypedYarnCommand>;
+    /// The parameters the command takes from Yarn, i.e. the flattened [`YarnCommand::In`] type.
+    fn parameters(&self) -> Vec<YarnFnParameterInfo>;
 }
 
 impl Clone for Box<dyn UntypedYarnCommand> {
@@ -191,6 +193,12 @@ where
     fn clone_box(&self) -> Box<dyn UntypedYarnCommand> {
         Box::new(self.clone())
     }
+
+    fn parameters(&self) -> Vec<YarnFnParameterInfo> {
+        let mut parameters = Vec::new();
+        T::In::parameter_infos(&mut parameters);
+        parameters
+    }
 }
 
 pub(crate) struct YarnCommandWrapper<Marker, F>
@@ -1,8 +1,10 @@
 use crate::prelude::*;
 use bevy::prelude::*;
+use yarnspinner::compiler::{ContentHashLineIds, LineIdStrategy, RandomLineIds, SequentialLineIds};
 
 pub(crate) fn development_file_generation_plugin(app: &mut App) {
-    app.register_type::<DevelopmentFileGeneration>();
+    app.register_type::<DevelopmentFileGeneration>()
+        .register_type::<LineIdGeneration>();
 }
 
 /// The kind of development experience you wish when creating Yarn files and dealing with missing localizations.
@@ -52,3 +54,30 @@ impl Default for DevelopmentFileGeneration {
         }
     }
 }
+
+/// The kind of line IDs that are added to lines without one when using [`DevelopmentFileGeneration::Full`] with [`Localizations`].
+/// Defaults to [`LineIdGeneration::Random`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect, Serialize, Deserialize)]
+#[reflect(Debug, Default, PartialEq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum LineIdGeneration {
+    /// Random numbers, e.g. `line:8124035`. See [`RandomLineIds`](yarnspinner::compiler::RandomLineIds).
+    #[default]
+    Random,
+    /// A hash of the file name, node name and line text, e.g. `line:3f9a0c21`, so that the same line gets the same ID on different branches.
+    /// See [`ContentHashLineIds`](yarnspinner::compiler::ContentHashLineIds).
+    ContentHash,
+    /// The node name and a number, e.g. `line:Start_001`, which is easier to read for translators.
+    /// See [`SequentialLineIds`](yarnspinner::compiler::SequentialLineIds).
+    Sequential,
+}
+
+impl LineIdGeneration {
+    pub(crate) fn strategy(self) -> &'static dyn LineIdStrategy {
+        match self {
+            Self::Random => &RandomLineIds,
+            Self::ContentHash => &ContentHashLineIds,
+            Self::Sequential => &SequentialLineIds,
+        }
+    }
+}
@@ -2,6 +2,8 @@ pub use self::events::{
     DialogueCompleteEvent, DialogueStartEvent, ExecuteCommandEvent, LineHintsEvent,
     NodeCompleteEvent, NodeStartEvent, PresentLineEvent, PresentOptionsEvent,
 };
+#[cfg(feature = "text_styles")]
+pub use self::markup_text_styles::{MarkupTextStyles, TextStyleOverride};
 pub use self::{
     builder::DialogueRunnerBuilder,
     dialogue_option::DialogueOption,
@@ -26,6 +28,8 @@ mod dialogue_option;
 mod events;
 mod inner;
 mod localized_line;
+#[cfg(feature = "text_styles")]
+mod markup_text_styles;
 mod runtime_interaction;
 
 pub(crate) fn dialogue_plugin(app: &mut App) {
@@ -6,7 +6,9 @@ use bevy::prelude::*;
 use bevy::utils::HashMap;
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 use std::any::{Any, TypeId};
+use std::borrow::Cow;
 use std::fmt::Debug;
+use yarnspinner::compiler::{Diagnostic, DiagnosticSeverity};
 
 pub(crate) fn dialogue_runner_builder_plugin(_app: &mut App) {}
 
@@ -18,6 +20,7 @@ pub struct DialogueRunnerBuilder {
     asset_providers: HashMap<TypeId, Box<dyn AssetProvider>>,
     library: YarnLibrary,
     commands: YarnCommands,
+    command_validation: Option<DiagnosticSeverity>,
     compilation: Compilation,
     localizations: Option<Localizations>,
     asset_server: SkipDebug<AssetServer>,
@@ -34,6 +37,7 @@ impl DialogueRunnerBuilder {
             asset_providers: HashMap::new(),
             library: create_extended_standard_library(),
             commands: YarnCommands::builtin_commands(),
+            command_validation: None,
             compilation: yarn_project.compilation().clone(),
             localizations: yarn_project.localizations().cloned(),
             asset_server: yarn_project.asset_server.clone(),
@@ -62,6 +66,29 @@ impl DialogueRunnerBuilder {
         self
     }
 
+    /// Registers a command in the [`YarnCommands`] of the [`DialogueRunner`]. See [`YarnCommands::add_command`].
+    /// Unlike commands added later via [`DialogueRunner::commands_mut`], these are known when the commands of the Yarn files are validated.
+    #[must_use]
+    pub fn add_command<Marker, F>(mut self, name: impl Into<Cow<'static, str>>, command: F) -> Self
+    where
+        Marker: 'static,
+        F: YarnCommand<Marker> + 'static + Clone,
+    {
+        self.commands.add_command(name, command);
+        self
+    }
+
+    /// Checks the commands in the Yarn files against the [`YarnCommands`] when building, see [`YarnCommands::validate`] for what is checked.
+    /// With [`DiagnosticSeverity::Warning`], mismatches are logged. With [`DiagnosticSeverity::Error`], building fails instead.
+    ///
+    /// By default, commands are not validated, as commands registered after building via [`DialogueRunner::commands_mut`]
+    /// would be reported as unknown. When enabling this, register all commands with [`DialogueRunnerBuilder::add_command`].
+    #[must_use]
+    pub fn with_command_validation(mut self, severity: DiagnosticSeverity) -> Self {
+        self.command_validation = Some(severity);
+        self
+    }
+
     /// Builds the [`DialogueRunner`]. See [`DialogueRunnerBuilder::try_build`] for the fallible version.
     pub fn build(self) -> DialogueRunner {
         self.try_build().unwrap_or_else(|error| {
@@ -70,7 +97,12 @@ impl DialogueRunnerBuilder {
     }
 
     /// Builds the [`DialogueRunner`].
+    /// Fails with a [`CompilerError`] if command validation is enabled with [`DiagnosticSeverity::Error`] and a command does not match the registered [`YarnCommands`].
     pub fn try_build(mut self) -> Result<DialogueRunner> {
+        if let Some(severity) = self.command_validation {
+            self.validate_commands(severity)?;
+        }
+
         let text_provider = Box::new(self.text_provider);
 
         let mut dialogue = Dialogue::new(self.variable_storage, text_provider.clone());
@@ -120,6 +152,30 @@ impl DialogueRunnerBuilder {
     }
 }
 
+impl DialogueRunnerBuilder {
+    fn validate_commands(&self, severity: DiagnosticSeverity) -> Result<()> {
+        let diagnostics = self.commands.validate(&self.compilation);
+        match severity {
+            DiagnosticSeverity::Error if !diagnostics.is_empty() => {
+                let errors = diagnostics
+                    .into_iter()
+                    .map(|diagnostic| Diagnostic {
+                        severity,
+                        ..diagnostic
+                    })
+                    .collect();
+                Err(CompilerError(errors).into())
+            }
+            _ => {
+                for diagnostic in diagnostics {
+                    warn!("{diagnostic}");
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 fn create_extended_standard_library() -> YarnLibrary {
     let mut library = YarnLibrary::standard_library();
     library
@@ -21,6 +21,11 @@ pub struct LocalizedLine {
     pub metadata: Vec<String>,
     /// The assets associated with this line, provided by [`AssetProvider`]s that were added with [`DialogueRunnerBuilder::add_asset_provider`].
     pub assets: LineAssets,
+    /// The language the text was actually taken from. `None` means the base language, i.e. the language the Yarn files are written in.
+    /// If this differs from [`DialogueRunner::text_language`], the line is not translated into the current language yet
+    /// and the [`TextProvider`] fell back to another language.
+    /// Code that builds a [`LocalizedLine`] with a struct literal, e.g. in tests, can set this to `None`.
+    pub language: Option<Language>,
 }
 impl LocalizedLine {
     // Documentation taken from `YarnLine`
@@ -48,6 +53,7 @@ impl LocalizedLine {
     /// #    }],
     /// #    metadata: vec![],
     /// #    assets: Default::default(),
+    /// #    language: None,
     /// # };
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!(Some("Alice"), line.character_name());
@@ -63,6 +69,7 @@ impl LocalizedLine {
     /// #    attributes: vec![],
     /// #    metadata: vec![],
     /// #    assets: Default::default(),
+    /// #    language: None,
     /// # };
     /// assert_eq!("Great, thanks", line.text);
     /// assert!(line.character_name().is_none());
@@ -101,6 +108,7 @@ impl LocalizedLine {
     /// #    }],
     /// #    metadata: vec![],
     /// #    assets: Default::default(),
+    /// #    language: None,
     /// # };
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!("Hello! How are you today?", &line.text_without_character_name());
@@ -116,6 +124,7 @@ impl LocalizedLine {
     /// #    attributes: vec![],
     /// #    metadata: vec![],
     /// #    assets: Default::default(),
+    /// #    language: None,
     /// # };
     /// assert_eq!("Great, thanks", line.text);
     /// assert_eq!("Great, thanks", &line.text_without_character_name());
@@ -139,6 +148,22 @@ impl LocalizedLine {
         &self.text[attribute.position..attribute.position + attribute.length]
     }
 
+    // Documentation taken from `YarnLine`
+    /// Splits [`LocalizedLine::text`] into an ordered sequence of non-overlapping [`MarkupSpan`]s, each carrying the attributes that apply to it.
+    ///
+    /// See [`markup_spans`](yarnspinner::runtime::markup_spans) for details.
+    pub fn spans(&self) -> Vec<MarkupSpan> {
+        yarnspinner::runtime::markup_spans(&self.text, &self.attributes)
+    }
+
+    // Documentation taken from `YarnLine`
+    /// Converts [`LocalizedLine::text`] and [`LocalizedLine::attributes`] into a tree of properly nested [`MarkupNode`]s.
+    ///
+    /// See [`markup_tree`](yarnspinner::runtime::markup_tree) for details.
+    pub fn markup_tree(&self) -> Vec<MarkupNode> {
+        yarnspinner::runtime::markup_tree(&self.text, &self.attributes)
+    }
+
     // Documentation taken from `YarnLine`
     /// Deletes an attribute from this markup.
     /// This method deletes the range of text covered by `attribute_to_delete`,
@@ -181,6 +206,7 @@ impl From<LocalizedLine> for YarnLine {
             id: line.id,
             text: line.text,
             attributes: line.attributes,
+            language: line.language,
         }
     }
 }
@@ -197,6 +223,7 @@ impl LocalizedLine {
             attributes: line.attributes,
             metadata,
             assets,
+            language: line.language,
         }
     }
 }
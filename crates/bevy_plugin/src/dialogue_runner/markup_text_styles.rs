@@ -0,0 +1,127 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// Maps the names of markup attributes to overrides of a base [`TextStyle`] and uses them to turn a [`LocalizedLine`] into [`TextSection`]s.
+///
+/// Requires the `text_styles` feature.
+///
+/// ## Example
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_yarnspinner::prelude::*;
+/// let styles = MarkupTextStyles::new(TextStyle::default())
+///     .with_override("b", TextStyleOverride::default().with_font_size(32.0))
+///     .with_override("angry", TextStyleOverride::default().with_color(Color::srgb(1.0, 0.0, 0.0)));
+/// # let line = LocalizedLine {
+/// #    id: "line".into(),
+/// #    text: "I am angry".to_owned(),
+/// #    attributes: vec![],
+/// #    metadata: vec![],
+/// #    assets: Default::default(),
+/// #    language: None,
+/// # };
+/// let sections = styles.text_sections(&line);
+/// # assert_eq!(1, sections.len());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MarkupTextStyles {
+    /// The style used for text that is not covered by any attribute with an override.
+    pub base: TextStyle,
+    /// The overrides applied to text covered by an attribute of the given name.
+    pub overrides: HashMap<String, TextStyleOverride>,
+}
+
+impl MarkupTextStyles {
+    /// Creates a new [`MarkupTextStyles`] without any overrides.
+    pub fn new(base: TextStyle) -> Self {
+        Self {
+            base,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Registers the [`TextStyleOverride`] to apply to text covered by attributes named `attribute_name`.
+    pub fn with_override(
+        mut self,
+        attribute_name: impl Into<String>,
+        style_override: TextStyleOverride,
+    ) -> Self {
+        self.overrides.insert(attribute_name.into(), style_override);
+        self
+    }
+
+    /// Returns the [`TextStyle`] for text covered by the passed attributes.
+    /// Overrides are applied from the outermost to the innermost attribute, so inner attributes win.
+    pub fn style_for<'a>(
+        &self,
+        attributes: impl IntoIterator<Item = &'a MarkupAttribute>,
+    ) -> TextStyle {
+        attributes
+            .into_iter()
+            .filter_map(|attribute| self.overrides.get(&attribute.name))
+            .fold(self.base.clone(), |style, style_override| {
+                style_override.apply(style)
+            })
+    }
+
+    /// Converts the text of `line` into [`TextSection`]s styled according to the attributes applying to each part of it.
+    /// Attributes without a registered override are ignored. Adjacent parts of the line that end up with the same style are not merged.
+    pub fn text_sections(&self, line: &LocalizedLine) -> Vec<TextSection> {
+        line.spans()
+            .into_iter()
+            .filter(|span| !span.text.is_empty())
+            .map(|span| {
+                let style = self.style_for(&span.attributes);
+                TextSection::new(span.text, style)
+            })
+            .collect()
+    }
+}
+
+/// A partial [`TextStyle`] used by [`MarkupTextStyles`]. Fields that are [`None`] keep the value of the style they are applied to.
+///
+/// Requires the `text_styles` feature.
+#[derive(Debug, Clone, Default)]
+pub struct TextStyleOverride {
+    /// The font to use instead of the current one.
+    pub font: Option<Handle<Font>>,
+    /// The font size to use instead of the current one.
+    pub font_size: Option<f32>,
+    /// The color to use instead of the current one.
+    pub color: Option<Color>,
+}
+
+impl TextStyleOverride {
+    /// Overrides the font.
+    pub fn with_font(mut self, font: Handle<Font>) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Overrides the font size.
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = Some(font_size);
+        self
+    }
+
+    /// Overrides the color.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Applies this override to `style`.
+    pub fn apply(&self, mut style: TextStyle) -> TextStyle {
+        if let Some(font) = &self.font {
+            style.font = font.clone();
+        }
+        if let Some(font_size) = self.font_size {
+            style.font_size = font_size;
+        }
+        if let Some(color) = self.color {
+            style.color = color;
+        }
+        style
+    }
+}
@@ -116,7 +116,28 @@ pub mod default_impl {
     pub use crate::line_provider::{
         file_extensions, FileExtensionAssetProvider, StringsFileTextProvider,
     };
-    pub use yarnspinner::runtime::{MemoryVariableStorage, StringTableTextProvider};
+    pub use yarnspinner::runtime::{
+        MemoryVariableStorage, PseudoLocalization, StringTableTextProvider,
+    };
+
+    /// Can wrap any [`TextProvider`](crate::prelude::TextProvider), e.g. the default [`StringsFileTextProvider`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use bevy::prelude::*;
+    /// # use bevy_yarnspinner::{prelude::*, default_impl::*};
+    /// fn spawn_dialogue_runner(mut commands: Commands, project: Res<YarnProject>) {
+    ///     let text_provider = PseudoLocalizedTextProvider::new(StringsFileTextProvider::from_yarn_project(&project))
+    ///         .with_pseudo_localization(PseudoLocalization::new().with_padding_percentage(40));
+    ///     let dialogue_runner = project
+    ///         .build_dialogue_runner()
+    ///         .with_text_provider(text_provider)
+    ///         .build();
+    ///     commands.spawn(dialogue_runner);
+    /// }
+    /// ```
+    pub use yarnspinner::runtime::PseudoLocalizedTextProvider;
 }
 
 pub mod events {
@@ -132,10 +153,12 @@ pub mod prelude {
 
     #[cfg(feature = "audio_assets")]
     pub use crate::default_impl::AudioAssetProvider;
+    #[cfg(feature = "text_styles")]
+    pub use crate::dialogue_runner::{MarkupTextStyles, TextStyleOverride};
     pub use crate::{
         commands::{YarnCommand, YarnCommands},
         default_impl::FileExtensionAssetProvider,
-        development_file_generation::DevelopmentFileGeneration,
+        development_file_generation::{DevelopmentFileGeneration, LineIdGeneration},
         dialogue_runner::{DialogueOption, DialogueRunner, DialogueRunnerBuilder, LocalizedLine},
         line_provider::{AssetProvider, LineAssets, TextProvider},
         localization::{Localization, Localizations},
@@ -151,19 +174,35 @@ pub mod prelude {
         IntoYarnValueFromNonYarnValue, Language, LineId, MarkupAttribute, MarkupValue, OptionId,
         VariableStorage, YarnFn, YarnLibrary, YarnValue,
     };
+    pub use yarnspinner::runtime::{MarkupNode, MarkupSpan};
     pub(crate) type SystemResult = Result<()>;
 }
 
 pub use crate::commands::{TaskFinishedIndicator, UntypedYarnCommand};
 pub use crate::dialogue_runner::{InnerDialogue, InnerDialogueMut};
+pub use yarnspinner::compiler::DiagnosticSeverity;
 pub use yarnspinner::core::{yarn_fn_type, UntypedYarnFn};
 pub use yarnspinner::prelude::{
     Compilation, StringInfo, TextProvider as UnderlyingTextProvider, YarnAnalysisContext,
     YarnCommand as UnderlyingYarnCommand, YarnLine as UnderlyingYarnLine,
 };
 
+pub mod strings_files {
+    //! Types for working with the strings files of translations: converting them to other formats than Yarn Spinner's own CSV, namely XLIFF 2.0 and gettext PO,
+    //! and reporting how far the translations are.
+    pub use crate::localization::{
+        LanguageTranslationStatus, LineCount, NodeTranslationStatus, StringsFileConverter,
+        StringsFileFormat, TranslationCounts, TranslationStatusReport, UntranslatedLine,
+    };
+}
+
 pub mod deferred_loading {
     //! Contains types needed for the deferred loading functionality, which is used when the list of Yarn files is not immediately available at startup.
     pub use crate::plugin::DeferredYarnSpinnerPlugin;
     pub use crate::project::LoadYarnProjectEvent;
 }
+
+pub mod voice_over {
+    //! Types for exporting the lines of a Yarn project as a voice-over recording script.
+    pub use crate::localization::{VoiceOverCharacter, VoiceOverLine, VoiceOverScript};
+}
@@ -7,6 +7,7 @@ use std::any::Any;
 use std::collections::HashMap;
 pub use strings_file_text_provider::StringsFileTextProvider;
 
+mod pseudo_localized_text_provider;
 mod shared_text_provider;
 mod strings_file_text_provider;
 
@@ -0,0 +1,26 @@
+use crate::default_impl::PseudoLocalizedTextProvider;
+use crate::prelude::*;
+use bevy::prelude::*;
+use std::any::Any;
+use std::collections::HashMap;
+
+impl<T> TextProvider for PseudoLocalizedTextProvider<T>
+where
+    T: TextProvider + Clone + 'static,
+{
+    fn set_base_string_table(&mut self, string_table: HashMap<LineId, StringInfo>) {
+        self.inner_mut().set_base_string_table(string_table)
+    }
+
+    fn extend_base_string_table(&mut self, string_table: HashMap<LineId, StringInfo>) {
+        self.inner_mut().extend_base_string_table(string_table)
+    }
+
+    fn take_fetched_assets(&mut self, asset: Box<dyn Any>) {
+        self.inner_mut().take_fetched_assets(asset)
+    }
+
+    fn fetch_assets(&self, world: &World) -> Option<Box<dyn Any + 'static>> {
+        self.inner().fetch_assets(world)
+    }
+}
@@ -58,6 +58,10 @@ impl UnderlyingTextProvider for SharedTextProvider {
         self.0.read().unwrap().get_text(id)
     }
 
+    fn get_text_with_language(&self, id: &LineId) -> Option<(String, Option<Language>)> {
+        self.0.read().unwrap().get_text_with_language(id)
+    }
+
     fn set_language(&mut self, language: Option<Language>) {
         self.0.write().unwrap().set_language(language)
     }
@@ -1,12 +1,15 @@
 use crate::fmt_utils::SkipDebug;
+use crate::localization::StringsFileLoaderSettings;
 use crate::prelude::*;
 use crate::UnderlyingTextProvider;
 
+use bevy::asset::{LoadState, RecursiveDependencyLoadState};
 use bevy::ecs::event::ManualEventReader;
 use bevy::prelude::*;
 use std::any::Any;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use yarnspinner::runtime::{TranslationChecker, TranslationIssue};
 
 pub(crate) fn strings_file_text_provider_plugin(_app: &mut App) {}
 
@@ -14,18 +17,25 @@ pub(crate) fn strings_file_text_provider_plugin(_app: &mut App) {}
 /// If the [`DialogueRunner`]'s language is the base language, i.e. the one the Yarn files are written in,
 /// this will send the lines as they appear in the Yarn file. If [`DialogueRunner::set_language`] or [`DialogueRunner::set_text_language`] were used to
 /// set the language to a language supported by a translation in the [`Localizations`], this loads the strings file for that translation from the disk at the
-/// specified path. If this fails, the base language is used as a fallback.
+/// specified path, together with the strings files of the translations in its [`Localizations::fallback_chain`].
+/// Lines that are missing in a translation are taken from the next translation in the chain, e.g. `pt-BR → pt`, and finally from the base language.
+/// Translations in the chain whose strings files failed to load are skipped, and those that are still loading are used once they are loaded.
+/// The language that actually served a line is reported in [`LocalizedLine::language`].
 #[derive(Debug, Clone)]
 pub struct StringsFileTextProvider {
     asset_server: SkipDebug<AssetServer>,
     localizations: Option<Localizations>,
     language: Option<Language>,
     base_string_table: HashMap<LineId, StringInfo>,
-    strings_file_handle: Option<Handle<StringsFile>>,
-    translation_string_table: Option<HashMap<LineId, String>>,
+    strings_file_handles: Vec<(Language, Handle<StringsFile>)>,
+    translation_string_tables: Option<TranslationStringTables>,
     event_reader: Arc<RwLock<ManualEventReader<AssetEvent<StringsFile>>>>,
+    known_markup_markers: Option<Vec<String>>,
 }
 
+/// The string tables of the translations in a fallback chain, in order of preference.
+type TranslationStringTables = Vec<(Language, HashMap<LineId, String>)>;
+
 impl UnderlyingTextProvider for StringsFileTextProvider {
     fn clone_shallow(&self) -> Box<dyn UnderlyingTextProvider> {
         Box::new(self.clone())
@@ -36,22 +46,31 @@ impl UnderlyingTextProvider for StringsFileTextProvider {
     }
 
     fn get_text(&self, id: &LineId) -> Option<String> {
+        self.get_text_with_language(id).map(|(text, _)| text)
+    }
+
+    fn get_text_with_language(&self, id: &LineId) -> Option<(String, Option<Language>)> {
+        let base_text = || {
+            self.base_string_table
+                .get(id)
+                .map(|info| (info.text.clone(), None))
+        };
         if self.is_base_language() {
-            return self.base_string_table.get(id).map(|info| info.text.clone());
+            return base_text();
         }
 
-        self.translation_string_table
-            .as_ref()
-            .and_then(|table| table.get(id).cloned())
-            .or_else(|| {
-                let language = self.language.as_ref().unwrap();
-                if self.translation_string_table.is_some() {
-                    warn!("Did not find translation for line {id} in language {language} because it is untranslated, falling back to base language.");
-                } else {
-                    warn!("Did not find translation for line {id} in language {language} because the strings file has not been loaded yet, falling back to base language.");
-                }
-                self.base_string_table.get(id).map(|info| info.text.clone())
+        let Some(translation_string_tables) = self.translation_string_tables.as_ref() else {
+            let language = self.language.as_ref().unwrap();
+            warn!("Did not find translation for line {id} in language {language} because the strings file has not been loaded yet, falling back to base language.");
+            return base_text();
+        };
+        translation_string_tables
+            .iter()
+            .find_map(|(language, table)| {
+                let text = table.get(id)?.clone();
+                Some((text, Some(language.clone())))
             })
+            .or_else(base_text)
     }
 
     fn set_language(&mut self, language: Option<Language>) {
@@ -72,18 +91,30 @@ impl UnderlyingTextProvider for StringsFileTextProvider {
             self.set_language_invalidating_translation(None);
             return;
         }
-        let Some(localization) = localizations.translation(&language) else {
+        if localizations.translation(&language).is_none() {
             let languages = localizations
                 .supported_languages()
                 .map(ToString::to_string)
                 .collect::<Vec<_>>()
                 .join(", ");
             panic!("Set language to {language}, but that language is not supported. Expected one of {languages}.");
-        };
-        let path = localization.strings_file.as_path();
-        let asset_path = path.to_string_lossy().replace('\\', "/");
-        self.strings_file_handle
-            .replace(self.asset_server.load(asset_path));
+        }
+        self.strings_file_handles = localizations
+            .fallback_chain(&language)
+            .into_iter()
+            .map(|localization| {
+                let path = localization.strings_file.as_path();
+                let asset_path = path.to_string_lossy().replace('\\', "/");
+                let known_markup_markers = self.known_markup_markers.clone();
+                let handle = self.asset_server.load_with_settings(
+                    asset_path,
+                    move |settings: &mut StringsFileLoaderSettings| {
+                        settings.known_markup_markers = known_markup_markers.clone();
+                    },
+                );
+                (localization.language.clone(), handle)
+            })
+            .collect();
     }
 
     fn get_language(&self) -> Option<Language> {
@@ -92,7 +123,7 @@ impl UnderlyingTextProvider for StringsFileTextProvider {
 
     fn are_lines_available(&self) -> bool {
         let is_base_language = self.is_base_language();
-        let has_fetched_translation = || self.translation_string_table.is_some();
+        let has_fetched_translation = || self.translation_string_tables.is_some();
         is_base_language || has_fetched_translation()
     }
 
@@ -113,15 +144,41 @@ impl StringsFileTextProvider {
             localizations: yarn_project.localizations.clone(),
             language: None,
             base_string_table: yarn_project.compilation.string_table.clone(),
-            strings_file_handle: None,
-            translation_string_table: None,
+            strings_file_handles: Vec::new(),
+            translation_string_tables: None,
             event_reader: Default::default(),
+            known_markup_markers: yarn_project.known_markup_markers.clone(),
         }
     }
     fn set_language_invalidating_translation(&mut self, language: impl Into<Option<Language>>) {
         self.language = language.into();
-        self.translation_string_table = None;
-        self.strings_file_handle = None;
+        self.translation_string_tables = None;
+        self.strings_file_handles.clear();
+    }
+
+    /// Logs inconsistencies between a translation and the base language, which would otherwise only show up once the line is reached in-game.
+    /// Markup errors are not repeated here, as they are already logged when the strings file is loaded.
+    fn log_translation_issues(&self, string_table: &HashMap<LineId, String>, language: &Language) {
+        let translation_checker = TranslationChecker::new();
+        let mut ids: Vec<_> = string_table.keys().collect();
+        ids.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+        for id in ids {
+            // Lines that are missing in the base language are obsolete, so there is nothing to compare them with
+            let Some(base) = self.base_string_table.get(id) else {
+                continue;
+            };
+            let issues = translation_checker.check(&base.text, &string_table[id], language);
+            for issue in issues {
+                if matches!(issue, TranslationIssue::InvalidMarkup(_)) {
+                    continue;
+                }
+                if issue.is_error() {
+                    error!("Translation of line {id} in language {language} does not match the base language: {issue}");
+                } else {
+                    warn!("Translation of line {id} in language {language} does not match the base language: {issue}");
+                }
+            }
+        }
     }
 
     fn is_base_language(&self) -> bool {
@@ -144,43 +201,62 @@ impl TextProvider for StringsFileTextProvider {
     }
 
     fn take_fetched_assets(&mut self, asset: Box<dyn Any>) {
-        let string_table: Box<HashMap<LineId, String>> = asset.downcast().unwrap();
-        self.translation_string_table.replace(*string_table);
+        let string_tables: Box<TranslationStringTables> = asset.downcast().unwrap();
+        self.translation_string_tables.replace(*string_tables);
     }
 
     fn fetch_assets(&self, world: &World) -> Option<Box<dyn Any + 'static>> {
         if self.is_base_language() {
             return None;
         }
-        let handle = self.strings_file_handle.as_ref()?;
-        if !self.asset_server.is_loaded_with_dependencies(handle) {
+        // Only the strings file of the requested language is waited for.
+        // Fallbacks that are still loading are added once they are loaded, and fallbacks that failed to load are skipped.
+        let (_, requested_handle) = self.strings_file_handles.first()?;
+        let is_loading = |handle: &Handle<StringsFile>| {
+            self.asset_server.load_state(handle) == LoadState::Loading
+                || self.asset_server.recursive_dependency_load_state(handle)
+                    == RecursiveDependencyLoadState::Loading
+        };
+        if is_loading(requested_handle) {
             return None;
         }
         let asset_events = world.resource::<Events<AssetEvent<StringsFile>>>();
         let strings_file_has_changed = || {
             let mut reader = self.event_reader.write().unwrap();
             reader.read(asset_events).any(|event| match event {
-                AssetEvent::Modified { id } => *id == handle.id(),
+                AssetEvent::Modified { id } | AssetEvent::LoadedWithDependencies { id } => self
+                    .strings_file_handles
+                    .iter()
+                    .any(|(_, handle)| *id == handle.id()),
                 _ => false,
             })
         };
-        let has_no_translation_yet = self.translation_string_table.is_none();
+        let has_no_translation_yet = self.translation_string_tables.is_none();
         if has_no_translation_yet || strings_file_has_changed() {
-            let strings_file = world.resource::<Assets<StringsFile>>().get(handle).unwrap();
-            let expected_language = self.language.as_ref().unwrap();
-            if let Some(record) = strings_file.get_offending_language(expected_language) {
-                let path = self.asset_server.get_path(handle).unwrap();
-                panic!("Expected strings file at {path} to only contain language {expected_language}, but its entry with id \"{id}\" is for language {actual_language}.",
-                           path = path.path().display(),
-                           id = record.id,
-                           actual_language = record.language,
-                    );
-            }
-            let string_table: HashMap<LineId, String> = strings_file
+            let strings_files = world.resource::<Assets<StringsFile>>();
+            let string_tables: TranslationStringTables = self
+                .strings_file_handles
                 .iter()
-                .map(|(id, record)| (id.clone(), record.text.clone()))
+                .filter(|(_, handle)| self.asset_server.is_loaded_with_dependencies(handle))
+                .filter_map(|(expected_language, handle)| {
+                    let strings_file = strings_files.get(handle)?;
+                    if let Some(record) = strings_file.get_offending_language(expected_language) {
+                        let path = self.asset_server.get_path(handle).unwrap();
+                        panic!("Expected strings file at {path} to only contain language {expected_language}, but its entry with id \"{id}\" is for language {actual_language}.",
+                                   path = path.path().display(),
+                                   id = record.id,
+                                   actual_language = record.language,
+                            );
+                    }
+                    let string_table = strings_file
+                        .iter()
+                        .map(|(id, record)| (id.clone(), record.text.clone()))
+                        .collect();
+                    self.log_translation_issues(&string_table, expected_language);
+                    Some((expected_language.clone(), string_table))
+                })
                 .collect();
-            Some(Box::new(string_table))
+            Some(Box::new(string_tables))
         } else {
             None
         }
@@ -1,13 +1,21 @@
-pub use self::localizations::*;
 pub(crate) use self::{
     line_id_generation::LineIdUpdateSystemSet,
     strings_file::UpdateAllStringsFilesForStringTableEvent, strings_file::*,
 };
+pub use self::{
+    localizations::*,
+    strings_file::{
+        LanguageTranslationStatus, LineCount, NodeTranslationStatus, StringsFileConverter,
+        StringsFileFormat, TranslationCounts, TranslationStatusReport, UntranslatedLine,
+    },
+    voice_over_script::{VoiceOverCharacter, VoiceOverLine, VoiceOverScript},
+};
 use bevy::prelude::*;
 
 mod line_id_generation;
 mod localizations;
 mod strings_file;
+mod voice_over_script;
 
 pub(crate) fn localization_plugin(app: &mut App) {
     app.add_plugins(localizations::localization_config_plugin)
@@ -1,7 +1,9 @@
 use crate::localization::UpdateAllStringsFilesForStringTableEvent;
 use crate::plugin::AssetRoot;
 use crate::prelude::*;
-use crate::project::{RecompileLoadedYarnFilesEvent, YarnFilesBeingLoaded};
+use crate::project::{
+    RecompileLoadedYarnFilesEvent, YarnFilesBeingLoaded, YarnProjectConfigToLoad,
+};
 use bevy::prelude::*;
 use bevy::utils::HashSet;
 use std::hash::Hash;
@@ -52,12 +54,22 @@ fn handle_yarn_file_events(
     mut recompile_events: EventWriter<RecompileLoadedYarnFilesEvent>,
     yarn_files_being_loaded: Res<YarnFilesBeingLoaded>,
     project: Option<Res<YarnProject>>,
+    project_to_load: Option<Res<YarnProjectConfigToLoad>>,
     mut update_strings_files_writer: EventWriter<UpdateAllStringsFilesForStringTableEvent>,
     mut dialogue_runners: Query<&mut DialogueRunner>,
     mut added_tags: Local<HashSet<Handle<YarnFile>>>,
     mut last_recompiled_yarn_file: Local<Option<YarnFile>>,
     asset_root: Res<AssetRoot>,
 ) -> SystemResult {
+    let line_id_generation = project
+        .as_ref()
+        .map(|project| project.line_id_generation)
+        .or_else(|| {
+            project_to_load
+                .as_ref()
+                .map(|project_to_load| project_to_load.line_id_generation)
+        })
+        .unwrap_or_default();
     let mut recompilation_needed = false;
     let mut already_handled = HashSet::new();
     for event in events.read() {
@@ -85,7 +97,7 @@ fn handle_yarn_file_events(
             yarn_file.string_table.clone(),
         ));
 
-        let Some(source_with_added_ids) = add_tags_to_lines(yarn_file)? else {
+        let Some(source_with_added_ids) = add_tags_to_lines(yarn_file, line_id_generation)? else {
             if matches!(event, AssetEvent::LoadedWithDependencies { .. }) {
                 continue;
             }
@@ -150,12 +162,19 @@ fn handle_yarn_file_events(
 }
 
 /// Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner-Console/blob/main/src/YarnSpinner.Console/Commands/TagCommand.cs#L11>
-fn add_tags_to_lines(yarn_file: &YarnFile) -> YarnCompilerResult<Option<String>> {
+fn add_tags_to_lines(
+    yarn_file: &YarnFile,
+    line_id_generation: LineIdGeneration,
+) -> YarnCompilerResult<Option<String>> {
     let existing_tags = yarn_file
         .string_table
         .iter()
         .filter(|(_, string_info)| !string_info.is_implicit_tag)
         .map(|(key, _)| key.clone())
         .collect();
-    YarnCompiler::add_tags_to_lines(yarn_file.file.source.clone(), existing_tags)
+    YarnCompiler::add_tags_to_lines_with_strategy(
+        yarn_file.file.clone(),
+        existing_tags,
+        line_id_generation.strategy(),
+    )
 }
@@ -43,6 +43,51 @@ impl Localizations {
             .find(|localization| localization.language == *language)
     }
 
+    /// Returns the translations to take the text of a line from when `language` is selected, in order of preference.
+    /// The chain starts with the translation for `language` itself and continues with the fallbacks set by [`Localization::with_fallbacks`],
+    /// or, if there are none, the languages derived by truncating subtags with [`Language::fallback_chain`], e.g. `pt-BR → pt`.
+    /// Languages without a translation are skipped and the base localization is never part of the chain, as it is always the last resort.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use bevy_yarnspinner::prelude::*;
+    /// let localizations = Localizations {
+    ///     base_localization: "en-US".into(),
+    ///     translations: vec![
+    ///         "pt".into(),
+    ///         "pt-BR".into(),
+    ///         Localization::with_language("pt-PT").with_fallbacks(["pt-BR", "pt"]),
+    ///     ],
+    /// };
+    /// let chain = |language: &str| -> Vec<String> {
+    ///     localizations
+    ///         .fallback_chain(&language.into())
+    ///         .iter()
+    ///         .map(|localization| localization.language.to_string())
+    ///         .collect()
+    /// };
+    /// assert_eq!(vec!["pt-BR", "pt"], chain("pt-BR"));
+    /// assert_eq!(vec!["pt-PT", "pt-BR", "pt"], chain("pt-PT"));
+    /// assert_eq!(vec!["pt"], chain("pt-AO"));
+    /// ```
+    pub fn fallback_chain(&self, language: &Language) -> Vec<&Localization> {
+        let languages = match self
+            .translation(language)
+            .and_then(|localization| localization.fallbacks.as_ref())
+        {
+            Some(fallbacks) => iter::once(language.clone())
+                .chain(fallbacks.iter().cloned())
+                .collect(),
+            None => language.fallback_chain(),
+        };
+        languages
+            .iter()
+            .take_while(|language| **language != self.base_localization.language)
+            .filter_map(|language| self.translation(language))
+            .collect()
+    }
+
     pub(crate) fn supported_localization(&self, language: &Language) -> Option<&Localization> {
         iter::once(&self.base_localization)
             .chain(self.translations.iter())
@@ -80,10 +125,15 @@ pub struct Localization {
     pub language: Language,
     /// The path to the strings file for this localization inside the `assets` folder.
     /// Defaults to `dialogue/{language}.strings.csv`. So, for the language "de-CH", you'd end up with "assets/dialogue/de-CH.strings.csv".
+    /// Use the extension `.xlf` or `.po` to store the translation as XLIFF 2.0 or gettext PO file instead, see [`StringsFileFormat`](crate::strings_files::StringsFileFormat).
     pub strings_file: PathBuf,
     /// The path to the subdirectory containing the assets for this localization inside the `assets` folder.
     /// Defaults to `dialogue/{language}/`.  So, for the language "de-CH", you'd end up with "assets/dialogue/de-CH/".
     pub assets_sub_folder: PathBuf,
+    /// The languages whose translations are used, in order, for lines that are missing in this localization.
+    /// Defaults to `None`, which derives them from the language, e.g. `pt-BR → pt`. See [`Localizations::fallback_chain`].
+    #[serde(default)]
+    pub fallbacks: Option<Vec<Language>>,
 }
 
 impl<T> From<T> for Localization
@@ -111,6 +161,7 @@ impl Localization {
             language,
             strings_file,
             assets_sub_folder,
+            fallbacks: None,
         }
     }
 
@@ -125,4 +176,15 @@ impl Localization {
         self.assets_sub_folder = assets_sub_folder.into();
         self
     }
+
+    /// Sets the languages whose translations are used, in order, for lines that are missing in this localization.
+    /// This replaces the fallbacks derived from the language, e.g. to let `pt-PT` fall back to `pt-BR` instead of just `pt`.
+    /// Pass an empty list to fall back straight to the base language.
+    pub fn with_fallbacks(
+        mut self,
+        fallbacks: impl IntoIterator<Item = impl Into<Language>>,
+    ) -> Self {
+        self.fallbacks = Some(fallbacks.into_iter().map(Into::into).collect());
+        self
+    }
 }
@@ -1,10 +1,24 @@
-pub(crate) use self::{asset::StringsFile, updating::UpdateAllStringsFilesForStringTableEvent};
+pub use self::conversion::{StringsFileConverter, StringsFileFormat};
+pub use self::translation_status::{
+    LanguageTranslationStatus, LineCount, NodeTranslationStatus, TranslationCounts,
+    TranslationStatusReport, UntranslatedLine,
+};
+pub(crate) use self::{
+    asset::{StringsFile, StringsFileLoaderSettings},
+    updating::UpdateAllStringsFilesForStringTableEvent,
+};
 use bevy::prelude::*;
 
 mod asset;
+mod conversion;
+mod markup_validation;
+mod po;
+mod translation_status;
 mod updating;
+mod xliff;
 
 pub(crate) fn strings_file_plugin(app: &mut App) {
     app.add_plugins(asset::strings_file_asset_plugin)
-        .add_plugins(updating::strings_file_updating_plugin);
+        .add_plugins(updating::strings_file_updating_plugin)
+        .add_plugins(translation_status::translation_status_plugin);
 }
@@ -1,5 +1,7 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner-Unity/blob/462c735766a4c4881cd1ef1f15de28c83b2ba0a8/Runtime/StringTableEntry.cs>
 
+use crate::localization::strings_file::markup_validation::validate_strings_file_markup;
+use crate::localization::strings_file::{po, xliff, StringsFileFormat};
 use crate::prelude::*;
 use anyhow::{anyhow, bail};
 use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
@@ -8,8 +10,9 @@ use bevy::reflect::TypePath;
 use bevy::utils::HashMap;
 use sha2::{Digest, Sha256};
 use std::fs;
-use std::fs::File;
 use std::path::Path;
+use yarnspinner::compiler::DiagnosticSeverity;
+use yarnspinner::runtime::MarkupValidator;
 
 pub(crate) fn strings_file_asset_plugin(app: &mut App) {
     app.init_asset::<StringsFile>()
@@ -19,26 +22,76 @@ pub(crate) fn strings_file_asset_plugin(app: &mut App) {
 #[derive(Debug, Default)]
 struct StringsFileAssetLoader;
 
+/// The settings used when loading a [`StringsFile`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct StringsFileLoaderSettings {
+    /// See [`YarnSpinnerPlugin::with_known_markup_markers`].
+    pub(crate) known_markup_markers: Option<Vec<String>>,
+}
+
 impl AssetLoader for StringsFileAssetLoader {
     type Asset = StringsFile;
-    type Settings = ();
+    type Settings = StringsFileLoaderSettings;
     type Error = anyhow::Error;
     async fn load<'a>(
         &'a self,
         reader: &'a mut Reader<'_>,
-        _settings: &'a (),
-        _load_context: &'a mut LoadContext<'_>,
+        settings: &'a StringsFileLoaderSettings,
+        load_context: &'a mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
+        let validator = match &settings.known_markup_markers {
+            Some(known_markers) => MarkupValidator::new().with_known_markers(known_markers.clone()),
+            None => MarkupValidator::new(),
+        };
+        let format = StringsFileFormat::from_path(load_context.path()).unwrap_or_default();
+        if format != StringsFileFormat::Csv {
+            let strings_file = StringsFile::read(&bytes, format)?;
+            let path = load_context.path().display();
+            for record in strings_file.records() {
+                for issue in validator.validate(&record.text) {
+                    if issue.is_error() {
+                        error!("{path}: line {}: {issue}", record.id);
+                    } else {
+                        warn!("{path}: line {}: {issue}", record.id);
+                    }
+                }
+            }
+            return Ok(strings_file);
+        }
+
         let mut csv_reader = csv::Reader::from_reader(bytes.as_slice());
-        let records: csv::Result<Vec<_>> = csv_reader.deserialize().collect();
-        let strings_file = StringsFile::new_with_single_language(records?)?;
+        let headers = csv_reader.headers()?.clone();
+        let string_records = csv_reader.records().collect::<csv::Result<Vec<_>>>()?;
+        let records = string_records
+            .iter()
+            .map(|record| record.deserialize(Some(&headers)))
+            .collect::<csv::Result<Vec<_>>>()?;
+
+        // Markup errors in translations would otherwise only show up once the line is reached in-game
+        if let Some(text_field) = headers.iter().position(|header| header == "text") {
+            let diagnostics = validate_strings_file_markup(
+                std::str::from_utf8(&bytes)?,
+                &load_context.path().to_string_lossy(),
+                text_field,
+                &string_records,
+                &validator,
+            );
+            for diagnostic in diagnostics {
+                match diagnostic.severity {
+                    DiagnosticSeverity::Error => error!("{diagnostic}"),
+                    DiagnosticSeverity::Warning => warn!("{diagnostic}"),
+                }
+            }
+        }
+
+        let strings_file = StringsFile::new_with_single_language(records)?;
         Ok(strings_file)
     }
 
     fn extensions(&self) -> &[&str] {
-        &["strings.csv"]
+        &["strings.csv", "xlf", "xliff", "po"]
     }
 }
 
@@ -98,8 +151,7 @@ impl StringsFile {
                 if records_equal_except_for_text(record, &other_record) {
                     continue;
                 }
-                let text_is_copied_from_base_language =
-                    Lock::compute_from(&record.text) == record.lock;
+                let text_is_copied_from_base_language = record.is_untranslated();
                 let text = if record.lock != other_record.lock
                     && !record.text.starts_with(UPDATE_PREFIX)
                     && !text_is_copied_from_base_language
@@ -168,7 +220,67 @@ impl StringsFile {
         Ok(Self(records))
     }
 
-    pub(crate) fn write_asset(&self, path: &Path) -> Result<()> {
+    /// Reads a strings file in the given format.
+    pub(crate) fn read(bytes: &[u8], format: StringsFileFormat) -> Result<Self> {
+        let records = match format {
+            StringsFileFormat::Csv => csv::Reader::from_reader(bytes)
+                .deserialize()
+                .collect::<csv::Result<Vec<_>>>()?,
+            StringsFileFormat::Xliff => xliff::read_xliff(std::str::from_utf8(bytes)?)?,
+            StringsFileFormat::Po => po::read_po(std::str::from_utf8(bytes)?)?,
+        };
+        Self::new_with_single_language(records)
+    }
+
+    /// Reads a strings file from the disk, using the format implied by the file extension.
+    pub(crate) fn read_file(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path)
+            .map_err(|e| anyhow!("Failed to read strings file \"{}\": {e}", path.display()))?;
+        let format = StringsFileFormat::from_path(path).unwrap_or_default();
+        Self::read(&bytes, format)
+            .with_context(|| format!("Failed to parse strings file \"{}\"", path.display()))
+    }
+
+    /// Writes the strings file in the given format. Formats like XLIFF and PO contain the base language text next to each translation,
+    /// which is taken from `base_string_table`.
+    pub(crate) fn write(
+        &self,
+        format: StringsFileFormat,
+        base_language: &Language,
+        base_string_table: &std::collections::HashMap<LineId, StringInfo>,
+    ) -> Result<String> {
+        let mut records = self.0.values().collect::<Vec<_>>();
+        records.sort_by(|lhs, rhs| {
+            lhs.file
+                .cmp(&rhs.file)
+                .then(lhs.line_number.cmp(&rhs.line_number))
+        });
+        match format {
+            StringsFileFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                for record in records {
+                    writer.serialize(record)?;
+                }
+                let bytes = writer
+                    .into_inner()
+                    .map_err(|e| anyhow!("Failed to write strings file: {e}"))?;
+                Ok(String::from_utf8(bytes)?)
+            }
+            StringsFileFormat::Xliff => {
+                xliff::write_xliff(&records, base_language, base_string_table)
+            }
+            StringsFileFormat::Po => po::write_po(&records, base_language, base_string_table),
+        }
+    }
+
+    /// Writes the strings file to the disk, using the format implied by the file extension.
+    /// Files with unknown extensions are written as CSV.
+    pub(crate) fn write_asset(
+        &self,
+        path: &Path,
+        base_language: &Language,
+        base_string_table: &std::collections::HashMap<LineId, StringInfo>,
+    ) -> Result<()> {
         if let Some(parent_dir) = path.parent() {
             fs::create_dir_all(parent_dir).map_err(|e| {
                 anyhow!(
@@ -177,19 +289,10 @@ impl StringsFile {
                 )
             })?;
         }
-        let file = File::create(path)
+        let format = StringsFileFormat::from_path(path).unwrap_or_default();
+        let contents = self.write(format, base_language, base_string_table)?;
+        fs::write(path, contents)
             .map_err(|e| anyhow!("Failed to create strings file \"{}\": {e}", path.display(),))?;
-        let mut writer = csv::Writer::from_writer(file);
-        let mut records = self.0.iter().map(|(_, record)| record).collect::<Vec<_>>();
-        records.sort_by(|lhs, rhs| {
-            lhs.file
-                .cmp(&rhs.file)
-                .then(lhs.line_number.cmp(&rhs.line_number))
-        });
-        for record in records {
-            writer.serialize(record)?;
-        }
-        writer.flush()?;
         Ok(())
     }
 
@@ -202,6 +305,10 @@ impl StringsFile {
             .find(|record| &record.language != expected_language)
     }
 
+    pub(crate) fn get(&self, line_id: &LineId) -> Option<&StringsFileRecord> {
+        self.0.get(line_id)
+    }
+
     pub(crate) fn iter(&self) -> impl Iterator<Item = (&LineId, &StringsFileRecord)> {
         self.0.iter()
     }
@@ -220,7 +327,7 @@ fn records_equal_except_for_text(lhs: &StringsFileRecord, rhs: &StringsFileRecor
         && lhs.lock == rhs.lock
         && lhs.comment == rhs.comment
 }
-const UPDATE_PREFIX: &str = "(NEEDS UPDATE) ";
+pub(crate) const UPDATE_PREFIX: &str = "(NEEDS UPDATE) ";
 
 fn combine_comments(full_old_comment: &str, new_metadata: &str) -> String {
     let translator_comment = extract_translator_comment(full_old_comment);
@@ -243,6 +350,23 @@ fn extract_translator_comment(comment: &str) -> Option<&str> {
 const LINE_METADATA_PREFIX: &str = "Line metadata: ";
 const LINE_METADATA_PREFIX_SEPARATOR: &str = ", ";
 
+/// Splits a comment of a [`StringsFileRecord`] into the comment written by a translator and the line metadata.
+pub(crate) fn split_comment(comment: &str) -> (Option<&str>, Vec<&str>) {
+    let metadata = comment
+        .split_once(LINE_METADATA_PREFIX)
+        .map(|(_, metadata)| metadata.split_whitespace().collect())
+        .unwrap_or_default();
+    (extract_translator_comment(comment), metadata)
+}
+
+/// The inverse of [`split_comment`].
+pub(crate) fn join_comment(translator_comment: Option<&str>, metadata: Vec<String>) -> String {
+    combine_comments(
+        translator_comment.unwrap_or_default(),
+        &read_comments(metadata),
+    )
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub(crate) struct StringsFileRecord {
     /// The language that the line is written in.
@@ -281,9 +405,16 @@ pub(crate) struct StringsFileRecord {
     pub(crate) comment: String,
 }
 
+impl StringsFileRecord {
+    /// Returns `true` if the text was copied from the base language and has not been translated yet.
+    pub(crate) fn is_untranslated(&self) -> bool {
+        Lock::compute_from(&self.text) == self.lock
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Reflect, Serialize, Deserialize)]
 #[reflect(Debug, PartialEq, Hash, Serialize, Deserialize)]
-pub(crate) struct Lock(String);
+pub(crate) struct Lock(pub(crate) String);
 
 impl Lock {
     /// Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner-Unity/blob/462c735766a4c4881cd1ef1f15de28c83b2ba0a8/Editor/Importers/YarnImporter.cs#L149>
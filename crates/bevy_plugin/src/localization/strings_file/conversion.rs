@@ -0,0 +1,141 @@
+use crate::prelude::*;
+use anyhow::bail;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// The file formats a translation's strings file can be stored in. Set the format of a [`Localization`] by giving
+/// [`Localization::strings_file`] the corresponding extension. The file is then loaded and, if [`DevelopmentFileGeneration::Full`] is used, updated in that format.
+///
+/// Use [`StringsFileConverter`] to convert between the formats, e.g. to send translations to a translation vendor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StringsFileFormat {
+    /// Yarn Spinner's own CSV format, using the extension `.strings.csv`.
+    #[default]
+    Csv,
+    /// [XLIFF 2.0](https://docs.oasis-open.org/xliff/xliff-core/v2.0/xliff-core-v2.0.html), using the extension `.xlf` or `.xliff`.
+    Xliff,
+    /// [gettext PO](https://www.gnu.org/software/gettext/manual/html_node/PO-Files.html), using the extension `.po`.
+    Po,
+}
+
+impl StringsFileFormat {
+    /// Returns the format implied by the extension of `path`, if it is known.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use bevy_yarnspinner::strings_files::StringsFileFormat;
+    /// assert_eq!(Some(StringsFileFormat::Csv), StringsFileFormat::from_path("dialogue/de-CH.strings.csv"));
+    /// assert_eq!(Some(StringsFileFormat::Xliff), StringsFileFormat::from_path("dialogue/de-CH.xlf"));
+    /// assert_eq!(Some(StringsFileFormat::Po), StringsFileFormat::from_path("dialogue/de-CH.po"));
+    /// assert_eq!(None, StringsFileFormat::from_path("dialogue/de-CH.txt"));
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "xlf" | "xliff" => Some(Self::Xliff),
+            "po" => Some(Self::Po),
+            _ => None,
+        }
+    }
+}
+
+/// Converts the strings files of translations between the formats in [`StringsFileFormat`], so that they can be worked on with translation tools.
+///
+/// Translations that were made for an older version of a line's base language text are detected through the lock stored with every line,
+/// just like when a strings file is updated during development. They are marked with a `(NEEDS UPDATE)` prefix in CSV files,
+/// the segment state `initial` in XLIFF files and the `fuzzy` flag in PO files.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use bevy_yarnspinner::prelude::*;
+/// # use bevy_yarnspinner::strings_files::*;
+/// # use std::path::Path;
+/// # fn example(yarn_project: &YarnProject) -> bevy_yarnspinner::Result<()> {
+/// let converter = StringsFileConverter::new("en-US", yarn_project.compilation().string_table.clone());
+/// // Send the current translation to a vendor
+/// let xliff = converter.export(
+///     "de-CH",
+///     Some(Path::new("assets/dialogue/de-CH.strings.csv")),
+///     StringsFileFormat::Xliff,
+/// )?;
+/// std::fs::write("de-CH.xlf", xliff)?;
+/// // Merge the translation the vendor sent back
+/// converter.convert(
+///     Path::new("de-CH.xlf"),
+///     Path::new("assets/dialogue/de-CH.strings.csv"),
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringsFileConverter {
+    base_language: Language,
+    string_table: HashMap<LineId, StringInfo>,
+}
+
+impl StringsFileConverter {
+    /// Creates a new converter for translations of the lines in `string_table`, which are written in `base_language`.
+    /// The string table is usually taken from [`Compilation::string_table`](crate::Compilation::string_table).
+    pub fn new(
+        base_language: impl Into<Language>,
+        string_table: HashMap<LineId, StringInfo>,
+    ) -> Self {
+        Self {
+            base_language: base_language.into(),
+            string_table,
+        }
+    }
+
+    /// Exports all lines of the string table for translation into `language`.
+    /// If an `existing_translation` is given, its translations are included. Its format is derived from its extension.
+    pub fn export(
+        &self,
+        language: impl Into<Language>,
+        existing_translation: Option<&Path>,
+        format: StringsFileFormat,
+    ) -> Result<String> {
+        let language = language.into();
+        let strings_file = match existing_translation {
+            Some(path) => {
+                let strings_file = StringsFile::read_file(path)?;
+                if let Some(actual_language) = strings_file.language() {
+                    if *actual_language != language {
+                        bail!(
+                            "Expected strings file \"{}\" to contain language {language}, but it contains {actual_language}.",
+                            path.display()
+                        );
+                    }
+                }
+                self.update(strings_file, language)?
+            }
+            None => self.strings_file_for(language)?,
+        };
+        strings_file.write(format, &self.base_language, &self.string_table)
+    }
+
+    /// Reads the translation at `source` and writes it to `target`, using the formats derived from their extensions.
+    /// New lines are added, and translations whose base language text changed since they were translated are marked as such.
+    pub fn convert(&self, source: &Path, target: &Path) -> Result<()> {
+        let strings_file = StringsFile::read_file(source)?;
+        let Some(language) = strings_file.language().cloned() else {
+            bail!(
+                "Cannot convert strings file \"{}\" because it contains no lines, so its language is unknown.",
+                source.display()
+            )
+        };
+        let strings_file = self.update(strings_file, language)?;
+        strings_file.write_asset(target, &self.base_language, &self.string_table)
+    }
+
+    fn update(&self, mut strings_file: StringsFile, language: Language) -> Result<StringsFile> {
+        strings_file.update_file(self.strings_file_for(language)?)?;
+        Ok(strings_file)
+    }
+
+    fn strings_file_for(&self, language: Language) -> Result<StringsFile> {
+        StringsFile::from_string_table(language, self.string_table.clone())
+    }
+}
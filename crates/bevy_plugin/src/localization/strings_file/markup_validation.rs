@@ -0,0 +1,174 @@
+use csv::StringRecord;
+use yarnspinner::compiler::{Diagnostic, DiagnosticCode, DiagnosticSeverity};
+use yarnspinner::core::Position;
+use yarnspinner::runtime::MarkupValidator;
+
+/// Validates the markup in the field `text_field` of every record of a strings file and
+/// returns diagnostics pointing into the CSV `source` the records were read from.
+pub(crate) fn validate_strings_file_markup(
+    source: &str,
+    file_name: &str,
+    text_field: usize,
+    records: &[StringRecord],
+    validator: &MarkupValidator,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for (index, record) in records.iter().enumerate() {
+        let (Some(text), Some(position)) = (record.get(text_field), record.position()) else {
+            continue;
+        };
+        let issues = validator.validate(text);
+        if issues.is_empty() {
+            continue;
+        }
+
+        let record_start = position.byte() as usize;
+        let record_end = records
+            .get(index + 1)
+            .and_then(StringRecord::position)
+            .map_or(source.len(), |next| next.byte() as usize);
+        let raw_record = &source[record_start..record_end];
+        let first_line = (position.line() as usize).saturating_sub(1);
+        let field_positions = field_source_positions(raw_record, text_field, first_line);
+        let source_position = |index: usize| field_positions[index.min(field_positions.len() - 1)];
+
+        for issue in issues {
+            let range = issue.source_range();
+            let severity = if issue.is_error() {
+                DiagnosticSeverity::Error
+            } else {
+                DiagnosticSeverity::Warning
+            };
+            diagnostics.push(Diagnostic {
+                code: DiagnosticCode::InvalidMarkup,
+                file_name: Some(file_name.to_owned()),
+                range: Some(source_position(range.start)..source_position(range.end)),
+                message: issue.to_string(),
+                context: Some(raw_record.trim_end().to_owned()),
+                severity,
+                start_line: first_line,
+                details: Default::default(),
+                fixes: Vec::new(),
+            });
+        }
+    }
+    diagnostics
+}
+
+/// Returns the position in the CSV source of every character of the field at `field_index` in `raw_record`,
+/// followed by the position right after the field's last character.
+/// Quotes around the field and the doubled quotes used to escape a quote are skipped, just like the CSV reader does.
+fn field_source_positions(
+    raw_record: &str,
+    field_index: usize,
+    first_line: usize,
+) -> Vec<Position> {
+    let mut positions = Vec::new();
+    let mut current = Position {
+        line: first_line,
+        character: 0,
+    };
+    let mut field_end = (field_index == 0).then_some(current);
+    let mut field = 0;
+    let mut at_field_start = true;
+    let mut in_quotes = false;
+    let mut characters = raw_record.chars().peekable();
+    while let Some(character) = characters.next() {
+        let position = current;
+        if character == '\n' {
+            current.line += 1;
+            current.character = 0;
+        } else {
+            current.character += 1;
+        }
+
+        let is_content = if in_quotes {
+            if character == '"' && characters.peek() == Some(&'"') {
+                // An escaped quote. Skip the second one
+                characters.next();
+                current.character += 1;
+                true
+            } else if character == '"' {
+                in_quotes = false;
+                false
+            } else {
+                true
+            }
+        } else {
+            match character {
+                '"' if at_field_start => {
+                    in_quotes = true;
+                    false
+                }
+                ',' => {
+                    if field == field_index {
+                        break;
+                    }
+                    field += 1;
+                    at_field_start = true;
+                    if field == field_index {
+                        field_end = Some(current);
+                    }
+                    continue;
+                }
+                '\r' | '\n' => break,
+                _ => true,
+            }
+        };
+        at_field_start = false;
+        if is_content && field == field_index {
+            positions.push(position);
+            field_end = Some(current);
+        }
+    }
+    positions.push(field_end.unwrap_or(current));
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_markup_issues_at_their_position_in_the_csv_file() {
+        let source = "language,id,text\n\
+            de-CH,line:1,Hallo [wave]Welt[/wave]\n\
+            de-CH,line:2,\"Ein \"\"[b]\"\" und\n[/i]\"\n";
+        let mut csv_reader = csv::Reader::from_reader(source.as_bytes());
+        let records: Vec<_> = csv_reader.records().map(Result::unwrap).collect();
+        let validator = MarkupValidator::new().with_known_markers(["b", "i"]);
+
+        let diagnostics =
+            validate_strings_file_markup(source, "de-CH.strings.csv", 2, &records, &validator);
+
+        assert_eq!(2, diagnostics.len());
+        // [wave] is unknown
+        assert_eq!(
+            Some(
+                Position {
+                    line: 1,
+                    character: 19,
+                }..Position {
+                    line: 1,
+                    character: 24,
+                }
+            ),
+            diagnostics[0].range
+        );
+        assert_eq!(DiagnosticSeverity::Warning, diagnostics[0].severity);
+        // [/i] closes nothing, so parsing fails. The range starts at the marker on the next line.
+        assert_eq!(DiagnosticSeverity::Error, diagnostics[1].severity);
+        assert_eq!(
+            Some(
+                Position {
+                    line: 3,
+                    character: 0,
+                }..Position {
+                    line: 3,
+                    character: 4,
+                }
+            ),
+            diagnostics[1].range
+        );
+    }
+}
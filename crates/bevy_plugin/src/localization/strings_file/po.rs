@@ -0,0 +1,374 @@
+//! Reading and writing strings files as [gettext PO files](https://www.gnu.org/software/gettext/manual/html_node/PO-Files.html).
+//!
+//! Every line becomes an entry with the line ID as its `msgctxt`, the base language text as its `msgid` and the translation as its `msgstr`.
+//! The Yarn file and line number are stored as a reference (`#:`), the node, [`Lock`] and line metadata tags as extracted comments (`#.`),
+//! and the translator comment as a translator comment (`#`).
+//! Untranslated lines have an empty `msgstr`, and translations that need to be updated because the base language text changed
+//! are marked with the `fuzzy` flag instead of a `(NEEDS UPDATE)` prefix.
+
+use crate::localization::strings_file::asset::{
+    join_comment, split_comment, Lock, StringsFileRecord, UPDATE_PREFIX,
+};
+use crate::prelude::*;
+use anyhow::bail;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+const NODE_PREFIX: &str = "node: ";
+const LOCK_PREFIX: &str = "lock: ";
+const TAGS_PREFIX: &str = "tags: ";
+const FUZZY_FLAG: &str = "fuzzy";
+
+pub(crate) fn write_po(
+    records: &[&StringsFileRecord],
+    base_language: &Language,
+    base_string_table: &HashMap<LineId, StringInfo>,
+) -> Result<String> {
+    // A strings file without lines, e.g. one generated before the Yarn files were tagged, does not know its language
+    let language = records
+        .first()
+        .map(|record| format!("Language: {}", record.language));
+    let mut po = String::new();
+    writeln!(po, r#"msgid """#)?;
+    writeln!(po, r#"msgstr """#)?;
+    for header in language.into_iter().chain([
+        format!("X-Source-Language: {base_language}"),
+        "MIME-Version: 1.0".to_owned(),
+        "Content-Type: text/plain; charset=UTF-8".to_owned(),
+        "Content-Transfer-Encoding: 8bit".to_owned(),
+    ]) {
+        writeln!(po, "\"{}\"", escape(&format!("{header}\n")))?;
+    }
+
+    for record in records {
+        writeln!(po)?;
+        let (translator_comment, tags) = split_comment(&record.comment);
+        for comment in translator_comment
+            .iter()
+            .flat_map(|comment| comment.lines())
+        {
+            writeln!(po, "# {comment}")?;
+        }
+        writeln!(po, "#. {NODE_PREFIX}{}", record.node)?;
+        writeln!(po, "#. {LOCK_PREFIX}{}", record.lock.0)?;
+        if !tags.is_empty() {
+            writeln!(po, "#. {TAGS_PREFIX}{}", tags.join(" "))?;
+        }
+        writeln!(po, "#: {}:{}", record.file, record.line_number)?;
+
+        let untranslated = record.is_untranslated();
+        let outdated_text = record.text.strip_prefix(UPDATE_PREFIX);
+        if outdated_text.is_some() {
+            writeln!(po, "#, {FUZZY_FLAG}")?;
+        }
+        let source = match base_string_table.get(&record.id) {
+            Some(string_info) => string_info.text.as_str(),
+            None if untranslated => record.text.as_str(),
+            // The line was removed from the Yarn files, but the translation is kept to not lose any work
+            None => "",
+        };
+        let target = if untranslated {
+            ""
+        } else {
+            outdated_text.unwrap_or(&record.text)
+        };
+        writeln!(po, "msgctxt \"{}\"", escape(&record.id.0))?;
+        writeln!(po, "msgid \"{}\"", escape(source))?;
+        writeln!(po, "msgstr \"{}\"", escape(target))?;
+    }
+    Ok(po)
+}
+
+pub(crate) fn read_po(po: &str) -> Result<Vec<StringsFileRecord>> {
+    let mut language = None;
+    let mut entries = Vec::new();
+    let mut entry = Entry::default();
+    let mut current_field: Option<Field> = None;
+    for (index, line) in po.lines().chain([""]).enumerate() {
+        let line = line.trim();
+        let error_context = || format!("Invalid PO file at line {}: \"{line}\"", index + 1);
+        if line.is_empty() {
+            if !entry.is_empty() {
+                entries.push(std::mem::take(&mut entry));
+            }
+            current_field = None;
+            continue;
+        }
+        if line.starts_with("#~") {
+            // Obsolete entry
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix("#.") {
+            entry.extracted_comments.push(comment.trim().to_owned());
+        } else if let Some(reference) = line.strip_prefix("#:") {
+            entry.reference = Some(reference.trim().to_owned());
+        } else if let Some(flags) = line.strip_prefix("#,") {
+            entry.fuzzy |= flags.split(',').any(|flag| flag.trim() == FUZZY_FLAG);
+        } else if line.starts_with("#|") {
+            // Previous msgid, which is not needed as we have the lock
+        } else if let Some(comment) = line.strip_prefix('#') {
+            entry.translator_comments.push(comment.trim().to_owned());
+        } else if line.starts_with('"') {
+            let Some(field) = current_field else {
+                bail!("{}: string without keyword", error_context());
+            };
+            let text = unescape(line).with_context(error_context)?;
+            entry
+                .field(field)
+                .get_or_insert_with(String::new)
+                .push_str(&text);
+        } else {
+            let (keyword, value) = line
+                .split_once(char::is_whitespace)
+                .with_context(error_context)?;
+            let field = match keyword {
+                "msgctxt" => Field::Context,
+                "msgid" => Field::Id,
+                "msgstr" | "msgstr[0]" => Field::Str,
+                "msgid_plural" => bail!(
+                    "{}: plural forms are not supported, use the [plural] markup marker instead",
+                    error_context()
+                ),
+                _ if keyword.starts_with("msgstr[") => Field::Ignored,
+                _ => bail!("{}: unknown keyword \"{keyword}\"", error_context()),
+            };
+            let text = unescape(value.trim()).with_context(error_context)?;
+            *entry.field(field) = Some(text);
+            current_field = Some(field);
+        }
+    }
+
+    let mut records = Vec::new();
+    for entry in entries {
+        if entry.context.is_none() && entry.id.as_deref() == Some("") {
+            language = read_language_from_header(entry.str.as_deref().unwrap_or_default());
+            continue;
+        }
+        let Some(language) = language.clone() else {
+            bail!("PO file has no language. Please add a \"Language\" header.")
+        };
+        records.push(entry.into_record(language)?);
+    }
+    Ok(records)
+}
+
+fn read_language_from_header(header: &str) -> Option<Language> {
+    header
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim() == "Language")
+        .map(|(_, language)| Language::new(language.trim()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Context,
+    Id,
+    Str,
+    Ignored,
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    translator_comments: Vec<String>,
+    extracted_comments: Vec<String>,
+    reference: Option<String>,
+    fuzzy: bool,
+    context: Option<String>,
+    id: Option<String>,
+    str: Option<String>,
+    ignored: Option<String>,
+}
+
+impl Entry {
+    fn is_empty(&self) -> bool {
+        self.context.is_none() && self.id.is_none() && self.str.is_none()
+    }
+
+    fn field(&mut self, field: Field) -> &mut Option<String> {
+        match field {
+            Field::Context => &mut self.context,
+            Field::Id => &mut self.id,
+            Field::Str => &mut self.str,
+            Field::Ignored => &mut self.ignored,
+        }
+    }
+
+    fn into_record(self, language: Language) -> Result<StringsFileRecord> {
+        let source = self.id.unwrap_or_default();
+        let Some(id) = self.context else {
+            bail!("Found PO entry without a line ID in its msgctxt: \"{source}\"")
+        };
+        let mut node = String::new();
+        let mut lock = None;
+        let mut tags = Vec::new();
+        for comment in &self.extracted_comments {
+            if let Some(value) = comment.strip_prefix(NODE_PREFIX) {
+                node = value.to_owned();
+            } else if let Some(value) = comment.strip_prefix(LOCK_PREFIX) {
+                lock = Some(Lock(value.to_owned()));
+            } else if let Some(value) = comment.strip_prefix(TAGS_PREFIX) {
+                tags.extend(value.split_whitespace().map(ToOwned::to_owned));
+            }
+        }
+        let (file, line_number) = match self
+            .reference
+            .as_deref()
+            .and_then(|reference| reference.rsplit_once(':'))
+        {
+            Some((file, line_number)) => (
+                file.to_owned(),
+                line_number.parse().with_context(|| {
+                    format!("Invalid line number \"{line_number}\" in PO entry \"{id}\"")
+                })?,
+            ),
+            None => (self.reference.unwrap_or_default(), 0),
+        };
+        let lock = lock.unwrap_or_else(|| Lock::compute_from(&source));
+        let text = match self.str {
+            Some(text) if text.is_empty() => source,
+            Some(text) if self.fuzzy => format!("{UPDATE_PREFIX}{text}"),
+            Some(text) => text,
+            None => source,
+        };
+        let translator_comment =
+            (!self.translator_comments.is_empty()).then(|| self.translator_comments.join("\n"));
+        Ok(StringsFileRecord {
+            language,
+            id: LineId(id),
+            text,
+            file,
+            node,
+            line_number,
+            lock,
+            comment: join_comment(translator_comment.as_deref(), tags),
+        })
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '\\' => escaped.push_str(r"\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str(r"\n"),
+            '\r' => escaped.push_str(r"\r"),
+            '\t' => escaped.push_str(r"\t"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Parses a quoted PO string, e.g. `"Hello \"World\""`.
+fn unescape(quoted: &str) -> Result<String> {
+    let Some(text) = quoted
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+    else {
+        bail!("expected a quoted string")
+    };
+    let mut unescaped = String::with_capacity(text.len());
+    let mut characters = text.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            unescaped.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some(escaped @ ('\\' | '"')) => unescaped.push(escaped),
+            Some(other) => bail!("unknown escape sequence \\{other}"),
+            None => bail!("unterminated escape sequence"),
+        }
+    }
+    Ok(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, text: &str, base_text: &str, comment: &str) -> StringsFileRecord {
+        StringsFileRecord {
+            language: Language::new("de-CH"),
+            id: LineId(id.to_owned()),
+            text: text.to_owned(),
+            file: "hello.yarn".to_owned(),
+            node: "Start".to_owned(),
+            line_number: 3,
+            lock: Lock::compute_from(base_text),
+            comment: comment.to_owned(),
+        }
+    }
+
+    #[test]
+    fn round_trips_records() {
+        let records = vec![
+            record(
+                "line:1",
+                "Sie sagte \"Hallo\"\\n",
+                "She said \"Hello\"\\n",
+                "Be polite, Line metadata: greeting lastline",
+            ),
+            record("line:2", "Untranslated", "Untranslated", ""),
+            record("line:3", &format!("{UPDATE_PREFIX}Tschüss"), "Goodbye", ""),
+        ];
+
+        let po = write_po(
+            &records.iter().collect::<Vec<_>>(),
+            &Language::new("en-US"),
+            &HashMap::new(),
+        )
+        .unwrap();
+
+        assert!(po.contains("\"Language: de-CH\\n\""));
+        assert!(po.contains("#: hello.yarn:3\n#, fuzzy\nmsgctxt \"line:3\""));
+        assert!(po.contains("msgctxt \"line:2\"\nmsgid \"Untranslated\"\nmsgstr \"\""));
+        assert_eq!(records, read_po(&po).unwrap());
+    }
+
+    #[test]
+    fn round_trips_empty_strings_file() {
+        let po = write_po(&[], &Language::new("en-US"), &HashMap::new()).unwrap();
+        assert!(read_po(&po).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reads_po_written_by_other_tools() {
+        let po = r#"
+msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+"Language: fr\n"
+
+# Translator's note
+#: intro.yarn:12
+#, c-format, fuzzy
+msgctxt "line:a"
+msgid ""
+"Hello "
+"world"
+msgstr "Bonjour "
+"le monde"
+
+#~ msgctxt "line:old"
+#~ msgid "Old"
+#~ msgstr "Vieux"
+"#;
+
+        let records = read_po(po).unwrap();
+
+        assert_eq!(1, records.len());
+        let record = &records[0];
+        assert_eq!(Language::new("fr"), record.language);
+        assert_eq!(format!("{UPDATE_PREFIX}Bonjour le monde"), record.text);
+        assert_eq!("intro.yarn", record.file);
+        assert_eq!(12, record.line_number);
+        assert_eq!(Lock::compute_from("Hello world"), record.lock);
+        assert_eq!("Translator's note", record.comment);
+    }
+}
@@ -0,0 +1,564 @@
+use crate::localization::strings_file::asset::{Lock, StringsFileRecord, UPDATE_PREFIX};
+use crate::localization::StringsFileLoaderSettings;
+use crate::prelude::*;
+use crate::project::CompilationSystemSet;
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::Path;
+use yarnspinner::runtime::count_words;
+
+pub(crate) fn translation_status_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        update_translation_status
+            .after(CompilationSystemSet)
+            .in_set(YarnSpinnerSystemSet)
+            .run_if(resource_exists::<YarnProject>.and_then(has_localizations)),
+    );
+}
+
+/// How far the translations of a [`YarnProject`] are, per language and per node. Lines count as
+/// - translated if the strings file contains a translation made for the current base language text,
+/// - needing an update if the translation was made for an older version of the base language text, which is marked with `(NEEDS UPDATE)` in strings files,
+/// - missing if the strings file does not contain the line or only a copy of the base language text.
+///
+/// Word counts are taken from the base language text with [`count_words`], the same way as for the script statistics of the compiler.
+///
+/// Available through [`YarnProject::translation_status`] once the strings files of all translations are loaded.
+/// Outside of a running game, e.g. on CI, use [`TranslationStatusReport::from_files`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TranslationStatusReport {
+    /// The language of the Yarn files.
+    pub base_language: Language,
+    /// The status of every translation, in the order of [`Localizations::translations`].
+    pub languages: Vec<LanguageTranslationStatus>,
+}
+
+/// The translation status of a single language in a [`TranslationStatusReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageTranslationStatus {
+    /// The language of the translation.
+    pub language: Language,
+    /// The counts summed over all nodes.
+    pub counts: TranslationCounts,
+    /// The status of every node, sorted by file and node name.
+    pub nodes: Vec<NodeTranslationStatus>,
+}
+
+/// The translation status of a single node in a [`LanguageTranslationStatus`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeTranslationStatus {
+    /// The name of the node.
+    pub node: String,
+    /// The name of the Yarn file containing the node.
+    pub file: String,
+    /// The counts of the node's lines.
+    pub counts: TranslationCounts,
+    /// The lines whose translation is missing, sorted by line number.
+    pub missing: Vec<UntranslatedLine>,
+    /// The lines whose translation needs to be updated, sorted by line number.
+    pub needs_update: Vec<UntranslatedLine>,
+}
+
+/// The number of lines and words in each translation state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct TranslationCounts {
+    /// Lines that are translated.
+    pub translated: LineCount,
+    /// Lines whose translation was made for an older version of the base language text.
+    pub needs_update: LineCount,
+    /// Lines that are not translated at all.
+    pub missing: LineCount,
+}
+
+/// A number of lines and the number of words they contain in the base language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct LineCount {
+    /// The number of lines.
+    pub lines: usize,
+    /// The number of words in the base language text of the lines.
+    pub words: usize,
+}
+
+/// A line that is missing or needs an update in a [`NodeTranslationStatus`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct UntranslatedLine {
+    /// The ID of the line.
+    pub line_id: LineId,
+    /// The 1-indexed line number in the Yarn file.
+    pub line_number: usize,
+    /// The base language text of the line.
+    pub text: String,
+    /// The number of words in [`UntranslatedLine::text`].
+    pub words: usize,
+}
+
+impl TranslationStatusReport {
+    /// Creates the report for the [`Localizations`] of a project whose lines are in `string_table`, usually [`Compilation::string_table`](crate::Compilation::string_table).
+    /// The strings files are read from the paths in [`Localization::strings_file`], relative to `asset_root`, which is usually the `assets` folder.
+    /// Translations without a strings file count as completely missing.
+    pub fn from_files(
+        localizations: &Localizations,
+        string_table: &HashMap<LineId, StringInfo>,
+        asset_root: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let strings_files = localizations
+            .translations
+            .iter()
+            .map(|localization| {
+                let path = asset_root.as_ref().join(&localization.strings_file);
+                let strings_file = path
+                    .is_file()
+                    .then(|| StringsFile::read_file(&path))
+                    .transpose()?;
+                Ok((localization.language.clone(), strings_file))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(
+            localizations.base_localization.language.clone(),
+            string_table,
+            strings_files
+                .iter()
+                .map(|(language, strings_file)| (language.clone(), strings_file.as_ref())),
+        ))
+    }
+
+    pub(crate) fn new<'a>(
+        base_language: Language,
+        string_table: &HashMap<LineId, StringInfo>,
+        translations: impl IntoIterator<Item = (Language, Option<&'a StringsFile>)>,
+    ) -> Self {
+        let languages = translations
+            .into_iter()
+            .map(|(language, strings_file)| {
+                LanguageTranslationStatus::new(language, string_table, strings_file)
+            })
+            .collect();
+        Self {
+            base_language,
+            languages,
+        }
+    }
+
+    /// Returns the status of the given language, if it is a translation.
+    pub fn language(&self, language: &Language) -> Option<&LanguageTranslationStatus> {
+        self.languages
+            .iter()
+            .find(|status| &status.language == language)
+    }
+
+    /// Serializes the report as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Renders the report as Markdown, with a summary table followed by a section per language that lists the missing lines and the lines needing an update.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        // Writing to a `String` cannot fail
+        let _ = self.write_markdown(&mut markdown);
+        markdown
+    }
+
+    fn write_markdown(&self, markdown: &mut String) -> std::fmt::Result {
+        writeln!(markdown, "# Translation status")?;
+        writeln!(markdown)?;
+        writeln!(
+            markdown,
+            "Base language: {}",
+            escape_markdown(&self.base_language.to_string())
+        )?;
+        writeln!(markdown)?;
+        writeln!(
+            markdown,
+            "| Language | Progress | Translated | Needs update | Missing |"
+        )?;
+        writeln!(markdown, "| --- | ---: | ---: | ---: | ---: |")?;
+        for status in &self.languages {
+            writeln!(
+                markdown,
+                "| {} | {} |",
+                escape_markdown(&status.language.to_string()),
+                status.counts.to_markdown_cells()
+            )?;
+        }
+        for status in &self.languages {
+            writeln!(markdown)?;
+            writeln!(
+                markdown,
+                "## {}",
+                escape_markdown(&status.language.to_string())
+            )?;
+            writeln!(markdown)?;
+            writeln!(
+                markdown,
+                "| Node | File | Progress | Translated | Needs update | Missing |"
+            )?;
+            writeln!(markdown, "| --- | --- | ---: | ---: | ---: | ---: |")?;
+            for node in &status.nodes {
+                writeln!(
+                    markdown,
+                    "| {} | {} | {} |",
+                    escape_markdown(&node.node),
+                    escape_markdown(&node.file),
+                    node.counts.to_markdown_cells()
+                )?;
+            }
+            write_markdown_line_list(markdown, "Needs update", status.needs_update())?;
+            write_markdown_line_list(markdown, "Missing", status.missing())?;
+        }
+        Ok(())
+    }
+}
+
+impl LanguageTranslationStatus {
+    fn new(
+        language: Language,
+        string_table: &HashMap<LineId, StringInfo>,
+        strings_file: Option<&StringsFile>,
+    ) -> Self {
+        let mut nodes = BTreeMap::<(&str, &str), NodeTranslationStatus>::new();
+        for (line_id, string_info) in string_table {
+            let node = nodes
+                .entry((&string_info.file_name, &string_info.node_name))
+                .or_insert_with(|| NodeTranslationStatus {
+                    node: string_info.node_name.clone(),
+                    file: string_info.file_name.clone(),
+                    counts: default(),
+                    missing: Vec::new(),
+                    needs_update: Vec::new(),
+                });
+            let words = count_words(&string_info.text);
+            let untranslated_line = || UntranslatedLine {
+                line_id: line_id.clone(),
+                line_number: string_info.line_number,
+                text: string_info.text.clone(),
+                words,
+            };
+            let record = strings_file.and_then(|strings_file| strings_file.get(line_id));
+            match LineState::of(record, &string_info.text) {
+                LineState::Translated => node.counts.translated.add(words),
+                LineState::NeedsUpdate => {
+                    node.counts.needs_update.add(words);
+                    node.needs_update.push(untranslated_line());
+                }
+                LineState::Missing => {
+                    node.counts.missing.add(words);
+                    node.missing.push(untranslated_line());
+                }
+            }
+        }
+        let mut nodes: Vec<_> = nodes.into_values().collect();
+        for node in &mut nodes {
+            node.missing.sort_by_key(|line| line.line_number);
+            node.needs_update.sort_by_key(|line| line.line_number);
+        }
+        let counts = nodes
+            .iter()
+            .fold(TranslationCounts::default(), |sum, node| sum + node.counts);
+        Self {
+            language,
+            counts,
+            nodes,
+        }
+    }
+
+    /// Iterates over the lines whose translation is missing, together with their node.
+    pub fn missing(&self) -> impl Iterator<Item = (&NodeTranslationStatus, &UntranslatedLine)> {
+        self.nodes
+            .iter()
+            .flat_map(|node| node.missing.iter().map(move |line| (node, line)))
+    }
+
+    /// Iterates over the lines whose translation needs to be updated, together with their node.
+    pub fn needs_update(
+        &self,
+    ) -> impl Iterator<Item = (&NodeTranslationStatus, &UntranslatedLine)> {
+        self.nodes
+            .iter()
+            .flat_map(|node| node.needs_update.iter().map(move |line| (node, line)))
+    }
+}
+
+impl TranslationCounts {
+    /// The total number of lines and words.
+    pub fn total(&self) -> LineCount {
+        self.translated + self.needs_update + self.missing
+    }
+
+    /// The share of translated lines, from `0.0` to `1.0`. Is `1.0` if there are no lines.
+    pub fn progress(&self) -> f32 {
+        let total = self.total().lines;
+        if total == 0 {
+            1.0
+        } else {
+            self.translated.lines as f32 / total as f32
+        }
+    }
+
+    fn to_markdown_cells(self) -> String {
+        format!(
+            "{:.1}% | {} | {} | {}",
+            self.progress() * 100.0,
+            self.translated,
+            self.needs_update,
+            self.missing
+        )
+    }
+}
+
+impl std::ops::Add for TranslationCounts {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            translated: self.translated + rhs.translated,
+            needs_update: self.needs_update + rhs.needs_update,
+            missing: self.missing + rhs.missing,
+        }
+    }
+}
+
+impl LineCount {
+    fn add(&mut self, words: usize) {
+        self.lines += 1;
+        self.words += words;
+    }
+}
+
+impl std::ops::Add for LineCount {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            lines: self.lines + rhs.lines,
+            words: self.words + rhs.words,
+        }
+    }
+}
+
+impl std::fmt::Display for LineCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let line_plural = if self.lines == 1 { "line" } else { "lines" };
+        let word_plural = if self.words == 1 { "word" } else { "words" };
+        write!(
+            f,
+            "{} {line_plural} ({} {word_plural})",
+            self.lines, self.words
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineState {
+    Translated,
+    NeedsUpdate,
+    Missing,
+}
+
+impl LineState {
+    fn of(record: Option<&StringsFileRecord>, base_text: &str) -> Self {
+        match record {
+            None => Self::Missing,
+            Some(record) if record.is_untranslated() => Self::Missing,
+            // The lock is checked as well because strings files are only marked during development
+            Some(record)
+                if record.text.starts_with(UPDATE_PREFIX)
+                    || record.lock != Lock::compute_from(base_text) =>
+            {
+                Self::NeedsUpdate
+            }
+            Some(_) => Self::Translated,
+        }
+    }
+}
+
+fn write_markdown_line_list<'a>(
+    markdown: &mut String,
+    title: &str,
+    lines: impl Iterator<Item = (&'a NodeTranslationStatus, &'a UntranslatedLine)>,
+) -> std::fmt::Result {
+    let mut lines = lines.peekable();
+    if lines.peek().is_none() {
+        return Ok(());
+    }
+    writeln!(markdown)?;
+    writeln!(markdown, "### {title}")?;
+    writeln!(markdown)?;
+    for (node, line) in lines {
+        writeln!(
+            markdown,
+            "- `{}` ({}, {}:{}): {}",
+            line.line_id,
+            escape_markdown(&node.node),
+            escape_markdown(&node.file),
+            line.line_number,
+            escape_markdown(&line.text)
+        )?;
+    }
+    Ok(())
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        if matches!(
+            character,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
+
+fn update_translation_status(
+    mut project: ResMut<YarnProject>,
+    asset_server: Res<AssetServer>,
+    strings_files: Res<Assets<StringsFile>>,
+    mut asset_events: EventReader<AssetEvent<StringsFile>>,
+    mut handles: Local<Vec<(Language, Handle<StringsFile>)>>,
+    mut is_outdated: Local<bool>,
+) {
+    let localizations = project.localizations.clone().unwrap();
+    if handles.is_empty() {
+        for localization in &localizations.translations {
+            let asset_path = localization
+                .strings_file
+                .to_string_lossy()
+                .replace('\\', "/");
+            let known_markup_markers = project.known_markup_markers.clone();
+            let handle = asset_server.load_with_settings(
+                asset_path,
+                move |settings: &mut StringsFileLoaderSettings| {
+                    settings.known_markup_markers = known_markup_markers.clone();
+                },
+            );
+            handles.push((localization.language.clone(), handle));
+        }
+    }
+    // The report itself is stored without triggering change detection, so a change means the project was recompiled
+    *is_outdated |= project.is_changed();
+    for event in asset_events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event {
+            *is_outdated |= handles.iter().any(|(_, handle)| handle.id() == *id);
+        }
+    }
+    // Strings files that failed to load count as completely missing
+    let all_loaded = handles.iter().all(|(_, handle)| {
+        matches!(
+            asset_server.get_load_state(handle),
+            Some(LoadState::Loaded | LoadState::Failed(_))
+        )
+    });
+    if !*is_outdated || !all_loaded {
+        return;
+    }
+    let report = TranslationStatusReport::new(
+        localizations.base_localization.language.clone(),
+        &project.compilation.string_table,
+        handles
+            .iter()
+            .map(|(language, handle)| (language.clone(), strings_files.get(handle))),
+    );
+    project.bypass_change_detection().translation_status = Some(report);
+    *is_outdated = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string_info(text: &str, node: &str, line_number: usize) -> StringInfo {
+        StringInfo {
+            text: text.to_owned(),
+            node_name: node.to_owned(),
+            file_name: "hello.yarn".to_owned(),
+            line_number,
+            ..Default::default()
+        }
+    }
+
+    fn record(id: &str, text: &str, base_text: &str) -> StringsFileRecord {
+        StringsFileRecord {
+            language: Language::new("de-CH"),
+            id: LineId(id.to_owned()),
+            text: text.to_owned(),
+            file: "hello.yarn".to_owned(),
+            node: String::new(),
+            line_number: 0,
+            lock: Lock::compute_from(base_text),
+            comment: String::new(),
+        }
+    }
+
+    #[test]
+    fn counts_lines_and_words_per_node() {
+        let string_table = HashMap::from([
+            (
+                LineId("line:1".to_owned()),
+                string_info("Mae: Hello [b]there[/b], {0}!", "Start", 1),
+            ),
+            (
+                LineId("line:2".to_owned()),
+                string_info("Goodbye now", "Start", 2),
+            ),
+            (
+                LineId("line:3".to_owned()),
+                string_info("I changed", "Start", 3),
+            ),
+            (
+                LineId("line:4".to_owned()),
+                string_info("Not in the strings file", "End", 1),
+            ),
+            (LineId("line:5".to_owned()), string_info("Copied", "End", 2)),
+        ]);
+        let strings_file = StringsFile::new_with_single_language(vec![
+            record(
+                "line:1",
+                "Mae: Hallo [b]du[/b], {0}!",
+                "Mae: Hello [b]there[/b], {0}!",
+            ),
+            record("line:2", "(NEEDS UPDATE) Tschüss", "Goodbye now"),
+            record("line:3", "Ich", "I did not change"),
+            record("line:5", "Copied", "Copied"),
+        ])
+        .unwrap();
+
+        let report = TranslationStatusReport::new(
+            Language::new("en"),
+            &string_table,
+            [(Language::new("de-CH"), Some(&strings_file))],
+        );
+
+        let status = report.language(&Language::new("de-CH")).unwrap();
+        let start = &status.nodes[1];
+        assert_eq!("Start", start.node);
+        assert_eq!(LineCount { lines: 1, words: 2 }, start.counts.translated);
+        assert_eq!(LineCount { lines: 2, words: 4 }, start.counts.needs_update);
+        let end = &status.nodes[0];
+        assert_eq!(LineCount { lines: 2, words: 6 }, end.counts.missing);
+        assert_eq!(
+            vec!["line:4", "line:5"],
+            status
+                .missing()
+                .map(|(_, line)| line.line_id.0.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0.2, status.counts.progress());
+        let markdown = report.to_markdown();
+        assert!(
+            markdown.contains(
+                "| de-CH | 20.0% | 1 line (2 words) | 2 lines (4 words) | 2 lines (6 words) |"
+            ),
+            "{markdown}"
+        );
+        assert!(markdown.contains("- `line:5` (End, hello.yarn:2): Copied"));
+        let json: TranslationStatusReport =
+            serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(report, json);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::localization::StringsFileLoaderSettings;
 use crate::plugin::AssetRoot;
 use crate::{localization::line_id_generation::LineIdUpdateSystemSet, prelude::*};
 use bevy::prelude::*;
@@ -46,7 +47,13 @@ fn update_all_strings_files_for_string_table(
         let language = &localization.language;
         let path = localization.strings_file.as_path();
         let asset_path = path.to_string_lossy().replace('\\', "/");
-        let handle = asset_server.load(asset_path);
+        let known_markup_markers = project.known_markup_markers.clone();
+        let handle = asset_server.load_with_settings(
+            asset_path,
+            move |settings: &mut StringsFileLoaderSettings| {
+                settings.known_markup_markers = known_markup_markers.clone();
+            },
+        );
         languages_to_handles.insert(language.clone(), handle);
     }
     if languages_to_handles.is_empty() {
@@ -119,7 +126,11 @@ fn update_all_strings_files_for_string_table(
     for (handle, path) in &dirty_paths {
         let strings_file = strings_files.get(handle).unwrap();
         let path = asset_root.0.join(path);
-        strings_file.write_asset(&path)?;
+        strings_file.write_asset(
+            &path,
+            &localizations.base_localization.language,
+            &project.compilation.string_table,
+        )?;
     }
     Ok(())
 }
@@ -0,0 +1,402 @@
+//! Reading and writing strings files as [XLIFF 2.0](https://docs.oasis-open.org/xliff/xliff-core/v2.0/xliff-core-v2.0.html).
+//!
+//! Every Yarn file becomes a `<file>` and every line a `<unit>` with the line ID as its `id`.
+//! The node, line number and [`Lock`] are stored with the [metadata module](https://docs.oasis-open.org/xliff/xliff-core/v2.0/os/xliff-core-v2.0-os.html#metadata_module),
+//! the translator comment and the line metadata tags as `<note>`s.
+//! Untranslated lines have no `<target>`, and translations that need to be updated because the base language text changed
+//! are marked with the segment state `initial` instead of a `(NEEDS UPDATE)` prefix.
+
+use crate::localization::strings_file::asset::{
+    join_comment, split_comment, Lock, StringsFileRecord, UPDATE_PREFIX,
+};
+use crate::prelude::*;
+use anyhow::bail;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+const NAMESPACE: &str = "urn:oasis:names:tc:xliff:document:2.0";
+const METADATA_NAMESPACE: &str = "urn:oasis:names:tc:xliff:metadata:2.0";
+const META_GROUP_CATEGORY: &str = "yarnspinner";
+const COMMENT_NOTE_CATEGORY: &str = "comment";
+const TAG_NOTE_CATEGORY: &str = "tag";
+
+pub(crate) fn write_xliff(
+    records: &[&StringsFileRecord],
+    base_language: &Language,
+    base_string_table: &HashMap<LineId, StringInfo>,
+) -> Result<String> {
+    // A strings file without lines, e.g. one generated before the Yarn files were tagged, does not know its language
+    let target_language = records
+        .first()
+        .map(|record| format!(r#" trgLang="{}""#, escape(record.language.to_string())))
+        .unwrap_or_default();
+    let mut xliff = String::new();
+    writeln!(xliff, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        xliff,
+        r#"<xliff xmlns="{NAMESPACE}" xmlns:mda="{METADATA_NAMESPACE}" version="2.0" srcLang="{}"{target_language}>"#,
+        escape(base_language.to_string()),
+    )?;
+    let mut current_file = None;
+    let mut file_count = 0;
+    for record in records {
+        if current_file != Some(&record.file) {
+            if current_file.is_some() {
+                writeln!(xliff, "  </file>")?;
+            }
+            file_count += 1;
+            writeln!(
+                xliff,
+                r#"  <file id="f{file_count}" original="{}">"#,
+                escape(&record.file)
+            )?;
+            current_file = Some(&record.file);
+        }
+        write_unit(&mut xliff, record, base_string_table)?;
+    }
+    if current_file.is_some() {
+        writeln!(xliff, "  </file>")?;
+    }
+    writeln!(xliff, "</xliff>")?;
+    Ok(xliff)
+}
+
+fn write_unit(
+    xliff: &mut String,
+    record: &StringsFileRecord,
+    base_string_table: &HashMap<LineId, StringInfo>,
+) -> Result<()> {
+    writeln!(
+        xliff,
+        r#"    <unit id="{}" xml:space="preserve">"#,
+        escape(&record.id.0)
+    )?;
+    writeln!(xliff, "      <mda:metadata>")?;
+    writeln!(
+        xliff,
+        r#"        <mda:metaGroup category="{META_GROUP_CATEGORY}">"#
+    )?;
+    for (meta_type, value) in [
+        ("node", record.node.clone()),
+        ("line_number", record.line_number.to_string()),
+        ("lock", record.lock.0.clone()),
+    ] {
+        writeln!(
+            xliff,
+            r#"          <mda:meta type="{meta_type}">{}</mda:meta>"#,
+            escape(&value)
+        )?;
+    }
+    writeln!(xliff, "        </mda:metaGroup>")?;
+    writeln!(xliff, "      </mda:metadata>")?;
+
+    let (translator_comment, tags) = split_comment(&record.comment);
+    let notes: Vec<_> = translator_comment
+        .map(|comment| (COMMENT_NOTE_CATEGORY, comment))
+        .into_iter()
+        .chain(tags.into_iter().map(|tag| (TAG_NOTE_CATEGORY, tag)))
+        .collect();
+    if !notes.is_empty() {
+        writeln!(xliff, "      <notes>")?;
+        for (category, note) in notes {
+            writeln!(
+                xliff,
+                r#"        <note category="{category}">{}</note>"#,
+                escape(note)
+            )?;
+        }
+        writeln!(xliff, "      </notes>")?;
+    }
+
+    let untranslated = record.is_untranslated();
+    let outdated_text = record.text.strip_prefix(UPDATE_PREFIX);
+    let source = match base_string_table.get(&record.id) {
+        Some(string_info) => string_info.text.as_str(),
+        None if untranslated => record.text.as_str(),
+        // The line was removed from the Yarn files, but the translation is kept to not lose any work
+        None => "",
+    };
+    let state = if untranslated || outdated_text.is_some() {
+        "initial"
+    } else {
+        "translated"
+    };
+    writeln!(xliff, r#"      <segment state="{state}">"#)?;
+    writeln!(xliff, "        <source>{}</source>", escape(source))?;
+    if !untranslated {
+        let target = outdated_text.unwrap_or(&record.text);
+        writeln!(xliff, "        <target>{}</target>", escape(target))?;
+    }
+    writeln!(xliff, "      </segment>")?;
+    writeln!(xliff, "    </unit>")?;
+    Ok(())
+}
+
+pub(crate) fn read_xliff(xliff: &str) -> Result<Vec<StringsFileRecord>> {
+    let mut reader = Reader::from_str(xliff);
+    let mut language = None;
+    let mut file = None;
+    let mut unit: Option<Unit> = None;
+    let mut current_text: Option<CurrentText> = None;
+    let mut records = Vec::new();
+    loop {
+        let event = reader.read_event()?;
+        match &event {
+            Event::Start(element) | Event::Empty(element) => {
+                let name = element.local_name();
+                match name.as_ref() {
+                    b"xliff" => language = attribute(element, "trgLang")?.map(Language::new),
+                    b"file" => file = attribute(element, "original")?,
+                    b"unit" => {
+                        let Some(id) = attribute(element, "id")? else {
+                            bail!("Found an XLIFF <unit> without an id")
+                        };
+                        unit = Some(Unit::new(id));
+                    }
+                    b"segment" => {
+                        if let Some(unit) = unit.as_mut() {
+                            unit.state = attribute(element, "state")?;
+                        }
+                    }
+                    b"meta" => {
+                        current_text =
+                            attribute(element, "type")?.map(|meta_type| match meta_type.as_str() {
+                                "node" => CurrentText::Node,
+                                "line_number" => CurrentText::LineNumber,
+                                "lock" => CurrentText::Lock,
+                                _ => CurrentText::Ignored,
+                            })
+                    }
+                    b"note" => {
+                        current_text = Some(match attribute(element, "category")?.as_deref() {
+                            Some(TAG_NOTE_CATEGORY) => CurrentText::Tag,
+                            _ => CurrentText::Comment,
+                        })
+                    }
+                    b"source" => current_text = Some(CurrentText::Source),
+                    b"target" => current_text = Some(CurrentText::Target),
+                    // Inline elements like <ph/> are not generated by Yarn Spinner, so only their text is kept
+                    _ => continue,
+                }
+                if let (Some(unit), Some(current)) = (unit.as_mut(), current_text) {
+                    unit.start_text(current);
+                }
+                if matches!(event, Event::Empty(_)) {
+                    current_text = None;
+                }
+            }
+            Event::Text(text) => {
+                if let (Some(unit), Some(current)) = (unit.as_mut(), current_text) {
+                    unit.push_text(current, &text.unescape()?);
+                }
+            }
+            Event::CData(text) => {
+                if let (Some(unit), Some(current)) = (unit.as_mut(), current_text) {
+                    unit.push_text(current, &String::from_utf8_lossy(text));
+                }
+            }
+            Event::End(element) => match element.local_name().as_ref() {
+                b"meta" | b"note" | b"source" | b"target" => current_text = None,
+                b"unit" => {
+                    let Some(language) = language.clone() else {
+                        bail!("XLIFF file has no target language. Please set the trgLang attribute of the <xliff> element.")
+                    };
+                    let unit = unit.take().unwrap();
+                    records.push(unit.into_record(language, file.clone().unwrap_or_default())?);
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(records)
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>> {
+    let Some(attribute) = element.try_get_attribute(name)? else {
+        return Ok(None);
+    };
+    Ok(Some(attribute.unescape_value()?.into_owned()))
+}
+
+/// The element whose text is currently being read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CurrentText {
+    Node,
+    LineNumber,
+    Lock,
+    Comment,
+    Tag,
+    Source,
+    Target,
+    Ignored,
+}
+
+#[derive(Debug, Default)]
+struct Unit {
+    id: String,
+    state: Option<String>,
+    node: String,
+    line_number: String,
+    lock: String,
+    comments: Vec<String>,
+    tags: Vec<String>,
+    source: String,
+    target: Option<String>,
+}
+
+impl Unit {
+    fn new(id: String) -> Self {
+        Self {
+            id,
+            ..Default::default()
+        }
+    }
+
+    fn start_text(&mut self, current: CurrentText) {
+        match current {
+            CurrentText::Comment => self.comments.push(String::new()),
+            CurrentText::Tag => self.tags.push(String::new()),
+            CurrentText::Target => self.target = Some(String::new()),
+            _ => {}
+        }
+    }
+
+    fn push_text(&mut self, current: CurrentText, text: &str) {
+        let buffer = match current {
+            CurrentText::Node => &mut self.node,
+            CurrentText::LineNumber => &mut self.line_number,
+            CurrentText::Lock => &mut self.lock,
+            CurrentText::Comment => self.comments.last_mut().unwrap(),
+            CurrentText::Tag => self.tags.last_mut().unwrap(),
+            CurrentText::Source => &mut self.source,
+            CurrentText::Target => self.target.get_or_insert_with(String::new),
+            CurrentText::Ignored => return,
+        };
+        buffer.push_str(text);
+    }
+
+    fn into_record(self, language: Language, file: String) -> Result<StringsFileRecord> {
+        let line_number = if self.line_number.is_empty() {
+            0
+        } else {
+            self.line_number.trim().parse().with_context(|| {
+                format!(
+                    "Invalid line number \"{}\" in XLIFF unit \"{}\"",
+                    self.line_number, self.id
+                )
+            })?
+        };
+        let lock = if self.lock.is_empty() {
+            Lock::compute_from(&self.source)
+        } else {
+            Lock(self.lock.trim().to_owned())
+        };
+        let text = match self.target {
+            Some(target) if self.state.as_deref() == Some("initial") => {
+                format!("{UPDATE_PREFIX}{target}")
+            }
+            Some(target) => target,
+            None => self.source,
+        };
+        let comment = (!self.comments.is_empty()).then(|| self.comments.join(" "));
+        Ok(StringsFileRecord {
+            language,
+            id: LineId(self.id),
+            text,
+            file,
+            node: self.node,
+            line_number,
+            lock,
+            comment: join_comment(comment.as_deref(), self.tags),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, text: &str, base_text: &str, comment: &str) -> StringsFileRecord {
+        StringsFileRecord {
+            language: Language::new("de-CH"),
+            id: LineId(id.to_owned()),
+            text: text.to_owned(),
+            file: "hello.yarn".to_owned(),
+            node: "Start".to_owned(),
+            line_number: 3,
+            lock: Lock::compute_from(base_text),
+            comment: comment.to_owned(),
+        }
+    }
+
+    #[test]
+    fn round_trips_records() {
+        let records = vec![
+            record(
+                "line:1",
+                "Hallo [b]Welt[/b] & <Mond>",
+                "Hello [b]world[/b] & <moon>",
+                "Be polite, Line metadata: greeting lastline",
+            ),
+            record("line:2", "Untranslated", "Untranslated", ""),
+            record("line:3", &format!("{UPDATE_PREFIX}Tschüss"), "Goodbye", ""),
+        ];
+        let base_string_table = HashMap::from([(
+            LineId("line:1".to_owned()),
+            StringInfo {
+                text: "Hello [b]world[/b] & <moon>".to_owned(),
+                ..Default::default()
+            },
+        )]);
+
+        let xliff = write_xliff(
+            &records.iter().collect::<Vec<_>>(),
+            &Language::new("en-US"),
+            &base_string_table,
+        )
+        .unwrap();
+
+        assert!(xliff.contains(r#"srcLang="en-US" trgLang="de-CH""#));
+        assert!(xliff.contains("<source>Hello [b]world[/b] &amp; &lt;moon&gt;</source>"));
+        assert!(xliff.contains(r#"<note category="tag">lastline</note>"#));
+        assert!(xliff.contains(r#"<segment state="initial">"#));
+        assert_eq!(records, read_xliff(&xliff).unwrap());
+    }
+
+    #[test]
+    fn round_trips_empty_strings_file() {
+        let xliff = write_xliff(&[], &Language::new("en-US"), &HashMap::new()).unwrap();
+        assert!(read_xliff(&xliff).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reads_xliff_written_by_other_tools() {
+        let xliff = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xliff xmlns="urn:oasis:names:tc:xliff:document:2.0" version="2.0" srcLang="en" trgLang="fr">
+  <file id="f1" original="intro.yarn">
+    <unit id="line:a">
+      <notes><note>First</note><note>Second</note></notes>
+      <segment>
+        <source>Hi <ph id="1"/>!</source>
+        <target>Salut <ph id="1"/>!</target>
+      </segment>
+    </unit>
+  </file>
+</xliff>"#;
+
+        let records = read_xliff(xliff).unwrap();
+
+        assert_eq!(1, records.len());
+        let record = &records[0];
+        assert_eq!(Language::new("fr"), record.language);
+        assert_eq!("Salut !", record.text);
+        assert_eq!("intro.yarn", record.file);
+        assert_eq!(Lock::compute_from("Hi !"), record.lock);
+        assert_eq!("First Second", record.comment);
+    }
+}
@@ -0,0 +1,441 @@
+use crate::prelude::*;
+use crate::UnderlyingYarnLine;
+use anyhow::anyhow;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+use yarnspinner::runtime::ParsedMarkup;
+
+/// A voice-over recording script, listing every line of a Yarn project grouped by the character speaking it, so that recording sessions can be booked per actor.
+///
+/// The character of a line is determined the same way as [`LocalizedLine::character_name`] does it: by the `Name:` prefix of the line.
+/// Lines without such a prefix are grouped under a character without name, which is listed last.
+///
+/// The audio file names follow the conventions of [`AudioAssetProvider`](crate::prelude::AudioAssetProvider):
+/// the file for the line `line:abc123` is expected at `<assets_sub_folder>/abc123.<extension>` inside the `assets` folder,
+/// where `<assets_sub_folder>` is [`Localization::assets_sub_folder`].
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use bevy_yarnspinner::prelude::*;
+/// # use bevy_yarnspinner::voice_over::*;
+/// # fn example(yarn_project: &YarnProject) -> bevy_yarnspinner::Result<()> {
+/// let script = VoiceOverScript::from_yarn_project(yarn_project)
+///     .expect("Voice-over requires localizations")
+///     .with_audio_file_extension("ogg");
+/// std::fs::write("voice_over.csv", script.to_csv()?)?;
+/// std::fs::write("voice_over.html", script.to_html())?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoiceOverScript {
+    /// The language the lines are recorded in.
+    pub language: Language,
+    /// The characters, sorted by name, with the lines without a character last.
+    pub characters: Vec<VoiceOverCharacter>,
+}
+
+/// All lines spoken by a single character in a [`VoiceOverScript`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoiceOverCharacter {
+    /// The name of the character, or `None` for lines without a `Name:` prefix.
+    pub name: Option<String>,
+    /// The lines of the character, sorted by file and line number.
+    pub lines: Vec<VoiceOverLine>,
+}
+
+/// A single line to record in a [`VoiceOverScript`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoiceOverLine {
+    /// The ID of the line.
+    pub line_id: LineId,
+    /// The text to speak, without the character name and markup.
+    pub text: String,
+    /// The text of the line preceding this one in the same node, including its character name, to give the actor context.
+    pub preceding_line: Option<String>,
+    /// The name of the node containing the line.
+    pub node: String,
+    /// The headers of the node, except for the title.
+    pub node_headers: Vec<(String, String)>,
+    /// The name of the Yarn file containing the line.
+    pub file: String,
+    /// The 1-indexed line number in the Yarn file.
+    pub line_number: usize,
+    /// The hashtags of the line, such as `#whisper`, including the `#`.
+    pub tags: Vec<String>,
+    /// The path of the audio file inside the `assets` folder, as looked up by [`AudioAssetProvider`](crate::prelude::AudioAssetProvider).
+    pub audio_file: PathBuf,
+}
+
+impl VoiceOverScript {
+    /// The extension of the audio files if none is set with [`VoiceOverScript::with_audio_file_extension`].
+    pub const DEFAULT_AUDIO_FILE_EXTENSION: &'static str = "wav";
+
+    /// Creates the script for the lines of `compilation`, which is usually taken from [`YarnProject::compilation`].
+    /// The audio files are placed in the [`Localization::assets_sub_folder`] of `localization`, which should be the base localization
+    /// since the lines are taken from the Yarn files.
+    pub fn new(compilation: &Compilation, localization: &Localization) -> Self {
+        let mut infos: Vec<_> = compilation.string_table.iter().collect();
+        infos.sort_by(|(_, lhs), (_, rhs)| {
+            (&lhs.file_name, &lhs.node_name, lhs.line_number).cmp(&(
+                &rhs.file_name,
+                &rhs.node_name,
+                rhs.line_number,
+            ))
+        });
+
+        let mut characters = BTreeMap::<Option<String>, Vec<VoiceOverLine>>::new();
+        let mut previous: Option<&StringInfo> = None;
+        for (line_id, string_info) in infos {
+            let preceding_line = previous
+                .filter(|previous| {
+                    previous.file_name == string_info.file_name
+                        && previous.node_name == string_info.node_name
+                })
+                .map(|previous| plain_text(&previous.text));
+            previous = Some(string_info);
+
+            let line = parse_line(line_id, &string_info.text);
+            let file_name = line_id.0.trim_start_matches("line:");
+            let audio_file = localization.assets_sub_folder.join(format!(
+                "{file_name}.{}",
+                Self::DEFAULT_AUDIO_FILE_EXTENSION
+            ));
+            characters
+                .entry(line.character_name().map(ToOwned::to_owned))
+                .or_default()
+                .push(VoiceOverLine {
+                    line_id: line_id.clone(),
+                    text: line.text_without_character_name(),
+                    preceding_line,
+                    node: string_info.node_name.clone(),
+                    node_headers: node_headers(compilation, &string_info.node_name),
+                    file: string_info.file_name.clone(),
+                    line_number: string_info.line_number,
+                    tags: string_info
+                        .metadata
+                        .iter()
+                        .map(|tag| format!("#{tag}"))
+                        .collect(),
+                    audio_file,
+                });
+        }
+
+        // `None` sorts first in a `BTreeMap`, but lines without a character are the least interesting for actors
+        let mut characters: Vec<_> = characters
+            .into_iter()
+            .map(|(name, mut lines)| {
+                lines.sort_by(|lhs, rhs| {
+                    (&lhs.file, lhs.line_number).cmp(&(&rhs.file, rhs.line_number))
+                });
+                VoiceOverCharacter { name, lines }
+            })
+            .collect();
+        if characters.first().is_some_and(|c| c.name.is_none()) {
+            characters.rotate_left(1);
+        }
+        Self {
+            language: localization.language.clone(),
+            characters,
+        }
+    }
+
+    /// Creates the script for the lines of `yarn_project`, placing the audio files in the assets sub folder of its base localization.
+    /// Returns `None` if the project has no [`Localizations`], as [`AudioAssetProvider`](crate::prelude::AudioAssetProvider) needs them to find audio files.
+    pub fn from_yarn_project(yarn_project: &YarnProject) -> Option<Self> {
+        let localizations = yarn_project.localizations()?;
+        Some(Self::new(
+            yarn_project.compilation(),
+            &localizations.base_localization,
+        ))
+    }
+
+    /// Sets the extension of the audio files, e.g. `"ogg"`. Defaults to [`VoiceOverScript::DEFAULT_AUDIO_FILE_EXTENSION`].
+    /// The [`AudioAssetProvider`](crate::prelude::AudioAssetProvider) looks for the extensions "mp3", "ogg" and "wav".
+    pub fn with_audio_file_extension(mut self, extension: impl AsRef<str>) -> Self {
+        let extension = extension.as_ref().trim_start_matches('.');
+        for line in self.lines_mut() {
+            line.audio_file.set_extension(extension);
+        }
+        self
+    }
+
+    /// Iterates over all lines together with the name of their character.
+    pub fn lines(&self) -> impl Iterator<Item = (Option<&str>, &VoiceOverLine)> {
+        self.characters.iter().flat_map(|character| {
+            character
+                .lines
+                .iter()
+                .map(|line| (character.name.as_deref(), line))
+        })
+    }
+
+    fn lines_mut(&mut self) -> impl Iterator<Item = &mut VoiceOverLine> {
+        self.characters
+            .iter_mut()
+            .flat_map(|character| character.lines.iter_mut())
+    }
+
+    /// Writes the script as CSV with one row per line, grouped by character.
+    pub fn to_csv(&self) -> Result<String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record([
+            "character",
+            "id",
+            "text",
+            "preceding line",
+            "node",
+            "node headers",
+            "file",
+            "line number",
+            "tags",
+            "audio file",
+        ])?;
+        for (character, line) in self.lines() {
+            writer.write_record([
+                character.unwrap_or_default(),
+                &line.line_id.0,
+                &line.text,
+                line.preceding_line.as_deref().unwrap_or_default(),
+                &line.node,
+                &line
+                    .node_headers
+                    .iter()
+                    .map(|(key, value)| format!("{key}: {value}"))
+                    .collect::<Vec<_>>()
+                    .join("; "),
+                &line.file,
+                &line.line_number.to_string(),
+                &line.tags.join(" "),
+                &audio_file_name(line),
+            ])?;
+        }
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| anyhow!("Failed to write voice-over script: {e}"))?;
+        Ok(String::from_utf8(bytes)?)
+    }
+
+    /// Renders the script as a printable HTML screenplay, starting a new page for every character.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        // Writing to a `String` cannot fail
+        let _ = self.write_html(&mut html);
+        html
+    }
+
+    fn write_html(&self, html: &mut String) -> std::fmt::Result {
+        let language = escape_html(&self.language.to_string());
+        writeln!(html, "<!DOCTYPE html>")?;
+        writeln!(html, "<html lang=\"{language}\">")?;
+        writeln!(html, "<head>")?;
+        writeln!(html, "<meta charset=\"utf-8\">")?;
+        writeln!(html, "<title>Voice-over script ({language})</title>")?;
+        writeln!(html, "<style>")?;
+        writeln!(html, "body {{ font-family: \"Courier New\", monospace; max-width: 48em; margin: 2em auto; }}")?;
+        writeln!(html, "section {{ break-before: page; }}")?;
+        writeln!(
+            html,
+            "h2 {{ text-align: center; text-transform: uppercase; }}"
+        )?;
+        writeln!(html, ".line {{ break-inside: avoid; margin: 1.5em 0; }}")?;
+        writeln!(html, ".context {{ color: #666; font-style: italic; }}")?;
+        writeln!(html, ".text {{ margin: 0.5em 4em; font-size: 1.1em; }}")?;
+        writeln!(html, ".info {{ color: #666; font-size: 0.8em; }}")?;
+        writeln!(html, "</style>")?;
+        writeln!(html, "</head>")?;
+        writeln!(html, "<body>")?;
+        writeln!(html, "<h1>Voice-over script ({language})</h1>")?;
+        for character in &self.characters {
+            let name = character.name.as_deref().unwrap_or("(No character)");
+            let line_plural = if character.lines.len() == 1 {
+                "line"
+            } else {
+                "lines"
+            };
+            writeln!(html, "<section>")?;
+            writeln!(
+                html,
+                "<h2>{} <small>({} {line_plural})</small></h2>",
+                escape_html(name),
+                character.lines.len()
+            )?;
+            for line in &character.lines {
+                writeln!(html, "<div class=\"line\">")?;
+                if let Some(preceding_line) = &line.preceding_line {
+                    writeln!(
+                        html,
+                        "<p class=\"context\">{}</p>",
+                        escape_html(preceding_line)
+                    )?;
+                }
+                let tags = if line.tags.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", escape_html(&line.tags.join(" ")))
+                };
+                writeln!(
+                    html,
+                    "<p class=\"text\">{}{tags}</p>",
+                    escape_html(&line.text)
+                )?;
+                let headers: String = line
+                    .node_headers
+                    .iter()
+                    .map(|(key, value)| format!(", {}: {}", escape_html(key), escape_html(value)))
+                    .collect();
+                writeln!(
+                    html,
+                    "<p class=\"info\">{} &middot; {} &middot; node {}{headers} &middot; {}:{}</p>",
+                    escape_html(&audio_file_name(line)),
+                    escape_html(&line.line_id.0),
+                    escape_html(&line.node),
+                    escape_html(&line.file),
+                    line.line_number
+                )?;
+                writeln!(html, "</div>")?;
+            }
+            writeln!(html, "</section>")?;
+        }
+        writeln!(html, "</body>")?;
+        writeln!(html, "</html>")?;
+        Ok(())
+    }
+}
+
+fn parse_line(line_id: &LineId, text: &str) -> UnderlyingYarnLine {
+    // The compiler already reported invalid markup, so the raw text is good enough as a fallback
+    let markup =
+        ParsedMarkup::parse(text).unwrap_or_else(|_| ParsedMarkup::from_parts(text, vec![]));
+    UnderlyingYarnLine {
+        id: line_id.clone(),
+        text: markup.text,
+        attributes: markup.attributes,
+        language: None,
+    }
+}
+
+fn plain_text(text: &str) -> String {
+    ParsedMarkup::parse(text)
+        .map(|markup| markup.text)
+        .unwrap_or_else(|_| text.to_owned())
+}
+
+fn node_headers(compilation: &Compilation, node_name: &str) -> Vec<(String, String)> {
+    compilation
+        .program
+        .as_ref()
+        .and_then(|program| program.nodes.get(node_name))
+        .map(|node| {
+            node.headers
+                .iter()
+                .filter(|header| header.key != "title")
+                .map(|header| (header.key.clone(), header.value.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn audio_file_name(line: &VoiceOverLine) -> String {
+    line.audio_file.to_string_lossy().replace('\\', "/")
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn compilation() -> Compilation {
+        let string_info = |text: &str, node: &str, line_number, metadata: &[&str]| StringInfo {
+            text: text.to_owned(),
+            node_name: node.to_owned(),
+            file_name: "hello.yarn".to_owned(),
+            line_number,
+            metadata: metadata.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        };
+        Compilation {
+            string_table: HashMap::from([
+                (
+                    LineId("line:1".to_owned()),
+                    string_info("Mae: Hi [b]Greg[/b]!", "Start", 3, &[]),
+                ),
+                (
+                    LineId("line:2".to_owned()),
+                    string_info("Greg: Psst, over here.", "Start", 4, &["whisper"]),
+                ),
+                (
+                    LineId("line:3".to_owned()),
+                    string_info("The wind howls.", "Start", 5, &[]),
+                ),
+                (
+                    LineId("line:4".to_owned()),
+                    string_info("Mae: Bye!", "End", 8, &[]),
+                ),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn groups_lines_by_character() {
+        let script = VoiceOverScript::new(&compilation(), &Localization::with_language("en-US"))
+            .with_audio_file_extension("ogg");
+
+        let names: Vec<_> = script
+            .characters
+            .iter()
+            .map(|character| character.name.as_deref())
+            .collect();
+        assert_eq!(vec![Some("Greg"), Some("Mae"), None], names);
+
+        let greg = &script.characters[0].lines[0];
+        assert_eq!("Psst, over here.", greg.text);
+        assert_eq!(Some("Mae: Hi Greg!"), greg.preceding_line.as_deref());
+        assert_eq!(vec!["#whisper"], greg.tags);
+        assert_eq!(PathBuf::from("dialogue/en-US/2.ogg"), greg.audio_file);
+
+        let mae: Vec<_> = script.characters[1]
+            .lines
+            .iter()
+            .map(|line| (line.text.as_str(), line.preceding_line.as_deref()))
+            .collect();
+        assert_eq!(vec![("Hi Greg!", None), ("Bye!", None)], mae);
+    }
+
+    #[test]
+    fn writes_csv_and_html() {
+        let script = VoiceOverScript::new(&compilation(), &Localization::with_language("en-US"));
+
+        let csv = script.to_csv().unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            Some("character,id,text,preceding line,node,node headers,file,line number,tags,audio file"),
+            lines.next()
+        );
+        assert_eq!(
+            Some("Greg,line:2,\"Psst, over here.\",Mae: Hi Greg!,Start,,hello.yarn,4,#whisper,dialogue/en-US/2.wav"),
+            lines.next()
+        );
+
+        let html = script.to_html();
+        assert!(html.contains("<h2>Mae <small>(2 lines)</small></h2>"));
+        assert!(html.contains("<p class=\"text\">Psst, over here. (#whisper)</p>"));
+    }
+}
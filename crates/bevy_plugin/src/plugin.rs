@@ -155,6 +155,28 @@ impl YarnSpinnerPlugin {
             .with_development_file_generation(development_file_generation);
         self
     }
+
+    /// Sets the kind of line IDs that are generated for lines without one, see [`LineIdGeneration`].
+    /// Only has an effect with [`DevelopmentFileGeneration::Full`] and [`Localizations`], as line IDs are not generated otherwise.
+    /// Defaults to [`LineIdGeneration::Random`].
+    #[must_use]
+    pub fn with_line_id_generation(mut self, line_id_generation: LineIdGeneration) -> Self {
+        self.project = self.project.with_line_id_generation(line_id_generation);
+        self
+    }
+
+    /// Sets the names of the markup markers the game handles, e.g. `wave` for `[wave]Hi[/wave]`.
+    /// Markers with other names in the Yarn files or strings files are then reported as warnings, as they are likely typos.
+    /// The markers with a special meaning to Yarn Spinner, such as `nomarkup` or `plural`, are always allowed.
+    /// By default, markers of any name are allowed.
+    #[must_use]
+    pub fn with_known_markup_markers(
+        mut self,
+        names: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.project = self.project.with_known_markup_markers(names);
+        self
+    }
 }
 
 impl Plugin for YarnSpinnerPlugin {
@@ -199,10 +221,19 @@ impl YarnApp for App {
             .register_type::<CompilerError>()
             .register_type::<yarnspinner::compiler::Diagnostic>()
             .register_type::<yarnspinner::compiler::DiagnosticSeverity>()
+            .register_type::<yarnspinner::compiler::DiagnosticCode>()
+            .register_type::<yarnspinner::compiler::DiagnosticDetails>()
+            .register_type::<yarnspinner::compiler::DiagnosticFix>()
             .register_type::<yarnspinner::compiler::DebugInfo>()
             .register_type::<LineInfo>()
+            .register_type::<yarnspinner::compiler::InstructionSource>()
+            .register_type::<yarnspinner::compiler::SourceConstruct>()
             .register_type::<yarnspinner::compiler::Declaration>()
             .register_type::<yarnspinner::compiler::DeclarationSource>()
+            .register_type::<yarnspinner::compiler::Manifest>()
+            .register_type::<yarnspinner::compiler::FunctionDefinition>()
+            .register_type::<yarnspinner::compiler::CommandDefinition>()
+            .register_type::<yarnspinner::compiler::ParameterDefinition>()
             .register_type::<StringInfo>()
             .register_type::<LineId>()
             .register_type::<yarnspinner::core::Position>()
@@ -1,4 +1,5 @@
 use crate::fmt_utils::SkipDebug;
+use crate::localization::TranslationStatusReport;
 use crate::prelude::*;
 use bevy::prelude::*;
 use bevy::utils::{HashMap, HashSet};
@@ -39,6 +40,9 @@ pub struct YarnProject {
     pub(crate) metadata: HashMap<LineId, Vec<String>>,
     pub(crate) watching_for_changes: bool,
     pub(crate) development_file_generation: DevelopmentFileGeneration,
+    pub(crate) line_id_generation: LineIdGeneration,
+    pub(crate) known_markup_markers: Option<Vec<String>>,
+    pub(crate) translation_status: Option<TranslationStatusReport>,
 }
 
 impl YarnProject {
@@ -57,6 +61,17 @@ impl YarnProject {
         self.localizations.as_ref()
     }
 
+    /// Returns the names of the markup markers handled by the game, if any. These come from [`YarnSpinnerPlugin::with_known_markup_markers`] or [`LoadYarnProjectEvent::with_known_markup_markers`].
+    pub fn known_markup_markers(&self) -> Option<&[String]> {
+        self.known_markup_markers.as_deref()
+    }
+
+    /// Returns how far the translations of this project are, if it has [`Localizations`].
+    /// The report is available once the strings files of all translations are loaded, and is kept up to date when the Yarn files or strings files change.
+    pub fn translation_status(&self) -> Option<&TranslationStatusReport> {
+        self.translation_status.as_ref()
+    }
+
     /// Constructs a [`DialogueRunner`] from this project using all defaults of [`DialogueRunnerBuilder`] .
     /// This is a convenience method for calling [`DialogueRunnerBuilder::build`] on an unconfigured builder returned by [`YarnProject::build_dialogue_runner`].
     pub fn create_dialogue_runner(&self) -> DialogueRunner {
@@ -100,6 +115,8 @@ pub struct LoadYarnProjectEvent {
     pub(crate) localizations: Option<Localizations>,
     pub(crate) yarn_files: HashSet<YarnFileSource>,
     pub(crate) development_file_generation: DevelopmentFileGeneration,
+    pub(crate) line_id_generation: LineIdGeneration,
+    pub(crate) known_markup_markers: Option<Vec<String>>,
 }
 
 impl Default for LoadYarnProjectEvent {
@@ -108,6 +125,8 @@ impl Default for LoadYarnProjectEvent {
             localizations: None,
             yarn_files: HashSet::from([YarnFileSource::Folder(DEFAULT_ASSET_DIR.into())]),
             development_file_generation: default(),
+            line_id_generation: default(),
+            known_markup_markers: None,
         }
     }
 }
@@ -144,6 +163,8 @@ impl LoadYarnProjectEvent {
             localizations: None,
             yarn_files,
             development_file_generation: default(),
+            line_id_generation: default(),
+            known_markup_markers: None,
         }
     }
 
@@ -192,6 +213,25 @@ impl LoadYarnProjectEvent {
         }
         self
     }
+
+    /// See [`YarnSpinnerPlugin::with_line_id_generation`].
+    #[must_use]
+    pub fn with_line_id_generation(mut self, line_id_generation: LineIdGeneration) -> Self {
+        self.line_id_generation = line_id_generation;
+        self
+    }
+
+    /// See [`YarnSpinnerPlugin::with_known_markup_markers`].
+    #[must_use]
+    pub fn with_known_markup_markers(
+        mut self,
+        names: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.known_markup_markers
+            .get_or_insert_with(Vec::new)
+            .extend(names.into_iter().map(Into::into));
+        self
+    }
 }
 
 impl<T, U> From<T> for LoadYarnProjectEvent
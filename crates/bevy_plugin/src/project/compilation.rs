@@ -3,6 +3,7 @@ use crate::localization::{LineIdUpdateSystemSet, UpdateAllStringsFilesForStringT
 use crate::plugin::AssetRoot;
 use crate::prelude::*;
 use crate::project::{CompilationSystemSet, LoadYarnProjectEvent, WatchingForChanges};
+use crate::yarn_file_asset::IncludedYarnFiles;
 use anyhow::bail;
 use bevy::prelude::*;
 use bevy::utils::{error, HashSet};
@@ -40,6 +41,8 @@ pub(crate) struct YarnProjectConfigToLoad {
     pub(crate) localizations: Option<Option<Localizations>>,
     pub(crate) watching_for_changes: bool,
     pub(crate) development_file_generation: DevelopmentFileGeneration,
+    pub(crate) line_id_generation: LineIdGeneration,
+    pub(crate) known_markup_markers: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default, Resource, Reflect)]
@@ -75,6 +78,8 @@ fn load_project(
             localizations: Some(event.localizations),
             watching_for_changes: is_watching_for_changes.0,
             development_file_generation: event.development_file_generation,
+            line_id_generation: event.line_id_generation,
+            known_markup_markers: event.known_markup_markers,
         });
         commands.insert_resource(YarnFilesToLoad(event.yarn_files));
         *already_loaded = true;
@@ -122,6 +127,7 @@ fn recompile_loaded_yarn_files(
         &yarn_files,
         yarn_project.localizations.as_ref(),
         yarn_project.development_file_generation,
+        yarn_project.known_markup_markers.as_deref(),
     )?
     else {
         return Ok(());
@@ -195,6 +201,7 @@ fn compile_loaded_yarn_files(
         &yarn_files,
         localizations,
         development_file_generation,
+        yarn_project_config_to_load.known_markup_markers.as_deref(),
     )?
     else {
         return Ok(());
@@ -219,7 +226,11 @@ fn compile_loaded_yarn_files(
                 )
                 .unwrap_or_default();
 
-                strings_file.write_asset(&path)?;
+                strings_file.write_asset(
+                    &path,
+                    &localizations.base_localization.language,
+                    &compilation.string_table,
+                )?;
                 info!(
                     "Generated \"{}\" (lang: {}).",
                     path.display(),
@@ -241,7 +252,10 @@ fn compile_loaded_yarn_files(
         asset_server: SkipDebug(asset_server.clone()),
         watching_for_changes: yarn_project_config_to_load.watching_for_changes,
         development_file_generation,
+        line_id_generation: yarn_project_config_to_load.line_id_generation,
         metadata,
+        known_markup_markers: yarn_project_config_to_load.known_markup_markers.clone(),
+        translation_status: None,
     });
 
     let file_plural = if file_count == 1 { "file" } else { "files" };
@@ -261,6 +275,7 @@ fn compile_yarn_files(
     yarn_files: &Res<Assets<YarnFile>>,
     localizations: Option<&Localizations>,
     development_file_generation: DevelopmentFileGeneration,
+    known_markup_markers: Option<&[String]>,
 ) -> Result<Option<Compilation>> {
     let yarn_files = yarn_file_handles
         .iter()
@@ -283,7 +298,18 @@ fn compile_yarn_files(
             }
         }
     }
+    let included_yarn_files = IncludedYarnFiles::new(yarn_files.clone());
     let inner_yarn_files = yarn_files.map(|file| file.file.clone());
-    let compilation = YarnCompiler::new().add_files(inner_yarn_files).compile()?;
+    let mut compiler = YarnCompiler::new();
+    compiler
+        .add_files(inner_yarn_files)
+        .with_file_resolver(included_yarn_files);
+    if let Some(known_markup_markers) = known_markup_markers {
+        compiler.declare_markup_markers(known_markup_markers.iter().cloned());
+    }
+    let compilation = compiler.compile()?;
+    for warning in &compilation.warnings {
+        warn!("{warning}");
+    }
     Ok(Some(compilation))
 }
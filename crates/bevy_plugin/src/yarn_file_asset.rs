@@ -2,20 +2,32 @@ use crate::prelude::*;
 use bevy::asset::{io::Reader, AsyncReadExt};
 use bevy::prelude::*;
 
-use bevy::asset::{AssetLoader, LoadContext};
+use bevy::asset::{AssetLoader, AssetPath, LoadContext};
+use std::collections::HashSet;
 use std::hash::Hash;
+use yarnspinner::compiler::FileResolver;
 use yarnspinner::prelude::YarnFile as InnerYarnFile;
 
 /// A Yarn file. These will mostly be created by loading them from disk with the [`AssetServer`].
+///
+/// The files it includes with `#include:` file tags, e.g. `#include:common.yarn` above the first node, are loaded along with it
+/// relative to its path and are compiled as part of the [`YarnProject`]. Changes to them are hot reloaded like changes to the file itself.
 #[derive(Debug, Clone, Eq, PartialEq, Reflect, Asset, Serialize, Deserialize)]
 #[reflect(Debug, PartialEq, Hash, Serialize, Deserialize)]
 pub struct YarnFile {
     pub(crate) file: InnerYarnFile,
     pub(crate) string_table: std::collections::HashMap<LineId, StringInfo>,
+    /// The path of the asset within its asset source, if it was loaded with an [`AssetServer`].
+    pub(crate) asset_path: Option<String>,
+    /// Named by their asset paths, so that the files they include in turn are resolved relative to those.
+    pub(crate) included_files: Vec<InnerYarnFile>,
+    /// The asset paths of the files included by this file, by their path relative to [`YarnFile::file_name`], see [`InnerYarnFile::include_path`].
+    pub(crate) include_asset_paths: std::collections::HashMap<String, String>,
 }
 
 impl YarnFile {
     /// Creates a new Yarn file from a filename and file content.
+    /// As it is not loaded from disk, the files it includes with `#include:` file tags cannot be resolved and make the compilation fail.
     pub fn new(filename: impl Into<String>, content: impl Into<String>) -> Self {
         let filename = filename.into();
         let content = content.into();
@@ -24,7 +36,13 @@ impl YarnFile {
             source: content,
         };
         let string_table = compile_string_table(file.clone()).unwrap();
-        Self { file, string_table }
+        Self {
+            file,
+            string_table,
+            asset_path: None,
+            included_files: Vec::new(),
+            include_asset_paths: Default::default(),
+        }
     }
 
     /// Returns the filename of the Yarn file.
@@ -61,6 +79,8 @@ impl Hash for YarnFile {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.file.file_name.hash(state);
         self.file.source.hash(state);
+        self.asset_path.hash(state);
+        self.included_files.hash(state);
     }
 }
 
@@ -85,7 +105,8 @@ impl AssetLoader for YarnFileAssetLoader {
     ) -> Result<Self::Asset, Self::Error> {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
-        let yarn_file = read_yarn_file(bytes, load_context)?;
+        let mut yarn_file = read_yarn_file(bytes, load_context)?;
+        read_included_files(&mut yarn_file, load_context).await;
         Ok(yarn_file)
     }
 
@@ -109,5 +130,127 @@ fn read_yarn_file(bytes: Vec<u8>, load_context: &LoadContext) -> Result<YarnFile
         .add_file(file.clone())
         .compile()?
         .string_table;
-    Ok(YarnFile { file, string_table })
+    Ok(YarnFile {
+        file,
+        string_table,
+        asset_path: Some(asset_path_name(load_context.asset_path())),
+        included_files: Vec::new(),
+        include_asset_paths: Default::default(),
+    })
+}
+
+/// The path of the asset within its asset source, using `/` as separator.
+fn asset_path_name(asset_path: &AssetPath) -> String {
+    asset_path.path().to_string_lossy().replace('\\', "/")
+}
+
+/// Reads the files included by `yarn_file` with `#include:` file tags, recursively. Reading them through the `load_context`
+/// makes Bevy reload `yarn_file` when they change. Files that cannot be read are skipped, as the compiler reports them along with the including tag.
+async fn read_included_files(yarn_file: &mut YarnFile, load_context: &mut LoadContext<'_>) {
+    let asset_path = asset_path_name(load_context.asset_path());
+    // The same include tags, resolved relative to the asset path instead of the file name
+    let located_file = InnerYarnFile {
+        file_name: asset_path.clone(),
+        source: yarn_file.file.source.clone(),
+    };
+    let mut paths_to_read: Vec<_> = located_file
+        .included_paths()
+        .iter()
+        .map(|path| path.trim_start_matches('/').to_owned())
+        .collect();
+    yarn_file.include_asset_paths = yarn_file
+        .file
+        .included_paths()
+        .into_iter()
+        .zip(paths_to_read.iter().cloned())
+        .collect();
+
+    let mut visited_paths = HashSet::from([asset_path]);
+    while let Some(path) = paths_to_read.pop() {
+        if !visited_paths.insert(path.clone()) {
+            continue;
+        }
+        let Ok(asset_path) = load_context.asset_path().resolve(&format!("/{path}")) else {
+            continue;
+        };
+        let Ok(bytes) = load_context.read_asset_bytes(asset_path).await else {
+            continue;
+        };
+        let Ok(source) = String::from_utf8(bytes) else {
+            continue;
+        };
+        let included_file = InnerYarnFile {
+            file_name: path,
+            source,
+        };
+        paths_to_read.extend(
+            included_file
+                .included_paths()
+                .iter()
+                .map(|path| path.trim_start_matches('/').to_owned()),
+        );
+        yarn_file.included_files.push(included_file);
+    }
+}
+
+/// Resolves `#include:` file tags with the files read along with the [`YarnFile`]s that include them.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IncludedYarnFiles {
+    /// The included files and the [`YarnFile`]s of the project, by their asset paths.
+    files: std::collections::HashMap<String, InnerYarnFile>,
+    /// The asset paths of the files included by the [`YarnFile`]s of the project,
+    /// by the name of the including file and the path the compiler resolves the include to.
+    asset_paths: std::collections::HashMap<(String, String), String>,
+}
+
+impl IncludedYarnFiles {
+    pub(crate) fn new<'a>(yarn_files: impl IntoIterator<Item = &'a YarnFile>) -> Self {
+        let yarn_files: Vec<_> = yarn_files.into_iter().collect();
+        let included_files = yarn_files
+            .iter()
+            .flat_map(|yarn_file| yarn_file.included_files.iter())
+            .map(|file| (file.file_name.clone(), file.clone()));
+        // Including a file of the project resolves to the file itself, which the compiler then does not compile a second time
+        let project_files = yarn_files.iter().filter_map(|yarn_file| {
+            let asset_path = yarn_file.asset_path.clone()?;
+            Some((asset_path, yarn_file.file.clone()))
+        });
+        let files = included_files.chain(project_files).collect();
+        let asset_paths = yarn_files
+            .iter()
+            .flat_map(|yarn_file| {
+                yarn_file
+                    .include_asset_paths
+                    .iter()
+                    .map(|(path, asset_path)| {
+                        (
+                            (yarn_file.file.file_name.clone(), path.clone()),
+                            asset_path.clone(),
+                        )
+                    })
+            })
+            .collect();
+        Self { files, asset_paths }
+    }
+}
+
+impl FileResolver for IncludedYarnFiles {
+    fn resolve(
+        &self,
+        path: &str,
+        including_file: &InnerYarnFile,
+    ) -> std::io::Result<InnerYarnFile> {
+        // Included files are named by their asset paths, so the files they include are found by the path itself
+        let asset_path = self
+            .asset_paths
+            .get(&(including_file.file_name.clone(), path.to_owned()))
+            .map(String::as_str)
+            .unwrap_or_else(|| path.trim_start_matches('/'));
+        self.files.get(asset_path).cloned().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no asset was found at this path relative to the including Yarn file",
+            )
+        })
+    }
 }
@@ -1,9 +1,10 @@
 use anyhow::Result;
 use bevy::prelude::*;
 use bevy::utils::Instant;
-use bevy_yarnspinner::{events::*, prelude::*};
+use bevy_yarnspinner::{events::*, prelude::*, DiagnosticSeverity};
 use std::thread::sleep;
 use utils::prelude::*;
+use yarnspinner::prelude::CompilerError;
 
 mod utils;
 
@@ -110,6 +111,33 @@ fn executes_commands_and_fns() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn strict_command_validation_rejects_unregistered_commands() -> Result<()> {
+    let mut app = App::new();
+    let project = app
+        .setup_default_plugins()
+        .add_plugins(YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file(
+            "commands.yarn",
+        )))
+        .load_project();
+
+    let error = project
+        .build_dialogue_runner()
+        .add_command("set_data", |_: In<String>| {})
+        .with_command_validation(DiagnosticSeverity::Error)
+        .try_build()
+        .unwrap_err();
+
+    let CompilerError(diagnostics) = error.downcast_ref::<CompilerError>().unwrap();
+    assert_eq!(1, diagnostics.len(), "{diagnostics:#?}");
+    assert!(diagnostics[0].message.contains("\"unregistered\""));
+    assert_eq!(
+        Some(8),
+        diagnostics[0].range.as_ref().map(|range| range.start.line)
+    );
+    Ok(())
+}
+
 #[derive(Debug, Resource)]
 struct Data(String);
 
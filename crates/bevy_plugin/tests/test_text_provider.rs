@@ -153,3 +153,65 @@ fn loads_line_from_translated_language() {
         .unwrap();
     assert_eq!("Mann: Also gut. Ich glaub das zwar nicht, aber es kann ja nicht schaden, wenn ich mir was wünsche. Ich möchte wissen, wer ich bin.", line);
 }
+
+#[test]
+fn reports_language_that_served_each_line() {
+    let mut app = App::new();
+
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("lines_with_ids.yarn"))
+            .with_localizations(Localizations {
+                base_localization: "en-US".into(),
+                translations: vec!["de-CH".into()],
+            })
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+    );
+
+    app.dialogue_runner_mut().set_text_language("de-CH");
+
+    app.load_lines();
+
+    let text_provider = app.dialogue_runner().text_provider();
+    let (_, translated_language) = text_provider
+        .get_text_with_language(&LineId("line:9".to_owned()))
+        .unwrap();
+    assert_eq!(Some(Language::new("de-CH")), translated_language);
+
+    let (line, fallback_language) = text_provider
+        .get_text_with_language(&LineId("line:10".to_owned()))
+        .unwrap();
+    assert_eq!("Hag: Funny,", line);
+    assert_eq!(None, fallback_language);
+}
+
+#[test]
+fn serves_translation_when_fallback_fails_to_load() {
+    let mut app = App::new();
+
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("lines_with_ids.yarn"))
+            .with_localizations(Localizations {
+                base_localization: "en-US".into(),
+                translations: vec![
+                    Localization::with_language("de-CH").with_fallbacks(["de"]),
+                    Localization::with_language("de")
+                        .with_strings_file("dialogue/does_not_exist.strings.csv"),
+                ],
+            })
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+    );
+
+    app.dialogue_runner_mut().set_text_language("de-CH");
+
+    app.load_lines();
+
+    let text_provider = app.dialogue_runner().text_provider();
+    let (_, translated_language) = text_provider
+        .get_text_with_language(&LineId("line:9".to_owned()))
+        .unwrap();
+    assert_eq!(Some(Language::new("de-CH")), translated_language);
+    let (_, fallback_language) = text_provider
+        .get_text_with_language(&LineId("line:10".to_owned()))
+        .unwrap();
+    assert_eq!(None, fallback_language);
+}
@@ -0,0 +1,57 @@
+use anyhow::Result;
+use bevy::prelude::*;
+use bevy_yarnspinner::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+use utils::prelude::*;
+
+mod utils;
+
+#[test]
+fn compiles_included_files_by_asset_path_once() -> Result<()> {
+    let dir = tempdir()?;
+    let files = [
+        (
+            "dialogue/a/one.yarn",
+            "#include:common.yarn\n#include:../shared.yarn\ntitle: One\n---\n<<jump A>>\n===\n",
+        ),
+        (
+            "dialogue/a/common.yarn",
+            "title: A\n---\nHello from A\n===\n",
+        ),
+        (
+            "dialogue/b/two.yarn",
+            "#include:common.yarn\n#include:../shared.yarn\ntitle: Two\n---\n<<jump B>>\n===\n",
+        ),
+        (
+            "dialogue/b/common.yarn",
+            "title: B\n---\n<<jump Shared>>\n===\n",
+        ),
+        (
+            "dialogue/shared.yarn",
+            "title: Shared\n---\nHello from the shared file\n===\n",
+        ),
+    ];
+    for (path, source) in files {
+        let path = dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, source)?;
+    }
+    let mut app = App::new();
+    app.setup_default_plugins_for_path(dir.path()).add_plugins(
+        YarnSpinnerPlugin::with_yarn_sources([
+            YarnFileSource::file("dialogue/a/one.yarn"),
+            YarnFileSource::file("dialogue/b/two.yarn"),
+            // Included by the files above as well
+            YarnFileSource::file("dialogue/shared.yarn"),
+        ]),
+    );
+
+    let project = app.load_project();
+    let program = project.compilation().program.as_ref().unwrap();
+    let mut node_names: Vec<_> = program.nodes.keys().map(String::as_str).collect();
+    node_names.sort();
+    assert_eq!(vec!["A", "B", "One", "Shared", "Two"], node_names);
+
+    Ok(())
+}
@@ -149,6 +149,7 @@ impl DialogueRunnerExt for DialogueRunner {
             id: LineId(line_id.to_string()),
             text: String::new(),
             attributes: vec![],
+            language: None,
         };
         self.asset_providers()
             .map(|p| p.get_assets(&line_id))
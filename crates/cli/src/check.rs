@@ -0,0 +1,48 @@
+//! The `check` command, which compiles Yarn files only to report their diagnostics.
+
+use crate::error::CliError;
+use crate::files::InputArgs;
+use crate::output::{diagnostics_to_json, Output};
+use clap::Args;
+use serde_json::json;
+use std::path::PathBuf;
+use yarnspinner::compiler::DiagnosticVec;
+use yarnspinner::prelude::*;
+
+#[derive(Debug, Args)]
+pub(crate) struct CheckArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Also write the diagnostics to this file as a SARIF log, e.g. for code scanning in CI.
+    #[arg(long, value_name = "PATH")]
+    sarif: Option<PathBuf>,
+}
+
+/// Errors are reported by returning them, which results in a non-zero exit code.
+pub(crate) fn check(args: &CheckArgs, output: &Output) -> Result<(), CliError> {
+    let result = args.input.compile(CompilationType::FullCompilation);
+    if let Some(path) = &args.sarif {
+        let diagnostics = match &result {
+            Ok(compilation) => Some(&compilation.warnings),
+            Err(CliError::Compilation(CompilerError(diagnostics))) => Some(diagnostics),
+            Err(_) => None,
+        };
+        // Without a compilation, e.g. when a file could not be read, there is nothing to report
+        if let Some(diagnostics) = diagnostics {
+            std::fs::write(path, diagnostics.to_sarif()).map_err(CliError::io(path))?;
+        }
+    }
+    let compilation = result?;
+    output.warnings(&compilation.warnings);
+    let human = match compilation.warnings.len() {
+        0 => "No problems found".to_owned(),
+        1 => "Found 1 warning".to_owned(),
+        count => format!("Found {count} warnings"),
+    };
+    output.success(
+        json!({ "diagnostics": diagnostics_to_json(&compilation.warnings) }),
+        &human,
+    );
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! The `compile` command, which writes the same files as the `ysc compile` command of the original Yarn Spinner:
+//! - `<name>.yarnc`: the compiled [`YarnProgram`], encoded as protobuf.
+//! - `<name>-Lines.csv`: the string table, with the columns `id`, `text`, `file`, `node` and `lineNumber`.
+//! - `<name>-Metadata.csv`: the tags of all lines that have any besides their `#line:` tag, with the columns `id`, `node`, `lineNumber` and `tags`.
+
+use crate::error::CliError;
+use crate::files::{self, InputArgs};
+use crate::output::{diagnostics_to_json, Output};
+use clap::Args;
+use prost::Message;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use yarnspinner::prelude::*;
+
+#[derive(Debug, Args)]
+pub(crate) struct CompileArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// The directory to write the output files to. It is created if it does not exist.
+    #[arg(short, long, default_value = ".")]
+    output_directory: PathBuf,
+
+    /// The name of the output files, e.g. `Dialogue` for `Dialogue.yarnc`.
+    #[arg(short = 'n', long, default_value = "Output")]
+    output_name: String,
+}
+
+pub(crate) fn compile(args: &CompileArgs, output: &Output) -> Result<(), CliError> {
+    let compilation = args.input.compile(CompilationType::FullCompilation)?;
+    let directory = &args.output_directory;
+    std::fs::create_dir_all(directory).map_err(CliError::io(directory))?;
+
+    let program_path = directory.join(format!("{}.yarnc", args.output_name));
+    let program = compilation
+        .program
+        .as_ref()
+        .map(Message::encode_to_vec)
+        .unwrap_or_default();
+    std::fs::write(&program_path, program).map_err(CliError::io(&program_path))?;
+
+    let lines_path = directory.join(format!("{}-Lines.csv", args.output_name));
+    write_lines(&lines_path, &compilation)?;
+    let metadata_path = directory.join(format!("{}-Metadata.csv", args.output_name));
+    write_metadata(&metadata_path, &compilation)?;
+
+    output.warnings(&compilation.warnings);
+    let written = [program_path, lines_path, metadata_path];
+    let human: Vec<_> = written
+        .iter()
+        .map(|path| format!("Wrote {}", path.display()))
+        .collect();
+    output.success(
+        json!({
+            "files": written,
+            "diagnostics": diagnostics_to_json(&compilation.warnings),
+        }),
+        &human.join("\n"),
+    );
+    Ok(())
+}
+
+fn write_lines(path: &Path, compilation: &Compilation) -> Result<(), CliError> {
+    let rows = files::sorted_string_table(compilation)
+        .into_iter()
+        .map(|(id, string_info)| {
+            [
+                id.0.clone(),
+                string_info.text.clone(),
+                string_info.file_name.clone(),
+                string_info.node_name.clone(),
+                string_info.line_number.to_string(),
+            ]
+        });
+    write_csv(path, ["id", "text", "file", "node", "lineNumber"], rows)
+}
+
+fn write_metadata(path: &Path, compilation: &Compilation) -> Result<(), CliError> {
+    let rows = files::sorted_string_table(compilation)
+        .into_iter()
+        .filter_map(|(id, string_info)| {
+            let tags = files::tags(string_info);
+            (!tags.is_empty()).then(|| {
+                [
+                    id.0.clone(),
+                    string_info.node_name.clone(),
+                    string_info.line_number.to_string(),
+                    tags.join(" "),
+                ]
+            })
+        });
+    write_csv(path, ["id", "node", "lineNumber", "tags"], rows)
+}
+
+fn write_csv<const N: usize>(
+    path: &Path,
+    header: [&str; N],
+    rows: impl IntoIterator<Item = [String; N]>,
+) -> Result<(), CliError> {
+    let write = || -> csv::Result<()> {
+        let mut writer = csv::Writer::from_path(path)?;
+        writer.write_record(header)?;
+        for row in rows {
+            writer.write_record(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    };
+    write().map_err(|error| CliError::io(path)(error.into()))
+}
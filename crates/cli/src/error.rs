@@ -0,0 +1,91 @@
+//! The ways a command can fail, each of which maps to a stable exit code.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use yarnspinner::compiler::ManifestError;
+use yarnspinner::prelude::*;
+
+#[derive(Debug)]
+pub(crate) enum CliError {
+    /// The Yarn files did not compile.
+    Compilation(CompilerError),
+    /// None of the given paths is or contains a `.yarn` file.
+    NoYarnFiles(Vec<PathBuf>),
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The manifest passed with `--manifest` could not be read or is malformed.
+    Manifest {
+        path: PathBuf,
+        source: ManifestError,
+    },
+    Dialogue(DialogueError),
+    /// Stdin was closed while `run` was waiting for the player to choose an option.
+    InputEnded,
+}
+
+impl CliError {
+    pub(crate) fn io(path: impl Into<PathBuf>) -> impl FnOnce(std::io::Error) -> Self {
+        let path = path.into();
+        move |source| Self::Io { path, source }
+    }
+
+    /// The exit code of the process. These are part of the public interface, see the crate documentation.
+    pub(crate) fn exit_code(&self) -> ExitCode {
+        let code = match self {
+            CliError::Compilation(_) => 1,
+            CliError::NoYarnFiles(_) => 2,
+            CliError::Io { .. } | CliError::Manifest { .. } => 3,
+            CliError::Dialogue(_) | CliError::InputEnded => 4,
+        };
+        ExitCode::from(code)
+    }
+}
+
+impl Error for CliError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CliError::Compilation(error) => Some(error),
+            CliError::Io { source, .. } => Some(source),
+            CliError::Manifest { source, .. } => Some(source),
+            CliError::Dialogue(error) => Some(error),
+            CliError::NoYarnFiles(_) | CliError::InputEnded => None,
+        }
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Compilation(error) => write!(f, "{error}"),
+            CliError::NoYarnFiles(paths) => {
+                let paths: Vec<_> = paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                write!(f, "No .yarn files found in {}", paths.join(", "))
+            }
+            CliError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            CliError::Manifest { path, source } => write!(f, "{}: {source}", path.display()),
+            CliError::Dialogue(error) => write!(f, "{error}"),
+            CliError::InputEnded => {
+                write!(f, "Input ended while waiting for an option to be chosen")
+            }
+        }
+    }
+}
+
+impl From<CompilerError> for CliError {
+    fn from(error: CompilerError) -> Self {
+        Self::Compilation(error)
+    }
+}
+
+impl From<DialogueError> for CliError {
+    fn from(error: DialogueError) -> Self {
+        Self::Dialogue(error)
+    }
+}
@@ -0,0 +1,142 @@
+//! Finding, reading and compiling the Yarn files passed on the command line.
+
+use crate::error::CliError;
+use clap::Args;
+use std::path::{Path, PathBuf};
+use yarnspinner::compiler::{FileSystemResolver, Manifest};
+use yarnspinner::prelude::*;
+
+/// The Yarn files a command works on.
+#[derive(Debug, Args)]
+pub(crate) struct InputArgs {
+    /// The `.yarn` files to use. Directories are searched recursively.
+    #[arg(required = true)]
+    pub(crate) inputs: Vec<PathBuf>,
+
+    /// A `.ysls.json` or `.toml` manifest declaring the functions and commands the game provides.
+    /// Commands that are not declared in it are reported, and the arguments of declared ones are type checked.
+    #[arg(long, value_name = "PATH")]
+    pub(crate) manifest: Option<PathBuf>,
+}
+
+impl InputArgs {
+    /// Returns the paths of all Yarn files, sorted so that the output does not depend on the file system.
+    pub(crate) fn find_yarn_files(&self) -> Result<Vec<PathBuf>, CliError> {
+        let mut files = Vec::new();
+        for input in &self.inputs {
+            if input.is_dir() {
+                find_yarn_files_in(input, &mut files)?;
+            } else if input.exists() {
+                files.push(input.clone());
+            } else {
+                let source = std::io::Error::from(std::io::ErrorKind::NotFound);
+                return Err(CliError::io(input)(source));
+            }
+        }
+        files.sort();
+        files.dedup();
+        if files.is_empty() {
+            return Err(CliError::NoYarnFiles(self.inputs.clone()));
+        }
+        Ok(files)
+    }
+
+    pub(crate) fn read_yarn_files(&self) -> Result<Vec<YarnFile>, CliError> {
+        self.find_yarn_files()?
+            .iter()
+            .map(|path| read_yarn_file(path))
+            .collect()
+    }
+
+    pub(crate) fn compile(
+        &self,
+        compilation_type: CompilationType,
+    ) -> Result<Compilation, CliError> {
+        let files = self.read_yarn_files()?;
+        let mut compiler = YarnCompiler::new();
+        if let Some(path) = &self.manifest {
+            let manifest = Manifest::read(path).map_err(|source| CliError::Manifest {
+                path: path.clone(),
+                source,
+            })?;
+            compiler.extend_manifest(manifest);
+        }
+        let compilation = compiler
+            .add_files(files)
+            .with_file_resolver(FileSystemResolver)
+            .with_compilation_type(compilation_type)
+            .compile()?;
+        Ok(compilation)
+    }
+}
+
+pub(crate) fn read_yarn_file(path: &Path) -> Result<YarnFile, CliError> {
+    let source = std::fs::read_to_string(path).map_err(CliError::io(path))?;
+    Ok(YarnFile {
+        file_name: path.display().to_string(),
+        source,
+    })
+}
+
+fn find_yarn_files_in(directory: &Path, files: &mut Vec<PathBuf>) -> Result<(), CliError> {
+    let entries = std::fs::read_dir(directory).map_err(CliError::io(directory))?;
+    for entry in entries {
+        let path = entry.map_err(CliError::io(directory))?.path();
+        if path.is_dir() {
+            find_yarn_files_in(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "yarn")
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the entries of the string table in the order they appear in the Yarn files.
+pub(crate) fn sorted_string_table(compilation: &Compilation) -> Vec<(&LineId, &StringInfo)> {
+    let mut string_table: Vec<_> = compilation.string_table.iter().collect();
+    string_table.sort_by(|(id, info), (other_id, other_info)| {
+        (&info.file_name, info.line_number, &id.0).cmp(&(
+            &other_info.file_name,
+            other_info.line_number,
+            &other_id.0,
+        ))
+    });
+    string_table
+}
+
+/// Returns the metadata of a line without its `#line:` tag, which is already its ID.
+pub(crate) fn tags(string_info: &StringInfo) -> Vec<&str> {
+    string_info
+        .metadata
+        .iter()
+        .map(String::as_str)
+        .filter(|tag| !tag.starts_with("line:"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_yarn_files_recursively() {
+        let directory = std::env::temp_dir().join("yarnspinner_cli_finds_yarn_files_recursively");
+        let nested = directory.join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(directory.join("b.yarn"), "").unwrap();
+        std::fs::write(nested.join("a.yarn"), "").unwrap();
+        std::fs::write(nested.join("notes.txt"), "").unwrap();
+        let args = InputArgs {
+            inputs: vec![directory.clone()],
+            manifest: None,
+        };
+
+        let files = args.find_yarn_files().unwrap();
+
+        assert_eq!(vec![directory.join("b.yarn"), nested.join("a.yarn")], files);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
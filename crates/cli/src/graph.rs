@@ -0,0 +1,71 @@
+//! The `graph` command, which prints the [`NodeGraph`](yarnspinner::compiler::NodeGraph) of Yarn files.
+
+use crate::error::CliError;
+use crate::files::InputArgs;
+use crate::output::Output;
+use clap::{Args, ValueEnum};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use yarnspinner::prelude::*;
+
+#[derive(Debug, Args)]
+pub(crate) struct GraphArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// The format to print the graph in. Ignored when `--json` is passed.
+    #[arg(short, long, value_enum, default_value_t = GraphFormat::Dot)]
+    format: GraphFormat,
+
+    /// The nodes the dialogue may start at. Nodes that cannot be reached from any of them are marked as unreachable.
+    #[arg(short, long = "start", default_value = "Start")]
+    start_nodes: Vec<String>,
+
+    /// Write the graph to this file instead of printing it.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum GraphFormat {
+    /// Graphviz, e.g. for `dot -Tsvg`.
+    Dot,
+    /// Mermaid flowcharts, e.g. for Markdown files.
+    Mermaid,
+    /// The same structure as serializing the graph with `serde`.
+    Json,
+}
+
+pub(crate) fn graph(args: &GraphArgs, output: &Output) -> Result<(), CliError> {
+    let graph = args
+        .input
+        .compile(CompilationType::FullCompilation)?
+        .node_graph()
+        .with_start_nodes(&args.start_nodes);
+    let format = if output.json && args.output.is_none() {
+        GraphFormat::Json
+    } else {
+        args.format
+    };
+    let text = match format {
+        GraphFormat::Dot => graph.to_dot(),
+        GraphFormat::Mermaid => graph.to_mermaid(),
+        GraphFormat::Json => graph.to_json(),
+    };
+
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, text).map_err(CliError::io(path))?;
+            output.success(
+                json!({ "files": [path] }),
+                &format!("Wrote {}", path.display()),
+            );
+        }
+        None if output.json => {
+            let graph: Value = serde_json::from_str(&text).expect("The graph is valid JSON");
+            output.success(json!({ "graph": graph }), "");
+        }
+        None => output.success(Value::Null, &text),
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! The `yarnspinner` command line tool, which compiles, checks, tags and plays Yarn files.
+//!
+//! ## Usage
+//!
+//! ```text
+//! yarnspinner compile dialogue/ --output-directory build --output-name Dialogue
+//! yarnspinner tag dialogue/ --strategy sequential
+//! yarnspinner check dialogue/ --manifest game.ysls.json --json --sarif diagnostics.sarif
+//! yarnspinner dump-strings dialogue/
+//! yarnspinner graph dialogue/ --format mermaid
+//! yarnspinner run dialogue/ --start Start
+//! ```
+//!
+//! Every command accepts any number of `.yarn` files and directories, which are searched recursively.
+//! Passing `--json` prints a single JSON object to stdout instead of human-readable text,
+//! which always contains a boolean `success` field. `run` instead prints one JSON object per line and event.
+//!
+//! ## Exit codes
+//!
+//! The exit codes are stable, so scripts and CI pipelines can rely on them:
+//!
+//! | Code | Meaning                                                                       |
+//! |------|-------------------------------------------------------------------------------|
+//! | 0    | Success                                                                       |
+//! | 1    | The Yarn files have errors, which are reported as diagnostics                 |
+//! | 2    | The command line arguments are invalid or no Yarn files were found           |
+//! | 3    | A file could not be read or written, or the manifest is malformed            |
+//! | 4    | The dialogue failed while running, or its input ended while waiting for a choice |
+
+use crate::output::Output;
+use clap::{Parser, Subcommand};
+use std::process::ExitCode;
+
+mod check;
+mod compile;
+mod error;
+mod files;
+mod graph;
+mod output;
+mod run;
+mod strings;
+mod tag;
+
+/// The friendly tool for writing game dialogue.
+#[derive(Debug, Parser)]
+#[command(name = "yarnspinner", version)]
+struct Cli {
+    /// Print machine-readable JSON to stdout instead of human-readable text.
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Compile Yarn files into a program, a string table and a metadata table.
+    Compile(compile::CompileArgs),
+    /// Add `#line:` tags to all lines that lack one, editing the files in place.
+    Tag(tag::TagArgs),
+    /// Report the diagnostics of Yarn files without writing any output.
+    Check(check::CheckArgs),
+    /// Print the string table of Yarn files.
+    DumpStrings(strings::DumpStringsArgs),
+    /// Print the graph of nodes and the jumps and options between them.
+    Graph(graph::GraphArgs),
+    /// Play Yarn files in the terminal.
+    Run(run::RunArgs),
+}
+
+fn main() -> ExitCode {
+    // Invalid arguments make clap exit with code 2 on its own
+    let cli = Cli::parse();
+    let output = Output::new(cli.json);
+    let result = match &cli.command {
+        Command::Compile(args) => compile::compile(args, &output),
+        Command::Tag(args) => tag::tag(args, &output),
+        Command::Check(args) => check::check(args, &output),
+        Command::DumpStrings(args) => strings::dump_strings(args, &output),
+        Command::Graph(args) => graph::graph(args, &output),
+        Command::Run(args) => run::run(args, &output),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            output.error(&error);
+            error.exit_code()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn verifies_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn json_flag_is_global() {
+        let cli = Cli::try_parse_from(["yarnspinner", "check", "dialogue", "--json"]).unwrap();
+
+        assert!(cli.json);
+        assert!(matches!(cli.command, Command::Check(_)));
+    }
+}
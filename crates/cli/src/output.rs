@@ -0,0 +1,64 @@
+//! Printing results either for humans or as JSON.
+
+use crate::error::CliError;
+use serde_json::{json, Value};
+use yarnspinner::compiler::{Diagnostic, DiagnosticVec};
+use yarnspinner::prelude::*;
+
+/// Where and how the results of a command are printed.
+/// Results go to stdout, while human-readable diagnostics and errors go to stderr so they never mix with results.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Output {
+    pub(crate) json: bool,
+}
+
+impl Output {
+    pub(crate) fn new(json: bool) -> Self {
+        Self { json }
+    }
+
+    /// Prints the result of a successful command. `value` is only printed when JSON was requested,
+    /// in which case a `success` field is added to it. Otherwise, `human` is printed if it is not empty.
+    pub(crate) fn success(&self, value: Value, human: &str) {
+        if self.json {
+            let mut value = value;
+            value["success"] = Value::Bool(true);
+            println!("{value:#}");
+        } else if !human.is_empty() {
+            println!("{human}");
+        }
+    }
+
+    /// Prints the warnings of a successful compilation for humans. In JSON, they are part of the result instead.
+    pub(crate) fn warnings(&self, warnings: &[Diagnostic]) {
+        if !self.json {
+            for warning in warnings {
+                eprintln!("{warning}");
+            }
+        }
+    }
+
+    /// Reports a failed command. Compilation errors are reported as `diagnostics` in JSON.
+    pub(crate) fn error(&self, error: &CliError) {
+        if !self.json {
+            eprintln!("{error}");
+            return;
+        }
+        let value = match error {
+            CliError::Compilation(CompilerError(diagnostics)) => json!({
+                "success": false,
+                "diagnostics": diagnostics_to_json(diagnostics),
+            }),
+            _ => json!({
+                "success": false,
+                "error": error.to_string(),
+            }),
+        };
+        println!("{value:#}");
+    }
+}
+
+/// Uses the format of [`DiagnosticVec::to_json`], which names codes by their stable ID, e.g. `"YS0001"`.
+pub(crate) fn diagnostics_to_json(diagnostics: &[Diagnostic]) -> Value {
+    serde_json::from_str(&diagnostics.to_vec().to_json()).expect("Diagnostics are valid JSON")
+}
@@ -0,0 +1,216 @@
+//! The `run` command, which plays Yarn files in the terminal.
+//!
+//! Options are chosen by entering their number. When `--json` is passed, every [`DialogueEvent`] is printed as a JSON object on its own line,
+//! so that the dialogue can be driven by another program through stdin and stdout.
+
+use crate::error::CliError;
+use crate::files::InputArgs;
+use crate::output::Output;
+use clap::Args;
+use serde_json::{json, Value};
+use std::io::{BufRead, IsTerminal};
+use yarnspinner::prelude::*;
+use yarnspinner::runtime::{Command, MemoryVariableStorage, StringTableTextProvider};
+
+#[derive(Debug, Args)]
+pub(crate) struct RunArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// The node to start the dialogue at.
+    #[arg(short, long, default_value = "Start")]
+    start: String,
+}
+
+pub(crate) fn run(args: &RunArgs, output: &Output) -> Result<(), CliError> {
+    let compilation = args.input.compile(CompilationType::FullCompilation)?;
+    output.warnings(&compilation.warnings);
+
+    let string_table = compilation
+        .string_table
+        .into_iter()
+        .map(|(id, string_info)| (id, string_info.text))
+        .collect();
+    let mut text_provider = StringTableTextProvider::new();
+    text_provider.extend_base_language(string_table);
+    let mut dialogue = Dialogue::new(
+        Box::new(MemoryVariableStorage::new()),
+        Box::new(text_provider),
+    );
+    dialogue.add_program(
+        compilation
+            .program
+            .expect("A full compilation always produces a program"),
+    );
+    dialogue.set_node(&args.start)?;
+
+    let stdin = std::io::stdin();
+    let mut player = Player {
+        json: output.json,
+        // Waiting for the player to read each line only makes sense if there is one
+        wait_after_lines: !output.json && stdin.is_terminal(),
+        input: stdin.lock(),
+    };
+    loop {
+        for event in dialogue.continue_()? {
+            match event {
+                DialogueEvent::Line(line) => player.line(&line),
+                DialogueEvent::Options(options) => {
+                    let option = player.choose(&options)?;
+                    dialogue.set_selected_option(option)?;
+                }
+                DialogueEvent::Command(command) => player.command(&command),
+                DialogueEvent::NodeStart(node) => {
+                    player.event(json!({ "event": "node_start", "node": node }))
+                }
+                DialogueEvent::NodeComplete(node) => {
+                    player.event(json!({ "event": "node_complete", "node": node }))
+                }
+                DialogueEvent::LineHints(_) => {}
+                DialogueEvent::DialogueComplete => {
+                    player.event(json!({ "event": "dialogue_complete" }));
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+struct Player<R> {
+    json: bool,
+    wait_after_lines: bool,
+    input: R,
+}
+
+impl<R: BufRead> Player<R> {
+    fn line(&mut self, line: &YarnLine) {
+        if self.json {
+            self.event(json!({ "event": "line", "id": line.id.0, "text": line.text }));
+            return;
+        }
+        println!("{}", line.text);
+        if self.wait_after_lines {
+            // Reaching the end of the input simply stops waiting
+            let _ = self.read_line();
+        }
+    }
+
+    fn command(&mut self, command: &Command) {
+        if self.json {
+            let parameters: Vec<_> = command.parameters.iter().map(ToString::to_string).collect();
+            self.event(json!({
+                "event": "command",
+                "name": command.name,
+                "parameters": parameters,
+            }));
+        } else {
+            println!("<<{}>>", command.raw);
+        }
+    }
+
+    /// Asks for the number of an option until an available one is chosen.
+    fn choose(&mut self, options: &[DialogueOption]) -> Result<OptionId, CliError> {
+        if self.json {
+            let options: Vec<_> = options
+                .iter()
+                .enumerate()
+                .map(|(index, option)| {
+                    json!({
+                        "number": index + 1,
+                        "id": option.line.id.0,
+                        "text": option.line.text,
+                        "is_available": option.is_available,
+                    })
+                })
+                .collect();
+            self.event(json!({ "event": "options", "options": options }));
+        } else {
+            for (index, option) in options.iter().enumerate() {
+                let unavailable = if option.is_available {
+                    ""
+                } else {
+                    " (unavailable)"
+                };
+                println!("  {}. {}{unavailable}", index + 1, option.line.text);
+            }
+        }
+
+        loop {
+            let input = self.read_line().ok_or(CliError::InputEnded)?;
+            let chosen = input
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| options.get(number.checked_sub(1)?))
+                .filter(|option| option.is_available);
+            match chosen {
+                Some(option) => return Ok(option.id),
+                None => eprintln!(
+                    "Please enter the number of an available option between 1 and {}",
+                    options.len()
+                ),
+            }
+        }
+    }
+
+    fn event(&self, event: Value) {
+        if self.json {
+            println!("{event}");
+        }
+    }
+
+    /// Returns `None` at the end of the input.
+    fn read_line(&mut self) -> Option<String> {
+        let mut line = String::new();
+        match self.input.read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn option(id: usize, is_available: bool) -> DialogueOption {
+        DialogueOption {
+            line: YarnLine {
+                id: LineId(format!("line:{id}")),
+                text: format!("Option {id}"),
+                attributes: Vec::new(),
+                language: None,
+            },
+            id: OptionId(id),
+            destination_node: String::new(),
+            is_available,
+        }
+    }
+
+    #[test]
+    fn chooses_first_valid_available_option() {
+        let mut player = Player {
+            json: false,
+            wait_after_lines: false,
+            input: "x\n5\n1\n2\n".as_bytes(),
+        };
+        let options = [option(0, false), option(1, true)];
+
+        let chosen = player.choose(&options).unwrap();
+
+        assert_eq!(OptionId(1), chosen);
+    }
+
+    #[test]
+    fn fails_when_input_ends_before_choice() {
+        let mut player = Player {
+            json: true,
+            wait_after_lines: false,
+            input: "".as_bytes(),
+        };
+
+        let result = player.choose(&[option(0, true)]);
+
+        assert!(matches!(result, Err(CliError::InputEnded)));
+    }
+}
@@ -0,0 +1,45 @@
+//! The `dump-strings` command, which prints the string table of Yarn files.
+
+use crate::error::CliError;
+use crate::files::{self, InputArgs};
+use crate::output::Output;
+use clap::Args;
+use serde_json::json;
+use yarnspinner::prelude::*;
+
+#[derive(Debug, Args)]
+pub(crate) struct DumpStringsArgs {
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+pub(crate) fn dump_strings(args: &DumpStringsArgs, output: &Output) -> Result<(), CliError> {
+    let compilation = args.input.compile(CompilationType::StringsOnly)?;
+    let string_table = files::sorted_string_table(&compilation);
+    let strings: Vec<_> = string_table
+        .iter()
+        .map(|(id, string_info)| {
+            json!({
+                "id": id.0,
+                "text": string_info.text,
+                "file": string_info.file_name,
+                "node": string_info.node_name,
+                "line_number": string_info.line_number,
+                "is_implicit_tag": string_info.is_implicit_tag,
+                "tags": files::tags(string_info),
+            })
+        })
+        .collect();
+    let human: Vec<_> = string_table
+        .iter()
+        .map(|(id, string_info)| {
+            format!(
+                "{}:{}\t{id}\t{}",
+                string_info.file_name, string_info.line_number, string_info.text
+            )
+        })
+        .collect();
+    output.warnings(&compilation.warnings);
+    output.success(json!({ "strings": strings }), &human.join("\n"));
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! The `tag` command, which adds `#line:` tags to untagged lines like the `ysc tag` command of the original Yarn Spinner.
+
+use crate::error::CliError;
+use crate::files::{self, InputArgs};
+use crate::output::Output;
+use clap::{Args, ValueEnum};
+use serde_json::json;
+use yarnspinner::compiler::{ContentHashLineIds, LineIdStrategy, RandomLineIds, SequentialLineIds};
+use yarnspinner::prelude::*;
+
+#[derive(Debug, Args)]
+pub(crate) struct TagArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// How the IDs of the new tags are generated.
+    #[arg(short, long, value_enum, default_value_t = TagStrategy::Random)]
+    strategy: TagStrategy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TagStrategy {
+    /// Random numbers, e.g. `#line:8124035`.
+    Random,
+    /// A hash of the file name, node name and line text, e.g. `#line:3f9a0c21`, which is the same on every run.
+    ContentHash,
+    /// The node name and a number, e.g. `#line:Start_001`.
+    Sequential,
+}
+
+impl TagStrategy {
+    fn line_id_strategy(self) -> &'static dyn LineIdStrategy {
+        match self {
+            Self::Random => &RandomLineIds,
+            Self::ContentHash => &ContentHashLineIds,
+            Self::Sequential => &SequentialLineIds,
+        }
+    }
+}
+
+pub(crate) fn tag(args: &TagArgs, output: &Output) -> Result<(), CliError> {
+    let paths = args.input.find_yarn_files()?;
+    let files = paths
+        .iter()
+        .map(|path| files::read_yarn_file(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    // New tags must be unique across all files, not just within the file they are added to
+    let mut existing_line_tags = explicit_line_ids(files.clone())?;
+
+    let mut tagged_files = Vec::new();
+    for (path, file) in paths.iter().zip(files) {
+        let Some(tagged_source) = YarnCompiler::add_tags_to_lines_with_strategy(
+            file.clone(),
+            existing_line_tags.clone(),
+            args.strategy.line_id_strategy(),
+        )?
+        else {
+            continue;
+        };
+        std::fs::write(path, &tagged_source).map_err(CliError::io(path))?;
+        let tagged_file = YarnFile {
+            source: tagged_source,
+            ..file
+        };
+        existing_line_tags.extend(explicit_line_ids([tagged_file])?);
+        tagged_files.push(path.display().to_string());
+    }
+
+    let human = if tagged_files.is_empty() {
+        "All lines are already tagged".to_owned()
+    } else {
+        tagged_files
+            .iter()
+            .map(|file| format!("Tagged {file}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    output.success(json!({ "tagged_files": tagged_files }), &human);
+    Ok(())
+}
+
+/// Returns the IDs of all lines that have a `#line:` tag.
+fn explicit_line_ids(files: impl IntoIterator<Item = YarnFile>) -> Result<Vec<LineId>, CliError> {
+    let compilation = YarnCompiler::new()
+        .add_files(files)
+        .with_compilation_type(CompilationType::StringsOnly)
+        .compile()?;
+    let line_ids = compilation
+        .string_table
+        .into_iter()
+        .filter(|(_, string_info)| !string_info.is_implicit_tag)
+        .map(|(line_id, _)| line_id)
+        .collect();
+    Ok(line_ids)
+}
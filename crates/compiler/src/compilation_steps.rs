@@ -11,6 +11,7 @@ mod parse_files;
 mod register_initial_variables;
 mod register_strings;
 mod resolve_deferred_type_diagnostic;
+mod validate_jump_targets;
 mod validate_unique_node_names;
 
 pub(crate) use self::{
@@ -18,5 +19,5 @@ pub(crate) use self::{
     clean_up_diagnostics::*, create_declarations_for_tracking_nodes::*, early_breaks::*,
     find_tracking_nodes::*, generate_code::*, get_declarations::*, parse_files::*,
     register_initial_variables::*, register_strings::*, resolve_deferred_type_diagnostic::*,
-    validate_unique_node_names::*,
+    validate_jump_targets::*, validate_unique_node_names::*,
 };
@@ -19,11 +19,18 @@ pub(crate) fn add_initial_value_registrations(
 
     for declaration in declarations {
         let Some(default_value) = declaration.default_value.clone() else {
-            state.diagnostics.push(Diagnostic::from_message(format!(
-                "Variable declaration {} (type {}) has a null default value. This is not allowed.",
-                declaration.name,
-                declaration.r#type.format()
-            )));
+            state.diagnostics.push(
+                Diagnostic::new(
+                    DiagnosticCode::MissingDefaultValue,
+                    format!(
+                        "Variable declaration {} (type {}) has a null default value. This is not allowed.",
+                        declaration.name,
+                        declaration.r#type.format()
+                    ),
+                )
+                .with_variable(declaration.name.clone())
+                .with_actual_types([declaration.r#type.format()]),
+            );
             continue;
         };
         if let Some(ref mut program) = compilation.program {
@@ -4,8 +4,11 @@ use antlr_rust::tree::ParseTreeVisitorCompat;
 
 pub(crate) fn check_types(mut state: CompilationIntermediate) -> CompilationIntermediate {
     for (file, known_types) in &mut state.parsed_files {
-        let mut visitor =
-            TypeCheckVisitor::new(state.known_variable_declarations.clone(), file.clone());
+        let mut visitor = TypeCheckVisitor::new(
+            state.known_variable_declarations.clone(),
+            state.job.manifest.clone(),
+            file.clone(),
+        );
         visitor.visit(file.tree.as_ref());
         state
             .known_variable_declarations
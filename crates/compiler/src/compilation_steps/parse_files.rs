@@ -1,8 +1,16 @@
 use crate::prelude::*;
+use std::rc::Rc;
 
 pub(crate) fn parse_files(mut state: CompilationIntermediate) -> CompilationIntermediate {
-    for (file, chars) in state.job.files.iter().zip(state.file_chars.iter()) {
-        let parse_result = parse_syntax_tree(file, chars, &mut state.diagnostics);
+    for ((file, chars), format_specifiers) in state
+        .job
+        .files
+        .iter()
+        .zip(state.file_chars.iter())
+        .zip(state.file_format_specifiers.iter())
+    {
+        let mut parse_result = parse_syntax_tree(file, chars, &mut state.diagnostics);
+        parse_result.format_specifiers = Rc::new(format_specifiers.clone());
         state.parsed_files.push((parse_result, Default::default()));
     }
     state
@@ -11,6 +11,7 @@ pub(crate) fn register_initial_variables(
     variables.extend(standard_library_declarations);
     let job_library_declarations = get_declarations_from_library(&state.job.library);
     variables.extend(job_library_declarations);
+    variables.extend(state.job.manifest.function_declarations());
 
     state
 }
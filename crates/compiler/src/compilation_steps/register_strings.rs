@@ -1,8 +1,13 @@
 use crate::prelude::*;
 use crate::visitors::{LastLineBeforeOptionsVisitor, StringTableGeneratorVisitor};
 use antlr_rust::tree::ParseTreeVisitorCompat;
+use yarnspinner_core::markup::MarkupValidator;
 
 pub(crate) fn register_strings(mut state: CompilationIntermediate) -> CompilationIntermediate {
+    let markup_validator = match &state.job.known_markup_markers {
+        Some(known_markers) => MarkupValidator::new().with_known_markers(known_markers.clone()),
+        None => MarkupValidator::new(),
+    };
     // First pass: parse all files, generate their syntax trees,
     // and figure out what variables they've declared
     for (file, _) in &state.parsed_files {
@@ -12,8 +17,11 @@ pub(crate) fn register_strings(mut state: CompilationIntermediate) -> Compilatio
         let mut last_line_tagger = LastLineBeforeOptionsVisitor::default();
         last_line_tagger.visit(file.tree.as_ref());
 
-        let mut visitor =
-            StringTableGeneratorVisitor::new(state.string_table.clone(), file.clone());
+        let mut visitor = StringTableGeneratorVisitor::new(
+            state.string_table.clone(),
+            file.clone(),
+            markup_validator.clone(),
+        );
         visitor.visit(file.tree.as_ref());
         state.diagnostics.extend(visitor.diagnostics);
         state.string_table.extend(visitor.string_table_manager);
@@ -51,9 +51,13 @@ pub(crate) fn validate_unique_node_names(
         // More than one node has this name! Report an error on both.
         for (header_context, file) in nodes {
             state.diagnostics.push(
-                Diagnostic::from_message(format!("More than one node is named {name}",))
-                    .with_file_name(file.name.clone())
-                    .with_parser_context(header_context.as_ref(), file.tokens()),
+                Diagnostic::new(
+                    DiagnosticCode::DuplicateNodeName,
+                    format!("More than one node is named {name}"),
+                )
+                .with_node(name.clone())
+                .with_file_name(file.name.clone())
+                .with_parser_context(header_context.as_ref(), file.tokens()),
             );
         }
     }
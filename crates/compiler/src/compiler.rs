@@ -1,13 +1,17 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/Compiler.cs>
 //! and <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/CompilationJob.cs>
 
+pub use self::tokenize::{SyntaxToken, SyntaxTokenKind};
 use crate::prelude::*;
 use std::path::Path;
+use std::sync::Arc;
 use yarnspinner_core::prelude::*;
 
 mod add_tags_to_lines;
 pub(crate) mod antlr_rust_ext;
+mod format;
 pub(crate) mod run_compilation;
+mod tokenize;
 pub(crate) mod utils;
 
 #[allow(missing_docs)]
@@ -31,6 +35,7 @@ pub type Result<T> = std::result::Result<T, CompilerError>;
 )]
 pub struct Compiler {
     /// The [`File`] structs that represent the content to parse..
+    /// The files they include with `#include:` file tags are resolved with the [`Compiler::file_resolver`] and compiled as well.
     pub files: Vec<File>,
 
     /// The [`Library`] that contains declarations for functions.
@@ -43,6 +48,17 @@ pub struct Compiler {
 
     /// The declarations for variables.
     pub variable_declarations: Vec<Declaration>,
+
+    /// See [`Compiler::known_markup_markers`].
+    pub(crate) known_markup_markers: Option<Vec<String>>,
+
+    /// See [`Compiler::manifest`].
+    pub(crate) manifest: Manifest,
+
+    /// See [`Compiler::file_resolver`].
+    #[cfg_attr(feature = "bevy", reflect(ignore))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) file_resolver: Option<SharedFileResolver>,
 }
 
 impl Compiler {
@@ -97,6 +113,54 @@ impl Compiler {
         self
     }
 
+    /// Declares names of markup markers that the game handles. Once called, every other marker in a line produces a warning.
+    /// See [`Compiler::known_markup_markers`].
+    pub fn declare_markup_markers(
+        &mut self,
+        names: impl IntoIterator<Item = impl Into<String>>,
+    ) -> &mut Self {
+        self.known_markup_markers
+            .get_or_insert_with(Vec::new)
+            .extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// The names of the markup markers the game handles, e.g. `wave` for `[wave]Hi[/wave]`, as declared with [`Compiler::declare_markup_markers`].
+    /// If set, markers with other names are reported as warnings, as they are likely typos.
+    /// The [`BUILT_IN_MARKERS`](yarnspinner_core::markup::BUILT_IN_MARKERS) are always allowed.
+    pub fn known_markup_markers(&self) -> Option<&[String]> {
+        self.known_markup_markers.as_deref()
+    }
+
+    /// Declares the functions and commands of the given [`Manifest`], so that their usages are type checked.
+    /// See [`Compiler::manifest`].
+    pub fn extend_manifest(&mut self, manifest: Manifest) -> &mut Self {
+        self.manifest.extend(manifest);
+        self
+    }
+
+    /// The functions and commands the game provides, e.g. loaded from a `.ysls.json` file, as declared with [`Compiler::extend_manifest`].
+    /// Unlike the [`Compiler::library`], this only declares signatures and also covers commands.
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Sets the [`FileResolver`] that provides the files included with `#include:` file tags. See [`Compiler::file_resolver`].
+    pub fn with_file_resolver(&mut self, file_resolver: impl FileResolver + 'static) -> &mut Self {
+        self.file_resolver = Some(SharedFileResolver(Arc::new(file_resolver)));
+        self
+    }
+
+    /// Provides the files included with `#include:` file tags, e.g. `#include:common.yarn` above the first node of a file, as set with [`Compiler::with_file_resolver`].
+    /// If `None`, files can only include files that were added to the [`Compiler`] as well. Use [`FileSystemResolver`] to read them from disk instead.
+    ///
+    /// Includes are not resolved for [`CompilationType::StringsOnly`], as the string table is then used to work on the lines of the given files.
+    pub fn file_resolver(&self) -> Option<&dyn FileResolver> {
+        self.file_resolver
+            .as_ref()
+            .map(|resolver| resolver.0.as_ref())
+    }
+
     /// Compiles the Yarn files previously added into a [`Compilation`].
     pub fn compile(&self) -> Result<Compilation> {
         run_compilation::compile(self)
@@ -28,17 +28,32 @@ impl Compiler {
     /// ## Return value
     /// Returns he modified source code, with line tags added.
     /// If all nodes already have line tags, returns `None`.
+    ///
+    /// The tags are random numbers, see [`Compiler::add_tags_to_lines_with_strategy`] for other kinds of tags.
     pub fn add_tags_to_lines(
         contents: impl Into<String>,
         existing_line_tags: Vec<LineId>,
     ) -> crate::Result<Option<String>> {
-        let contents = contents.into();
-        let chars: Vec<_> = contents.chars().map(|c| c as u32).collect();
-        // First, get the parse tree for this source code.
         let file = File {
             file_name: "<input>".to_string(),
-            source: contents,
+            source: contents.into(),
         };
+        Self::add_tags_to_lines_with_strategy(file, existing_line_tags, &RandomLineIds)
+    }
+
+    /// Like [`Compiler::add_tags_to_lines`], but generates the line tags with the given [`LineIdStrategy`],
+    /// e.g. [`ContentHashLineIds`] to get the same tags for the same lines on different branches.
+    /// The strategy is passed the [`File::file_name`] of `file`.
+    pub fn add_tags_to_lines_with_strategy(
+        file: File,
+        existing_line_tags: Vec<LineId>,
+        strategy: &dyn LineIdStrategy,
+    ) -> crate::Result<Option<String>> {
+        let contents = file.source.clone();
+        let mut chars: Vec<_> = contents.chars().map(|c| c as u32).collect();
+        // Format specifiers are not part of the grammar, see `extract_format_specifiers`
+        extract_format_specifiers(&mut chars);
+        // First, get the parse tree for this source code.
         let (parse_source, diagnostics) = parse_source(&file, &chars);
         let tree = parse_source.tree.clone();
         // Were there any error-level diagnostics?
@@ -48,8 +63,12 @@ impl Compiler {
         }
 
         // Create the line listener, which will produce TextReplacements for each new line tag.
-        let untagged_line_listener =
-            Box::new(UntaggedLineListener::new(existing_line_tags, parse_source));
+        let untagged_line_listener = Box::new(UntaggedLineListener::new(
+            existing_line_tags,
+            parse_source,
+            &contents,
+            strategy,
+        ));
         let rewritten_nodes = untagged_line_listener.rewritten_lines.clone();
         let rewrote_anything = untagged_line_listener.rewrote_anything.clone();
 
@@ -0,0 +1,613 @@
+use crate::prelude::generated::{yarnspinnerlexer, yarnspinnerparser::*};
+use crate::prelude::*;
+use crate::Result;
+use antlr_rust::parser_rule_context::ParserRuleContext;
+use antlr_rust::token::Token;
+use antlr_rust::tree::TerminalNode;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use yarnspinner_core::prelude::*;
+
+/// The indentation used for the bodies of options and `<<if>>` statements.
+const INDENTATION: &str = "    ";
+
+impl Compiler {
+    /// Formats the Yarn files previously added into a canonical layout, so that diffs only contain actual changes.
+    /// The formatted files are returned in the same order as [`Compiler::files`].
+    ///
+    /// The formatter normalizes:
+    /// - the layout of node headers, e.g. `title:Start` becomes `title: Start`
+    /// - the indentation of option blocks and `<<if>>` bodies to four spaces per level
+    /// - the spacing inside of built-in commands such as `<<set>>` and inside of expressions, e.g. `{$gold+1}` becomes `{$gold + 1}`
+    /// - the placement of conditions and hashtags such as `#line:` tags, which follow the line separated by single spaces
+    /// - blank lines, of which at most one is kept in a row and exactly one separates nodes
+    ///
+    /// Comments are preserved. The text of lines and of custom commands is never changed, as it is part of the compiled output.
+    ///
+    /// ## Errors
+    ///
+    /// Only files that compile can be formatted, so compilation errors are returned as they are.
+    /// As a safety net, the formatted files are compiled as well. If their [`Program`] or string table differ in anything
+    /// but line numbers from the ones of the original files, an error is returned instead of the formatted files.
+    pub fn format(&self) -> Result<Vec<File>> {
+        let mut compiler = self.clone();
+        compiler.compilation_type = CompilationType::FullCompilation;
+        let original = compiler.compile()?;
+
+        let formatted_files = compiler
+            .files
+            .iter()
+            .map(format_file)
+            .collect::<Result<Vec<_>>>()?;
+        compiler.files.clone_from(&formatted_files);
+        match compiler.compile() {
+            Ok(formatted) if compiles_to_same_output(&original, &formatted) => Ok(formatted_files),
+            _ => Err(CompilerError(vec![Diagnostic::new(DiagnosticCode::FormattingChangesOutput,
+                "Formatting would change what the Yarn files compile to, so they were left as they are. \
+                This is a bug. Please report it at https://github.com/YarnSpinnerTool/YarnSpinner-Rust/issues/new",
+            )
+            .with_severity(DiagnosticSeverity::Error)])),
+        }
+    }
+}
+
+fn compiles_to_same_output(original: &Compilation, formatted: &Compilation) -> bool {
+    // Line numbers are expected to change when formatting
+    let without_line_numbers = |compilation: &Compilation| -> HashMap<LineId, StringInfo> {
+        compilation
+            .string_table
+            .iter()
+            .map(|(line_id, string_info)| {
+                let string_info = StringInfo {
+                    line_number: 0,
+                    ..string_info.clone()
+                };
+                (line_id.clone(), string_info)
+            })
+            .collect()
+    };
+    original.program == formatted.program
+        && without_line_numbers(original) == without_line_numbers(formatted)
+}
+
+fn format_file(file: &File) -> Result<File> {
+    let source: Vec<char> = file.source.chars().collect();
+    let mut chars: Vec<u32> = source.iter().map(|&c| c as u32).collect();
+    // Format specifiers are not part of the grammar, see `extract_format_specifiers`
+    let format_specifiers = extract_format_specifiers(&mut chars);
+    let mut diagnostics = Vec::new();
+    let parse_result = parse_syntax_tree(file, &chars, &mut diagnostics);
+    if diagnostics.has_errors() {
+        return Err(CompilerError(diagnostics));
+    }
+
+    let tokens: Vec<_> = parse_result
+        .tokens()
+        .get_tokens()
+        .iter()
+        .map(|token| SourceToken {
+            token_type: token.get_token_type(),
+            channel: token.get_channel(),
+            start: token.get_start(),
+            stop: token.get_stop(),
+            line: token.get_line_as_usize().saturating_sub(1),
+        })
+        .collect();
+    let mut formatter = Formatter::new(&file.source, &source, tokens, &format_specifiers);
+    formatter.format_dialogue(&parse_result.tree);
+    Ok(File {
+        file_name: file.file_name.clone(),
+        source: formatter.finish(),
+    })
+}
+
+/// The parts of a token the formatter needs, copied out of the token stream.
+#[derive(Debug, Clone, Copy)]
+struct SourceToken {
+    token_type: isize,
+    channel: isize,
+    /// The index of the first character of this token in the source.
+    start: isize,
+    /// The index of the last character of this token in the source. Smaller than `start` for tokens without text.
+    stop: isize,
+    /// The zero-based line of this token.
+    line: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Comment {
+    line: usize,
+    text: String,
+}
+
+/// Prints a parse tree in the canonical layout, one source line at a time.
+///
+/// The parse tree determines the layout, i.e. which statements go on which line and how deeply they are indented.
+/// The content of the lines is printed from the tokens instead, which includes the comments the parser never sees.
+struct Formatter<'a> {
+    source_lines: Vec<&'a str>,
+    /// The original source, including the format specifiers that were cut out before parsing.
+    source: &'a [char],
+    tokens: Vec<SourceToken>,
+    format_specifiers: &'a FormatSpecifiers,
+    comments: VecDeque<Comment>,
+    output: Vec<String>,
+    depth: usize,
+    /// The source line that the last printed line came from.
+    last_line: Option<usize>,
+    force_blank_line: bool,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(
+        source: &'a str,
+        chars: &'a [char],
+        tokens: Vec<SourceToken>,
+        format_specifiers: &'a FormatSpecifiers,
+    ) -> Self {
+        let mut formatter = Self {
+            source_lines: source.lines().collect(),
+            source: chars,
+            tokens,
+            format_specifiers,
+            comments: VecDeque::new(),
+            output: Vec::new(),
+            depth: 0,
+            last_line: None,
+            force_blank_line: false,
+        };
+        formatter.comments = formatter
+            .tokens
+            .iter()
+            .filter(|token| token.channel == yarnspinnerlexer::COMMENTS as isize)
+            .map(|token| Comment {
+                line: token.line,
+                text: formatter.text(token).trim().to_owned(),
+            })
+            .collect();
+        formatter
+    }
+
+    fn finish(mut self) -> String {
+        self.flush_comments_before(usize::MAX);
+        let mut output = self.output.join("\n");
+        output.push('\n');
+        output
+    }
+
+    fn format_dialogue(&mut self, dialogue: &DialogueContextAll) {
+        let file_hashtags = dialogue.file_hashtag_all();
+        for hashtag in &file_hashtags {
+            let (start, stop) = token_range(hashtag.as_ref());
+            let line = self.tokens[start].line;
+            let text = self.render(start, stop);
+            self.emit(line, text, true);
+        }
+        for (index, node) in dialogue.node_all().iter().enumerate() {
+            self.force_blank_line = index > 0 || !file_hashtags.is_empty();
+            self.format_node(node);
+        }
+    }
+
+    fn format_node(&mut self, node: &NodeContextAll) {
+        let mut is_raw_text = false;
+        for (index, header) in node.header_all().iter().enumerate() {
+            let key = header.header_key.as_ref().unwrap();
+            let key_text = self.text(&self.tokens[key.get_token_index() as usize]);
+            let value_text = header
+                .header_value
+                .as_ref()
+                .map(|value| self.text(&self.tokens[value.get_token_index() as usize]))
+                .unwrap_or_default();
+            if key_text == "tags" {
+                is_raw_text |= value_text.split(' ').any(|tag| tag == "rawText");
+            }
+            // The value is kept exactly as written, as even trailing whitespace is part of the compiled header
+            let text = if value_text.is_empty() {
+                format!("{key_text}:")
+            } else {
+                format!("{key_text}: {value_text}")
+            };
+            let line = key.get_line_as_usize().saturating_sub(1);
+            self.emit(line, text, index == 0);
+        }
+
+        let body_start = node.BODY_START().unwrap().symbol.get_line_as_usize() - 1;
+        self.emit(body_start, "---".to_owned(), false);
+        let body_end = node.BODY_END().unwrap().symbol.get_line_as_usize() - 1;
+        if is_raw_text {
+            // The body of a raw text node is a single string in the string table, so it must not change at all
+            for line in body_start + 1..body_end {
+                self.output.push(self.source_lines[line].to_owned());
+            }
+            self.comments.retain(|comment| comment.line >= body_end);
+            self.last_line = Some(body_end.saturating_sub(1));
+        } else if let Some(body) = node.body() {
+            self.format_statements(&body.statement_all());
+        }
+        self.flush_comments_before(body_end);
+        self.emit(body_end, "===".to_owned(), false);
+    }
+
+    fn format_statements(&mut self, statements: &[Rc<StatementContextAll>]) {
+        for statement in statements {
+            self.format_statement(statement);
+        }
+    }
+
+    fn format_statement(&mut self, statement: &StatementContextAll) {
+        if let Some(if_statement) = statement.if_statement() {
+            self.format_if_statement(&if_statement);
+        } else if let Some(options) = statement.shortcut_option_statement() {
+            for option in options.shortcut_option_all() {
+                let (start, _) = token_range(option.as_ref());
+                let (_, stop) = token_range(option.line_statement().unwrap().as_ref());
+                self.emit_range(start, stop);
+                self.format_block(&option.statement_all());
+            }
+        } else if statement.INDENT().is_some() {
+            // An indented block that does not belong to an option, which is kept as indented
+            self.format_block(&statement.statement_all());
+        } else {
+            // All other statements fit on a single line
+            let (start, stop) = token_range(statement);
+            self.emit_range(start, stop);
+        }
+    }
+
+    fn format_if_statement(&mut self, if_statement: &If_statementContextAll) {
+        let if_clause = if_statement.if_clause().unwrap();
+        self.emit_clause(
+            &if_clause.COMMAND_START().unwrap(),
+            &if_clause.COMMAND_END().unwrap(),
+        );
+        self.format_block(&if_clause.statement_all());
+        for else_if_clause in if_statement.else_if_clause_all() {
+            self.emit_clause(
+                &else_if_clause.COMMAND_START().unwrap(),
+                &else_if_clause.COMMAND_END().unwrap(),
+            );
+            self.format_block(&else_if_clause.statement_all());
+        }
+        if let Some(else_clause) = if_statement.else_clause() {
+            self.emit_clause(
+                &else_clause.COMMAND_START().unwrap(),
+                &else_clause.COMMAND_END().unwrap(),
+            );
+            self.format_block(&else_clause.statement_all());
+        }
+        self.emit_clause(
+            &if_statement.COMMAND_START().unwrap(),
+            &if_statement.COMMAND_END().unwrap(),
+        );
+    }
+
+    fn format_block(&mut self, statements: &[Rc<StatementContextAll>]) {
+        self.depth += 1;
+        self.format_statements(statements);
+        self.depth -= 1;
+    }
+
+    fn emit_clause(
+        &mut self,
+        command_start: &TerminalNode<'_, YarnSpinnerParserContextType>,
+        command_end: &TerminalNode<'_, YarnSpinnerParserContextType>,
+    ) {
+        self.emit_range(
+            command_start.symbol.get_token_index() as usize,
+            command_end.symbol.get_token_index() as usize,
+        );
+    }
+
+    fn emit_range(&mut self, start: usize, stop: usize) {
+        let line = self.tokens[start].line;
+        let text = self.render(start, stop);
+        self.emit(line, text, true);
+    }
+
+    /// Prints a line, preceded by the comments above it and followed by the comments next to it in the source.
+    fn emit(&mut self, line: usize, text: String, allow_blank_line: bool) {
+        self.flush_comments_before(line);
+        self.push_line(line, text, allow_blank_line);
+        while self
+            .comments
+            .front()
+            .is_some_and(|comment| comment.line == line)
+        {
+            let comment = self.comments.pop_front().unwrap();
+            let last_line = self.output.last_mut().unwrap();
+            last_line.push(' ');
+            last_line.push_str(&comment.text);
+        }
+    }
+
+    fn flush_comments_before(&mut self, line: usize) {
+        while self
+            .comments
+            .front()
+            .is_some_and(|comment| comment.line < line)
+        {
+            let comment = self.comments.pop_front().unwrap();
+            self.push_line(comment.line, comment.text, true);
+        }
+    }
+
+    fn push_line(&mut self, line: usize, text: String, allow_blank_line: bool) {
+        // Blank lines are kept where they were, since a blank line after an option ends its option group
+        let follows_blank_line = self.last_line.is_some_and(|last_line| line > last_line + 1);
+        let is_first_line = self.output.is_empty();
+        if !is_first_line && (self.force_blank_line || (allow_blank_line && follows_blank_line)) {
+            self.output.push(String::new());
+        }
+        self.force_blank_line = false;
+        self.output
+            .push(format!("{}{}", INDENTATION.repeat(self.depth), text));
+        self.last_line = Some(line);
+    }
+
+    /// Prints the tokens between `start` and `stop` (inclusive) as a single line.
+    fn render(&self, start: usize, stop: usize) -> String {
+        let tokens: Vec<usize> = (start..=stop)
+            .filter(|&index| {
+                let token = &self.tokens[index];
+                token.channel == 0
+                    && !matches!(
+                        token.token_type,
+                        NEWLINE | INDENT | DEDENT | BLANK_LINE_FOLLOWING_OPTION
+                    )
+            })
+            .collect();
+
+        let mut output = String::new();
+        let mut previous: Option<&SourceToken> = None;
+        let mut index = 0;
+        while index < tokens.len() {
+            let token = &self.tokens[tokens[index]];
+            index += 1;
+            match token.token_type {
+                SHORTCUT_ARROW => output.push_str("-> "),
+                HASHTAG => {
+                    truncate_trailing_whitespace(&mut output);
+                    output.push_str(" #");
+                }
+                HASHTAG_TEXT => output.push_str(&self.text(token)),
+                EXPRESSION_START | COMMAND_EXPRESSION_START => {
+                    let expression: Vec<_> = tokens[index..]
+                        .iter()
+                        .copied()
+                        .take_while(|&index| self.tokens[index].token_type != EXPRESSION_END)
+                        .collect();
+                    index += expression.len() + 1;
+                    if previous.is_some_and(|previous| previous.stop + 1 < token.start) {
+                        push_separator(&mut output);
+                    }
+                    output.push('{');
+                    output.push_str(&self.format_expression(&expression));
+                    let format_specifier = expression.first().and_then(|&first| {
+                        let start = usize::try_from(self.tokens[first].start).ok()?;
+                        self.format_specifiers.get(&start)
+                    });
+                    if let Some(format_specifier) = format_specifier {
+                        output.push(':');
+                        output.push_str(format_specifier);
+                    }
+                    output.push('}');
+                }
+                COMMAND_START if self.is_built_in_command(tokens.get(index)) => {
+                    let command: Vec<_> = tokens[index..]
+                        .iter()
+                        .copied()
+                        .take_while(|&index| self.tokens[index].token_type != COMMAND_END)
+                        .collect();
+                    index += command.len() + 1;
+                    // A condition follows the text of its line
+                    truncate_trailing_whitespace(&mut output);
+                    push_separator(&mut output);
+                    output.push_str("<<");
+                    output.push_str(self.text(&self.tokens[command[0]]).trim());
+                    if command.len() > 1 {
+                        output.push(' ');
+                        output.push_str(&self.format_expression(&command[1..]));
+                    }
+                    output.push_str(">>");
+                }
+                COMMAND_START => output.push_str("<<"),
+                COMMAND_TEXT_END => output.push_str(">>"),
+                _ => {
+                    // Text of lines and custom commands is kept as it is, as it ends up in the compiled output.
+                    // Whitespace between tokens that the lexer skipped is not part of any text, so a single space suffices.
+                    let is_separated =
+                        previous.is_some_and(|previous| previous.stop + 1 < token.start);
+                    if is_separated {
+                        push_separator(&mut output);
+                    }
+                    let text = self.text(token);
+                    if output.is_empty() || output.ends_with("-> ") {
+                        // Leading whitespace of a line is never part of its text
+                        output.push_str(text.trim_start());
+                    } else {
+                        output.push_str(&text);
+                    }
+                }
+            }
+            previous = tokens.get(index - 1).map(|&index| &self.tokens[index]);
+        }
+        output.trim().to_owned()
+    }
+
+    fn is_built_in_command(&self, next_token: Option<&usize>) -> bool {
+        next_token.is_some_and(|&index| {
+            matches!(
+                self.tokens[index].token_type,
+                COMMAND_IF
+                    | COMMAND_ELSEIF
+                    | COMMAND_ELSE
+                    | COMMAND_ENDIF
+                    | COMMAND_SET
+                    | COMMAND_CALL
+                    | COMMAND_DECLARE
+                    | COMMAND_JUMP
+            )
+        })
+    }
+
+    /// Prints the tokens of an expression with single spaces around binary operators and after commas.
+    fn format_expression(&self, tokens: &[usize]) -> String {
+        let mut output = String::new();
+        let mut previous_type = None;
+        let mut previous_is_unary_symbol = false;
+        for &index in tokens {
+            let token = &self.tokens[index];
+            let text = self.text(token);
+            let is_unary = match token.token_type {
+                OPERATOR_LOGICAL_NOT => true,
+                OPERATOR_MATHS_SUBTRACTION => !previous_type.is_some_and(ends_operand),
+                _ => false,
+            };
+            let needs_space = match (previous_type, token.token_type) {
+                (None, _) => false,
+                (_, RPAREN | COMMA | EXPRESSION_END) => false,
+                (Some(FUNC_ID), LPAREN) => false,
+                (Some(LPAREN | EXPRESSION_START | DOT), _) | (_, DOT) => false,
+                _ => !previous_is_unary_symbol,
+            };
+            if needs_space {
+                output.push(' ');
+            }
+            output.push_str(&text);
+            // `not` is a word and needs a space, unlike `!` and `-`
+            previous_is_unary_symbol = is_unary && text != "not";
+            previous_type = Some(token.token_type);
+        }
+        output
+    }
+
+    fn text(&self, token: &SourceToken) -> String {
+        let (Ok(start), Ok(stop)) = (usize::try_from(token.start), usize::try_from(token.stop))
+        else {
+            return String::new();
+        };
+        self.source
+            .get(start..=stop)
+            .map(|chars| chars.iter().collect())
+            .unwrap_or_default()
+    }
+}
+
+fn token_range<'input>(context: &impl ParserRuleContext<'input>) -> (usize, usize) {
+    (
+        context.start().get_token_index() as usize,
+        context.stop().get_token_index() as usize,
+    )
+}
+
+/// Whether a token of this type can be the last token of an operand, in which case a following `-` is a binary operator.
+fn ends_operand(token_type: isize) -> bool {
+    matches!(
+        token_type,
+        NUMBER | STRING | VAR_ID | ID | RPAREN | KEYWORD_TRUE | KEYWORD_FALSE | KEYWORD_NULL
+    )
+}
+
+fn push_separator(output: &mut String) {
+    if !output.is_empty() && !output.ends_with(' ') {
+        output.push(' ');
+    }
+}
+
+fn truncate_trailing_whitespace(output: &mut String) {
+    output.truncate(output.trim_end().len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(source: &str) -> String {
+        let formatted = Compiler::new()
+            .add_file(File {
+                file_name: "test.yarn".to_owned(),
+                source: source.to_owned(),
+            })
+            .format()
+            .unwrap();
+        formatted[0].source.clone()
+    }
+
+    #[test]
+    fn formats_to_canonical_layout() {
+        let source = "\
+title:Start
+tags:
+---
+  <<declare $gold=5>>
+<<set $gold to $gold*2+ -1>>
+
+
+Mae: You have {$gold+1} gold.   #line:a1
+-> Buy <<if $gold>=10>>#line:a2
+  <<set $gold -= 10>>
+        Mae: Thanks!
+->Leave
+<<if not visited(\"Shop\") and $gold>0>>
+<<wait 2>>
+        <<else>>
+    Goodbye.
+<<endif>>
+===
+title: Shop
+---
+<<jump Start>>
+===
+";
+        let expected = "\
+title: Start
+tags:
+---
+<<declare $gold = 5>>
+<<set $gold to $gold * 2 + -1>>
+
+Mae: You have {$gold + 1} gold. #line:a1
+-> Buy <<if $gold >= 10>> #line:a2
+    <<set $gold -= 10>>
+    Mae: Thanks!
+-> Leave
+<<if not visited(\"Shop\") and $gold > 0>>
+    <<wait 2>>
+<<else>>
+    Goodbye.
+<<endif>>
+===
+
+title: Shop
+---
+<<jump Start>>
+===
+";
+
+        assert_eq!(expected, format(source));
+        assert_eq!(expected, format(expected));
+    }
+
+    #[test]
+    fn preserves_comments_and_format_specifiers() {
+        let source = "\
+title: Start
+---
+// Above the line
+Mae: You owe me {  $debt :0.00 } gold. // Next to the line
+    // Before the end
+===
+";
+        let expected = "\
+title: Start
+---
+// Above the line
+Mae: You owe me {$debt:0.00} gold. // Next to the line
+// Before the end
+===
+";
+
+        assert_eq!(expected, format(source));
+    }
+}
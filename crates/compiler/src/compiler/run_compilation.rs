@@ -1,4 +1,5 @@
 use crate::compilation_steps::*;
+use crate::file_resolver::resolve_included_files;
 use crate::output::*;
 use crate::prelude::*;
 use crate::string_table_manager::StringTableManager;
@@ -14,6 +15,7 @@ pub(crate) fn compile(compiler: &Compiler) -> Result<Compilation> {
         &register_strings,
         &validate_unique_node_names,
         &break_on_job_with_only_strings,
+        &validate_jump_targets,
         &get_declarations,
         &check_types,
         &find_tracking_nodes,
@@ -25,7 +27,30 @@ pub(crate) fn compile(compiler: &Compiler) -> Result<Compilation> {
         &add_initial_value_registrations,
     ];
 
-    let chars: Vec<Vec<u32>> = compiler
+    // The string table is used to work on the lines of the given files, which is why it leaves out included files
+    let (included_files, include_diagnostics) =
+        if compiler.compilation_type == CompilationType::StringsOnly {
+            Default::default()
+        } else {
+            resolve_included_files(compiler)
+        };
+    let compiler_with_included_files;
+    let compiler = if included_files.is_empty() {
+        compiler
+    } else {
+        compiler_with_included_files = Compiler {
+            files: compiler
+                .files
+                .iter()
+                .cloned()
+                .chain(included_files)
+                .collect(),
+            ..compiler.clone()
+        };
+        &compiler_with_included_files
+    };
+
+    let (chars, format_specifiers): (Vec<Vec<u32>>, Vec<_>) = compiler
         .files
         .iter()
         .map(|file| {
@@ -36,11 +61,14 @@ pub(crate) fn compile(compiler: &Compiler) -> Result<Compilation> {
                 None => file.source.as_str(),
                 Some(sanitized_string) => sanitized_string,
             };
-            source.chars().map(|c| c as u32).collect()
+            let mut chars: Vec<_> = source.chars().map(|c| c as u32).collect();
+            let format_specifiers = extract_format_specifiers(&mut chars);
+            (chars, format_specifiers)
         })
-        .collect();
+        .unzip();
     let chars: Vec<_> = chars.iter().map(|c| c.as_slice()).collect();
-    let initial = CompilationIntermediate::from_job(compiler, chars);
+    let mut initial = CompilationIntermediate::from_job(compiler, chars, format_specifiers);
+    initial.diagnostics = include_diagnostics;
     let intermediate = compiler_steps.into_iter().fold(initial, |state, step| {
         if state.early_break {
             state
@@ -60,6 +88,8 @@ type CompilationStep = dyn Fn(CompilationIntermediate) -> CompilationIntermediat
 pub(crate) struct CompilationIntermediate<'input> {
     pub(crate) job: &'input Compiler,
     pub(crate) file_chars: Vec<&'input [u32]>,
+    /// The format specifiers cut out of each file in [`CompilationIntermediate::file_chars`]
+    pub(crate) file_format_specifiers: Vec<FormatSpecifiers>,
     pub(crate) result: Option<Result<Compilation>>,
     /// All variable declarations that we've encountered, PLUS the ones we knew about before
     pub(crate) known_variable_declarations: Vec<Declaration>,
@@ -75,10 +105,15 @@ pub(crate) struct CompilationIntermediate<'input> {
 }
 
 impl<'input> CompilationIntermediate<'input> {
-    pub(crate) fn from_job(compiler: &'input Compiler, chars: Vec<&'input [u32]>) -> Self {
+    pub(crate) fn from_job(
+        compiler: &'input Compiler,
+        chars: Vec<&'input [u32]>,
+        format_specifiers: Vec<FormatSpecifiers>,
+    ) -> Self {
         Self {
             job: compiler,
             file_chars: chars,
+            file_format_specifiers: format_specifiers,
             result: Default::default(),
             known_variable_declarations: Default::default(),
             derived_variable_declarations: Default::default(),
@@ -0,0 +1,212 @@
+use crate::prelude::generated::yarnspinnerlexer::{
+    self, YarnSpinnerLexer as GeneratedYarnSpinnerLexer,
+};
+use crate::prelude::*;
+use antlr_rust::input_stream::CodePoint32BitCharStream;
+use antlr_rust::token::{Token, TOKEN_EOF};
+use antlr_rust::TokenSource;
+use std::ops::Range;
+use yarnspinner_core::prelude::*;
+
+impl Compiler {
+    /// Splits Yarn source code into the tokens produced by the Yarn Spinner lexer, e.g. for syntax highlighting.
+    /// Whitespace and newlines are not included. Unlike [`Compiler::compile`], this never fails:
+    /// text the lexer does not understand is returned as [`SyntaxTokenKind::Error`].
+    pub fn tokenize(source: &str) -> Vec<SyntaxToken> {
+        // Using 32 bit codepoints so that columns count characters, like in the rest of the compiler
+        let chars: Vec<u32> = source.chars().map(|c| c as u32).collect();
+        let input = CodePoint32BitCharStream::new(&chars);
+        let mut lexer = GeneratedYarnSpinnerLexer::new(input);
+        lexer.remove_error_listeners();
+
+        let mut tokens = Vec::new();
+        let mut is_in_header = true;
+        loop {
+            let token = lexer.next_token();
+            let token_type = token.get_token_type();
+            if token_type == TOKEN_EOF {
+                break;
+            }
+            let kind = match token_type {
+                yarnspinnerlexer::ID if is_in_header => Some(SyntaxTokenKind::HeaderKey),
+                yarnspinnerlexer::BODY_START => {
+                    is_in_header = false;
+                    Some(SyntaxTokenKind::BodyDelimiter)
+                }
+                yarnspinnerlexer::BODY_END => {
+                    is_in_header = true;
+                    Some(SyntaxTokenKind::BodyDelimiter)
+                }
+                _ => SyntaxTokenKind::from_token_type(token_type),
+            };
+            let Some(kind) = kind else {
+                continue;
+            };
+            let text = token.get_text().to_owned();
+            if text.is_empty() {
+                continue;
+            }
+            let start = Position {
+                line: token.get_line_as_usize().saturating_sub(1),
+                character: token.get_column_as_usize(),
+            };
+            let end = Position {
+                line: start.line,
+                character: start.character + text.chars().count(),
+            };
+            tokens.push(SyntaxToken {
+                kind,
+                text,
+                range: start..end,
+            });
+        }
+        tokens
+    }
+}
+
+/// A token of Yarn source code, as returned by [`Compiler::tokenize`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SyntaxToken {
+    /// The category of the token.
+    pub kind: SyntaxTokenKind,
+    /// The source text of the token.
+    pub text: String,
+    /// The range of the token in the source code. Tokens never span multiple lines.
+    pub range: Range<Position>,
+}
+
+/// The category of a [`SyntaxToken`], grouping the token types of the lexer by how they are usually highlighted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SyntaxTokenKind {
+    /// The key of a node header, e.g. `title` in `title: Start`.
+    HeaderKey,
+    /// The value of a node header, e.g. `Start` in `title: Start`.
+    HeaderValue,
+    /// The delimiters of a node body, `---` and `===`.
+    BodyDelimiter,
+    /// A comment, e.g. `// TODO`.
+    Comment,
+    /// Text of a line or option.
+    Text,
+    /// A hashtag, e.g. `#line:a1b2c3`.
+    Hashtag,
+    /// A keyword, e.g. `if` in `<<if $x>>` or `true`.
+    Keyword,
+    /// An identifier that is not a variable or function, e.g. the node name in `<<jump Start>>`.
+    Identifier,
+    /// A variable, e.g. `$gold`.
+    Variable,
+    /// A function name, e.g. `visited` in `visited("Start")`.
+    Function,
+    /// A number literal.
+    Number,
+    /// A string literal.
+    String,
+    /// An operator, e.g. `+` or `and`.
+    Operator,
+    /// The text of a command that is not built into Yarn, e.g. `wait 2` in `<<wait 2>>`.
+    Command,
+    /// Punctuation, such as `<<`, `>>`, `{`, `}`, `->` or parentheses.
+    Punctuation,
+    /// Text that the lexer could not recognize.
+    Error,
+}
+
+impl SyntaxTokenKind {
+    fn from_token_type(token_type: isize) -> Option<Self> {
+        use yarnspinnerlexer::*;
+        let kind = match token_type {
+            ID => Self::Identifier,
+            BODY_START | BODY_END => Self::BodyDelimiter,
+            HEADER_DELIMITER
+            | SHORTCUT_ARROW
+            | COMMAND_START
+            | EXPRESSION_START
+            | COMMAND_EXPRESSION_START
+            | EXPRESSION_END
+            | COMMAND_END
+            | COMMAND_TEXT_END
+            | LPAREN
+            | RPAREN
+            | COMMA
+            | DOT => Self::Punctuation,
+            REST_OF_LINE => Self::HeaderValue,
+            COMMENT | TEXT_COMMENT | TEXT_COMMANDHASHTAG_COMMENT => Self::Comment,
+            TEXT | ESCAPED_ANY | TEXT_ESCAPE => Self::Text,
+            HASHTAG | HASHTAG_TEXT => Self::Hashtag,
+            KEYWORD_TRUE | KEYWORD_FALSE | KEYWORD_NULL | EXPRESSION_AS | COMMAND_IF
+            | COMMAND_ELSEIF | COMMAND_ELSE | COMMAND_SET | COMMAND_ENDIF | COMMAND_CALL
+            | COMMAND_DECLARE | COMMAND_JUMP | COMMAND_ENUM | COMMAND_CASE | COMMAND_ENDENUM
+            | COMMAND_LOCAL | TYPE_STRING | TYPE_NUMBER | TYPE_BOOL => Self::Keyword,
+            OPERATOR_ASSIGNMENT
+            | OPERATOR_LOGICAL_LESS_THAN_EQUALS
+            | OPERATOR_LOGICAL_GREATER_THAN_EQUALS
+            | OPERATOR_LOGICAL_EQUALS
+            | OPERATOR_LOGICAL_LESS
+            | OPERATOR_LOGICAL_GREATER
+            | OPERATOR_LOGICAL_NOT_EQUALS
+            | OPERATOR_LOGICAL_AND
+            | OPERATOR_LOGICAL_OR
+            | OPERATOR_LOGICAL_XOR
+            | OPERATOR_LOGICAL_NOT
+            | OPERATOR_MATHS_ADDITION_EQUALS
+            | OPERATOR_MATHS_SUBTRACTION_EQUALS
+            | OPERATOR_MATHS_MULTIPLICATION_EQUALS
+            | OPERATOR_MATHS_MODULUS_EQUALS
+            | OPERATOR_MATHS_DIVISION_EQUALS
+            | OPERATOR_MATHS_ADDITION
+            | OPERATOR_MATHS_SUBTRACTION
+            | OPERATOR_MATHS_MULTIPLICATION
+            | OPERATOR_MATHS_DIVISION
+            | OPERATOR_MATHS_MODULUS => Self::Operator,
+            STRING => Self::String,
+            FUNC_ID => Self::Function,
+            VAR_ID => Self::Variable,
+            NUMBER => Self::Number,
+            COMMAND_TEXT => Self::Command,
+            UNESCAPABLE_CHARACTER | TEXT_COMMANDHASHTAG_ERROR => Self::Error,
+            // Whitespace, newlines and the indentation tokens that only matter to the parser
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenizes_headers_and_commands() {
+        let tokens = Compiler::tokenize("title: Start\n---\n<<set $gold to 10>>\n===\n");
+        let kinds: Vec<_> = tokens
+            .iter()
+            .map(|token| (token.kind, token.text.trim()))
+            .collect();
+
+        for expected in [
+            (SyntaxTokenKind::HeaderKey, "title"),
+            (SyntaxTokenKind::HeaderValue, "Start"),
+            (SyntaxTokenKind::BodyDelimiter, "---"),
+            (SyntaxTokenKind::Keyword, "set"),
+            (SyntaxTokenKind::Variable, "$gold"),
+            (SyntaxTokenKind::Operator, "to"),
+            (SyntaxTokenKind::Number, "10"),
+            (SyntaxTokenKind::BodyDelimiter, "==="),
+        ] {
+            assert!(
+                kinds.contains(&expected),
+                "missing {expected:?} in {kinds:?}"
+            );
+        }
+        let gold = tokens
+            .iter()
+            .find(|token| token.kind == SyntaxTokenKind::Variable)
+            .unwrap();
+        assert_eq!(2, gold.range.start.line);
+        assert_eq!(6, gold.range.start.character);
+        assert_eq!(11, gold.range.end.character);
+    }
+}
@@ -49,8 +49,9 @@ pub(crate) fn parse_syntax_tree<'a, 'b: 'a>(
     lexer.add_error_listener(Box::new(lexer_error_listener));
 
     let tokens = CommonTokenStream::new(lexer);
-    let mut parser = YarnSpinnerParser::with_strategy(tokens, ErrorStrategy::new());
     let parser_error_listener = ParserErrorListener::new(file.clone());
+    let error_strategy = ErrorStrategy::new(parser_error_listener.error_code.clone());
+    let mut parser = YarnSpinnerParser::with_strategy(tokens, error_strategy);
     let parser_error_listener_diagnostics = parser_error_listener.diagnostics.clone();
 
     parser.remove_error_listeners();
@@ -68,9 +69,18 @@ pub(crate) fn parse_syntax_tree<'a, 'b: 'a>(
         .chain(lexer_diagnostics_borrowed.iter())
         .chain(parser_error_listener_diagnostics_borrowed.iter())
         .cloned();
+    let diagnostics_start = diagnostics.len();
     diagnostics.extend(new_diagnostics);
+    let syntax_error_lines = diagnostics[diagnostics_start..]
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+        .filter_map(|diagnostic| diagnostic.range.as_ref())
+        .map(|range| range.start.line)
+        .collect();
 
-    FileParseResult::new(file_name, tree, Rc::new(parser))
+    let mut parse_result = FileParseResult::new(file_name, tree, Rc::new(parser));
+    parse_result.syntax_error_lines = Rc::new(syntax_error_lines);
+    parse_result
 }
 
 pub(crate) fn get_line_id_for_node_name(name: &str) -> LineId {
@@ -252,20 +262,23 @@ mod tests {
         let _parsed_file = parse_syntax_tree(&mixed_indentation_input, &chars, &mut diagnostics);
         assert_eq!(1, diagnostics.len());
         assert_eq!(
-            Diagnostic::from_message("Indentation contains tabs and spaces")
-                .with_context("\t   ")
-                .with_start_line(3)
-                .with_file_name("test.yarn")
-                .with_range(
-                    Position {
-                        line: 3,
-                        character: 0
-                    }..Position {
-                        line: 3,
-                        character: 5
-                    }
-                )
-                .with_severity(DiagnosticSeverity::Warning),
+            Diagnostic::new(
+                DiagnosticCode::MixedIndentation,
+                "Indentation contains tabs and spaces"
+            )
+            .with_context("\t   ")
+            .with_start_line(3)
+            .with_file_name("test.yarn")
+            .with_range(
+                Position {
+                    line: 3,
+                    character: 0
+                }..Position {
+                    line: 3,
+                    character: 5
+                }
+            )
+            .with_severity(DiagnosticSeverity::Warning),
             diagnostics[0]
         );
     }
@@ -6,20 +6,34 @@ use antlr_rust::errors::{ANTLRError, InputMisMatchError, NoViableAltError};
 use antlr_rust::parser::ParserNodeType;
 use antlr_rust::parser_rule_context::ParserRuleContext;
 use antlr_rust::rule_context::CustomRuleContext;
-use antlr_rust::token::Token;
+use antlr_rust::token::{Token, TOKEN_EOF};
 use antlr_rust::token_factory::TokenFactory;
 use antlr_rust::tree::Tree;
 use antlr_rust::{DefaultErrorStrategy, ErrorStrategy as AntlrErrorStrategy, Parser};
+use std::cell::Cell;
 use std::rc::Rc;
 
+/// The tokens at which a Yarn script can be parsed again after a syntax error:
+/// the end of a node, the end of a line, and the delimiters of a command.
+const SYNCHRONISATION_TOKENS: [isize; 4] = [
+    yarnspinnerparser::BODY_END,
+    yarnspinnerparser::NEWLINE,
+    yarnspinnerparser::COMMAND_START,
+    yarnspinnerparser::COMMAND_END,
+];
+
 pub(crate) struct ErrorStrategy<'input, Ctx: ParserNodeType<'input>> {
     default_error_strategy: DefaultErrorStrategy<'input, Ctx>,
+    /// Shared with the [`ParserErrorListener`](crate::listeners::ParserErrorListener), which reports the next error with this code
+    /// instead of [`DiagnosticCode::SyntaxError`]. antlr only passes the message to error listeners.
+    error_code: Rc<Cell<Option<DiagnosticCode>>>,
 }
 
 impl<'input, Ctx: ParserNodeType<'input>> ErrorStrategy<'input, Ctx> {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(error_code: Rc<Cell<Option<DiagnosticCode>>>) -> Self {
         Self {
             default_error_strategy: DefaultErrorStrategy::new(),
+            error_code,
         }
     }
 }
@@ -40,7 +54,17 @@ impl<'input, T: Parser<'input>> AntlrErrorStrategy<'input, T> for ErrorStrategy<
         self.default_error_strategy.recover_inline(recognizer)
     }
 
+    /// ## Implementation notes
+    /// The `DefaultErrorStrategy` skips tokens until it finds one that can follow any of the rules currently being parsed.
+    /// In Yarn, such a token is often found in the middle of the broken line, which then produces a follow-up error.
+    /// So we first skip the rest of the broken construct up to the next node end, line end or command delimiter,
+    /// and only then let the `DefaultErrorStrategy` resynchronise from there.
     fn recover(&mut self, recognizer: &mut T, e: &ANTLRError) -> Result<(), ANTLRError> {
+        let mut token_type = recognizer.get_input_stream_mut().la(1);
+        while token_type != TOKEN_EOF && !SYNCHRONISATION_TOKENS.contains(&token_type) {
+            recognizer.consume(self);
+            token_type = recognizer.get_input_stream_mut().la(1);
+        }
         self.default_error_strategy.recover(recognizer, e)
     }
 
@@ -56,7 +80,7 @@ impl<'input, T: Parser<'input>> AntlrErrorStrategy<'input, T> for ErrorStrategy<
     /// ## Implementation notes
     /// The implementation of `DefaultErrorStrategy` will run as well, right after this.
     /// This means that behaviors overridden in the original implementation will instead be handled twice here.
-    /// This is alright, since this amounts simply printing an unspecific error message after the specific one.
+    /// The unspecific error message reported after the specific one is dropped by the [`ParserErrorListener`](crate::listeners::ParserErrorListener).
     /// The reason we have to do this is because the API to tell the `DefaultErrorStrategy` that we are currently
     /// in error recovery mode is private, so we can only access it indirectly by handling something.
     fn report_error(&mut self, recognizer: &mut T, e: &ANTLRError) {
@@ -125,6 +149,7 @@ impl<'input, Ctx: ParserNodeType<'input>> ErrorStrategy<'input, Ctx> {
                         // We have exited a body in the middle of an if
                         // statement. The programmer forgot to include an
                         // <<endif>>.
+                        self.error_code.set(Some(DiagnosticCode::MissingEndif));
                         Some(format!(
                             "Expected an <<endif>> to match the <<if>> statement on line {}",
                             rule_context.start().get_line_as_usize()
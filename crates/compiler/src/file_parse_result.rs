@@ -1,6 +1,7 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/FileParseResult.cs>
 
 use crate::prelude::{generated::yarnspinnerparser::*, *};
+use antlr_rust::parser_rule_context::ParserRuleContext;
 use std::rc::Rc;
 
 /// Contains the result of parsing a single file of source code.
@@ -19,6 +20,14 @@ pub(crate) struct FileParseResult<'input> {
     /// We also end up leading the `ErrorStrategy` into the public interface, but using generics here makes
     /// the code a lot more complicated without actually providing much benefit.
     pub parser: Rc<ActualYarnSpinnerParser<'input>>,
+
+    /// This was not in the original.
+    /// The format specifiers that were cut out of the source before parsing, see [`extract_format_specifiers`].
+    pub format_specifiers: Rc<FormatSpecifiers>,
+
+    /// This was not in the original.
+    /// The zero-based lines on which syntax errors were reported while parsing this file.
+    pub syntax_error_lines: Rc<Vec<usize>>,
 }
 
 impl<'input> FileParseResult<'input> {
@@ -27,7 +36,23 @@ impl<'input> FileParseResult<'input> {
         tree: Rc<DialogueContextAll<'input>>,
         parser: Rc<ActualYarnSpinnerParser<'input>>,
     ) -> Self {
-        Self { name, tree, parser }
+        Self {
+            name,
+            tree,
+            parser,
+            format_specifiers: Default::default(),
+            syntax_error_lines: Default::default(),
+        }
+    }
+
+    /// Whether a syntax error was reported somewhere inside the given node or statement.
+    /// Such parts are only partially parsed, so checking their semantics would only produce follow-up errors.
+    pub(crate) fn has_syntax_errors(&self, ctx: &impl ParserRuleContext<'input>) -> bool {
+        let first_line = ctx.start().get_line_as_usize().saturating_sub(1);
+        let last_line = ctx.stop().get_line_as_usize().saturating_sub(1);
+        self.syntax_error_lines
+            .iter()
+            .any(|line| (first_line..=last_line).contains(line))
     }
 
     pub(crate) fn tokens(&self) -> &ActualTokenStream<'input> {
@@ -0,0 +1,415 @@
+//! Resolution of the files that other files include with `#include:` file tags, e.g.
+//! ```text
+//! #include:common/helpers.yarn
+//!
+//! title: Start
+//! ---
+//! <<jump Helper>>
+//! ===
+//! ```
+
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Provides the files included with `#include:` file tags, which are compiled as if they had been added to the [`Compiler`] as well.
+/// Set it with [`Compiler::with_file_resolver`]. By default, none is set and only the files added to the [`Compiler`] can be included.
+pub trait FileResolver: Debug + Send + Sync {
+    /// Returns the file at `path`, which is the included path relative to `including_file`, see [`File::include_path`].
+    /// The files that the returned file includes in turn are resolved relative to its name, which is thus usually `path`.
+    /// If its name is that of a file that was added or included already, that file is used instead of compiling it twice.
+    fn resolve(&self, path: &str, including_file: &File) -> std::io::Result<File>;
+}
+
+/// Reads included files from disk. This is opt-in, see [`Compiler::with_file_resolver`].
+/// Relative paths are relative to the working directory, as are the names of files added with [`Compiler::read_file`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FileSystemResolver;
+
+impl FileResolver for FileSystemResolver {
+    fn resolve(&self, path: &str, _including_file: &File) -> std::io::Result<File> {
+        let source = std::fs::read_to_string(path)?;
+        Ok(File {
+            file_name: path.to_owned(),
+            source,
+        })
+    }
+}
+
+/// A [`FileResolver`] that is shared between clones of a [`Compiler`]. Two of them are equal if they point to the same resolver.
+#[derive(Debug, Clone)]
+pub(crate) struct SharedFileResolver(pub(crate) Arc<dyn FileResolver>);
+
+impl PartialEq for SharedFileResolver {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl File {
+    /// The paths of the files this file includes with `#include:` file tags, see [`File::include_path`].
+    pub fn included_paths(&self) -> Vec<String> {
+        include_tags(&self.source)
+            .iter()
+            .map(|tag| self.include_path(&tag.path))
+            .collect()
+    }
+
+    /// Resolves a path written in an `#include:` tag of this file relative to the directory of [`File::file_name`].
+    /// The result uses `/` as separator and contains no `.` or `..` components where avoidable,
+    /// e.g. `../common.yarn` included by `dialogue/chapters/one.yarn` becomes `dialogue/common.yarn`.
+    pub fn include_path(&self, path: &str) -> String {
+        let path = path.replace('\\', "/");
+        if path.starts_with('/') {
+            return normalize_path(&path);
+        }
+        match self.file_name.replace('\\', "/").rsplit_once('/') {
+            Some((directory, _)) => normalize_path(&format!("{directory}/{path}")),
+            None => normalize_path(&path),
+        }
+    }
+}
+
+/// Uses `/` as separator and removes `.` and `..` components where possible.
+fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut components: Vec<&str> = Vec::new();
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." if components.last().is_some_and(|last| *last != "..") => {
+                components.pop();
+            }
+            _ => components.push(component),
+        }
+    }
+    let joined = components.join("/");
+    if path.starts_with('/') {
+        format!("/{joined}")
+    } else {
+        joined
+    }
+}
+
+/// Resolves the includes of all files of the compiler, recursively.
+/// Returns the included files that are not part of the compiler already, as well as diagnostics
+/// for includes that cannot be resolved and for files that include each other in a cycle.
+pub(crate) fn resolve_included_files(compiler: &Compiler) -> (Vec<File>, Vec<Diagnostic>) {
+    let mut resolution = IncludeResolution {
+        resolver: compiler.file_resolver(),
+        added_files: compiler
+            .files
+            .iter()
+            .map(|file| (normalize_path(&file.file_name), file))
+            .collect(),
+        included_files: Vec::new(),
+        included_names: HashSet::new(),
+        stack: Vec::new(),
+        finished: HashSet::new(),
+        diagnostics: Vec::new(),
+    };
+    for file in &compiler.files {
+        let path = normalize_path(&file.file_name);
+        if !resolution.finished.contains(&path) {
+            resolution.visit(path, file);
+        }
+    }
+    (resolution.included_files, resolution.diagnostics)
+}
+
+struct IncludeResolution<'a> {
+    resolver: Option<&'a dyn FileResolver>,
+    /// The files added to the [`Compiler`] directly, by their normalized [`File::file_name`].
+    added_files: HashMap<String, &'a File>,
+    included_files: Vec<File>,
+    /// The normalized [`File::file_name`]s of the included files.
+    included_names: HashSet<String>,
+    /// The paths of the files whose includes are currently being resolved, each included by the one before it.
+    stack: Vec<String>,
+    /// The paths of the files whose includes are all resolved.
+    finished: HashSet<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl IncludeResolution<'_> {
+    /// Depth-first search through the files included by `file`, which was added or included as `path`.
+    fn visit(&mut self, path: String, file: &File) {
+        self.stack.push(path);
+        for tag in include_tags(&file.source) {
+            let included_path = file.include_path(&tag.path);
+            // The resolver may return a file that is known already under another path, so files are identified by their names
+            let (included_path, included_file) = if self.is_known(&included_path) {
+                (included_path, None)
+            } else {
+                match self.resolve(&included_path, file) {
+                    Ok(included_file) => {
+                        let name = normalize_path(&included_file.file_name);
+                        let is_new = !self.is_known(&name);
+                        (name, is_new.then_some(included_file))
+                    }
+                    Err(error) => {
+                        let message = format!("Cannot include \"{included_path}\": {error}");
+                        self.diagnostics.push(tag.diagnostic(
+                            DiagnosticCode::UnresolvedInclude,
+                            message,
+                            file,
+                        ));
+                        continue;
+                    }
+                }
+            };
+            if let Some(index) = self.stack.iter().position(|path| *path == included_path) {
+                let cycle = self.stack[index..]
+                    .iter()
+                    .chain([&included_path])
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                let message = format!("Files include each other in a cycle: {cycle}");
+                self.diagnostics
+                    .push(tag.diagnostic(DiagnosticCode::IncludeCycle, message, file));
+            } else if self.finished.contains(&included_path) {
+                continue;
+            } else if let Some(included_file) = included_file {
+                self.included_names.insert(included_path.clone());
+                self.visit(included_path, &included_file);
+                self.included_files.push(included_file);
+            } else if let Some(added_file) = self.added_files.get(&included_path).copied() {
+                self.visit(included_path, added_file);
+            }
+        }
+        let path = self.stack.pop().unwrap();
+        self.finished.insert(path);
+    }
+
+    /// Whether the file at the normalized `path` was added or included already.
+    fn is_known(&self, path: &str) -> bool {
+        self.added_files.contains_key(path) || self.included_names.contains(path)
+    }
+
+    fn resolve(&self, path: &str, including_file: &File) -> std::io::Result<File> {
+        match self.resolver {
+            Some(resolver) => resolver.resolve(path, including_file),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "the file was not added to the compiler and no file resolver is set",
+            )),
+        }
+    }
+}
+
+/// An `#include:` file tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IncludeTag {
+    /// The path as written in the tag.
+    path: String,
+    line: String,
+    range: Range<Position>,
+}
+
+impl IncludeTag {
+    fn diagnostic(&self, code: DiagnosticCode, message: String, file: &File) -> Diagnostic {
+        Diagnostic::new(code, message)
+            .with_file_name(&file.file_name)
+            .with_range(self.range.clone())
+            .with_context(&self.line)
+            .with_start_line(self.range.start.line)
+    }
+}
+
+/// Finds the `#include:` tags among the file tags, which are the hashtags on their own lines above the first node.
+fn include_tags(source: &str) -> Vec<IncludeTag> {
+    let mut tags = Vec::new();
+    for (line_index, line) in source.trim_start_matches('\u{feff}').lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+        let Some(hashtag) = trimmed.strip_prefix('#') else {
+            // The first header of the first node
+            break;
+        };
+        let text = hashtag
+            .split(char::is_whitespace)
+            .next()
+            .unwrap_or_default();
+        let Some(path) = text.strip_prefix("include:") else {
+            continue;
+        };
+        let start = Position {
+            line: line_index,
+            character: line.chars().count() - trimmed.chars().count(),
+        };
+        let end = Position {
+            character: start.character + 1 + text.chars().count(),
+            ..start
+        };
+        tags.push(IncludeTag {
+            path: path.to_owned(),
+            line: line.to_owned(),
+            range: start..end,
+        });
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct InMemoryResolver(Vec<File>);
+
+    impl FileResolver for InMemoryResolver {
+        fn resolve(&self, path: &str, _including_file: &File) -> std::io::Result<File> {
+            self.0
+                .iter()
+                .find(|file| file.file_name == path)
+                .cloned()
+                .ok_or_else(|| std::io::ErrorKind::NotFound.into())
+        }
+    }
+
+    /// Resolves the paths to files that may be named differently.
+    #[derive(Debug)]
+    struct AliasResolver(Vec<(&'static str, File)>);
+
+    impl FileResolver for AliasResolver {
+        fn resolve(&self, path: &str, _including_file: &File) -> std::io::Result<File> {
+            self.0
+                .iter()
+                .find(|(alias, _)| *alias == path)
+                .map(|(_, file)| file.clone())
+                .ok_or_else(|| std::io::ErrorKind::NotFound.into())
+        }
+    }
+
+    fn file(file_name: &str, source: &str) -> File {
+        File {
+            file_name: file_name.to_owned(),
+            source: source.to_owned(),
+        }
+    }
+
+    #[test]
+    fn include_paths_are_relative_to_the_including_file() {
+        let file = file(
+            "dialogue\\chapters/one.yarn",
+            "// Shared nodes
+#include:../common.yarn
+#include:./two.yarn
+#other_tag
+
+title: Start
+---
+#include:not_a_file_tag.yarn
+===",
+        );
+        assert_eq!(
+            vec!["dialogue/common.yarn", "dialogue/chapters/two.yarn"],
+            file.included_paths()
+        );
+        assert_eq!("/shared.yarn", file.include_path("/shared.yarn"));
+        assert_eq!("../shared.yarn", file.include_path("../../../shared.yarn"));
+    }
+
+    #[test]
+    fn resolves_nested_includes_once_and_reports_cycles_and_missing_files() {
+        let node = |title: &str| format!("title: {title}\n---\nHi\n===\n");
+        let mut compiler = Compiler::new();
+        compiler
+            .add_file(file(
+                "one.yarn",
+                &format!(
+                    "#include:common/a.yarn\n#include:missing.yarn\n{}",
+                    node("One")
+                ),
+            ))
+            .add_file(file(
+                "two.yarn",
+                &format!("#include:common/a.yarn\n{}", node("Two")),
+            ))
+            .with_file_resolver(InMemoryResolver(vec![
+                file("common/a.yarn", &format!("#include:b.yarn\n{}", node("A"))),
+                file(
+                    "common/b.yarn",
+                    &format!("#include:../one.yarn\n{}", node("B")),
+                ),
+            ]));
+
+        let (included_files, diagnostics) = resolve_included_files(&compiler);
+
+        let included_names: Vec<_> = included_files
+            .iter()
+            .map(|file| file.file_name.as_str())
+            .collect();
+        assert_eq!(vec!["common/b.yarn", "common/a.yarn"], included_names);
+        let summary: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.code,
+                    diagnostic.file_name.clone().unwrap(),
+                    diagnostic.range.clone().unwrap().start.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (DiagnosticCode::IncludeCycle, "common/b.yarn".to_owned(), 0),
+                (DiagnosticCode::UnresolvedInclude, "one.yarn".to_owned(), 1),
+            ],
+            summary
+        );
+        assert!(diagnostics[0]
+            .message
+            .ends_with("one.yarn -> common/a.yarn -> common/b.yarn -> one.yarn"));
+    }
+
+    #[test]
+    fn includes_only_added_files_without_resolver() {
+        let mut compiler = Compiler::new();
+        compiler
+            .add_file(file(
+                "dialogue/one.yarn",
+                "#include:../common.yarn\n#include:two.yarn\ntitle: One\n---\nHi\n===\n",
+            ))
+            .add_file(file("common.yarn", "title: Common\n---\nHi\n===\n"));
+
+        let (included_files, diagnostics) = resolve_included_files(&compiler);
+
+        assert!(included_files.is_empty());
+        assert_eq!(1, diagnostics.len(), "{diagnostics:#?}");
+        assert_eq!(DiagnosticCode::UnresolvedInclude, diagnostics[0].code);
+        assert!(diagnostics[0]
+            .message
+            .starts_with("Cannot include \"dialogue/two.yarn\""));
+    }
+
+    #[test]
+    fn includes_files_resolved_under_different_paths_once() {
+        let node = |title: &str| format!("title: {title}\n---\nHi\n===\n");
+        let mut compiler = Compiler::new();
+        compiler
+            .add_file(file(
+                "one.yarn",
+                &format!(
+                    "#include:a/common.yarn\n#include:b/common.yarn\n#include:alias.yarn\n{}",
+                    node("One")
+                ),
+            ))
+            .add_file(file("two.yarn", &node("Two")))
+            .with_file_resolver(AliasResolver(vec![
+                ("a/common.yarn", file("common.yarn", &node("Common"))),
+                ("b/common.yarn", file("common.yarn", &node("Common"))),
+                ("alias.yarn", file("two.yarn", &node("Two"))),
+            ]));
+
+        let (included_files, diagnostics) = resolve_included_files(&compiler);
+
+        assert_eq!(vec![file("common.yarn", &node("Common"))], included_files);
+        assert!(diagnostics.is_empty(), "{diagnostics:#?}");
+    }
+}
@@ -0,0 +1,180 @@
+//! Inline format specifiers of interpolated expressions, e.g. `{$gold:0.00}`.
+//!
+//! ## Implementation notes
+//! This has no equivalent in the original C# code. The grammar has no notion of format specifiers,
+//! so they are cut out of the source before it is lexed and replaced by whitespace, which keeps all positions intact.
+//! The string table generator then reattaches them to the substitution markers of their expressions, e.g. `{0:0.00}`,
+//! where the runtime picks them up as a [`NumberStyle`](yarnspinner_core::prelude::NumberStyle).
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// The format specifiers of a file, keyed by the character offset of the first character of the expression they belong to.
+pub(crate) type FormatSpecifiers = HashMap<usize, String>;
+
+/// Removes all format specifiers from `chars` and returns them.
+///
+/// A format specifier is the text between the last colon outside of a string literal and the closing brace of an interpolated expression.
+/// Since colons cannot appear in expressions, this never mistakes part of an expression for a format specifier.
+/// Only the interpolations in the text of lines and options are looked at, so braces in node headers, commands, string literals and comments are left alone.
+pub(crate) fn extract_format_specifiers(chars: &mut [u32]) -> FormatSpecifiers {
+    let mut format_specifiers = FormatSpecifiers::new();
+    let mut in_body = false;
+    let mut line_start = 0;
+    while line_start < chars.len() {
+        let line_end = chars[line_start..]
+            .iter()
+            .position(|&character| is(character, '\n'))
+            .map_or(chars.len(), |position| line_start + position);
+        let line: String = chars[line_start..line_end]
+            .iter()
+            .filter_map(|&character| char::from_u32(character))
+            .collect();
+        match line.trim() {
+            "---" => in_body = true,
+            "===" => in_body = false,
+            _ if in_body => extract_from_line(chars, line_start..line_end, &mut format_specifiers),
+            _ => {}
+        }
+        line_start = line_end + 1;
+    }
+    format_specifiers
+}
+
+fn extract_from_line(
+    chars: &mut [u32],
+    line: Range<usize>,
+    format_specifiers: &mut FormatSpecifiers,
+) {
+    let mut index = line.start;
+    while index < line.end {
+        let next_is = |expected: char| index + 1 < line.end && is(chars[index + 1], expected);
+        if is(chars[index], '\\') {
+            index += 2;
+            continue;
+        }
+        if is(chars[index], '/') && next_is('/') {
+            // The rest of the line is a comment
+            return;
+        }
+        if is(chars[index], '<') && next_is('<') {
+            index = skip_command(chars, index + 2, line.end);
+            continue;
+        }
+        if !is(chars[index], '{') {
+            index += 1;
+            continue;
+        }
+
+        let mut in_string = false;
+        let mut colon = None;
+        let mut end = None;
+        let mut position = index + 1;
+        while position < line.end {
+            let character = chars[position];
+            if in_string {
+                if is(character, '\\') {
+                    position += 1;
+                } else if is(character, '"') {
+                    in_string = false;
+                }
+            } else if is(character, '"') {
+                in_string = true;
+            } else if is(character, ':') {
+                colon = Some(position);
+            } else if is(character, '}') {
+                end = Some(position);
+                break;
+            } else if is(character, '{') {
+                break;
+            }
+            position += 1;
+        }
+
+        let (Some(colon), Some(end)) = (colon, end) else {
+            index = position.max(index + 1);
+            continue;
+        };
+        let Some(expression_start) =
+            (index + 1..colon).find(|&position| !is_whitespace(chars[position]))
+        else {
+            // Not an expression at all, so leave it to the parser to complain
+            index = end + 1;
+            continue;
+        };
+        let format_specifier: String = chars[colon + 1..end]
+            .iter()
+            .filter_map(|&character| char::from_u32(character))
+            .collect();
+        format_specifiers.insert(expression_start, format_specifier.trim().to_owned());
+        chars[colon..end].fill(' ' as u32);
+        index = end + 1;
+    }
+}
+
+/// Returns the index after the `>>` that closes the command whose contents start at `start`, skipping over string literals.
+fn skip_command(chars: &[u32], start: usize, line_end: usize) -> usize {
+    let mut in_string = false;
+    let mut position = start;
+    while position < line_end {
+        let character = chars[position];
+        if in_string {
+            if is(character, '\\') {
+                position += 1;
+            } else if is(character, '"') {
+                in_string = false;
+            }
+        } else if is(character, '"') {
+            in_string = true;
+        } else if is(character, '>') && position + 1 < line_end && is(chars[position + 1], '>') {
+            return position + 2;
+        }
+        position += 1;
+    }
+    line_end
+}
+
+fn is(character: u32, expected: char) -> bool {
+    character == expected as u32
+}
+
+fn is_whitespace(character: u32) -> bool {
+    char::from_u32(character).is_some_and(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_format_specifiers_and_keeps_positions() {
+        let source = "title: A\n---\nMae: {$gold:0.00} gold, {\"a:b\"} and { $ratio : percent }! \\{x:y}\n===";
+        let mut chars: Vec<_> = source.chars().map(|c| c as u32).collect();
+
+        let format_specifiers = extract_format_specifiers(&mut chars);
+
+        let blanked: String = chars.iter().map(|&c| char::from_u32(c).unwrap()).collect();
+        assert_eq!(
+            "title: A\n---\nMae: {$gold     } gold, {\"a:b\"} and { $ratio           }! \\{x:y}\n===",
+            blanked
+        );
+        assert_eq!(
+            FormatSpecifiers::from([(19, "0.00".to_owned()), (51, "percent".to_owned())]),
+            format_specifiers
+        );
+    }
+
+    #[test]
+    fn ignores_braces_outside_of_line_text() {
+        let source =
+            "title: A\ncolor: {a:b}\n---\n<<set $x to \"{a:b}\">>\n<<wait {$delay:0.0}>>\n\
+            -> Go {$x:0.0} <<if \"{a:b}\" == $y>> // {c:d}\n===";
+        let mut chars: Vec<_> = source.chars().map(|c| c as u32).collect();
+
+        let format_specifiers = extract_format_specifiers(&mut chars);
+
+        let blanked: String = chars.iter().map(|&c| char::from_u32(c).unwrap()).collect();
+        assert_eq!(source.replace("{$x:0.0}", "{$x    }"), blanked);
+        assert_eq!(1, format_specifiers.len());
+    }
+}
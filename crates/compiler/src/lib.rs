@@ -13,7 +13,11 @@ pub(crate) mod compilation_steps;
 pub(crate) mod compiler;
 pub(crate) mod error_strategy;
 mod file_parse_result;
+mod file_resolver;
+mod format_specifiers;
+mod line_id_strategy;
 pub(crate) mod listeners;
+mod manifest;
 mod output;
 mod parser;
 pub(crate) mod parser_rule_context_ext;
@@ -27,12 +31,18 @@ pub mod prelude {
     //! Everything you need to get started with the Yarn Spinner compiler.
     pub(crate) use crate::{
         compiler::antlr_rust_ext::*, compiler::run_compilation::*, compiler::utils::*,
-        file_parse_result::*, parser::*, parser_rule_context_ext::*, string_table_manager::*,
-        token_ext::*,
+        file_parse_result::*, file_resolver::SharedFileResolver, format_specifiers::*, parser::*,
+        parser_rule_context_ext::*, string_table_manager::*, token_ext::*,
     };
     pub use crate::{
-        compiler::{CompilationType, Compiler, File},
-        listeners::{Diagnostic, DiagnosticSeverity, DiagnosticVec},
+        compiler::{CompilationType, Compiler, File, SyntaxToken, SyntaxTokenKind},
+        file_resolver::{FileResolver, FileSystemResolver},
+        line_id_strategy::*,
+        listeners::{
+            Diagnostic, DiagnosticCode, DiagnosticDetails, DiagnosticFix, DiagnosticSeverity,
+            DiagnosticVec,
+        },
+        manifest::*,
         output::*,
     };
     pub(crate) use yarnspinner_core::prelude::*;
@@ -0,0 +1,131 @@
+//! Strategies for generating the IDs that [`Compiler::add_tags_to_lines_with_strategy`] adds to lines without a `#line:` tag.
+
+use crate::prelude::*;
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use std::fmt::Debug;
+
+/// Generates the IDs that are added to lines without a `#line:` tag. See [`Compiler::add_tags_to_lines_with_strategy`].
+///
+/// The built-in strategies are [`RandomLineIds`], [`ContentHashLineIds`] and [`SequentialLineIds`].
+pub trait LineIdStrategy: Debug + Send + Sync {
+    /// Generates the ID for `line`, which must start with `line:` and must not be contained in `existing_line_ids`.
+    /// These contain all IDs in use, including the ones generated earlier for the same file.
+    fn generate_line_id(&self, line: &UntaggedLine, existing_line_ids: &[LineId]) -> LineId;
+}
+
+/// A line that is about to be assigned an ID by a [`LineIdStrategy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UntaggedLine<'a> {
+    /// The name of the file the line is in.
+    pub file_name: &'a str,
+    /// The title of the node the line is in.
+    pub node_name: &'a str,
+    /// The text of the line as written in the file, without hashtags.
+    pub text: &'a str,
+}
+
+/// Generates IDs of random numbers, e.g. `line:8124035`. This is the strategy used by [`Compiler::add_tags_to_lines`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RandomLineIds;
+
+impl LineIdStrategy for RandomLineIds {
+    fn generate_line_id(&self, _line: &UntaggedLine, existing_line_ids: &[LineId]) -> LineId {
+        let mut rng = SmallRng::from_entropy();
+        loop {
+            let line: usize = rng.gen_range(0..0x1000000);
+            let tag = LineId(format!("line:{}", line));
+            if !existing_line_ids.contains(&tag) {
+                return tag;
+            }
+        }
+    }
+}
+
+/// Generates IDs from a hash of the file name, node name and text of the line, e.g. `line:3f9a0c21`.
+/// Tagging the same line on different branches thus results in the same ID.
+/// If the ID is already taken, e.g. because a node contains the same line twice, the hash is repeated with a counter until a free ID is found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ContentHashLineIds;
+
+impl LineIdStrategy for ContentHashLineIds {
+    fn generate_line_id(&self, line: &UntaggedLine, existing_line_ids: &[LineId]) -> LineId {
+        (0_u64..)
+            .map(|attempt| {
+                let hash = fnv1a([
+                    line.file_name.as_bytes(),
+                    line.node_name.as_bytes(),
+                    line.text.as_bytes(),
+                    &attempt.to_le_bytes(),
+                ]);
+                // Fold the hash to 32 bits to keep the IDs short
+                LineId(format!("line:{:08x}", (hash ^ (hash >> 32)) as u32))
+            })
+            .find(|line_id| !existing_line_ids.contains(line_id))
+            .unwrap()
+    }
+}
+
+/// The 64 bit FNV-1a hash of the given parts, which unlike [`std::hash::DefaultHasher`] is stable across Rust versions.
+fn fnv1a<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    parts
+        .into_iter()
+        // Separate the parts so that e.g. ("ab", "c") and ("a", "bc") hash differently
+        .flat_map(|part| part.iter().copied().chain([0xff]))
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        })
+}
+
+/// Generates readable IDs made of the node name and the lowest free number in that node, e.g. `line:Start_001`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SequentialLineIds;
+
+impl LineIdStrategy for SequentialLineIds {
+    fn generate_line_id(&self, line: &UntaggedLine, existing_line_ids: &[LineId]) -> LineId {
+        (1_usize..)
+            .map(|number| LineId(format!("line:{}_{number:03}", line.node_name)))
+            .find(|line_id| !existing_line_ids.contains(line_id))
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: UntaggedLine = UntaggedLine {
+        file_name: "test.yarn",
+        node_name: "Start",
+        text: "Hello there",
+    };
+
+    #[test]
+    fn content_hash_is_deterministic_and_resolves_collisions() {
+        let first = ContentHashLineIds.generate_line_id(&LINE, &[]);
+        assert_eq!(first, ContentHashLineIds.generate_line_id(&LINE, &[]));
+        assert!(first.0.starts_with("line:"));
+
+        let second = ContentHashLineIds.generate_line_id(&LINE, std::slice::from_ref(&first));
+        assert_ne!(first, second);
+
+        let other_node = UntaggedLine {
+            node_name: "End",
+            ..LINE
+        };
+        assert_ne!(first, ContentHashLineIds.generate_line_id(&other_node, &[]));
+    }
+
+    #[test]
+    fn sequential_ids_use_lowest_free_number() {
+        let existing = [
+            LineId("line:Start_001".to_owned()),
+            LineId("line:Start_003".to_owned()),
+        ];
+        assert_eq!(
+            LineId("line:Start_002".to_owned()),
+            SequentialLineIds.generate_line_id(&LINE, &existing)
+        );
+    }
+}
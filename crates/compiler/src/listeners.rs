@@ -2,5 +2,7 @@ mod compiler_listener;
 mod error_listener;
 mod untagged_line_listener;
 
-pub use self::error_listener::{Diagnostic, DiagnosticSeverity, DiagnosticVec};
+pub use self::error_listener::{
+    Diagnostic, DiagnosticCode, DiagnosticDetails, DiagnosticFix, DiagnosticSeverity, DiagnosticVec,
+};
 pub(crate) use self::{compiler_listener::*, error_listener::*, untagged_line_listener::*};
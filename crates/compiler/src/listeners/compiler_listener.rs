@@ -31,6 +31,8 @@ pub(crate) struct CompilerListener<'input> {
     pub(crate) current_node: Option<Node>,
     /// The current debug information that describes [`current_node`].
     current_debug_info: DebugInfo,
+    /// The kind of statement that instructions are currently being emitted for.
+    pub(crate) current_construct: SourceConstruct,
     /// Whether we are currently parsing the
     /// current node as a 'raw text' node, or as a fully syntactic node.
     is_current_node_raw_text: bool,
@@ -50,6 +52,7 @@ impl<'input> CompilerListener<'input> {
             tracking_nodes: Rc::new(RefCell::new(tracking_nodes)),
             current_node: Default::default(),
             current_debug_info: Default::default(),
+            current_construct: Default::default(),
             is_current_node_raw_text: Default::default(),
             diagnostics: Default::default(),
             program: Default::default(),
@@ -85,9 +88,12 @@ impl<'input> YarnSpinnerParserListener<'input> for CompilerListener<'input> {
         if name.is_empty() {
             // We don't have a name for this node. We can't emit code for it.
             self.diagnostics.borrow_mut().push(
-                Diagnostic::from_message("Missing title header for node")
-                    .with_file_name(self.file.name.clone())
-                    .with_parser_context(ctx, self.file.tokens()),
+                Diagnostic::new(
+                    DiagnosticCode::MissingTitle,
+                    "Missing title header for node",
+                )
+                .with_file_name(self.file.name.clone())
+                .with_parser_context(ctx, self.file.tokens()),
             );
         } else {
             if !self.program.borrow().nodes.contains_key(name) {
@@ -197,9 +203,10 @@ impl<'input> YarnSpinnerParserListener<'input> for CompilerListener<'input> {
             CodeGenerationVisitor::generate_tracking_code(self, track);
         }
         // We have exited the body; emit a 'stop' opcode here.
-        self.emit(Emit::from_op_code(OpCode::Stop).with_source(Position {
+        let end_of_node = Position {
             line: (ctx.stop().line as usize).saturating_sub(1),
             character: 0,
-        }));
+        };
+        self.emit(Emit::from_op_code(OpCode::Stop).with_source(end_of_node..end_of_node));
     }
 }
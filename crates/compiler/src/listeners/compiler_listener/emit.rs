@@ -1,6 +1,9 @@
 use crate::listeners::CompilerListener;
 use crate::prelude::*;
+use antlr_rust::rule_context::CustomRuleContext;
 use antlr_rust::token::Token;
+use antlr_rust::token_factory::TokenFactory;
+use std::ops::Range;
 use yarnspinner_core::prelude::OpCode;
 use yarnspinner_core::prelude::*;
 
@@ -12,17 +15,25 @@ impl<'input> CompilerListener<'input> {
             operands: emit.operands,
         };
 
+        let source = emit.source.map(|range| InstructionSource {
+            range,
+            construct: self.current_construct,
+        });
         let current_node = self.current_node.as_mut().unwrap();
+        self.current_debug_info.line_positions.insert(
+            current_node.instructions.len(),
+            source.as_ref().map(|source| source.range.start),
+        );
         self.current_debug_info
-            .line_positions
-            .insert(current_node.instructions.len(), emit.source);
+            .instruction_sources
+            .insert(current_node.instructions.len(), source);
         current_node.instructions.push(instruction);
     }
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct Emit {
-    source: Option<Position>,
+    source: Option<Range<Position>>,
     op_code: OpCode,
     operands: Vec<Operand>,
 }
@@ -36,7 +47,7 @@ impl Emit {
         }
     }
 
-    pub(crate) fn with_source(mut self, source: Position) -> Self {
+    pub(crate) fn with_source(mut self, source: Range<Position>) -> Self {
         self.source = Some(source);
         self
     }
@@ -47,10 +58,18 @@ impl Emit {
     }
 
     pub(crate) fn with_token(mut self, token: &(impl Token + ?Sized)) -> Self {
-        self.source = Some(Position {
-            line: token.get_line_as_usize().saturating_sub(1),
-            character: token.get_column_as_usize(),
-        });
+        self.source = Some(token.range());
+        self
+    }
+
+    /// Marks the instruction as produced by the entire `ctx`, e.g. a whole sub-expression.
+    pub(crate) fn with_parser_context<'input, T>(mut self, ctx: &T) -> Self
+    where
+        T: ParserRuleContextExtRangeSource<'input>,
+    <<<<T as CustomRuleContext<'input>>::TF as TokenFactory<'input>>::Inner as Token>::Data as ToOwned>::Owned:
+        Into<String>,
+    {
+        self.source = Some(ctx.range());
         self
     }
 }
@@ -11,11 +11,17 @@ use antlr_rust::token::Token;
 use antlr_rust::token_factory::TokenFactory;
 use antlr_rust::tree::ParseTreeListener;
 pub use diagnostic::*;
-use std::cell::RefCell;
+pub use diagnostic_code::*;
+pub use diagnostic_fix::*;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use yarnspinner_core::prelude::*;
 
 mod diagnostic;
+mod diagnostic_code;
+#[cfg(feature = "serde")]
+mod diagnostic_export;
+mod diagnostic_fix;
 pub(crate) struct LexerErrorListener {
     pub(crate) diagnostics: RefCell<Vec<Diagnostic>>,
     file_name: String,
@@ -50,7 +56,7 @@ impl<'input, T: Recognizer<'input>> ErrorListener<'input, T> for LexerErrorListe
             character: column + 1,
         };
         self.diagnostics.borrow_mut().push(
-            Diagnostic::from_message(msg)
+            Diagnostic::new(DiagnosticCode::UnrecognizedInput, msg)
                 .with_range(range)
                 .with_file_name(&self.file_name),
         );
@@ -64,6 +70,8 @@ pub(crate) struct ParserErrorListener {
     /// So we take the entire file instead of just the file name
     /// and extract the lines ourselves.
     file: File,
+    /// Set by the [`ErrorStrategy`](crate::error_strategy::ErrorStrategy) when it recognizes a specific error.
+    pub(crate) error_code: Rc<Cell<Option<DiagnosticCode>>>,
 }
 
 impl ParserErrorListener {
@@ -71,6 +79,7 @@ impl ParserErrorListener {
         Self {
             diagnostics: Default::default(),
             file,
+            error_code: Default::default(),
         }
     }
 }
@@ -93,7 +102,11 @@ impl<'input, T: Recognizer<'input>> ErrorListener<'input, T> for ParserErrorList
             line: line.saturating_sub(1),
             character: (column + 1) as usize,
         };
-        let mut diagnostic = Diagnostic::from_message(msg)
+        let code = self
+            .error_code
+            .take()
+            .unwrap_or(DiagnosticCode::SyntaxError);
+        let mut diagnostic = Diagnostic::new(code, msg)
             .with_file_name(&self.file.file_name)
             .with_range(range);
         if let Some(offending_symbol) = offending_symbol {
@@ -126,6 +139,15 @@ impl<'input, T: Recognizer<'input>> ErrorListener<'input, T> for ParserErrorList
             let line = offending_symbol.get_line_as_usize().saturating_sub(1);
             let column = offending_symbol.get_column_as_usize();
             let length = offending_symbol.get_text().len();
+            if code == DiagnosticCode::MissingEndif {
+                // The offending token is the `===` that ends the node, so the `<<endif>>` belongs right above it
+                let start_of_line = Position { line, character: 0 };
+                diagnostic = diagnostic.with_fix(DiagnosticFix::new(
+                    "Add `<<endif>>`",
+                    start_of_line..start_of_line,
+                    "<<endif>>\n",
+                ));
+            }
             diagnostic = diagnostic
                 .with_context(string)
                 .with_start_line(line)
@@ -139,7 +161,16 @@ impl<'input, T: Recognizer<'input>> ErrorListener<'input, T> for ParserErrorList
                     },
                 );
         }
-        self.diagnostics.borrow_mut().push(diagnostic);
+        let mut diagnostics = self.diagnostics.borrow_mut();
+        // The `ErrorStrategy` reports a specific message for an error, after which antlr reports its own generic one.
+        // Only keep the first, more helpful one.
+        if diagnostics
+            .last()
+            .is_some_and(|previous| previous.range == diagnostic.range)
+        {
+            return;
+        }
+        diagnostics.push(diagnostic);
     }
 }
 
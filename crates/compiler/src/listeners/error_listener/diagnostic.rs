@@ -1,3 +1,5 @@
+#[cfg(feature = "serde")]
+use super::diagnostic_export::*;
 use crate::parser_rule_context_ext::ParserRuleContextExt;
 use crate::prelude::*;
 use annotate_snippets::{Annotation, AnnotationType, Renderer, Slice, Snippet, SourceAnnotation};
@@ -29,6 +31,9 @@ use yarnspinner_core::prelude::*;
     reflect(Serialize, Deserialize)
 )]
 pub struct Diagnostic {
+    /// The kind of the issue, which identifies it independently of the wording of [`Diagnostic::message`].
+    pub code: DiagnosticCode,
+
     /// The path, URI or file-name that the issue occurred in.
     pub file_name: Option<String>,
 
@@ -46,17 +51,26 @@ pub struct Diagnostic {
 
     /// The line the context starts on.
     pub start_line: usize,
+
+    /// Structured information about the issue, such as the name of the variable it is about.
+    pub details: DiagnosticDetails,
+
+    /// Edits that resolve the issue, from most to least likely to be what the user intended.
+    pub fixes: Vec<DiagnosticFix>,
 }
 
 impl Diagnostic {
-    pub(crate) fn from_message(message: impl Into<String>) -> Self {
+    pub(crate) fn new(code: DiagnosticCode, message: impl Into<String>) -> Self {
         Self {
+            code,
             message: message.into(),
             file_name: Default::default(),
             range: Default::default(),
             context: Default::default(),
             severity: Default::default(),
             start_line: Default::default(),
+            details: Default::default(),
+            fixes: Default::default(),
         }
     }
 
@@ -103,6 +117,58 @@ impl Diagnostic {
         self.severity = severity;
         self
     }
+
+    pub(crate) fn with_variable(mut self, variable: impl Into<String>) -> Self {
+        self.details.variable = Some(variable.into());
+        self
+    }
+
+    pub(crate) fn with_node(mut self, node: impl Into<String>) -> Self {
+        self.details.node = Some(node.into());
+        self
+    }
+
+    pub(crate) fn with_function(mut self, function: impl Into<String>) -> Self {
+        self.details.function = Some(function.into());
+        self
+    }
+
+    pub(crate) fn with_line_id(mut self, line_id: impl Into<String>) -> Self {
+        self.details.line_id = Some(line_id.into());
+        self
+    }
+
+    pub(crate) fn with_operator(mut self, operator: impl Into<String>) -> Self {
+        self.details.operator = Some(operator.into());
+        self
+    }
+
+    pub(crate) fn with_expected_types<T: Into<String>>(
+        mut self,
+        types: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.details.expected_types = types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub(crate) fn with_actual_types<T: Into<String>>(
+        mut self,
+        types: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.details.actual_types = types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub(crate) fn with_counts(mut self, expected: usize, actual: usize) -> Self {
+        self.details.expected_count = Some(expected);
+        self.details.actual_count = Some(actual);
+        self
+    }
+
+    pub(crate) fn with_fix(mut self, fix: impl Into<Option<DiagnosticFix>>) -> Self {
+        self.fixes.extend(fix.into());
+        self
+    }
 }
 
 impl Display for Diagnostic {
@@ -115,10 +181,18 @@ impl Display for Diagnostic {
         let snippet = Snippet {
             title: Some(Annotation {
                 label: Some(label),
-                id: None,
+                id: Some(self.code.as_str()),
                 annotation_type,
             }),
-            footer: vec![],
+            footer: self
+                .fixes
+                .iter()
+                .map(|fix| Annotation {
+                    label: Some(&fix.title),
+                    id: None,
+                    annotation_type: AnnotationType::Help,
+                })
+                .collect(),
             slices: vec![Slice {
                 source: self.context.as_deref().unwrap_or("<unknown line>"),
                 line_start: self.start_line + 1,
@@ -172,12 +246,35 @@ fn convert_absolute_range_to_relative(diagnostic: &Diagnostic) -> (usize, usize)
 pub trait DiagnosticVec {
     /// Returns `true` if any of the [`Diagnostic`]s in the vector are of [`DiagnosticSeverity::Error`].
     fn has_errors(&self) -> bool;
+
+    /// Serializes the [`Diagnostic`]s as a JSON array for tools, independent of the `serde` representation of [`Diagnostic`].
+    /// Every diagnostic is an object with its `code`, e.g. `"YS0001"`, the `name` of the code, e.g. `"UndeclaredVariable"`,
+    /// its `severity` as `"error"` or `"warning"`, its `message`, `file_name` and zero-based `range`,
+    /// the [`DiagnosticDetails`] that are set as `details`, and its [`DiagnosticFix`]es as `fixes`.
+    #[cfg(feature = "serde")]
+    fn to_json(&self) -> String;
+
+    /// Serializes the [`Diagnostic`]s as a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log,
+    /// the format used by code scanning tools such as GitHub's. Every [`DiagnosticCode`] that occurs is listed as a rule,
+    /// the [`DiagnosticDetails`] of each result are stored in its `properties`, and its [`DiagnosticFix`]es become SARIF `fixes`.
+    #[cfg(feature = "serde")]
+    fn to_sarif(&self) -> String;
 }
 
 impl DiagnosticVec for Vec<Diagnostic> {
     fn has_errors(&self) -> bool {
         self.iter().any(|d| d.severity == DiagnosticSeverity::Error)
     }
+
+    #[cfg(feature = "serde")]
+    fn to_json(&self) -> String {
+        diagnostics_to_json(self)
+    }
+
+    #[cfg(feature = "serde")]
+    fn to_sarif(&self) -> String {
+        diagnostics_to_sarif(self)
+    }
 }
 
 /// The severity of the issue.
@@ -0,0 +1,353 @@
+#[cfg(any(feature = "bevy", feature = "serde"))]
+use crate::prelude::*;
+use core::fmt;
+use std::fmt::{Display, Formatter};
+
+/// Identifies the kind of a [`Diagnostic`] by a stable code, e.g. `YS0001` for [`DiagnosticCode::UndeclaredVariable`].
+///
+/// Tools such as CI pipelines and editor integrations should match on this instead of on [`Diagnostic::message`], which may be reworded at any time.
+/// Codes are never renumbered or reused. New kinds of diagnostics get new codes, so this enum is not exhaustive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+#[non_exhaustive]
+pub enum DiagnosticCode {
+    /// `YS0001`: A variable is neither declared nor used in a way that reveals its type.
+    UndeclaredVariable,
+    /// `YS0002`: The source code does not follow the Yarn grammar.
+    SyntaxError,
+    /// `YS0003`: The source code contains characters that cannot start any token.
+    UnrecognizedInput,
+    /// `YS0004`: An indentation contains both tabs and spaces.
+    MixedIndentation,
+    /// `YS0005`: A command spans multiple lines.
+    NewlineInCommand,
+    /// `YS0006`: A node has no `title` header.
+    MissingTitle,
+    /// `YS0007`: The title of a node contains characters that are not allowed in node names.
+    InvalidNodeName,
+    /// `YS0008`: A value is assigned to a variable of a different type.
+    InvalidAssignment,
+    /// `YS0009`: A function or command is called with the wrong number of parameters.
+    WrongParameterCount,
+    /// `YS0010`: A function or command is called with a parameter of the wrong type.
+    InvalidParameterType,
+    /// `YS0011`: `null` is used, which is not a value in Yarn Spinner 2.0 and later.
+    NullValue,
+    /// `YS0012`: More than one node has the same title.
+    DuplicateNodeName,
+    /// `YS0013`: More than one line has the same `#line:` tag.
+    DuplicateLineId,
+    /// `YS0014`: A variable is declared more than once.
+    DuplicateDeclaration,
+    /// `YS0015`: A declaration names a type that does not exist.
+    UnknownType,
+    /// `YS0016`: The default value of a declaration does not have the declared type.
+    DeclarationTypeMismatch,
+    /// `YS0017`: The default value of a declaration is a variable or function call instead of a constant.
+    NonConstantDeclaration,
+    /// `YS0018`: A number literal cannot be parsed.
+    InvalidNumber,
+    /// `YS0019`: A declaration has no default value.
+    MissingDefaultValue,
+    /// `YS0020`: The type of an expression cannot be determined from its context.
+    UndeterminedType,
+    /// `YS0021`: The terms of an operation have different types.
+    MixedOperandTypes,
+    /// `YS0022`: An operator is used with a type that does not support it.
+    UnsupportedOperator,
+    /// `YS0023`: An expression has a type that is not allowed where it is used, e.g. a jump to a number.
+    InvalidOperandType,
+    /// `YS0024`: A format specifier such as `{$gold:0.00}` cannot be parsed.
+    InvalidFormatSpecifier,
+    /// `YS0025`: A format specifier is used in a command.
+    FormatSpecifierInCommand,
+    /// `YS0026`: The markup of a line is invalid.
+    InvalidMarkup,
+    /// `YS0027`: Formatting the files would change what they compile to. This is a bug in the formatter.
+    FormattingChangesOutput,
+    /// `YS0028`: A variable is implicitly declared, but its name is so similar to a declared variable's that it is likely a typo.
+    MisspelledVariable,
+    /// `YS0029`: A function is neither declared nor in the library, but its name is so similar to one that is that it is likely a typo.
+    MisspelledFunction,
+    /// `YS0030`: A jump targets a node that does not exist.
+    UnknownNode,
+    /// `YS0031`: A command is not declared in the [`Manifest`](crate::prelude::Manifest), even though it declares commands.
+    UnknownCommand,
+    /// `YS0032`: A function or command is used that is marked as deprecated in the [`Manifest`](crate::prelude::Manifest).
+    DeprecatedUsage,
+    /// `YS0033`: A file included with an `#include:` file tag cannot be found or read.
+    UnresolvedInclude,
+    /// `YS0034`: Files include each other in a cycle.
+    IncludeCycle,
+    /// `YS0035`: An `<<if>>` statement is not closed with an `<<endif>>` before the end of its node.
+    MissingEndif,
+}
+
+impl DiagnosticCode {
+    /// All codes, in ascending order.
+    pub const ALL: [DiagnosticCode; 35] = [
+        DiagnosticCode::UndeclaredVariable,
+        DiagnosticCode::SyntaxError,
+        DiagnosticCode::UnrecognizedInput,
+        DiagnosticCode::MixedIndentation,
+        DiagnosticCode::NewlineInCommand,
+        DiagnosticCode::MissingTitle,
+        DiagnosticCode::InvalidNodeName,
+        DiagnosticCode::InvalidAssignment,
+        DiagnosticCode::WrongParameterCount,
+        DiagnosticCode::InvalidParameterType,
+        DiagnosticCode::NullValue,
+        DiagnosticCode::DuplicateNodeName,
+        DiagnosticCode::DuplicateLineId,
+        DiagnosticCode::DuplicateDeclaration,
+        DiagnosticCode::UnknownType,
+        DiagnosticCode::DeclarationTypeMismatch,
+        DiagnosticCode::NonConstantDeclaration,
+        DiagnosticCode::InvalidNumber,
+        DiagnosticCode::MissingDefaultValue,
+        DiagnosticCode::UndeterminedType,
+        DiagnosticCode::MixedOperandTypes,
+        DiagnosticCode::UnsupportedOperator,
+        DiagnosticCode::InvalidOperandType,
+        DiagnosticCode::InvalidFormatSpecifier,
+        DiagnosticCode::FormatSpecifierInCommand,
+        DiagnosticCode::InvalidMarkup,
+        DiagnosticCode::FormattingChangesOutput,
+        DiagnosticCode::MisspelledVariable,
+        DiagnosticCode::MisspelledFunction,
+        DiagnosticCode::UnknownNode,
+        DiagnosticCode::UnknownCommand,
+        DiagnosticCode::DeprecatedUsage,
+        DiagnosticCode::UnresolvedInclude,
+        DiagnosticCode::IncludeCycle,
+        DiagnosticCode::MissingEndif,
+    ];
+
+    /// The stable code, e.g. `YS0001`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticCode::UndeclaredVariable => "YS0001",
+            DiagnosticCode::SyntaxError => "YS0002",
+            DiagnosticCode::UnrecognizedInput => "YS0003",
+            DiagnosticCode::MixedIndentation => "YS0004",
+            DiagnosticCode::NewlineInCommand => "YS0005",
+            DiagnosticCode::MissingTitle => "YS0006",
+            DiagnosticCode::InvalidNodeName => "YS0007",
+            DiagnosticCode::InvalidAssignment => "YS0008",
+            DiagnosticCode::WrongParameterCount => "YS0009",
+            DiagnosticCode::InvalidParameterType => "YS0010",
+            DiagnosticCode::NullValue => "YS0011",
+            DiagnosticCode::DuplicateNodeName => "YS0012",
+            DiagnosticCode::DuplicateLineId => "YS0013",
+            DiagnosticCode::DuplicateDeclaration => "YS0014",
+            DiagnosticCode::UnknownType => "YS0015",
+            DiagnosticCode::DeclarationTypeMismatch => "YS0016",
+            DiagnosticCode::NonConstantDeclaration => "YS0017",
+            DiagnosticCode::InvalidNumber => "YS0018",
+            DiagnosticCode::MissingDefaultValue => "YS0019",
+            DiagnosticCode::UndeterminedType => "YS0020",
+            DiagnosticCode::MixedOperandTypes => "YS0021",
+            DiagnosticCode::UnsupportedOperator => "YS0022",
+            DiagnosticCode::InvalidOperandType => "YS0023",
+            DiagnosticCode::InvalidFormatSpecifier => "YS0024",
+            DiagnosticCode::FormatSpecifierInCommand => "YS0025",
+            DiagnosticCode::InvalidMarkup => "YS0026",
+            DiagnosticCode::FormattingChangesOutput => "YS0027",
+            DiagnosticCode::MisspelledVariable => "YS0028",
+            DiagnosticCode::MisspelledFunction => "YS0029",
+            DiagnosticCode::UnknownNode => "YS0030",
+            DiagnosticCode::UnknownCommand => "YS0031",
+            DiagnosticCode::DeprecatedUsage => "YS0032",
+            DiagnosticCode::UnresolvedInclude => "YS0033",
+            DiagnosticCode::IncludeCycle => "YS0034",
+            DiagnosticCode::MissingEndif => "YS0035",
+        }
+    }
+
+    /// The name of the variant, e.g. `UndeclaredVariable`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiagnosticCode::UndeclaredVariable => "UndeclaredVariable",
+            DiagnosticCode::SyntaxError => "SyntaxError",
+            DiagnosticCode::UnrecognizedInput => "UnrecognizedInput",
+            DiagnosticCode::MixedIndentation => "MixedIndentation",
+            DiagnosticCode::NewlineInCommand => "NewlineInCommand",
+            DiagnosticCode::MissingTitle => "MissingTitle",
+            DiagnosticCode::InvalidNodeName => "InvalidNodeName",
+            DiagnosticCode::InvalidAssignment => "InvalidAssignment",
+            DiagnosticCode::WrongParameterCount => "WrongParameterCount",
+            DiagnosticCode::InvalidParameterType => "InvalidParameterType",
+            DiagnosticCode::NullValue => "NullValue",
+            DiagnosticCode::DuplicateNodeName => "DuplicateNodeName",
+            DiagnosticCode::DuplicateLineId => "DuplicateLineId",
+            DiagnosticCode::DuplicateDeclaration => "DuplicateDeclaration",
+            DiagnosticCode::UnknownType => "UnknownType",
+            DiagnosticCode::DeclarationTypeMismatch => "DeclarationTypeMismatch",
+            DiagnosticCode::NonConstantDeclaration => "NonConstantDeclaration",
+            DiagnosticCode::InvalidNumber => "InvalidNumber",
+            DiagnosticCode::MissingDefaultValue => "MissingDefaultValue",
+            DiagnosticCode::UndeterminedType => "UndeterminedType",
+            DiagnosticCode::MixedOperandTypes => "MixedOperandTypes",
+            DiagnosticCode::UnsupportedOperator => "UnsupportedOperator",
+            DiagnosticCode::InvalidOperandType => "InvalidOperandType",
+            DiagnosticCode::InvalidFormatSpecifier => "InvalidFormatSpecifier",
+            DiagnosticCode::FormatSpecifierInCommand => "FormatSpecifierInCommand",
+            DiagnosticCode::InvalidMarkup => "InvalidMarkup",
+            DiagnosticCode::FormattingChangesOutput => "FormattingChangesOutput",
+            DiagnosticCode::MisspelledVariable => "MisspelledVariable",
+            DiagnosticCode::MisspelledFunction => "MisspelledFunction",
+            DiagnosticCode::UnknownNode => "UnknownNode",
+            DiagnosticCode::UnknownCommand => "UnknownCommand",
+            DiagnosticCode::DeprecatedUsage => "DeprecatedUsage",
+            DiagnosticCode::UnresolvedInclude => "UnresolvedInclude",
+            DiagnosticCode::IncludeCycle => "IncludeCycle",
+            DiagnosticCode::MissingEndif => "MissingEndif",
+        }
+    }
+
+    /// A one-sentence description of what this kind of diagnostic means, independent of any particular occurrence.
+    pub fn description(&self) -> &'static str {
+        match self {
+            DiagnosticCode::UndeclaredVariable => {
+                "A variable is neither declared nor used in a way that reveals its type."
+            }
+            DiagnosticCode::SyntaxError => "The source code does not follow the Yarn grammar.",
+            DiagnosticCode::UnrecognizedInput => {
+                "The source code contains characters that cannot start any token."
+            }
+            DiagnosticCode::MixedIndentation => "An indentation contains both tabs and spaces.",
+            DiagnosticCode::NewlineInCommand => "A command spans multiple lines.",
+            DiagnosticCode::MissingTitle => "A node has no title header.",
+            DiagnosticCode::InvalidNodeName => {
+                "The title of a node contains characters that are not allowed in node names."
+            }
+            DiagnosticCode::InvalidAssignment => {
+                "A value is assigned to a variable of a different type."
+            }
+            DiagnosticCode::WrongParameterCount => {
+                "A function or command is called with the wrong number of parameters."
+            }
+            DiagnosticCode::InvalidParameterType => {
+                "A function or command is called with a parameter of the wrong type."
+            }
+            DiagnosticCode::NullValue => {
+                "Null is used, which is not a value in Yarn Spinner 2.0 and later."
+            }
+            DiagnosticCode::DuplicateNodeName => "More than one node has the same title.",
+            DiagnosticCode::DuplicateLineId => "More than one line has the same line tag.",
+            DiagnosticCode::DuplicateDeclaration => "A variable is declared more than once.",
+            DiagnosticCode::UnknownType => "A declaration names a type that does not exist.",
+            DiagnosticCode::DeclarationTypeMismatch => {
+                "The default value of a declaration does not have the declared type."
+            }
+            DiagnosticCode::NonConstantDeclaration => {
+                "The default value of a declaration is a variable or function call instead of a constant."
+            }
+            DiagnosticCode::InvalidNumber => "A number literal cannot be parsed.",
+            DiagnosticCode::MissingDefaultValue => "A declaration has no default value.",
+            DiagnosticCode::UndeterminedType => {
+                "The type of an expression cannot be determined from its context."
+            }
+            DiagnosticCode::MixedOperandTypes => "The terms of an operation have different types.",
+            DiagnosticCode::UnsupportedOperator => {
+                "An operator is used with a type that does not support it."
+            }
+            DiagnosticCode::InvalidOperandType => {
+                "An expression has a type that is not allowed where it is used."
+            }
+            DiagnosticCode::InvalidFormatSpecifier => "A format specifier cannot be parsed.",
+            DiagnosticCode::FormatSpecifierInCommand => "A format specifier is used in a command.",
+            DiagnosticCode::InvalidMarkup => "The markup of a line is invalid.",
+            DiagnosticCode::FormattingChangesOutput => {
+                "Formatting the files would change what they compile to."
+            }
+            DiagnosticCode::MisspelledVariable => {
+                "An implicitly declared variable has a name very similar to a declared one."
+            }
+            DiagnosticCode::MisspelledFunction => {
+                "An unknown function has a name very similar to a known one."
+            }
+            DiagnosticCode::UnknownNode => "A jump targets a node that does not exist.",
+            DiagnosticCode::UnknownCommand => "A command is not declared in the manifest.",
+            DiagnosticCode::DeprecatedUsage => {
+                "A function or command is used that is marked as deprecated."
+            }
+            DiagnosticCode::UnresolvedInclude => {
+                "A file included with an include tag cannot be found or read."
+            }
+            DiagnosticCode::IncludeCycle => "Files include each other in a cycle.",
+            DiagnosticCode::MissingEndif => "An if statement is not closed with an endif.",
+        }
+    }
+}
+
+impl Display for DiagnosticCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Structured information about a [`Diagnostic`], so that tools do not need to extract it from [`Diagnostic::message`].
+/// Which fields are set depends on the [`DiagnosticCode`]. Types are formatted like in messages, e.g. `Number`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct DiagnosticDetails {
+    /// The variable the diagnostic is about, including its `$`.
+    pub variable: Option<String>,
+
+    /// The node the diagnostic is about.
+    pub node: Option<String>,
+
+    /// The function the diagnostic is about.
+    pub function: Option<String>,
+
+    /// The line ID the diagnostic is about, e.g. `line:a1b2c3`.
+    pub line_id: Option<String>,
+
+    /// The operator the diagnostic is about, e.g. `+`.
+    pub operator: Option<String>,
+
+    /// The types that would have been valid. More than one type means that any of them would have been valid.
+    pub expected_types: Vec<String>,
+
+    /// The types that were found, e.g. the types of all terms of an operation.
+    pub actual_types: Vec<String>,
+
+    /// The number of things that were expected, e.g. parameters of a function.
+    pub expected_count: Option<usize>,
+
+    /// The number of things that were found.
+    pub actual_count: Option<usize>,
+}
+
+impl DiagnosticDetails {
+    /// Whether no field is set.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_stable_and_unique() {
+        for (index, code) in DiagnosticCode::ALL.iter().enumerate() {
+            assert_eq!(format!("YS{:04}", index + 1), code.as_str());
+            assert_eq!(format!("{code:?}"), code.name());
+        }
+    }
+}
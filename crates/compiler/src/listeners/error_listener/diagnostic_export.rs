@@ -0,0 +1,305 @@
+//! Serializes diagnostics for tools, see [`DiagnosticVec::to_json`] and [`DiagnosticVec::to_sarif`].
+
+use crate::prelude::*;
+use serde_json::{json, Map, Value};
+use std::ops::Range;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const TOOL_NAME: &str = "Yarn Spinner for Rust";
+const TOOL_URI: &str = "https://github.com/YarnSpinnerTool/YarnSpinner-Rust";
+
+pub(crate) fn diagnostics_to_json(diagnostics: &[Diagnostic]) -> String {
+    let diagnostics: Vec<_> = diagnostics.iter().map(json_diagnostic).collect();
+    serde_json::to_string_pretty(&diagnostics).expect("Diagnostics are always serializable")
+}
+
+/// Writes a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log with a single run.
+/// Every [`DiagnosticCode`] that occurs becomes a rule, and every diagnostic a result referring to it.
+pub(crate) fn diagnostics_to_sarif(diagnostics: &[Diagnostic]) -> String {
+    let mut rules: Vec<DiagnosticCode> = diagnostics.iter().map(|d| d.code).collect();
+    rules.sort();
+    rules.dedup();
+
+    let results: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            // Guaranteed to be found, as all codes were collected above
+            let rule_index = rules.binary_search(&diagnostic.code).unwrap_or_default();
+            let mut result = json!({
+                "ruleId": diagnostic.code.as_str(),
+                "ruleIndex": rule_index,
+                "level": level(diagnostic),
+                "message": { "text": diagnostic.message },
+                "locations": location(diagnostic).into_iter().collect::<Vec<_>>(),
+                "properties": details(&diagnostic.details),
+            });
+            if let Some(fixes) = sarif_fixes(diagnostic) {
+                result["fixes"] = fixes;
+            }
+            result
+        })
+        .collect();
+    let rules: Vec<_> = rules
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.as_str(),
+                "name": rule.name(),
+                "shortDescription": { "text": rule.description() },
+            })
+        })
+        .collect();
+    let sarif = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "informationUri": TOOL_URI,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    });
+    serde_json::to_string_pretty(&sarif).expect("SARIF logs are always serializable")
+}
+
+fn json_diagnostic(diagnostic: &Diagnostic) -> Value {
+    let fixes: Vec<_> = diagnostic
+        .fixes
+        .iter()
+        .map(|fix| {
+            json!({
+                "title": fix.title,
+                "range": json_range(&fix.range),
+                "replacement": fix.replacement,
+            })
+        })
+        .collect();
+    json!({
+        "code": diagnostic.code.as_str(),
+        "name": diagnostic.code.name(),
+        "severity": level(diagnostic),
+        "message": diagnostic.message,
+        "file_name": diagnostic.file_name,
+        "range": diagnostic.range.as_ref().map(json_range),
+        "details": details(&diagnostic.details),
+        "fixes": fixes,
+    })
+}
+
+/// The severity as named by SARIF, which the JSON output uses as well.
+fn level(diagnostic: &Diagnostic) -> &'static str {
+    match diagnostic.severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+    }
+}
+
+/// A SARIF location, whose lines and columns are 1-based, unlike the ones of [`Position`].
+fn location(diagnostic: &Diagnostic) -> Option<Value> {
+    let file_name = diagnostic.file_name.as_deref()?;
+    let mut physical_location = json!({ "artifactLocation": { "uri": file_name } });
+    if let Some(range) = &diagnostic.range {
+        physical_location["region"] = region(range);
+    }
+    Some(json!({ "physicalLocation": physical_location }))
+}
+
+fn region(range: &Range<Position>) -> Value {
+    json!({
+        "startLine": range.start.line + 1,
+        "startColumn": range.start.character + 1,
+        "endLine": range.end.line + 1,
+        "endColumn": range.end.character + 1,
+    })
+}
+
+/// The [`DiagnosticFix`]es as SARIF fixes, which need to name the file they edit.
+fn sarif_fixes(diagnostic: &Diagnostic) -> Option<Value> {
+    let file_name = diagnostic.file_name.as_deref()?;
+    if diagnostic.fixes.is_empty() {
+        return None;
+    }
+    let fixes: Vec<_> = diagnostic
+        .fixes
+        .iter()
+        .map(|fix| {
+            json!({
+                "description": { "text": fix.title },
+                "artifactChanges": [{
+                    "artifactLocation": { "uri": file_name },
+                    "replacements": [{
+                        "deletedRegion": region(&fix.range),
+                        "insertedContent": { "text": fix.replacement },
+                    }],
+                }],
+            })
+        })
+        .collect();
+    Some(fixes.into())
+}
+
+fn json_range(range: &Range<Position>) -> Value {
+    json!({
+        "start": { "line": range.start.line, "character": range.start.character },
+        "end": { "line": range.end.line, "character": range.end.character },
+    })
+}
+
+/// The fields of [`DiagnosticDetails`] that are set, as a JSON object.
+fn details(details: &DiagnosticDetails) -> Value {
+    let mut fields = Map::new();
+    let strings = [
+        ("variable", &details.variable),
+        ("node", &details.node),
+        ("function", &details.function),
+        ("line_id", &details.line_id),
+        ("operator", &details.operator),
+    ];
+    for (key, value) in strings {
+        if let Some(value) = value {
+            fields.insert(key.to_owned(), value.as_str().into());
+        }
+    }
+    if !details.expected_types.is_empty() {
+        fields.insert(
+            "expected_types".to_owned(),
+            details.expected_types.clone().into(),
+        );
+    }
+    if !details.actual_types.is_empty() {
+        fields.insert(
+            "actual_types".to_owned(),
+            details.actual_types.clone().into(),
+        );
+    }
+    if let Some(count) = details.expected_count {
+        fields.insert("expected_count".to_owned(), count.into());
+    }
+    if let Some(count) = details.actual_count {
+        fields.insert("actual_count".to_owned(), count.into());
+    }
+    fields.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostics() -> Vec<Diagnostic> {
+        vec![
+            Diagnostic::new(
+                DiagnosticCode::UndeclaredVariable,
+                "Can't figure out the type of variable \"$gold\"",
+            )
+            .with_file_name("test.yarn")
+            .with_range(
+                Position {
+                    line: 2,
+                    character: 6,
+                }..Position {
+                    line: 2,
+                    character: 11,
+                },
+            )
+            .with_variable("$gold")
+            .with_fix(DiagnosticFix::new(
+                "Did you mean `$gold`?",
+                Position {
+                    line: 2,
+                    character: 6,
+                }..Position {
+                    line: 2,
+                    character: 12,
+                },
+                "$gold",
+            )),
+            Diagnostic::new(
+                DiagnosticCode::MixedIndentation,
+                "Indentation contains tabs and spaces",
+            )
+            .with_severity(DiagnosticSeverity::Warning),
+        ]
+    }
+
+    #[test]
+    fn serializes_diagnostics_as_json() {
+        let json: Value = serde_json::from_str(&diagnostics_to_json(&diagnostics())).unwrap();
+
+        assert_eq!(
+            json!({
+                "code": "YS0001",
+                "name": "UndeclaredVariable",
+                "severity": "error",
+                "message": "Can't figure out the type of variable \"$gold\"",
+                "file_name": "test.yarn",
+                "range": {
+                    "start": { "line": 2, "character": 6 },
+                    "end": { "line": 2, "character": 11 },
+                },
+                "details": { "variable": "$gold" },
+                "fixes": [{
+                    "title": "Did you mean `$gold`?",
+                    "range": {
+                        "start": { "line": 2, "character": 6 },
+                        "end": { "line": 2, "character": 12 },
+                    },
+                    "replacement": "$gold",
+                }],
+            }),
+            json[0]
+        );
+        assert_eq!(
+            json!({
+                "code": "YS0004",
+                "name": "MixedIndentation",
+                "severity": "warning",
+                "message": "Indentation contains tabs and spaces",
+                "file_name": null,
+                "range": null,
+                "details": {},
+                "fixes": [],
+            }),
+            json[1]
+        );
+    }
+
+    #[test]
+    fn serializes_diagnostics_as_sarif() {
+        let sarif: Value = serde_json::from_str(&diagnostics_to_sarif(&diagnostics())).unwrap();
+
+        assert_eq!("2.1.0", sarif["version"]);
+        let run = &sarif["runs"][0];
+        let rules = &run["tool"]["driver"]["rules"];
+        assert_eq!(
+            vec!["YS0001", "YS0004"],
+            rules
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|rule| rule["id"].as_str().unwrap())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("MixedIndentation", rules[1]["name"]);
+
+        let results = &run["results"];
+        assert_eq!(1, results[1]["ruleIndex"]);
+        assert_eq!(json!([]), results[1]["locations"]);
+        assert_eq!(
+            json!({ "startLine": 3, "startColumn": 7, "endLine": 3, "endColumn": 12 }),
+            results[0]["locations"][0]["physicalLocation"]["region"]
+        );
+        assert_eq!(
+            json!([{
+                "deletedRegion": { "startLine": 3, "startColumn": 7, "endLine": 3, "endColumn": 13 },
+                "insertedContent": { "text": "$gold" },
+            }]),
+            results[0]["fixes"][0]["artifactChanges"][0]["replacements"]
+        );
+        assert_eq!(json!({ "variable": "$gold" }), results[0]["properties"]);
+    }
+}
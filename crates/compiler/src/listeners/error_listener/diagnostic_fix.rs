@@ -0,0 +1,159 @@
+use crate::prelude::*;
+use std::ops::Range;
+
+/// An edit that resolves a [`Diagnostic`], e.g. replacing a misspelled node name with the one it most likely refers to.
+///
+/// The edit applies to the file of [`Diagnostic::file_name`].
+/// Editor integrations can offer it as a quick-fix, and other tools can use [`DiagnosticFix::apply`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct DiagnosticFix {
+    /// A short description of the edit for humans, e.g. ``Did you mean `Tavern`?``.
+    pub title: String,
+
+    /// The range of the source that is replaced. An empty range inserts [`DiagnosticFix::replacement`] at its start.
+    pub range: Range<Position>,
+
+    /// The text that replaces the [`DiagnosticFix::range`].
+    pub replacement: String,
+}
+
+impl DiagnosticFix {
+    pub(crate) fn new(
+        title: impl Into<String>,
+        range: impl Into<Range<Position>>,
+        replacement: impl Into<String>,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            range: range.into(),
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Replaces a misspelled name in `range` with the `suggestion` found by [`closest_match`].
+    pub(crate) fn did_you_mean(suggestion: &str, range: impl Into<Range<Position>>) -> Self {
+        Self::new(format!("Did you mean `{suggestion}`?"), range, suggestion)
+    }
+
+    /// Returns the `source` of the file this fix is for with the fix applied.
+    /// Positions past the end of a line or of the source are treated as the end of it.
+    pub fn apply(&self, source: &str) -> String {
+        let start = byte_offset(source, &self.range.start);
+        let end = byte_offset(source, &self.range.end).max(start);
+        let mut fixed = String::with_capacity(source.len() + self.replacement.len());
+        fixed.push_str(&source[..start]);
+        fixed.push_str(&self.replacement);
+        fixed.push_str(&source[end..]);
+        fixed
+    }
+}
+
+fn byte_offset(source: &str, position: &Position) -> usize {
+    let line_start: usize = source
+        .split_inclusive('\n')
+        .take(position.line)
+        .map(str::len)
+        .sum();
+    let line = source[line_start..]
+        .split_inclusive('\n')
+        .next()
+        .unwrap_or_default();
+    let line_length = line.trim_end_matches(['\r', '\n']).len();
+    let character = line
+        .char_indices()
+        .nth(position.character)
+        .map_or(line_length, |(index, _)| index.min(line_length));
+    line_start + character
+}
+
+/// Returns the candidate that `name` most likely is a misspelling of, if any is similar enough.
+/// Ties are broken alphabetically so that the suggestion does not depend on the order of declarations.
+pub(crate) fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let name = name.to_lowercase();
+    // Allow roughly one typo per three characters, so that short names don't match everything
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.to_lowercase() != name)
+        .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// The Levenshtein distance, i.e. the number of characters that need to be inserted, removed or replaced to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<_> = b.chars().collect();
+    let mut previous_row: Vec<_> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_row[j] + usize::from(a_char != *b_char);
+            row[j + 1] = substitution.min(previous_row[j + 1] + 1).min(row[j] + 1);
+        }
+        previous_row = row;
+    }
+    previous_row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_closest_match() {
+        let candidates = ["Tavern", "Town", "Start"];
+
+        assert_eq!(Some("Tavern"), closest_match("Tavren", candidates));
+        assert_eq!(Some("$gold"), closest_match("$golld", ["$gold", "$goal"]));
+        assert_eq!(Some("visited"), closest_match("Visitd", ["visited"]));
+        assert_eq!(None, closest_match("Forest", candidates));
+        assert_eq!(None, closest_match("Town", candidates));
+    }
+
+    #[test]
+    fn applies_fix() {
+        let source = "title: Start\n---\n<<jump Tavren>>\n===\n";
+        let replace = DiagnosticFix::new(
+            "Did you mean `Tavern`?",
+            Position {
+                line: 2,
+                character: 7,
+            }..Position {
+                line: 2,
+                character: 13,
+            },
+            "Tavern",
+        );
+        let insert = DiagnosticFix::new(
+            "Add `<<endif>>`",
+            Position {
+                line: 3,
+                character: 0,
+            }..Position {
+                line: 3,
+                character: 0,
+            },
+            "<<endif>>\n",
+        );
+
+        assert_eq!(
+            "title: Start\n---\n<<jump Tavern>>\n===\n",
+            replace.apply(source)
+        );
+        assert_eq!(
+            "title: Start\n---\n<<jump Tavren>>\n<<endif>>\n===\n",
+            insert.apply(source)
+        );
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::parser::generated::yarnspinnerparser::Line_statementContext;
 use crate::prelude::generated::yarnspinnerparser::{
-    Line_statementContextAttrs, YarnSpinnerParserContextType,
+    HeaderContext, Line_statementContextAttrs, YarnSpinnerParserContextType,
 };
 use crate::prelude::generated::yarnspinnerparserlistener::YarnSpinnerParserListener;
 use crate::prelude::*;
@@ -11,8 +11,7 @@ use antlr_rust::int_stream::IntStream;
 use antlr_rust::parser_rule_context::ParserRuleContext;
 use antlr_rust::token::Token;
 use antlr_rust::token_stream::TokenStream;
-use antlr_rust::tree::ParseTreeListener;
-use rand::{rngs::SmallRng, Rng, SeedableRng};
+use antlr_rust::tree::{ParseTree, ParseTreeListener};
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::atomic::AtomicBool;
@@ -20,36 +19,42 @@ use std::sync::atomic::AtomicBool;
 pub(crate) struct UntaggedLineListener<'input> {
     existing_line_tags: Vec<LineId>,
     file: FileParseResult<'input>,
+    strategy: &'input dyn LineIdStrategy,
+    current_node_name: String,
     pub(crate) rewritten_lines: Rc<RefCell<Vec<String>>>,
     pub(crate) rewrote_anything: Rc<AtomicBool>,
 }
 
 impl<'input> UntaggedLineListener<'input> {
-    pub fn new(existing_line_tags: Vec<LineId>, file: FileParseResult<'input>) -> Self {
-        let original_source = file
-            .tokens()
-            .get_all_text()
-            .lines()
-            .map(|s| s.to_owned())
-            .collect();
+    /// Implementation note: the original reads the source from the tokens,
+    /// which no longer contain the format specifiers, so we pass it in instead.
+    pub fn new(
+        existing_line_tags: Vec<LineId>,
+        file: FileParseResult<'input>,
+        original_source: &str,
+        strategy: &'input dyn LineIdStrategy,
+    ) -> Self {
+        let original_source = original_source.lines().map(|s| s.to_owned()).collect();
         Self {
             existing_line_tags,
             file,
+            strategy,
+            current_node_name: Default::default(),
             rewritten_lines: Rc::new(RefCell::new(original_source)),
             rewrote_anything: Default::default(),
         }
     }
 
     /// Generates a new unique line tag that is not present in `existing_line_tags`.
-    fn generate_string(&self) -> LineId {
-        let mut rng = SmallRng::from_entropy();
-        loop {
-            let line: usize = rng.gen_range(0..0x1000000);
-            let tag = LineId(format!("line:{}", line));
-            if !self.existing_line_tags.contains(&tag) {
-                return tag;
-            }
-        }
+    fn generate_string(&self, ctx: &Line_statementContext<'input>) -> LineId {
+        let text = ctx.line_formatted_text().unwrap().get_text();
+        let line = UntaggedLine {
+            file_name: &self.file.name,
+            node_name: &self.current_node_name,
+            text: text.trim(),
+        };
+        self.strategy
+            .generate_line_id(&line, &self.existing_line_tags)
     }
 }
 
@@ -59,6 +64,17 @@ impl<'input> ParseTreeListener<'input, YarnSpinnerParserContextType>
 }
 
 impl<'input> YarnSpinnerParserListener<'input> for UntaggedLineListener<'input> {
+    fn exit_header(&mut self, ctx: &HeaderContext<'input>) {
+        if ctx.header_key.as_ref().unwrap().get_text() == "title" {
+            self.current_node_name = ctx
+                .header_value
+                .as_ref()
+                .map(|value| value.get_text())
+                .unwrap_or_default()
+                .to_owned();
+        }
+    }
+
     fn exit_line_statement(&mut self, ctx: &Line_statementContext<'input>) {
         // We're looking at a complete line statement.
 
@@ -94,7 +110,7 @@ impl<'input> YarnSpinnerParserListener<'input> for UntaggedLineListener<'input>
         let previous_token = tokens.get(previous_token_index);
 
         // Generate a new, unique line ID.
-        let new_line_id = self.generate_string();
+        let new_line_id = self.generate_string(ctx);
         // Record that we've used this new line ID, so that we don't
         // accidentally use it twice.
         self.existing_line_tags.push(new_line_id.clone());
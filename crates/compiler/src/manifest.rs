@@ -0,0 +1,394 @@
+//! Declarations of the functions and commands a game implements, so that Yarn scripts can be compiled and checked
+//! without linking the game's code. Modelled after the `.ysls.json` files of the original Yarn Spinner language server.
+
+use crate::prelude::*;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "serde")]
+use std::path::Path;
+use yarnspinner_core::types::FunctionType;
+
+/// The functions and commands a game provides, usually loaded with [`Manifest::from_json`] or [`Manifest::from_toml`].
+/// Add it to a compilation with [`Compiler::extend_manifest`].
+///
+/// Functions are type checked like the ones in a [`Library`]. Once at least one command is declared,
+/// `<<commands>>` that are not declared produce warnings and the arguments of declared ones are checked.
+///
+/// The serialized format uses the same keys as `.ysls.json` files, e.g.
+/// ```json
+/// {
+///     "Functions": [
+///         { "YarnName": "dice", "ReturnType": "number", "Parameters": [{ "Name": "sides", "Type": "number" }] }
+///     ],
+///     "Commands": [
+///         { "YarnName": "walk", "Parameters": [{ "Name": "character", "Type": "string" }, { "Name": "speed", "Type": "number", "DefaultValue": "1" }] }
+///     ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct Manifest {
+    /// The functions that can be called in expressions, e.g. `{dice(6)}`.
+    pub functions: Vec<FunctionDefinition>,
+    /// The commands that can be run, e.g. `<<walk Mae>>`.
+    pub commands: Vec<CommandDefinition>,
+}
+
+/// A function declared in a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct FunctionDefinition {
+    /// The name the function is called by in Yarn scripts.
+    pub name: String,
+    /// The parameters of the function, in order.
+    pub parameters: Vec<ParameterDefinition>,
+    /// The type of the value the function returns.
+    pub return_type: Type,
+    /// A description of what the function does.
+    pub documentation: Option<String>,
+    /// If set, the function is deprecated and calling it produces a warning containing this note.
+    pub deprecation: Option<String>,
+}
+
+/// A command declared in a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct CommandDefinition {
+    /// The name the command is run by in Yarn scripts, i.e. the first word of the command.
+    pub name: String,
+    /// The parameters of the command, in order.
+    pub parameters: Vec<ParameterDefinition>,
+    /// A description of what the command does.
+    pub documentation: Option<String>,
+    /// If set, the command is deprecated and running it produces a warning containing this note.
+    pub deprecation: Option<String>,
+}
+
+/// A parameter of a [`FunctionDefinition`] or [`CommandDefinition`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ParameterDefinition {
+    /// The name of the parameter, used in diagnostics.
+    pub name: String,
+    /// The type of the parameter. [`Type::Any`] accepts every argument.
+    pub r#type: Type,
+    /// A description of the parameter.
+    pub documentation: Option<String>,
+    /// Whether the parameter has a default value and may thus be omitted. Only supported by commands.
+    pub is_optional: bool,
+    /// Whether the parameter accepts any number of arguments. Only supported by commands, and only for the last parameter.
+    pub is_variadic: bool,
+}
+
+impl Manifest {
+    /// Parses a manifest in the `.ysls.json` format.
+    #[cfg(feature = "serde")]
+    pub fn from_json(source: &str) -> Result<Self, ManifestError> {
+        let manifest: serialized::Manifest =
+            serde_json::from_str(source).map_err(|e| ManifestError::Syntax(e.to_string()))?;
+        manifest.try_into()
+    }
+
+    /// Parses a manifest that uses the keys of the `.ysls.json` format in TOML.
+    #[cfg(feature = "serde")]
+    pub fn from_toml(source: &str) -> Result<Self, ManifestError> {
+        let manifest: serialized::Manifest =
+            toml::from_str(source).map_err(|e| ManifestError::Syntax(e.to_string()))?;
+        manifest.try_into()
+    }
+
+    /// Reads a manifest from disk, as TOML if the file ends in `.toml` and as JSON otherwise.
+    #[cfg(feature = "serde")]
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path).map_err(|e| ManifestError::Io(e.to_string()))?;
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "toml")
+        {
+            Self::from_toml(&source)
+        } else {
+            Self::from_json(&source)
+        }
+    }
+
+    /// Adds the functions and commands of another manifest to this one.
+    pub fn extend(&mut self, other: Manifest) {
+        self.functions.extend(other.functions);
+        self.commands.extend(other.commands);
+    }
+
+    /// Gets the declared command with the given name.
+    pub fn command(&self, name: &str) -> Option<&CommandDefinition> {
+        self.commands.iter().find(|command| command.name == name)
+    }
+
+    /// Gets the declared function with the given name.
+    pub fn function(&self, name: &str) -> Option<&FunctionDefinition> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+
+    pub(crate) fn function_declarations(&self) -> impl Iterator<Item = Declaration> + '_ {
+        self.functions.iter().map(|function| {
+            let mut function_type = FunctionType::default();
+            for parameter in &function.parameters {
+                function_type.add_parameter(parameter.r#type.clone());
+            }
+            function_type.set_return_type(function.return_type.clone());
+            Declaration::new(&function.name, function_type)
+                .with_description_optional(function.documentation.clone())
+                .with_source_file_name(DeclarationSource::External)
+        })
+    }
+}
+
+impl CommandDefinition {
+    /// The range of how many arguments the command accepts, where `None` means there is no upper bound.
+    pub fn argument_count(&self) -> (usize, Option<usize>) {
+        let required = self
+            .parameters
+            .iter()
+            .filter(|parameter| !parameter.is_optional && !parameter.is_variadic)
+            .count();
+        let maximum = (!self
+            .parameters
+            .last()
+            .is_some_and(|parameter| parameter.is_variadic))
+        .then_some(self.parameters.len());
+        (required, maximum)
+    }
+
+    /// Gets the parameter that receives the argument at `index`, taking a variadic last parameter into account.
+    pub fn parameter_for_argument(&self, index: usize) -> Option<&ParameterDefinition> {
+        self.parameters.get(index).or_else(|| {
+            self.parameters
+                .last()
+                .filter(|parameter| parameter.is_variadic)
+        })
+    }
+}
+
+/// An error that occurred while loading a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestError {
+    /// The manifest file could not be read.
+    Io(String),
+    /// The manifest is not valid JSON or TOML, or is missing required keys.
+    Syntax(String),
+    /// A parameter or return type is not one of `string`, `number`, `bool` or `any`.
+    UnknownType {
+        /// The function or command whose signature contains the type.
+        name: String,
+        /// The unknown type.
+        r#type: String,
+    },
+}
+
+impl Error for ManifestError {}
+
+impl Display for ManifestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManifestError::Io(message) => write!(f, "Failed to read manifest: {message}"),
+            ManifestError::Syntax(message) => write!(f, "Invalid manifest: {message}"),
+            ManifestError::UnknownType { name, r#type } => write!(
+                f,
+                "Unknown type \"{type}\" in the signature of \"{name}\". Expected \"string\", \"number\", \"bool\" or \"any\""
+            ),
+        }
+    }
+}
+
+/// The `.ysls.json` representation of a [`Manifest`], which names types by their Yarn keyword.
+#[cfg(feature = "serde")]
+mod serialized {
+    use super::ManifestError;
+    use crate::prelude::*;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    pub(super) struct Manifest {
+        #[serde(default)]
+        functions: Vec<Function>,
+        #[serde(default)]
+        commands: Vec<Command>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Function {
+        yarn_name: String,
+        #[serde(default)]
+        parameters: Vec<Parameter>,
+        #[serde(default)]
+        return_type: Option<String>,
+        documentation: Option<String>,
+        deprecated: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Command {
+        yarn_name: String,
+        #[serde(default)]
+        parameters: Vec<Parameter>,
+        documentation: Option<String>,
+        deprecated: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Parameter {
+        name: String,
+        #[serde(default)]
+        r#type: Option<String>,
+        documentation: Option<String>,
+        default_value: Option<String>,
+        #[serde(default)]
+        is_params_array: bool,
+    }
+
+    impl TryFrom<Manifest> for super::Manifest {
+        type Error = ManifestError;
+
+        fn try_from(manifest: Manifest) -> Result<Self, Self::Error> {
+            let functions = manifest
+                .functions
+                .into_iter()
+                .map(|function| {
+                    Ok(super::FunctionDefinition {
+                        parameters: parameters(&function.yarn_name, function.parameters)?,
+                        return_type: to_type(&function.yarn_name, function.return_type)?,
+                        name: function.yarn_name,
+                        documentation: function.documentation,
+                        deprecation: function.deprecated,
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            let commands = manifest
+                .commands
+                .into_iter()
+                .map(|command| {
+                    Ok(super::CommandDefinition {
+                        parameters: parameters(&command.yarn_name, command.parameters)?,
+                        name: command.yarn_name,
+                        documentation: command.documentation,
+                        deprecation: command.deprecated,
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(Self {
+                functions,
+                commands,
+            })
+        }
+    }
+
+    fn parameters(
+        name: &str,
+        parameters: Vec<Parameter>,
+    ) -> Result<Vec<super::ParameterDefinition>, ManifestError> {
+        parameters
+            .into_iter()
+            .map(|parameter| {
+                Ok(super::ParameterDefinition {
+                    r#type: to_type(name, parameter.r#type)?,
+                    name: parameter.name,
+                    documentation: parameter.documentation,
+                    is_optional: parameter.default_value.is_some(),
+                    is_variadic: parameter.is_params_array,
+                })
+            })
+            .collect()
+    }
+
+    /// A missing type means the value may be of any type
+    fn to_type(name: &str, r#type: Option<String>) -> Result<Type, ManifestError> {
+        let Some(r#type) = r#type else {
+            return Ok(Type::Any);
+        };
+        match r#type.to_lowercase().as_str() {
+            "string" => Ok(Type::String),
+            "number" => Ok(Type::Number),
+            "bool" | "boolean" => Ok(Type::Boolean),
+            "any" => Ok(Type::Any),
+            _ => Err(ManifestError::UnknownType {
+                name: name.to_owned(),
+                r#type,
+            }),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_json_and_toml() {
+        let json = r#"{
+            "Functions": [
+                { "YarnName": "dice", "ReturnType": "number", "Parameters": [{ "Name": "sides", "Type": "number" }], "Deprecated": "Use roll instead" }
+            ],
+            "Commands": [
+                { "YarnName": "walk", "Parameters": [{ "Name": "character", "Type": "string" }, { "Name": "speed", "Type": "number", "DefaultValue": "1" }] }
+            ]
+        }"#;
+        let toml = r#"
+            [[Functions]]
+            YarnName = "dice"
+            ReturnType = "number"
+            Parameters = [{ Name = "sides", Type = "number" }]
+            Deprecated = "Use roll instead"
+
+            [[Commands]]
+            YarnName = "walk"
+            Parameters = [{ Name = "character", Type = "string" }, { Name = "speed", Type = "number", DefaultValue = "1" }]
+        "#;
+        let from_json = Manifest::from_json(json).unwrap();
+        assert_eq!(from_json, Manifest::from_toml(toml).unwrap());
+
+        let dice = from_json.function("dice").unwrap();
+        assert_eq!(Type::Number, dice.return_type);
+        assert_eq!(Some("Use roll instead"), dice.deprecation.as_deref());
+        let walk = from_json.command("walk").unwrap();
+        assert_eq!((1, Some(2)), walk.argument_count());
+        assert_eq!(Type::String, walk.parameters[0].r#type);
+    }
+
+    #[test]
+    fn rejects_unknown_types() {
+        let json = r#"{ "Commands": [{ "YarnName": "walk", "Parameters": [{ "Name": "to", "Type": "vector" }] }] }"#;
+        assert_eq!(
+            Err(ManifestError::UnknownType {
+                name: "walk".to_owned(),
+                r#type: "vector".to_owned()
+            }),
+            Manifest::from_json(json)
+        );
+    }
+}
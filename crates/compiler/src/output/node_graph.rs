@@ -0,0 +1,758 @@
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+use std::ops::Range;
+
+/// The flow between the nodes of a compiled Yarn program, as created by [`Compilation::node_graph`].
+/// Can be rendered to [Graphviz DOT](https://graphviz.org/doc/info/lang.html) with [`NodeGraph::to_dot`],
+/// to [Mermaid](https://mermaid.js.org/syntax/flowchart.html) with [`NodeGraph::to_mermaid`] and to JSON with [`NodeGraph::to_json`].
+///
+/// Edges are created for every `<<jump>>` in a node. This version of Yarn Spinner has no `<<detour>>` statement, so jumps are the only way to move between nodes.
+/// Jumps inside `<<if>>` statements and options are annotated with the conditions and option text leading to them,
+/// which are reconstructed from the compiled program. Jumps to expressions, such as `<<jump {$next_node}>>`, cannot be resolved statically and are kept as [`EdgeTarget::Expression`].
+///
+/// Nodes are flagged when they cannot be reached from the start nodes, which are `["Start"]` by default, or when they have no outgoing edges and so end the dialogue.
+/// Nodes that are only reachable through jumps to expressions are reported as unreachable.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NodeGraph {
+    /// The nodes the dialogue may start at. Used to determine [`GraphNode::is_reachable`].
+    pub start_nodes: Vec<String>,
+    /// The nodes of the program, sorted by name.
+    pub nodes: Vec<GraphNode>,
+    /// The edges between the nodes, sorted by source node and position in the node.
+    pub edges: Vec<GraphEdge>,
+}
+
+/// A node in a [`NodeGraph`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GraphNode {
+    /// The title of the node.
+    pub name: String,
+    /// The name of the Yarn file containing the node, if known.
+    pub file_name: Option<String>,
+    /// The headers of the node except for the title, in the order they appear in the file.
+    pub headers: Vec<(String, String)>,
+    /// The tags of the node, taken from its `tags` header.
+    pub tags: Vec<String>,
+    /// The position of the node in visual editors, taken from its `position` header, which has the format `x,y`.
+    /// Positions with coordinates that are not finite, such as `NaN`, are ignored, as they cannot be represented in JSON.
+    pub position: Option<(f32, f32)>,
+    /// The color of the node in visual editors, taken from its `colorID` header.
+    pub color_id: Option<u32>,
+    /// Whether the node can be reached from [`NodeGraph::start_nodes`] through jumps to node names.
+    pub is_reachable: bool,
+    /// Whether the node has any outgoing edges. Nodes without exits end the dialogue.
+    pub has_exit: bool,
+}
+
+/// A jump from one node to another in a [`NodeGraph`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GraphEdge {
+    /// The name of the node containing the jump.
+    pub from: String,
+    /// The destination of the jump.
+    pub target: EdgeTarget,
+    /// The conditions that must be true for the jump to be reached, from outermost to innermost,
+    /// e.g. `$gold > 10` for a jump inside `<<if $gold > 10>>` or `!($gold > 10)` for one in the corresponding `<<else>>`.
+    pub conditions: Vec<String>,
+    /// The text of the option that must be selected for the jump to be reached, if any.
+    pub option: Option<String>,
+    /// The 1-indexed line number of the jump in the Yarn file, if known.
+    pub line_number: Option<usize>,
+}
+
+/// The destination of a [`GraphEdge`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EdgeTarget {
+    /// A jump to a node given by name, e.g. `<<jump End>>`.
+    Node(String),
+    /// A jump to a node whose name is computed at runtime, e.g. `<<jump {$next_node}>>`. Contains the expression.
+    Expression(String),
+}
+
+impl Compilation {
+    /// Extracts the [`NodeGraph`] of the compiled Yarn files.
+    /// Returns an empty graph if the compilation has no [`Program`], as is the case for [`CompilationType::StringsOnly`].
+    pub fn node_graph(&self) -> NodeGraph {
+        let Some(program) = self.program.as_ref() else {
+            return NodeGraph::default();
+        };
+        let mut names: Vec<_> = program.nodes.keys().collect();
+        names.sort();
+        let mut nodes = Vec::with_capacity(names.len());
+        let mut edges = Vec::new();
+        for name in names {
+            let node = &program.nodes[name];
+            let debug_info = self.debug_info.get(name);
+            nodes.push(GraphNode::new(node, debug_info));
+            edges.extend(find_edges(node, debug_info, &self.string_table));
+        }
+        let mut graph = NodeGraph {
+            start_nodes: vec!["Start".to_owned()],
+            nodes,
+            edges,
+        };
+        graph.update_flags();
+        graph
+    }
+}
+
+impl NodeGraph {
+    /// Sets the nodes the dialogue may start at and recomputes [`GraphNode::is_reachable`] accordingly.
+    pub fn with_start_nodes(
+        mut self,
+        start_nodes: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.start_nodes = start_nodes.into_iter().map(Into::into).collect();
+        self.update_flags();
+        self
+    }
+
+    /// Returns the node with the given name, if it exists.
+    pub fn node(&self, name: &str) -> Option<&GraphNode> {
+        self.nodes.iter().find(|node| node.name == name)
+    }
+
+    /// Iterates over the edges leaving the node with the given name.
+    pub fn edges_from<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a GraphEdge> {
+        self.edges.iter().filter(move |edge| edge.from == name)
+    }
+
+    /// Iterates over the nodes that cannot be reached from [`NodeGraph::start_nodes`].
+    pub fn unreachable_nodes(&self) -> impl Iterator<Item = &GraphNode> {
+        self.nodes.iter().filter(|node| !node.is_reachable)
+    }
+
+    /// Iterates over the nodes without outgoing edges.
+    pub fn dead_ends(&self) -> impl Iterator<Item = &GraphNode> {
+        self.nodes.iter().filter(|node| !node.has_exit)
+    }
+
+    fn update_flags(&mut self) {
+        let mut reachable = HashSet::new();
+        let mut queue: VecDeque<&str> = self.start_nodes.iter().map(String::as_str).collect();
+        while let Some(name) = queue.pop_front() {
+            if !reachable.insert(name) {
+                continue;
+            }
+            for edge in self.edges_from(name) {
+                if let EdgeTarget::Node(target) = &edge.target {
+                    queue.push_back(target);
+                }
+            }
+        }
+        let reachable: HashSet<String> = reachable.into_iter().map(ToOwned::to_owned).collect();
+        let with_exit: HashSet<String> = self.edges.iter().map(|edge| edge.from.clone()).collect();
+        for node in &mut self.nodes {
+            node.is_reachable = reachable.contains(&node.name);
+            node.has_exit = with_exit.contains(&node.name);
+        }
+    }
+
+    /// Renders the graph in the [Graphviz DOT](https://graphviz.org/doc/info/lang.html) language.
+    /// Unreachable nodes are drawn with a dashed red border, nodes without exits with a double border.
+    /// Node positions are passed on as `pos` attributes, which are respected by the `neato` and `fdp` layout engines.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        // Writing to a `String` cannot fail
+        let _ = self.write_dot(&mut dot);
+        dot
+    }
+
+    fn write_dot(&self, dot: &mut String) -> std::fmt::Result {
+        writeln!(dot, "digraph dialogue {{")?;
+        writeln!(
+            dot,
+            "    node [shape=box, style=\"rounded,filled\", fillcolor=white, colorscheme=pastel19];"
+        )?;
+        for node in &self.nodes {
+            let mut attributes = vec![format!("label={}", dot_string(&node_label(node)))];
+            let mut style = "rounded,filled";
+            if !node.is_reachable {
+                style = "rounded,filled,dashed";
+                attributes.push("color=red".to_owned());
+                attributes.push("penwidth=2".to_owned());
+            }
+            if !node.has_exit {
+                attributes.push("peripheries=2".to_owned());
+            }
+            attributes.push(format!("style={}", dot_string(style)));
+            if let Some(color_id) = node.color_id.filter(|id| (1..=9).contains(id)) {
+                attributes.push(format!("fillcolor={color_id}"));
+            }
+            if let Some((x, y)) = node.position {
+                // Visual editors have the y axis pointing down, Graphviz has it pointing up
+                attributes.push(format!("pos={}", dot_string(&format!("{x},{}!", -y))));
+            }
+            writeln!(
+                dot,
+                "    {} [{}];",
+                dot_string(&node.name),
+                attributes.join(", ")
+            )?;
+        }
+        let unresolved_targets = self.unresolved_targets();
+        for (index, target) in unresolved_targets.iter().enumerate() {
+            writeln!(
+                dot,
+                "    {} [label={}, shape=diamond, style=dashed, color=red];",
+                dot_string(&format!("?{index}")),
+                dot_string(&target.to_string())
+            )?;
+        }
+        for edge in &self.edges {
+            let target = match unresolved_targets.iter().position(|&t| *t == edge.target) {
+                Some(index) => format!("?{index}"),
+                None => edge.target.to_string(),
+            };
+            let label = edge_label(edge, "\n");
+            let label = if label.is_empty() {
+                String::new()
+            } else {
+                format!(" [label={}]", dot_string(&label))
+            };
+            writeln!(
+                dot,
+                "    {} -> {}{label};",
+                dot_string(&edge.from),
+                dot_string(&target)
+            )?;
+        }
+        writeln!(dot, "}}")?;
+        Ok(())
+    }
+
+    /// Renders the graph as a [Mermaid flowchart](https://mermaid.js.org/syntax/flowchart.html).
+    /// Unreachable nodes get the class `unreachable` and nodes without exits the class `deadEnd`, which are both styled in the output.
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::new();
+        // Writing to a `String` cannot fail
+        let _ = self.write_mermaid(&mut mermaid);
+        mermaid
+    }
+
+    fn write_mermaid(&self, mermaid: &mut String) -> std::fmt::Result {
+        // Node names may contain characters that are not allowed in Mermaid IDs, so we use generated IDs instead
+        let ids: HashMap<&str, String> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.name.as_str(), format!("n{index}")))
+            .collect();
+        let unresolved_targets = self.unresolved_targets();
+        writeln!(mermaid, "flowchart TD")?;
+        for node in &self.nodes {
+            writeln!(
+                mermaid,
+                "    {}[\"{}\"]",
+                ids[node.name.as_str()],
+                mermaid_escape(&node_label(node))
+            )?;
+        }
+        for (index, target) in unresolved_targets.iter().enumerate() {
+            writeln!(
+                mermaid,
+                "    x{index}{{{{\"{}\"}}}}",
+                mermaid_escape(&target.to_string())
+            )?;
+        }
+        for edge in &self.edges {
+            let target = match &edge.target {
+                EdgeTarget::Node(name) if ids.contains_key(name.as_str()) => {
+                    ids[name.as_str()].clone()
+                }
+                target => {
+                    let index = unresolved_targets
+                        .iter()
+                        .position(|&t| t == target)
+                        .unwrap();
+                    format!("x{index}")
+                }
+            };
+            let arrow = match edge.target {
+                EdgeTarget::Node(_) => "-->",
+                EdgeTarget::Expression(_) => "-.->",
+            };
+            let label = edge_label(edge, "<br>");
+            let label = if label.is_empty() {
+                String::new()
+            } else {
+                format!("|\"{}\"|", mermaid_escape(&label))
+            };
+            writeln!(
+                mermaid,
+                "    {} {arrow}{label} {target}",
+                ids[edge.from.as_str()]
+            )?;
+        }
+        writeln!(
+            mermaid,
+            "    classDef unreachable stroke:#d00,stroke-width:2px,stroke-dasharray:5 5"
+        )?;
+        writeln!(mermaid, "    classDef deadEnd stroke:#333,stroke-width:4px")?;
+        for (class, nodes) in [
+            ("unreachable", self.unreachable_nodes().collect::<Vec<_>>()),
+            ("deadEnd", self.dead_ends().collect()),
+        ] {
+            if !nodes.is_empty() {
+                let node_ids: Vec<_> = nodes
+                    .iter()
+                    .map(|node| ids[node.name.as_str()].as_str())
+                    .collect();
+                writeln!(mermaid, "    class {} {class}", node_ids.join(","))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Serializes the graph as pretty-printed JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("A node graph only contains serializable values")
+    }
+
+    /// The targets of edges that do not point to a node of the program, in order of appearance.
+    fn unresolved_targets(&self) -> Vec<&EdgeTarget> {
+        let mut targets = Vec::new();
+        for edge in &self.edges {
+            let is_resolved = match &edge.target {
+                EdgeTarget::Node(name) => self.node(name).is_some(),
+                EdgeTarget::Expression(_) => false,
+            };
+            if !is_resolved && !targets.contains(&&edge.target) {
+                targets.push(&edge.target);
+            }
+        }
+        targets
+    }
+}
+
+impl GraphNode {
+    fn new(node: &Node, debug_info: Option<&DebugInfo>) -> Self {
+        let headers: Vec<_> = node
+            .headers
+            .iter()
+            .filter(|header| header.key != "title")
+            .map(|header| (header.key.clone(), header.value.clone()))
+            .collect();
+        let header = |key: &str| {
+            headers
+                .iter()
+                .find(|(header_key, _)| header_key == key)
+                .map(|(_, value)| value.trim())
+        };
+        let position = header("position").and_then(|position| {
+            let (x, y) = position.split_once(',')?;
+            let x: f32 = x.trim().parse().ok()?;
+            let y: f32 = y.trim().parse().ok()?;
+            (x.is_finite() && y.is_finite()).then_some((x, y))
+        });
+        let color_id = header("colorID").and_then(|color_id| color_id.parse().ok());
+        Self {
+            name: node.name.clone(),
+            file_name: debug_info.map(|debug_info| debug_info.file_name.clone()),
+            tags: node.tags.clone(),
+            position,
+            color_id,
+            headers,
+            is_reachable: false,
+            has_exit: false,
+        }
+    }
+}
+
+impl std::fmt::Display for EdgeTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EdgeTarget::Node(name) => write!(f, "{name}"),
+            EdgeTarget::Expression(expression) => write!(f, "{{{expression}}}"),
+        }
+    }
+}
+
+/// A value on the stack during the symbolic execution of a node, rendered as Yarn source code.
+#[derive(Debug, Clone, Default)]
+struct SymbolicValue {
+    text: String,
+    /// The value of a string literal, which is used to resolve jump targets.
+    string_literal: Option<String>,
+    /// Whether the text needs parentheses when used as an operand.
+    is_compound: bool,
+}
+
+impl SymbolicValue {
+    fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    fn as_operand(&self) -> String {
+        if self.is_compound {
+            format!("({})", self.text)
+        } else {
+            self.text.clone()
+        }
+    }
+
+    fn negated(&self) -> String {
+        format!("!{}", self.as_operand())
+    }
+}
+
+/// A range of instructions that is only run under a condition or after selecting an option.
+struct Scope {
+    instructions: Range<usize>,
+    condition: Option<String>,
+    option: Option<String>,
+}
+
+/// Finds the jumps of a node by executing its instructions symbolically from top to bottom.
+/// The conditions of `<<if>>` statements and options are recovered from the layout of the code generated for them,
+/// see `CodeGenerationVisitor::generate_code_for_clause` and `CodeGenerationVisitor::visit_shortcut_option_statement`.
+fn find_edges(
+    node: &Node,
+    debug_info: Option<&DebugInfo>,
+    string_table: &HashMap<LineId, StringInfo>,
+) -> Vec<GraphEdge> {
+    let label = |operand: Option<String>| {
+        operand
+            .and_then(|label| node.labels.get(&label))
+            .map(|&index| index as usize)
+    };
+    let mut stack: Vec<SymbolicValue> = Vec::new();
+    let mut scopes: Vec<Scope> = Vec::new();
+    let mut edges = Vec::new();
+    for (index, instruction) in node.instructions.iter().enumerate() {
+        match instruction.opcode() {
+            OpCode::PushString => {
+                let value: String = operand(instruction, 0).unwrap_or_default();
+                stack.push(SymbolicValue {
+                    text: format!("\"{value}\""),
+                    string_literal: Some(value),
+                    is_compound: false,
+                });
+            }
+            OpCode::PushFloat => {
+                let value: f32 = operand(instruction, 0).unwrap_or_default();
+                stack.push(SymbolicValue::new(value.to_string()));
+            }
+            OpCode::PushBool => {
+                let value: bool = operand(instruction, 0).unwrap_or_default();
+                stack.push(SymbolicValue::new(value.to_string()));
+            }
+            OpCode::PushNull => stack.push(SymbolicValue::new("null")),
+            OpCode::PushVariable => {
+                let name: String = operand(instruction, 0).unwrap_or_default();
+                stack.push(SymbolicValue::new(name));
+            }
+            OpCode::CallFunc => {
+                let name: String = operand(instruction, 0).unwrap_or_default();
+                let parameter_count = stack
+                    .pop()
+                    .and_then(|count| count.text.parse::<f32>().ok())
+                    .unwrap_or_default() as usize;
+                let mut parameters: Vec<_> = (0..parameter_count)
+                    .map(|_| stack.pop().unwrap_or_default())
+                    .collect();
+                parameters.reverse();
+                stack.push(call_function(&name, &parameters));
+            }
+            OpCode::Pop => {
+                stack.pop();
+            }
+            OpCode::RunLine | OpCode::RunCommand => {
+                let substitution_count: usize = operand(instruction, 1).unwrap_or_default();
+                stack.truncate(stack.len().saturating_sub(substitution_count));
+            }
+            OpCode::JumpIfFalse => {
+                let condition = stack.last().cloned().unwrap_or_default();
+                let Some(end_of_clause) = label(operand(instruction, 0)) else {
+                    continue;
+                };
+                scopes.push(Scope {
+                    instructions: index + 1..end_of_clause,
+                    condition: Some(condition.text.clone()),
+                    option: None,
+                });
+                // Every clause ends with a jump to the end of the whole if statement, so the remaining clauses run only if this condition is false
+                let end_of_if_statement = end_of_clause
+                    .checked_sub(1)
+                    .and_then(|index| node.instructions.get(index))
+                    .filter(|instruction| instruction.opcode() == OpCode::JumpTo)
+                    .and_then(|instruction| label(operand(instruction, 0)));
+                if let Some(end_of_if_statement) = end_of_if_statement {
+                    scopes.push(Scope {
+                        instructions: end_of_clause..end_of_if_statement,
+                        condition: Some(condition.negated()),
+                        option: None,
+                    });
+                }
+            }
+            OpCode::AddOption => {
+                let substitution_count: usize = operand(instruction, 2).unwrap_or_default();
+                stack.truncate(stack.len().saturating_sub(substitution_count));
+                let has_condition: bool = operand(instruction, 3).unwrap_or_default();
+                let condition = has_condition.then(|| stack.pop().unwrap_or_default().text);
+                let destination: Option<String> = operand(instruction, 1);
+                let Some(start) = label(destination.clone()) else {
+                    continue;
+                };
+                let end_of_group = destination.as_deref().and_then(end_of_group_label);
+                let end = node
+                    .instructions
+                    .iter()
+                    .enumerate()
+                    .skip(start)
+                    .find(|(_, instruction)| {
+                        instruction.opcode() == OpCode::JumpTo
+                            && operand::<String>(instruction, 0) == end_of_group
+                    })
+                    .map(|(index, _)| index)
+                    .unwrap_or(node.instructions.len());
+                let line_id: Option<String> = operand(instruction, 0);
+                let text = line_id
+                    .and_then(|line_id| string_table.get(&LineId(line_id)))
+                    .map(|string_info| string_info.text.clone());
+                scopes.push(Scope {
+                    instructions: start..end,
+                    condition,
+                    option: text,
+                });
+            }
+            OpCode::ShowOptions => stack.push(SymbolicValue::new("<selected option>")),
+            OpCode::RunNode => {
+                let destination = stack.pop().unwrap_or_default();
+                let target = match destination.string_literal {
+                    Some(name) => EdgeTarget::Node(name),
+                    None => EdgeTarget::Expression(destination.text),
+                };
+                let mut enclosing_scopes: Vec<_> = scopes
+                    .iter()
+                    .filter(|scope| scope.instructions.contains(&index))
+                    .collect();
+                enclosing_scopes.sort_by_key(|scope| scope.instructions.start);
+                let line_number = debug_info
+                    .and_then(|debug_info| debug_info.instruction_sources.get(&index))
+                    .and_then(Option::as_ref)
+                    .map(|source| source.range.start.line + 1);
+                edges.push(GraphEdge {
+                    from: node.name.clone(),
+                    target,
+                    conditions: enclosing_scopes
+                        .iter()
+                        .filter_map(|scope| scope.condition.clone())
+                        .collect(),
+                    option: enclosing_scopes
+                        .iter()
+                        .rev()
+                        .find_map(|scope| scope.option.clone()),
+                    line_number,
+                });
+            }
+            OpCode::JumpTo | OpCode::Jump | OpCode::StoreVariable | OpCode::Stop => {}
+        }
+    }
+    edges
+}
+
+fn operand<T: TryFrom<Operand>>(instruction: &Instruction, index: usize) -> Option<T> {
+    instruction.operands.get(index)?.clone().try_into().ok()
+}
+
+/// Option destination labels are named `L<n>shortcutoption_<node>_<k>` for the k-th option of a group,
+/// and the label for the end of the group, `L<n-k>group_end`, is registered right before them.
+fn end_of_group_label(destination: &str) -> Option<String> {
+    let label_number: usize = destination
+        .strip_prefix('L')?
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()?;
+    let option_number: usize = destination.rsplit('_').next()?.parse().ok()?;
+    let group_number = label_number.checked_sub(option_number)?;
+    Some(format!("L{group_number}group_end"))
+}
+
+fn call_function(name: &str, parameters: &[SymbolicValue]) -> SymbolicValue {
+    // Operators are called by their canonical name, such as `Number.Add`
+    let operator = name.rsplit_once('.').map(|(_, operator)| operator);
+    let symbol = match operator {
+        Some("EqualTo") => "==",
+        Some("NotEqualTo") => "!=",
+        Some("GreaterThan") => ">",
+        Some("GreaterThanOrEqualTo") => ">=",
+        Some("LessThan") => "<",
+        Some("LessThanOrEqualTo") => "<=",
+        Some("And") => "&&",
+        Some("Or") => "||",
+        Some("Xor") => "^",
+        Some("Add") => "+",
+        Some("Subtract") => "-",
+        Some("Multiply") => "*",
+        Some("Divide") => "/",
+        Some("Modulo") => "%",
+        Some("Not") => "!",
+        Some("UnarySubtract") => "-",
+        _ => "",
+    };
+    match parameters {
+        [operand] if !symbol.is_empty() => {
+            SymbolicValue::new(format!("{symbol}{}", operand.as_operand()))
+        }
+        [lhs, rhs] if !symbol.is_empty() => SymbolicValue {
+            text: format!("{} {symbol} {}", lhs.as_operand(), rhs.as_operand()),
+            string_literal: None,
+            is_compound: true,
+        },
+        _ => {
+            let parameters: Vec<_> = parameters.iter().map(|p| p.text.as_str()).collect();
+            SymbolicValue::new(format!("{name}({})", parameters.join(", ")))
+        }
+    }
+}
+
+fn node_label(node: &GraphNode) -> String {
+    if node.is_reachable {
+        node.name.clone()
+    } else {
+        format!("{}\n(unreachable)", node.name)
+    }
+}
+
+fn edge_label(edge: &GraphEdge, line_break: &str) -> String {
+    let mut lines = Vec::new();
+    if !edge.conditions.is_empty() {
+        lines.push(edge.conditions.join(" && "));
+    }
+    if let Some(option) = &edge.option {
+        lines.push(format!("-> {option}"));
+    }
+    lines.join(line_break)
+}
+
+fn dot_string(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+        .replace('\n', "<br>")
+        .replace("#lt;br#gt;", "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_edges_with_conditions_and_options() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: Start
+position: 100,-20
+colorID: 3
+---
+<<if $gold > 10>>
+    <<jump Shop>>
+<<else>>
+    -> Beg
+        <<jump Street>>
+    -> Leave <<if not $is_cursed>>
+        <<jump {$exit}>>
+<<endif>>
+===
+title: Shop
+---
+Welcome!
+===
+title: Street
+---
+<<jump Shop>>
+===
+title: Secret
+---
+Nobody ever comes here.
+===
+"
+            .to_string(),
+        };
+        let compilation = Compiler::new().add_file(file).compile().unwrap();
+
+        let graph = compilation.node_graph();
+
+        let start = graph.node("Start").unwrap();
+        assert_eq!(Some((100.0, -20.0)), start.position);
+        assert_eq!(Some(3), start.color_id);
+        let edges: Vec<_> = graph.edges_from("Start").collect();
+        assert_eq!(3, edges.len());
+        assert_eq!(EdgeTarget::Node("Shop".to_owned()), edges[0].target);
+        assert_eq!(vec!["$gold > 10"], edges[0].conditions);
+        assert_eq!(None, edges[0].option);
+        assert_eq!(Some(6), edges[0].line_number);
+        assert_eq!(EdgeTarget::Node("Street".to_owned()), edges[1].target);
+        assert_eq!(vec!["!($gold > 10)"], edges[1].conditions);
+        assert_eq!(Some("Beg"), edges[1].option.as_deref());
+        assert_eq!(EdgeTarget::Expression("$exit".to_owned()), edges[2].target);
+        assert_eq!(vec!["!($gold > 10)", "!$is_cursed"], edges[2].conditions);
+
+        let unreachable: Vec<_> = graph.unreachable_nodes().map(|node| &node.name).collect();
+        assert_eq!(vec!["Secret"], unreachable);
+        let dead_ends: Vec<_> = graph.dead_ends().map(|node| &node.name).collect();
+        assert_eq!(vec!["Secret", "Shop"], dead_ends);
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"Start\" -> \"Shop\" [label=\"$gold > 10\"];"));
+        assert!(dot.contains("\"Secret\" [label=\"Secret\\n(unreachable)\""));
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.contains("class n0 unreachable"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_to_json() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: Start
+position: NaN,3
+---
+<<jump {$exit}>>
+===
+title: End
+position: 1.5,-2
+---
+The end.
+===
+"
+            .to_string(),
+        };
+        let compilation = Compiler::new().add_file(file).compile().unwrap();
+        let graph = compilation.node_graph();
+        assert_eq!(None, graph.node("Start").unwrap().position);
+
+        let json = graph.to_json();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            serde_json::json!({"Expression": "$exit"}),
+            value["edges"][0]["target"]
+        );
+        assert_eq!(
+            serde_json::json!([1.5, -2.0]),
+            value["nodes"][0]["position"]
+        );
+        let deserialized: NodeGraph = serde_json::from_str(&json).unwrap();
+        assert_eq!(graph, deserialized);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+use yarnspinner_core::markup::{
+    count_words, ParsedMarkup, CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY,
+};
+
+/// Statistics about the content of a [`Compilation`], as created by [`Compilation::statistics`].
+/// Useful for budgeting translation and voice-over work, and for tracking the growth of a script over time.
+///
+/// Words are counted with [`count_words`] on the text of lines and options.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScriptStatistics {
+    /// The statistics of the whole script.
+    pub total: ContentStatistics,
+    /// The statistics of every Yarn file, keyed by file name.
+    pub files: BTreeMap<String, FileStatistics>,
+    /// The statistics of the lines and options spoken by every character, keyed by the name of the character.
+    /// Lines without a `Name:` prefix are not included. The option groups of a character are those containing at least one of its options.
+    pub characters: BTreeMap<String, ContentStatistics>,
+}
+
+/// The statistics of a single Yarn file in [`ScriptStatistics`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FileStatistics {
+    /// The statistics of the whole file.
+    pub total: ContentStatistics,
+    /// The statistics of every node in the file, keyed by node name.
+    pub nodes: BTreeMap<String, ContentStatistics>,
+}
+
+/// Counts describing a part of a script, such as a node, a file or the lines of a character.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ContentStatistics {
+    /// The number of lines, not counting options.
+    pub lines: usize,
+    /// The number of options.
+    pub options: usize,
+    /// The number of words in all lines and options.
+    pub words: usize,
+    /// How often each command is run, keyed by command name, e.g. `"wait"` for `<<wait 2>>`.
+    pub commands: BTreeMap<String, usize>,
+    /// The number of times options are presented to the player.
+    pub option_groups: usize,
+    /// The average number of options per option group, or `0.0` if there are no option groups.
+    pub average_branching_factor: f32,
+}
+
+impl Compilation {
+    /// Gathers the [`ScriptStatistics`] of the compiled Yarn files.
+    /// Returns empty statistics if the compilation has no [`Program`], as is the case for [`CompilationType::StringsOnly`].
+    pub fn statistics(&self) -> ScriptStatistics {
+        let mut statistics = ScriptStatistics::default();
+        let Some(program) = self.program.as_ref() else {
+            return statistics;
+        };
+        for (node_name, node) in &program.nodes {
+            let mut node_statistics = ContentStatistics::default();
+            let mut characters_with_options = BTreeSet::new();
+            for instruction in &node.instructions {
+                match instruction.opcode() {
+                    OpCode::RunLine | OpCode::AddOption => {
+                        let is_option = instruction.opcode() == OpCode::AddOption;
+                        let line_id = LineId(instruction.read_operand(0));
+                        let Some(string_info) = self.string_table.get(&line_id) else {
+                            continue;
+                        };
+                        let words = count_words(&string_info.text);
+                        node_statistics.add_text(is_option, words);
+                        if let Some(character) = character_name(&string_info.text) {
+                            statistics
+                                .characters
+                                .entry(character.clone())
+                                .or_default()
+                                .add_text(is_option, words);
+                            if is_option {
+                                characters_with_options.insert(character);
+                            }
+                        }
+                    }
+                    OpCode::RunCommand => {
+                        let command_text: String = instruction.read_operand(0);
+                        if let Some(name) = command_text.split_whitespace().next() {
+                            *node_statistics.commands.entry(name.to_owned()).or_default() += 1;
+                        }
+                    }
+                    OpCode::ShowOptions => {
+                        node_statistics.option_groups += 1;
+                        for character in std::mem::take(&mut characters_with_options) {
+                            statistics
+                                .characters
+                                .entry(character)
+                                .or_default()
+                                .option_groups += 1;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            node_statistics.update_average_branching_factor();
+
+            let file_name = self
+                .debug_info
+                .get(node_name)
+                .map(|debug_info| debug_info.file_name.clone())
+                .unwrap_or_default();
+            let file_statistics = statistics.files.entry(file_name).or_default();
+            file_statistics.total.merge(&node_statistics);
+            statistics.total.merge(&node_statistics);
+            file_statistics
+                .nodes
+                .insert(node_name.clone(), node_statistics);
+        }
+        for character_statistics in statistics.characters.values_mut() {
+            character_statistics.update_average_branching_factor();
+        }
+        statistics
+    }
+}
+
+impl ContentStatistics {
+    /// Adds the counts of `other` to these statistics.
+    pub fn merge(&mut self, other: &Self) {
+        self.lines += other.lines;
+        self.options += other.options;
+        self.words += other.words;
+        for (command, count) in &other.commands {
+            *self.commands.entry(command.clone()).or_default() += count;
+        }
+        self.option_groups += other.option_groups;
+        self.update_average_branching_factor();
+    }
+
+    fn add_text(&mut self, is_option: bool, words: usize) {
+        if is_option {
+            self.options += 1;
+        } else {
+            self.lines += 1;
+        }
+        self.words += words;
+    }
+
+    fn update_average_branching_factor(&mut self) {
+        self.average_branching_factor = if self.option_groups == 0 {
+            0.0
+        } else {
+            self.options as f32 / self.option_groups as f32
+        };
+    }
+}
+
+/// Returns the character speaking the text, if any.
+fn character_name(text: &str) -> Option<String> {
+    // Invalid markup was already reported as a warning by the compiler
+    let markup = ParsedMarkup::parse(text).ok()?;
+    markup
+        .attributes
+        .iter()
+        .find(|attribute| attribute.name == CHARACTER_ATTRIBUTE)?
+        .property(CHARACTER_ATTRIBUTE_NAME_PROPERTY)
+        .map(|name| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_content_per_file_node_and_character() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: Start
+---
+<<declare $coins = 0>>
+Mae: Hi there, [b]Greg[/b]!
+Greg: You owe me {$coins} coins.
+<<wait 2>>
+-> Pay up
+    <<wait 1>>
+-> Run away
+-> Greg: Laugh
+<<jump End>>
+===
+title: End
+---
+The end.
+==="
+            .to_string(),
+        };
+        let compilation = Compiler::new().add_file(file).compile().unwrap();
+
+        let statistics = compilation.statistics();
+
+        let start = &statistics.files["test.yarn"].nodes["Start"];
+        assert_eq!(2, start.lines);
+        assert_eq!(3, start.options);
+        assert_eq!(12, start.words);
+        assert_eq!(Some(&2), start.commands.get("wait"));
+        assert_eq!(1, start.option_groups);
+        assert_eq!(3.0, start.average_branching_factor);
+
+        assert_eq!(3, statistics.total.lines);
+        assert_eq!(14, statistics.total.words);
+        assert_eq!(3, statistics.characters["Mae"].words);
+        assert_eq!(5, statistics.characters["Greg"].words);
+        assert_eq!(1, statistics.characters["Greg"].options);
+        assert_eq!(1, statistics.characters["Greg"].option_groups);
+        assert_eq!(1.0, statistics.characters["Greg"].average_branching_factor);
+        assert_eq!(0, statistics.characters["Mae"].option_groups);
+    }
+}
@@ -10,7 +10,7 @@ use super::generated::yarnspinnerlexer::{
 };
 use crate::collections::*;
 use crate::listeners::Diagnostic;
-use crate::prelude::{create_common_token, DiagnosticSeverity, TokenExt};
+use crate::prelude::{create_common_token, DiagnosticCode, DiagnosticSeverity, TokenExt};
 use antlr_rust::token::CommonToken;
 use antlr_rust::{
     char_stream::CharStream,
@@ -316,12 +316,15 @@ where
 
         if saw_spaces && saw_tabs {
             self.diagnostics.borrow_mut().push(
-                Diagnostic::from_message("Indentation contains tabs and spaces")
-                    .with_range(get_newline_indentation_range(current_token))
-                    .with_context(get_newline_indentation_text(current_token))
-                    .with_start_line(current_token.line as usize)
-                    .with_file_name(self.file_name.clone())
-                    .with_severity(DiagnosticSeverity::Warning),
+                Diagnostic::new(
+                    DiagnosticCode::MixedIndentation,
+                    "Indentation contains tabs and spaces",
+                )
+                .with_range(get_newline_indentation_range(current_token))
+                .with_context(get_newline_indentation_text(current_token))
+                .with_start_line(current_token.line as usize)
+                .with_file_name(self.file_name.clone())
+                .with_severity(DiagnosticSeverity::Warning),
             );
         }
 
@@ -356,20 +359,23 @@ where
             let line_len = token.get_text().lines().count();
             let last_line_len = token.get_text().lines().last().unwrap().len();
             self.diagnostics.borrow_mut().push(
-                Diagnostic::from_message("Newlines are not allowed in commands")
-                    .with_range(
-                        Position {
-                            line: token.get_line_as_usize() - 1,
-                            character: token.get_column_as_usize(),
-                        }..Position {
-                            line: token.get_line_as_usize() - 1 + line_len,
-                            character: last_line_len,
-                        },
-                    )
-                    .with_context(token.get_text().to_string())
-                    .with_start_line(token.get_line_as_usize() - 1)
-                    .with_file_name(self.file_name.clone())
-                    .with_severity(DiagnosticSeverity::Error),
+                Diagnostic::new(
+                    DiagnosticCode::NewlineInCommand,
+                    "Newlines are not allowed in commands",
+                )
+                .with_range(
+                    Position {
+                        line: token.get_line_as_usize() - 1,
+                        character: token.get_column_as_usize(),
+                    }..Position {
+                        line: token.get_line_as_usize() - 1 + line_len,
+                        character: last_line_len,
+                    },
+                )
+                .with_context(token.get_text().to_string())
+                .with_start_line(token.get_line_as_usize() - 1)
+                .with_file_name(self.file_name.clone())
+                .with_severity(DiagnosticSeverity::Error),
             );
         }
     }
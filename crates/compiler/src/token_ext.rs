@@ -1,4 +1,6 @@
 use antlr_rust::token::Token;
+use std::ops::Range;
+use yarnspinner_core::prelude::Position;
 
 pub(crate) trait TokenExt: Token {
     fn get_line_as_usize(&self) -> usize {
@@ -8,6 +10,21 @@ pub(crate) trait TokenExt: Token {
     fn get_column_as_usize(&self) -> usize {
         usize::try_from(self.get_column()).unwrap_or_default()
     }
+
+    /// The range of the source this token was read from.
+    fn range(&self) -> Range<Position> {
+        let start = Position {
+            line: self.get_line_as_usize().saturating_sub(1),
+            character: self.get_column_as_usize(),
+        };
+        // The stop index is inclusive and before the start index for empty tokens such as EOF
+        let length = usize::try_from(self.get_stop() - self.get_start() + 1).unwrap_or_default();
+        let end = Position {
+            character: start.character + length,
+            ..start
+        };
+        start..end
+    }
 }
 
 impl<T: Token + ?Sized> TokenExt for T {}
@@ -8,7 +8,7 @@ use crate::prelude::*;
 use antlr_rust::parser_rule_context::ParserRuleContext;
 use antlr_rust::token::Token;
 use antlr_rust::tree::{ParseTree, ParseTreeVisitorCompat, Tree};
-use std::ops::Deref;
+use std::ops::{Deref, Range};
 use std::rc::Rc;
 use yarnspinner_core::prelude::OpCode;
 use yarnspinner_core::prelude::*;
@@ -85,6 +85,32 @@ impl<'a, 'input: 'a> ParseTreeVisitorCompat<'input> for CodeGenerationVisitor<'a
 }
 
 impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVisitor<'a, 'input> {
+    /// any statement, which determines the kind of construct the instructions emitted for it belong to
+    fn visit_statement(&mut self, ctx: &StatementContext<'input>) -> Self::Return {
+        let construct = if ctx.line_statement().is_some() {
+            SourceConstruct::Line
+        } else if ctx.shortcut_option_statement().is_some() {
+            SourceConstruct::ShortcutOption
+        } else if ctx.if_statement().is_some() {
+            SourceConstruct::Condition
+        } else if ctx.set_statement().is_some() {
+            SourceConstruct::Set
+        } else if ctx.call_statement().is_some() {
+            SourceConstruct::Call
+        } else if ctx.command_statement().is_some() {
+            SourceConstruct::Command
+        } else if ctx.jump_statement().is_some() {
+            SourceConstruct::Jump
+        } else {
+            self.compiler_listener.current_construct
+        };
+        // Statements nest, e.g. in an <<if>>, so restore the enclosing construct afterwards
+        let enclosing_construct =
+            std::mem::replace(&mut self.compiler_listener.current_construct, construct);
+        ParseTreeVisitorCompat::visit_children(self, ctx);
+        self.compiler_listener.current_construct = enclosing_construct;
+    }
+
     /// a regular ol' line of text
     fn visit_line_statement(&mut self, ctx: &Line_statementContext<'input>) -> Self::Return {
         // [sic] TODO: add support for line conditions:
@@ -105,7 +131,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
         let line_id = line_id_tag.text.as_ref().unwrap().get_text().to_owned();
         self.compiler_listener.emit(
             Emit::from_op_code(OpCode::RunLine)
-                .with_token(ctx.start().deref())
+                .with_parser_context(ctx)
                 .with_operand(line_id)
                 .with_operand(expression_count),
         );
@@ -125,7 +151,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             ctx.expression(0).unwrap() as Rc<ActualParserContext<'input>>,
             ctx.expression(1).unwrap(),
         ];
-        self.generate_code_for_operation(operator, operator_token.deref(), &r#type, &expressions)
+        self.generate_code_for_operation(operator, ctx.range(), &r#type, &expressions)
     }
 
     /// < <= > >=
@@ -137,17 +163,16 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             ctx.expression(0).unwrap() as Rc<ActualParserContext<'input>>,
             ctx.expression(1).unwrap(),
         ];
-        self.generate_code_for_operation(operator, operator_token.deref(), &r#type, &expressions)
+        self.generate_code_for_operation(operator, ctx.range(), &r#type, &expressions)
     }
 
     /// -expression
     fn visit_expNegative(&mut self, ctx: &ExpNegativeContext<'input>) -> Self::Return {
-        let operator_token = ctx.op.as_ref().unwrap();
         let r#type = self.compiler_listener.types.get(ctx).unwrap().clone();
         let expressions = vec![ctx.expression().unwrap() as Rc<ActualParserContext<'input>>];
         self.generate_code_for_operation(
             Operator::UnarySubtract,
-            operator_token.deref(),
+            ctx.range(),
             &r#type,
             &expressions,
         )
@@ -162,7 +187,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             ctx.expression(0).unwrap() as Rc<ActualParserContext<'input>>,
             ctx.expression(1).unwrap(),
         ];
-        self.generate_code_for_operation(operator, operator_token.deref(), &r#type, &expressions)
+        self.generate_code_for_operation(operator, ctx.range(), &r#type, &expressions)
     }
 
     /// + -
@@ -174,20 +199,14 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             ctx.expression(0).unwrap() as Rc<ActualParserContext<'input>>,
             ctx.expression(1).unwrap(),
         ];
-        self.generate_code_for_operation(operator, operator_token.deref(), &r#type, &expressions)
+        self.generate_code_for_operation(operator, ctx.range(), &r#type, &expressions)
     }
 
     /// [sic] (not NOT !)expression
     fn visit_expNot(&mut self, ctx: &ExpNotContext<'input>) -> Self::Return {
-        let operator_token = ctx.op.as_ref().unwrap();
         let r#type = self.compiler_listener.types.get(ctx).unwrap().clone();
         let expressions = vec![ctx.expression().unwrap() as Rc<ActualParserContext<'input>>];
-        self.generate_code_for_operation(
-            Operator::Not,
-            operator_token.deref(),
-            &r#type,
-            &expressions,
-        )
+        self.generate_code_for_operation(Operator::Not, ctx.range(), &r#type, &expressions)
     }
 
     /// Variable
@@ -204,14 +223,14 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             ctx.expression(0).unwrap() as Rc<ActualParserContext<'input>>,
             ctx.expression(1).unwrap(),
         ];
-        self.generate_code_for_operation(operator, operator_token.deref(), &r#type, &expressions)
+        self.generate_code_for_operation(operator, ctx.range(), &r#type, &expressions)
     }
 
     fn visit_valueNumber(&mut self, ctx: &ValueNumberContext<'input>) -> Self::Return {
         let number: f32 = ctx.NUMBER().unwrap().get_text().parse().unwrap();
         self.compiler_listener.emit(
             Emit::from_op_code(OpCode::PushFloat)
-                .with_token(ctx.start().deref())
+                .with_parser_context(ctx)
                 .with_operand(number),
         )
     }
@@ -219,7 +238,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
     fn visit_valueTrue(&mut self, ctx: &ValueTrueContext<'input>) -> Self::Return {
         self.compiler_listener.emit(
             Emit::from_op_code(OpCode::PushBool)
-                .with_token(ctx.start().deref())
+                .with_parser_context(ctx)
                 .with_operand(true),
         )
     }
@@ -227,7 +246,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
     fn visit_valueFalse(&mut self, ctx: &ValueFalseContext<'input>) -> Self::Return {
         self.compiler_listener.emit(
             Emit::from_op_code(OpCode::PushBool)
-                .with_token(ctx.start().deref())
+                .with_parser_context(ctx)
                 .with_operand(false),
         )
     }
@@ -246,7 +265,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             .to_owned();
         self.compiler_listener.emit(
             Emit::from_op_code(OpCode::PushString)
-                .with_token(ctx.start().deref())
+                .with_parser_context(ctx)
                 .with_operand(string_value),
         )
     }
@@ -254,7 +273,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
     /// null value
     fn visit_valueNull(&mut self, ctx: &ValueNullContext<'input>) -> Self::Return {
         self.compiler_listener
-            .emit(Emit::from_op_code(OpCode::PushNull).with_token(ctx.start().deref()))
+            .emit(Emit::from_op_code(OpCode::PushNull).with_parser_context(ctx))
     }
 
     /// all we need do is visit the function itself, it will handle everything
@@ -266,7 +285,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
         let variable_name = ctx.VAR_ID().unwrap().get_text();
         self.compiler_listener.emit(
             Emit::from_op_code(OpCode::PushVariable)
-                .with_token(ctx.start().deref())
+                .with_parser_context(ctx)
                 .with_operand(variable_name),
         )
     }
@@ -279,11 +298,10 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             self.visit(parameter.as_ref());
         }
 
-        let token = ctx.start();
         // push the number of parameters onto the stack
         self.compiler_listener.emit(
             Emit::from_op_code(OpCode::PushFloat)
-                .with_token(token.deref())
+                .with_parser_context(ctx)
                 .with_operand(expressions.len()),
         );
 
@@ -291,7 +309,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
         let function_name = ctx.FUNC_ID().unwrap().get_text();
         self.compiler_listener.emit(
             Emit::from_op_code(OpCode::CallFunc)
-                .with_token(token.deref())
+                .with_parser_context(ctx)
                 .with_operand(function_name),
         );
     }
@@ -357,7 +375,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
                 .clone();
             self.generate_code_for_operation(
                 op,
-                operator_token.as_ref(),
+                operator_token.range(),
                 &r#type,
                 &[variable.clone(), expression.clone()],
             )
@@ -436,13 +454,13 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
                 // "stop" is a special command that immediately stops
                 // execution
                 self.compiler_listener.emit(
-                    Emit::from_op_code(OpCode::Stop).with_token(formatted_text.start().deref()),
+                    Emit::from_op_code(OpCode::Stop).with_parser_context(formatted_text.as_ref()),
                 );
             }
             _ => {
                 self.compiler_listener.emit(
                     Emit::from_op_code(OpCode::RunCommand)
-                        .with_token(formatted_text.start().deref())
+                        .with_parser_context(formatted_text.as_ref())
                         .with_operand(composed_string)
                         .with_operand(expression_count),
                 );
@@ -512,7 +530,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
             // And add this option to the list.
             self.compiler_listener.emit(
                 Emit::from_op_code(OpCode::AddOption)
-                    .with_token(line_statement.start().deref())
+                    .with_parser_context(line_statement.as_ref())
                     .with_operand(line_id)
                     .with_operand(option_destination_label)
                     .with_operand(expression_count)
@@ -576,7 +594,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
                 .with_operand(destination.get_text().to_owned()),
         );
         self.compiler_listener
-            .emit(Emit::from_op_code(OpCode::RunNode).with_token(ctx.start().deref()))
+            .emit(Emit::from_op_code(OpCode::RunNode).with_parser_context(ctx))
     }
 
     /// A <<jump>> command, which immediately jumps to another node, given an
@@ -588,7 +606,7 @@ impl<'a, 'input: 'a> YarnSpinnerParserVisitorCompat<'input> for CodeGenerationVi
         // Evaluate the expression, and jump to the result on the stack.
         self.visit(ctx.expression().unwrap().as_ref());
         self.compiler_listener
-            .emit(Emit::from_op_code(OpCode::RunNode).with_token(ctx.start().deref()))
+            .emit(Emit::from_op_code(OpCode::RunNode).with_parser_context(ctx))
     }
 }
 
@@ -618,7 +636,7 @@ impl<'a, 'input: 'a> CodeGenerationVisitor<'a, 'input> {
     fn generate_code_for_operation(
         &mut self,
         op: Operator,
-        operator_token: &impl Token,
+        source: Range<Position>,
         r#type: &Type,
         operands: &[Rc<ActualParserContext<'input>>],
     ) {
@@ -631,7 +649,7 @@ impl<'a, 'input: 'a> CodeGenerationVisitor<'a, 'input> {
         // Indicate that we are pushing this many items for comparison
         self.compiler_listener.emit(
             Emit::from_op_code(OpCode::PushFloat)
-                .with_token(operator_token)
+                .with_source(source.clone())
                 .with_operand(operands.len()),
         );
         // Figure out the canonical name for the method that the VM should
@@ -648,7 +666,7 @@ impl<'a, 'input: 'a> CodeGenerationVisitor<'a, 'input> {
         // Call that function.
         self.compiler_listener.emit(
             Emit::from_op_code(OpCode::CallFunc)
-                .with_token(operator_token)
+                .with_source(source)
                 .with_operand(function_name),
         );
     }
@@ -669,7 +687,7 @@ impl<'a, 'input: 'a> CodeGenerationVisitor<'a, 'input> {
 
             self.compiler_listener.emit(
                 Emit::from_op_code(OpCode::JumpIfFalse)
-                    .with_token(expression.start().deref())
+                    .with_parser_context(expression.as_ref())
                     .with_operand(end_of_clause_label.clone()),
             );
         }
@@ -54,7 +54,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
         } else {
             let message = format!("Failed to parse {text} as a float",);
             self.diagnostics.push(
-                Diagnostic::from_message(message)
+                Diagnostic::new(DiagnosticCode::InvalidNumber, message)
                     .with_file_name(&self.file.name)
                     .with_parser_context(ctx, self.file.tokens()),
             );
@@ -79,7 +79,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
             "Variable declarations must be constant values, but `{text}` is another variable",
         );
         self.diagnostics.push(
-            Diagnostic::from_message(message)
+            Diagnostic::new(DiagnosticCode::NonConstantDeclaration, message)
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens()),
         );
@@ -94,7 +94,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
     fn visit_valueNull(&mut self, ctx: &ValueNullContext<'input>) -> Self::Return {
         let message = "Null is not a permitted type in Yarn Spinner 2.0 and later";
         self.diagnostics.push(
-            Diagnostic::from_message(message)
+            Diagnostic::new(DiagnosticCode::NullValue, message)
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens()),
         );
@@ -106,7 +106,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for ConstantValueVisitor<'in
         let message =
             format!("Variable declarations must be constant values, but `{text}` is a function",);
         self.diagnostics.push(
-            Diagnostic::from_message(message)
+            Diagnostic::new(DiagnosticCode::NonConstantDeclaration, message)
                 .with_file_name(&self.file.name)
                 .with_parser_context(ctx, self.file.tokens()),
         );
@@ -100,7 +100,8 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
                 let message =
                     format!("The node '{current_node_name}' contains illegal characters.");
                 self.diagnostics.push(
-                    Diagnostic::from_message(message)
+                    Diagnostic::new(DiagnosticCode::InvalidNodeName, message)
+                        .with_node(current_node_name)
                         .with_file_name(self.file.name.clone())
                         .with_parser_context(header.as_ref(), self.file.tokens()),
                 );
@@ -112,6 +113,12 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
     }
 
     fn visit_declare_statement(&mut self, ctx: &Declare_statementContext<'input>) -> Self::Return {
+        // Declarations are still collected from nodes with syntax errors, as long as they themselves parsed,
+        // so that the rest of the file does not report the variables as undeclared.
+        if self.file.has_syntax_errors(ctx) {
+            return;
+        }
+
         // Get the name of the variable we're declaring
         let variable_context = ctx.variable().unwrap();
         let variable_name = variable_context.get_text();
@@ -132,7 +139,8 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
                 existing_explicit_declaration.name, existing_explicit_declaration.source_file_name,
             );
             self.diagnostics.push(
-                Diagnostic::from_message(msg)
+                Diagnostic::new(DiagnosticCode::DuplicateDeclaration, msg)
+                    .with_variable(variable_name)
                     .with_file_name(&self.file.name)
                     .with_parser_context(ctx, self.file.tokens()),
             );
@@ -163,7 +171,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
                         // We didn't find a type by this name.
                         let msg = format!("Unknown type {}", declaration_type.get_text());
                         self.diagnostics.push(
-                            Diagnostic::from_message(msg)
+                            Diagnostic::new(DiagnosticCode::UnknownType, msg)
                                 .with_file_name(&self.file.name)
                                 .with_parser_context(ctx, self.file.tokens()),
                         );
@@ -184,7 +192,10 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for DeclarationVisitor<'inpu
                         value.r#type.format()
                     );
                     self.diagnostics.push(
-                        Diagnostic::from_message(msg)
+                        Diagnostic::new(DiagnosticCode::DeclarationTypeMismatch, msg)
+                            .with_variable(variable_name)
+                            .with_expected_types([declaration_type.get_text()])
+                            .with_actual_types([value.r#type.format()])
                             .with_file_name(&self.file.name)
                             .with_parser_context(ctx, self.file.tokens()),
                     );
@@ -238,6 +249,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            ..Default::default()
         }
         .compile()
         .unwrap();
@@ -328,6 +340,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            ..Default::default()
         }
         .compile();
 
@@ -335,22 +348,29 @@ mod tests {
         assert_eq!(2, diagnostics.len());
         assert_eq!(
             diagnostics[0],
-            Diagnostic::from_message("Type string does not match value 1 (Number)".to_string())
-                .with_file_name("test.yarn".to_string())
-                .with_context(file.source.clone())
-                .with_range(
-                    Position {
-                        line: 2,
-                        character: 0,
-                    }..Position {
-                        line: 2,
-                        character: 31,
-                    }
-                )
+            Diagnostic::new(
+                DiagnosticCode::DeclarationTypeMismatch,
+                "Type string does not match value 1 (Number)".to_string()
+            )
+            .with_variable("$foo")
+            .with_expected_types(["string"])
+            .with_actual_types(["Number"])
+            .with_file_name("test.yarn".to_string())
+            .with_context(file.source.clone())
+            .with_range(
+                Position {
+                    line: 2,
+                    character: 0,
+                }..Position {
+                    line: 2,
+                    character: 31,
+                }
+            )
         );
         assert_eq!(
             diagnostics[1],
-            Diagnostic::from_message("Can't figure out the type of variable $foo given its context. Specify its type with a <<declare>> statement.".to_string())
+            Diagnostic::new(DiagnosticCode::UndeclaredVariable, "Can't figure out the type of variable $foo given its context. Specify its type with a <<declare>> statement.".to_string())
+                .with_variable("$foo")
                 .with_file_name("test.yarn".to_string())
                 .with_context(file.source)
                 .with_range(
@@ -0,0 +1,100 @@
+use crate::listeners::closest_match;
+use crate::prelude::generated::yarnspinnerparser::*;
+use crate::prelude::generated::yarnspinnerparservisitor::YarnSpinnerParserVisitorCompat;
+use crate::prelude::*;
+use antlr_rust::token::Token;
+use antlr_rust::tree::ParseTreeVisitorCompat;
+use std::collections::HashSet;
+
+/// A visitor that reports `<<jump>>`s to nodes that exist in none of the compiled files.
+/// Jumps to expressions such as `<<jump {$destination}>>` are not checked, as their destination is only known at runtime.
+pub(crate) struct JumpTargetVisitor<'input> {
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    node_names: HashSet<String>,
+    file: FileParseResult<'input>,
+    _dummy: (),
+}
+
+impl<'input> JumpTargetVisitor<'input> {
+    pub(crate) fn new(node_names: HashSet<String>, file: FileParseResult<'input>) -> Self {
+        Self {
+            diagnostics: Default::default(),
+            node_names,
+            file,
+            _dummy: (),
+        }
+    }
+}
+
+impl<'input> ParseTreeVisitorCompat<'input> for JumpTargetVisitor<'input> {
+    type Node = YarnSpinnerParserContextType;
+    type Return = ();
+
+    fn temp_result(&mut self) -> &mut Self::Return {
+        &mut self._dummy
+    }
+}
+
+impl<'input> YarnSpinnerParserVisitorCompat<'input> for JumpTargetVisitor<'input> {
+    fn visit_jumpToNodeName(&mut self, ctx: &JumpToNodeNameContext<'input>) -> Self::Return {
+        let Some(destination) = ctx.destination.as_ref() else {
+            return;
+        };
+        let name = destination.get_text();
+        if self.node_names.contains(name) {
+            return;
+        }
+        // Warn instead of erroring, as the node may be part of another program that is loaded alongside this one
+        let suggestion = closest_match(name, self.node_names.iter().map(String::as_str))
+            .map(|suggestion| DiagnosticFix::did_you_mean(suggestion, destination.range()));
+        let diagnostic = Diagnostic::new(
+            DiagnosticCode::UnknownNode,
+            format!("No node named \"{name}\" exists"),
+        )
+        .with_severity(DiagnosticSeverity::Warning)
+        .with_node(name)
+        .with_file_name(&self.file.name)
+        .with_parser_context(ctx, self.file.tokens())
+        .with_fix(suggestion);
+        self.diagnostics.push(diagnostic);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggests_existing_node_for_unknown_jump_target() {
+        let source = "title: Start
+---
+<<jump Tavren>>
+<<jump Nowhere>>
+===
+title: Tavern
+---
+Welcome!
+===";
+        let result = Compiler::new()
+            .add_file(File {
+                file_name: "test.yarn".to_string(),
+                source: source.to_string(),
+            })
+            .compile()
+            .unwrap();
+
+        assert_eq!(2, result.warnings.len());
+        assert!(result
+            .warnings
+            .iter()
+            .all(|warning| warning.code == DiagnosticCode::UnknownNode));
+        let fixes: Vec<_> = result
+            .warnings
+            .iter()
+            .flat_map(|warning| &warning.fixes)
+            .collect();
+        assert_eq!(1, fixes.len());
+        assert_eq!("Tavern", fixes[0].replacement);
+        assert!(fixes[0].apply(source).contains("<<jump Tavern>>"));
+    }
+}
@@ -5,6 +5,7 @@ use antlr_rust::parser_rule_context::ParserRuleContext;
 use antlr_rust::token::Token;
 use antlr_rust::tree::{ParseTree, ParseTreeVisitorCompat, Tree};
 use std::rc::Rc;
+use yarnspinner_core::markup::MarkupValidator;
 
 #[derive(Clone)]
 /// A Visitor that walks an expression parse tree and generates string
@@ -12,11 +13,17 @@ use std::rc::Rc;
 /// This string table can then be provided
 /// to future compilation passes, or stored for later use. Call the
 /// [`visit`] method to begin generating string table entries.
+///
+/// ## Implementation notes
+///
+/// In contrast to the original, the markup of every line is validated here as well,
+/// so that errors are reported at compile time instead of when the line is run.
 pub(crate) struct StringTableGeneratorVisitor<'input> {
     pub(crate) diagnostics: Vec<Diagnostic>,
     current_node_name: String,
     pub(crate) string_table_manager: StringTableManager,
     file: FileParseResult<'input>,
+    markup_validator: MarkupValidator,
     _dummy: (),
 }
 
@@ -24,15 +31,78 @@ impl<'input> StringTableGeneratorVisitor<'input> {
     pub(crate) fn new(
         string_table_manager: StringTableManager,
         file: FileParseResult<'input>,
+        markup_validator: MarkupValidator,
     ) -> Self {
         Self {
             file,
             string_table_manager,
+            markup_validator,
             diagnostics: Default::default(),
             current_node_name: Default::default(),
             _dummy: (),
         }
     }
+
+    fn format_specifier_of(&mut self, expression: &ExpressionContextAll<'input>) -> Option<String> {
+        let start = usize::try_from(expression.start().get_start()).ok()?;
+        let format_specifier = self.file.format_specifiers.get(&start)?;
+        match format_specifier.parse::<NumberStyle>() {
+            Ok(_) => Some(format_specifier.clone()),
+            Err(error) => {
+                self.diagnostics.push(
+                    Diagnostic::new(DiagnosticCode::InvalidFormatSpecifier, error.to_string())
+                        .with_parser_context(expression, self.file.tokens())
+                        .with_file_name(&self.file.name),
+                );
+                None
+            }
+        }
+    }
+
+    /// Reports problems in the markup of a line as diagnostics pointing at the exact markers in the source.
+    fn validate_markup(&mut self, ctx: &Line_formatted_textContext<'input>) {
+        let line_start = ctx.start().get_start();
+        let mut text: Vec<_> = ctx
+            .get_text_with_whitespace(self.file.tokens())
+            .chars()
+            .collect();
+        // Expressions are substituted at runtime, so blank them out with digits that keep all positions intact.
+        for expression in ctx.expression_all() {
+            let start = usize::try_from(expression.start().get_start() - line_start)
+                .unwrap_or_default()
+                .min(text.len());
+            let stop = usize::try_from(expression.stop().get_stop() + 1 - line_start)
+                .unwrap_or_default()
+                .clamp(start, text.len());
+            text[start..stop].fill('0');
+        }
+        let text: String = text.into_iter().collect();
+
+        let line = ctx.start().get_line_as_usize().saturating_sub(1);
+        let column = ctx.start().get_column_as_usize();
+        for issue in self.markup_validator.validate(&text) {
+            let source_range = issue.source_range();
+            let range = Position {
+                line,
+                character: column + source_range.start,
+            }..Position {
+                line,
+                character: column + source_range.end,
+            };
+            let severity = if issue.is_error() {
+                DiagnosticSeverity::Error
+            } else {
+                DiagnosticSeverity::Warning
+            };
+            self.diagnostics.push(
+                Diagnostic::new(DiagnosticCode::InvalidMarkup, issue.to_string())
+                    .with_file_name(&self.file.name)
+                    .with_parser_context(ctx, self.file.tokens())
+                    .with_range(range)
+                    .with_severity(severity),
+            );
+        }
+    }
 }
 
 impl<'input> ParseTreeVisitorCompat<'input> for StringTableGeneratorVisitor<'input> {
@@ -108,9 +178,13 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for StringTableGeneratorVisi
                 let diagnostic_context = line_id_tag.clone().unwrap();
                 let line_id = line_id.get_text();
                 self.diagnostics.push(
-                    Diagnostic::from_message(format!("Duplicate line ID {line_id}"))
-                        .with_parser_context(diagnostic_context.as_ref(), self.file.tokens())
-                        .with_file_name(&self.file.name),
+                    Diagnostic::new(
+                        DiagnosticCode::DuplicateLineId,
+                        format!("Duplicate line ID {line_id}"),
+                    )
+                    .with_line_id(line_id)
+                    .with_parser_context(diagnostic_context.as_ref(), self.file.tokens())
+                    .with_file_name(&self.file.name),
                 );
                 return;
             }
@@ -119,7 +193,11 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for StringTableGeneratorVisi
         let line_number = ctx.start().get_line_as_usize();
         let hashtag_texts = get_hashtag_texts(&hashtags);
 
-        let composed_string = generate_formatted_text(&ctx.line_formatted_text().unwrap());
+        let line_formatted_text = ctx.line_formatted_text().unwrap();
+        self.validate_markup(&line_formatted_text);
+        let composed_string = generate_formatted_text(&line_formatted_text, |expression| {
+            self.format_specifier_of(expression)
+        });
 
         let string_id = self.string_table_manager.insert(
             line_id.map(|t| t.get_text().into()),
@@ -143,9 +221,17 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for StringTableGeneratorVisi
 /// `Hi there { some_expression }, how are you { another_expression } doing?`
 /// and turns it into
 /// `Hi there {0}, how are you {1}? doing`
-fn generate_formatted_text(ctx: &Line_formatted_textContext) -> String {
+///
+/// ## Implementation notes
+///
+/// The format specifier returned by `format_specifier_of` for an expression is written after its index, e.g. `{0:0.00}`.
+fn generate_formatted_text<'input>(
+    ctx: &Line_formatted_textContext<'input>,
+    mut format_specifier_of: impl FnMut(&ExpressionContextAll<'input>) -> Option<String>,
+) -> String {
     let mut expression_count = 0;
     let mut composed_string = String::new();
+    let mut expressions = ctx.expression_all().into_iter();
     // First, visit all of the nodes, which are either terminal
     // text nodes or expressions. if they're expressions, we
     // evaluate them, and inject a positional reference into the
@@ -161,6 +247,13 @@ fn generate_formatted_text(ctx: &Line_formatted_textContext) -> String {
             // captured already has them. So, we just need to write
             // the expression count.
             composed_string.push_str(&expression_count.to_string());
+            let format_specifier = expressions
+                .next()
+                .and_then(|expression| format_specifier_of(&expression));
+            if let Some(format_specifier) = format_specifier {
+                composed_string.push(':');
+                composed_string.push_str(&format_specifier);
+            }
             expression_count += 1;
         }
     }
@@ -240,7 +333,7 @@ A line with {$many} many {(1 -(1 * 2))}{$cool} expressions
             .unwrap()
             .line_formatted_text()
             .unwrap();
-        generate_formatted_text(&line_formatted_text)
+        generate_formatted_text(&line_formatted_text, |_| None)
     }
 
     #[test]
@@ -260,6 +353,7 @@ a {1 + 3} cool expression
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            ..Default::default()
         }
         .compile()
         .unwrap();
@@ -318,11 +412,12 @@ a {very} cool expression
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            ..Default::default()
         }
         .compile();
 
         let diagnostics = result.unwrap_err().0;
-        assert_eq!(2, diagnostics.len());
+        assert_eq!(1, diagnostics.len());
 
         let range = Position {
             line: 4,
@@ -332,26 +427,124 @@ a {very} cool expression
             character: 8,
         };
         let context = "a {very} cool expression\n       ^".to_owned();
-        let first_expected =
-            Diagnostic::from_message("Unexpected \"}\" while reading a function call".to_string())
-                .with_file_name("test.yarn".to_string())
-                .with_range(range.clone())
-                .with_context(context.clone())
-                .with_start_line(4)
-                .with_severity(DiagnosticSeverity::Error);
-
-        let second_expected =
-            Diagnostic::from_message("mismatched input '}' expecting '('".to_string())
-                .with_file_name("test.yarn".to_string())
-                .with_range(range)
-                .with_context(context)
-                .with_start_line(4)
-                .with_severity(DiagnosticSeverity::Error);
-        if diagnostics[0] == first_expected {
-            assert_eq!(diagnostics[1], second_expected);
-        } else {
-            assert_eq!(diagnostics[0], second_expected);
-            assert_eq!(diagnostics[1], first_expected);
-        }
+        let expected = Diagnostic::new(
+            DiagnosticCode::SyntaxError,
+            "Unexpected \"}\" while reading a function call".to_string(),
+        )
+        .with_file_name("test.yarn".to_string())
+        .with_range(range)
+        .with_context(context)
+        .with_start_line(4)
+        .with_severity(DiagnosticSeverity::Error);
+        assert_eq!(expected, diagnostics[0]);
+    }
+
+    #[test]
+    fn reports_markup_issues_at_their_markers() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: test
+---
+Hello [wave]world[/wave] and {$name} [shout]![/shout]
+==="
+            .to_string(),
+        };
+        let result = Compiler::new()
+            .add_file(file)
+            .declare_variable(Declaration::new("$name", Type::String).with_default_value("Mae"))
+            .declare_markup_markers(["wave"])
+            .compile()
+            .unwrap();
+
+        let range = Position {
+            line: 2,
+            character: 37,
+        }..Position {
+            line: 2,
+            character: 43,
+        };
+        assert_eq!(1, result.warnings.len());
+        assert_eq!(Some(range), result.warnings[0].range);
+        assert_eq!(DiagnosticSeverity::Warning, result.warnings[0].severity);
+    }
+
+    #[test]
+    fn fails_on_invalid_markup() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: test
+---
+A [b p=]broken[/b] line
+==="
+            .to_string(),
+        };
+        let result = Compiler::new().add_file(file).compile();
+
+        let diagnostics = result.unwrap_err().0;
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(DiagnosticSeverity::Error, diagnostics[0].severity);
+        assert_eq!(
+            Some(
+                Position {
+                    line: 2,
+                    character: 2,
+                }..Position {
+                    line: 2,
+                    character: 8,
+                }
+            ),
+            diagnostics[0].range
+        );
+    }
+
+    #[test]
+    fn keeps_format_specifiers_in_string_table() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: test
+---
+You have {$gold:#,##0.00} gold and {$name} is {$ratio : percent} done
+==="
+            .to_string(),
+        };
+        let result = Compiler::new()
+            .add_file(file)
+            .declare_variable(Declaration::new("$name", Type::String).with_default_value("Mae"))
+            .compile()
+            .unwrap();
+
+        let string_info = result.string_table.values().next().unwrap();
+        assert_eq!(
+            "You have {0:#,##0.00} gold and {1} is {2:percent} done",
+            string_info.text
+        );
+    }
+
+    #[test]
+    fn fails_on_invalid_format_specifiers() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: test
+---
+{$name:0.00} has {$gold:0.00 gold}
+==="
+            .to_string(),
+        };
+        let result = Compiler::new()
+            .add_file(file)
+            .declare_variable(Declaration::new("$name", Type::String).with_default_value("Mae"))
+            .compile();
+
+        let diagnostics = result.unwrap_err().0;
+        assert_eq!(2, diagnostics.len());
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error));
+        assert!(diagnostics.iter().any(|diagnostic| diagnostic
+            .message
+            .contains("Invalid number format \"0.00 gold\"")));
+        assert!(diagnostics.iter().any(|diagnostic| diagnostic
+            .message
+            .contains("Terms of 'format specifier' must be Number")));
     }
 }
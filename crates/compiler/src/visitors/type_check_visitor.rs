@@ -1,5 +1,6 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/TypeCheckVisitor.cs>
 
+use crate::listeners::closest_match;
 use crate::parser_rule_context_ext::ParserRuleContextExt;
 use crate::prelude::generated::yarnspinnerlexer;
 use crate::prelude::generated::yarnspinnerparser::*;
@@ -10,10 +11,13 @@ use antlr_rust::parser_rule_context::ParserRuleContext;
 use antlr_rust::token::Token;
 use antlr_rust::tree::{ParseTree, ParseTreeVisitorCompat};
 use check_operation::*;
+use std::collections::HashMap;
+use std::ops::Range;
 use std::path::Path;
 use yarnspinner_core::prelude::*;
 use yarnspinner_core::types::*;
 
+mod check_command;
 mod check_operation;
 
 /// A visitor that walks the parse tree, checking for type consistency
@@ -68,6 +72,9 @@ pub(crate) struct TypeCheckVisitor<'input> {
     /// on the [`ValueContext`] directly using a `partial`
     hints: KnownTypes,
 
+    /// The externally declared functions and commands, used to check commands and report deprecations.
+    manifest: Manifest,
+
     file: FileParseResult<'input>,
     _dummy: Option<Type>,
 }
@@ -75,11 +82,13 @@ pub(crate) struct TypeCheckVisitor<'input> {
 impl<'input> TypeCheckVisitor<'input> {
     pub(crate) fn new(
         existing_declarations: Vec<Declaration>,
+        manifest: Manifest,
         file: FileParseResult<'input>,
     ) -> Self {
         Self {
             file,
             existing_declarations,
+            manifest,
             diagnostics: Default::default(),
             new_declarations: Default::default(),
             deferred_types: Default::default(),
@@ -105,6 +114,77 @@ impl<'input> TypeCheckVisitor<'input> {
             .iter_mut()
             .chain(self.new_declarations.iter_mut())
     }
+
+    /// Returns the format specifier written after the given interpolated expression, e.g. `0.00` for `{$gold:0.00}`.
+    fn format_specifier_of(&self, expression: &ExpressionContextAll<'input>) -> Option<String> {
+        let start = usize::try_from(expression.start().get_start()).ok()?;
+        self.file.format_specifiers.get(&start).cloned()
+    }
+
+    /// Suggests the declared variable that the variable `name` at `range` is most likely a misspelling of.
+    /// Implicit declarations are only considered if `include_implicit` is set, as they may be misspelled themselves.
+    fn misspelled_variable_fix(
+        &self,
+        name: &str,
+        range: Range<Position>,
+        include_implicit: bool,
+    ) -> Option<DiagnosticFix> {
+        let candidates = self
+            .declarations()
+            .filter(|decl| include_implicit || !decl.is_implicit)
+            .filter(|decl| !matches!(decl.r#type, Type::Function(_)))
+            .map(|decl| decl.name.as_str());
+        closest_match(name, candidates)
+            .map(|suggestion| DiagnosticFix::did_you_mean(suggestion, range))
+    }
+
+    /// Warns if a variable that is about to be implicitly declared has a name very similar to an
+    /// explicitly declared one, as it is then most likely a typo.
+    fn check_for_misspelled_variable(&mut self, variable_context: &VariableContextAll<'input>) {
+        let name = variable_context.get_text();
+        let Some(fix) = self.misspelled_variable_fix(&name, variable_context.range(), false) else {
+            return;
+        };
+        let message = format!(
+            "{name} is implicitly declared, but is very similar to the declared variable {}",
+            fix.replacement
+        );
+        self.diagnostics.push(
+            Diagnostic::new(DiagnosticCode::MisspelledVariable, message)
+                .with_severity(DiagnosticSeverity::Warning)
+                .with_variable(name)
+                .with_file_name(&self.file.name)
+                .with_parser_context(variable_context, self.file.tokens())
+                .with_fix(fix),
+        );
+    }
+
+    /// Suggests wrapping `expression` in the standard library function that converts it to `expected_type`.
+    fn conversion_fix(
+        &self,
+        expression: &ExpressionContextAll<'input>,
+        expected_type: Option<&Type>,
+        actual_type: Option<&Type>,
+    ) -> Option<DiagnosticFix> {
+        if !matches!(
+            actual_type,
+            Some(Type::String | Type::Number | Type::Boolean)
+        ) {
+            return None;
+        }
+        let function = match expected_type? {
+            Type::String => "string",
+            Type::Number => "number",
+            Type::Boolean => "bool",
+            _ => return None,
+        };
+        let text = expression.get_text_with_whitespace(self.file.tokens());
+        Some(DiagnosticFix::new(
+            format!("Convert with `{function}()`"),
+            expression.range(),
+            format!("{function}({text})"),
+        ))
+    }
 }
 
 impl<'input> ParseTreeVisitorCompat<'input> for TypeCheckVisitor<'input> {
@@ -119,6 +199,9 @@ impl<'input> ParseTreeVisitorCompat<'input> for TypeCheckVisitor<'input> {
 
 impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input> {
     fn visit_node(&mut self, ctx: &NodeContext<'input>) -> Self::Return {
+        if self.file.has_syntax_errors(ctx) {
+            return None;
+        }
         for header in ctx.header_all() {
             let key = header.header_key.as_ref().unwrap().get_text();
             if key == "title" {
@@ -250,21 +333,24 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
 
     fn visit_valueNull(&mut self, ctx: &ValueNullContext<'input>) -> Self::Return {
         self.diagnostics.push(
-            Diagnostic::from_message("Null is not a permitted type in Yarn Spinner 2.0 and later")
-                .with_file_name(&self.file.name)
-                .with_parser_context(ctx, self.file.tokens()),
+            Diagnostic::new(
+                DiagnosticCode::NullValue,
+                "Null is not a permitted type in Yarn Spinner 2.0 and later",
+            )
+            .with_file_name(&self.file.name)
+            .with_parser_context(ctx, self.file.tokens()),
         );
 
         None
     }
 
     fn visit_valueFunc(&mut self, ctx: &ValueFuncContext<'input>) -> Self::Return {
-        let function_name = ctx
+        let function_token = ctx
             .function_call()
             .unwrap()
             .get_token(yarnspinnerlexer::FUNC_ID, 0)
-            .unwrap()
-            .get_text();
+            .unwrap();
+        let function_name = function_token.get_text();
 
         let function_declaration = self
             .declarations()
@@ -276,6 +362,20 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                 unreachable!("Internal error: function declaration is not of type Function. This is a bug. Please report it at https://github.com/YarnSpinnerTool/YarnSpinner-Rust/issues/new")
             };
 
+            if let Some(deprecation) = self
+                .manifest
+                .function(&function_name)
+                .and_then(|function| function.deprecation.as_ref())
+            {
+                let message = format!("Function \"{function_name}\" is deprecated: {deprecation}");
+                let diagnostic = Diagnostic::new(DiagnosticCode::DeprecatedUsage, message)
+                    .with_severity(DiagnosticSeverity::Warning)
+                    .with_function(function_name.clone())
+                    .with_file_name(&self.file.name)
+                    .with_parser_context(ctx, self.file.tokens());
+                self.diagnostics.push(diagnostic);
+            }
+
             // we have an existing function but its undefined
             // if we also have a type hint we can use that to update it
             if function_type.return_type.is_none() {
@@ -291,6 +391,28 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
             }
             function_type
         } else {
+            // Functions may be registered after compilation, so an unknown function is only
+            // worth a warning if it is very similar to one we know about.
+            let known_functions = self
+                .declarations()
+                .filter(|decl| !decl.is_implicit && matches!(decl.r#type, Type::Function(_)))
+                .map(|decl| decl.name.as_str());
+            if let Some(suggestion) = closest_match(&function_name, known_functions) {
+                let message = format!(
+                    "Function \"{function_name}\" is neither declared nor in the library, but is very similar to \"{suggestion}\""
+                );
+                let diagnostic = Diagnostic::new(DiagnosticCode::MisspelledFunction, message)
+                    .with_severity(DiagnosticSeverity::Warning)
+                    .with_function(function_name.clone())
+                    .with_file_name(&self.file.name)
+                    .with_parser_context(ctx, self.file.tokens())
+                    .with_fix(DiagnosticFix::did_you_mean(
+                        suggestion,
+                        function_token.symbol.range(),
+                    ));
+                self.diagnostics.push(diagnostic);
+            }
+
             // We don't have a declaration for this function. Create an
             // implicit one.
             let mut function_type = FunctionType::default();
@@ -330,13 +452,18 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
             } else {
                 "parameters"
             };
-            let diagnostic = Diagnostic::from_message(format!(
-                "Function \"{}\" expects {} {}, but received {}",
-                function_name,
-                expected_parameter_types.len(),
-                parameters,
-                supplied_parameters.len()
-            ))
+            let diagnostic = Diagnostic::new(
+                DiagnosticCode::WrongParameterCount,
+                format!(
+                    "Function \"{}\" expects {} {}, but received {}",
+                    function_name,
+                    expected_parameter_types.len(),
+                    parameters,
+                    supplied_parameters.len()
+                ),
+            )
+            .with_function(function_name.clone())
+            .with_counts(expected_parameter_types.len(), supplied_parameters.len())
             .with_file_name(&self.file.name)
             .with_parser_context(ctx, self.file.tokens());
             self.diagnostics.push(diagnostic);
@@ -365,15 +492,26 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                 expected_type = &supplied_type;
             }
             if !supplied_type.is_sub_type_of(expected_type) {
-                let diagnostic = Diagnostic::from_message(format!(
-                    "{} parameter {} expects a {}, not a {}",
-                    function_name,
-                    i + 1,
-                    expected_type.format(),
-                    supplied_type.format()
-                ))
+                let diagnostic = Diagnostic::new(
+                    DiagnosticCode::InvalidParameterType,
+                    format!(
+                        "{} parameter {} expects a {}, not a {}",
+                        function_name,
+                        i + 1,
+                        expected_type.format(),
+                        supplied_type.format()
+                    ),
+                )
+                .with_function(function_name.clone())
+                .with_expected_types([expected_type.format()])
+                .with_actual_types([supplied_type.format()])
                 .with_file_name(&self.file.name)
-                .with_parser_context(ctx, self.file.tokens());
+                .with_parser_context(ctx, self.file.tokens())
+                .with_fix(self.conversion_fix(
+                    &supplied_parameter,
+                    expected_type.as_ref(),
+                    supplied_type.as_ref(),
+                ));
                 self.diagnostics.push(diagnostic);
                 return *function_type.return_type;
             }
@@ -409,10 +547,14 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
         // creating a new diagnostic for us having an undefined variable
         // this won't get added into the existing diags though because its possible a later pass will clear it up
         // so we save this as a potential diagnostic for the compiler itself to resolve
-        let diagnostic =
-            Diagnostic::from_message(format_cannot_determine_variable_type_error(&name))
-                .with_file_name(&self.file.name)
-                .with_parser_context(ctx, self.file.tokens());
+        let diagnostic = Diagnostic::new(
+            DiagnosticCode::UndeclaredVariable,
+            format_cannot_determine_variable_type_error(&name),
+        )
+        .with_variable(name.clone())
+        .with_file_name(&self.file.name)
+        .with_parser_context(ctx, self.file.tokens())
+        .with_fix(self.misspelled_variable_fix(&name, ctx.range(), true));
         self.deferred_types
             .push(DeferredTypeDiagnostic { name, diagnostic });
 
@@ -462,13 +604,24 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                 // to the type of the variable.
                 match (variable_type.as_ref(), expression_type.as_ref()) {
                     (Some(variable_type), _) if !expression_type.is_sub_type_of(variable_type) => {
-                        let diagnostic = Diagnostic::from_message(format!(
-                            "{variable_name} ({}) cannot be assigned a {}",
-                            variable_type.format(),
-                            expression_type.format(),
-                        ))
+                        let diagnostic = Diagnostic::new(
+                            DiagnosticCode::InvalidAssignment,
+                            format!(
+                                "{variable_name} ({}) cannot be assigned a {}",
+                                variable_type.format(),
+                                expression_type.format(),
+                            ),
+                        )
+                        .with_variable(variable_name.clone())
+                        .with_expected_types([variable_type.format()])
+                        .with_actual_types([expression_type.format()])
                         .with_file_name(&self.file.name)
-                        .with_parser_context(ctx, self.file.tokens());
+                        .with_parser_context(ctx, self.file.tokens())
+                        .with_fix(self.conversion_fix(
+                            &expression_context,
+                            Some(variable_type),
+                            expression_type.as_ref(),
+                        ));
                         self.diagnostics.push(diagnostic);
                     }
                     (None, Some(expression_type)) => {
@@ -492,11 +645,19 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
                                 .with_range(variable_context.range())
                                 .with_implicit();
                             self.new_declarations.push(decl);
+                            self.check_for_misspelled_variable(&variable_context);
                         } else {
                             self.diagnostics.push(
-                                Diagnostic::from_message(
+                                Diagnostic::new(
+                                    DiagnosticCode::UndeclaredVariable,
                                     format_cannot_determine_variable_type_error(&variable_name),
                                 )
+                                .with_fix(self.misspelled_variable_fix(
+                                    &variable_name,
+                                    variable_context.range(),
+                                    true,
+                                ))
+                                .with_variable(variable_name)
                                 .with_file_name(&self.file.name)
                                 .with_parser_context(ctx, self.file.tokens()),
                             )
@@ -537,7 +698,7 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
         }
         if variable_type.is_none() && expression_type.is_none() {
             self.diagnostics.push(
-                            Diagnostic::from_message(
+                            Diagnostic::new(DiagnosticCode::UndeterminedType,
                                 format!("Type of expression \"{}\" can't be determined without more context. Please declare one or more terms.", ctx.get_text_with_whitespace(self.file.tokens())))
                                 .with_file_name(&self.file.name)
                                 .with_parser_context(ctx, self.file.tokens()));
@@ -552,6 +713,51 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for TypeCheckVisitor<'input>
         // The expression's type must resolve to a string.
         self.check_operation(ctx, expressions, None, "jump statement", &[Type::String])
     }
+
+    fn visit_line_formatted_text(
+        &mut self,
+        ctx: &Line_formatted_textContext<'input>,
+    ) -> Self::Return {
+        for expression in ctx.expression_all() {
+            if self.format_specifier_of(&expression).is_some() {
+                // Format specifiers describe how numbers are presented, so the expression must be a number
+                let expressions = &[expression.clone().into()];
+                self.check_operation(
+                    expression.as_ref(),
+                    expressions,
+                    None,
+                    "format specifier",
+                    &[Type::Number],
+                );
+            } else {
+                self.visit(expression.as_ref());
+            }
+        }
+        None
+    }
+
+    fn visit_command_formatted_text(
+        &mut self,
+        ctx: &Command_formatted_textContext<'input>,
+    ) -> Self::Return {
+        let mut expression_types = HashMap::new();
+        for expression in ctx.expression_all() {
+            if let Some(format_specifier) = self.format_specifier_of(&expression) {
+                let message = format!(
+                    "Format specifiers are only supported in lines and options, but \"{format_specifier}\" is used in a command"
+                );
+                self.diagnostics.push(
+                    Diagnostic::new(DiagnosticCode::FormatSpecifierInCommand, message)
+                        .with_parser_context(expression.as_ref(), self.file.tokens())
+                        .with_file_name(&self.file.name),
+                );
+            }
+            let r#type = self.visit(expression.as_ref());
+            expression_types.insert(expression.start().get_token_index(), r#type);
+        }
+        self.check_command(ctx, &expression_types);
+        None
+    }
 }
 
 trait DeclarationVecExt {
@@ -629,6 +835,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            ..Default::default()
         }
         .compile()
         .unwrap();
@@ -654,6 +861,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            ..Default::default()
         }
         .compile();
 
@@ -663,54 +871,64 @@ mod tests {
 
         assert_contains(
             &diagnostics,
-            &Diagnostic::from_message("$foo (Number) cannot be assigned a String")
-                .with_file_name("test.yarn")
-                .with_range(
-                    Position {
-                        line: 3,
-                        character: 0,
-                    }..Position {
-                        line: 3,
-                        character: 25,
-                    },
-                ),
+            &Diagnostic::new(
+                DiagnosticCode::InvalidAssignment,
+                "$foo (Number) cannot be assigned a String",
+            )
+            .with_file_name("test.yarn")
+            .with_range(
+                Position {
+                    line: 3,
+                    character: 0,
+                }..Position {
+                    line: 3,
+                    character: 25,
+                },
+            ),
         );
 
         assert_contains(
             &diagnostics,
-            &Diagnostic::from_message("$bar (Bool) cannot be assigned a Number")
-                .with_file_name("test.yarn")
-                .with_range(
-                    Position {
-                        line: 6,
-                        character: 0,
-                    }..Position {
-                        line: 6,
-                        character: 19,
-                    },
-                ),
+            &Diagnostic::new(
+                DiagnosticCode::InvalidAssignment,
+                "$bar (Bool) cannot be assigned a Number",
+            )
+            .with_file_name("test.yarn")
+            .with_range(
+                Position {
+                    line: 6,
+                    character: 0,
+                }..Position {
+                    line: 6,
+                    character: 19,
+                },
+            ),
         );
 
         assert_contains(
             &diagnostics,
-            &Diagnostic::from_message("$baz (String) cannot be assigned a Bool")
-                .with_file_name("test.yarn")
-                .with_range(
-                    Position {
-                        line: 7,
-                        character: 0,
-                    }..Position {
-                        line: 7,
-                        character: 21,
-                    },
-                ),
+            &Diagnostic::new(
+                DiagnosticCode::InvalidAssignment,
+                "$baz (String) cannot be assigned a Bool",
+            )
+            .with_file_name("test.yarn")
+            .with_range(
+                Position {
+                    line: 7,
+                    character: 0,
+                }..Position {
+                    line: 7,
+                    character: 21,
+                },
+            ),
         );
     }
 
     fn assert_contains(diagnostics: &[Diagnostic], expected: &Diagnostic) {
         assert!(
             // Does not factor in context or start line because these are subject to frequent change
-            diagnostics.iter().any(|d| d.file_name == expected.file_name
+            diagnostics.iter().any(|d| d.code == expected.code
+                && d.file_name == expected.file_name
                 && d.message == expected.message
                 && d.range == expected.range),
             "Expected diagnostics:\n{}\nto contain:\n- {:?}",
@@ -741,6 +959,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            ..Default::default()
         }
         .compile()
         .unwrap();
@@ -764,6 +983,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            ..Default::default()
         }
         .compile();
 
@@ -773,62 +993,257 @@ mod tests {
 
         assert_contains(
             &diagnostics,
-            &Diagnostic::from_message("$foo (Number) cannot be assigned a undefined")
-                .with_file_name("test.yarn")
-                .with_range(
-                    Position {
-                        line: 4,
-                        character: 0,
-                    }..Position {
-                        line: 4,
-                        character: 27,
-                    },
-                ),
+            &Diagnostic::new(
+                DiagnosticCode::InvalidAssignment,
+                "$foo (Number) cannot be assigned a undefined",
+            )
+            .with_file_name("test.yarn")
+            .with_range(
+                Position {
+                    line: 4,
+                    character: 0,
+                }..Position {
+                    line: 4,
+                    character: 27,
+                },
+            ),
         );
 
         assert_contains(
             &diagnostics,
-            &Diagnostic::from_message("$foo (Number) cannot be assigned a undefined")
-                .with_file_name("test.yarn")
-                .with_range(
-                    Position {
-                        line: 5,
-                        character: 0,
-                    }..Position {
-                        line: 5,
-                        character: 32,
-                    },
-                ),
+            &Diagnostic::new(
+                DiagnosticCode::InvalidAssignment,
+                "$foo (Number) cannot be assigned a undefined",
+            )
+            .with_file_name("test.yarn")
+            .with_range(
+                Position {
+                    line: 5,
+                    character: 0,
+                }..Position {
+                    line: 5,
+                    character: 32,
+                },
+            ),
         );
 
         assert_contains(
             &diagnostics,
-            &Diagnostic::from_message("All terms of + must be the same, not Number, String")
-                .with_file_name("test.yarn")
-                .with_range(
-                    Position {
-                        line: 4,
-                        character: 14,
-                    }..Position {
-                        line: 4,
-                        character: 25,
-                    },
-                ),
+            &Diagnostic::new(
+                DiagnosticCode::MixedOperandTypes,
+                "All terms of + must be the same, not Number, String",
+            )
+            .with_file_name("test.yarn")
+            .with_range(
+                Position {
+                    line: 4,
+                    character: 14,
+                }..Position {
+                    line: 4,
+                    character: 25,
+                },
+            ),
         );
 
         assert_contains(
             &diagnostics,
-            &Diagnostic::from_message("All terms of * must be the same, not Number, String")
-                .with_file_name("test.yarn")
-                .with_range(
-                    Position {
-                        line: 5,
-                        character: 14,
-                    }..Position {
-                        line: 5,
-                        character: 30,
+            &Diagnostic::new(
+                DiagnosticCode::MixedOperandTypes,
+                "All terms of * must be the same, not Number, String",
+            )
+            .with_file_name("test.yarn")
+            .with_range(
+                Position {
+                    line: 5,
+                    character: 14,
+                }..Position {
+                    line: 5,
+                    character: 30,
+                },
+            ),
+        );
+    }
+
+    #[test]
+    fn suggests_declared_variable_for_misspelled_one() {
+        let source = "title: test
+---
+<<declare $gold to 0>>
+<<set $golld to $golld + 1>>
+===";
+        let result = Compiler::new()
+            .add_file(File {
+                file_name: "test.yarn".to_string(),
+                source: source.to_string(),
+            })
+            .compile()
+            .unwrap();
+
+        assert!(!result.warnings.is_empty());
+        for warning in &result.warnings {
+            assert_eq!(DiagnosticCode::MisspelledVariable, warning.code);
+            assert_eq!(DiagnosticSeverity::Warning, warning.severity);
+            let fixed = warning.fixes[0].apply(source);
+            assert_eq!(1, fixed.matches("$golld").count());
+            assert_eq!(2, fixed.matches("$gold ").count());
+        }
+    }
+
+    #[test]
+    fn suggests_conversion_for_type_mismatch() {
+        let source = "title: test
+---
+<<declare $gold to 0>>
+<<set $gold to \"5\">>
+===";
+        let diagnostics = Compiler::new()
+            .add_file(File {
+                file_name: "test.yarn".to_string(),
+                source: source.to_string(),
+            })
+            .compile()
+            .unwrap_err()
+            .0;
+
+        let fix = &diagnostics[0].fixes[0];
+        assert_eq!("Convert with `number()`", fix.title);
+        let fixed = fix.apply(source);
+        assert!(fixed.contains("<<set $gold to number(\"5\")>>"));
+        Compiler::new()
+            .add_file(File {
+                file_name: "test.yarn".to_string(),
+                source: fixed,
+            })
+            .compile()
+            .unwrap();
+    }
+
+    #[test]
+    fn suggests_library_function_for_misspelled_one() {
+        let source = "title: test
+---
+<<if numbr(\"5\") > 1>>
+    Hello
+<<endif>>
+===";
+        let result = Compiler::new()
+            .add_file(File {
+                file_name: "test.yarn".to_string(),
+                source: source.to_string(),
+            })
+            .compile()
+            .unwrap();
+
+        assert_eq!(1, result.warnings.len());
+        let warning = &result.warnings[0];
+        assert_eq!(DiagnosticCode::MisspelledFunction, warning.code);
+        assert_eq!(Some("numbr"), warning.details.function.as_deref());
+        assert!(warning.fixes[0]
+            .apply(source)
+            .contains("<<if number(\"5\") > 1>>"));
+    }
+
+    fn walk_manifest() -> Manifest {
+        Manifest {
+            functions: vec![FunctionDefinition {
+                name: "dice".to_owned(),
+                parameters: vec![ParameterDefinition {
+                    name: "sides".to_owned(),
+                    r#type: Type::Number,
+                    ..Default::default()
+                }],
+                return_type: Type::Number,
+                deprecation: Some("Use roll instead".to_owned()),
+                ..Default::default()
+            }],
+            commands: vec![CommandDefinition {
+                name: "walk".to_owned(),
+                parameters: vec![
+                    ParameterDefinition {
+                        name: "character".to_owned(),
+                        r#type: Type::String,
+                        ..Default::default()
                     },
-                ),
+                    ParameterDefinition {
+                        name: "speed".to_owned(),
+                        r#type: Type::Number,
+                        is_optional: true,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn checks_command_arguments_against_manifest() {
+        let source = "title: test
+---
+<<declare $speed to 2>>
+<<walk Mae>>
+<<walk \"Mae Borowski\" {$speed}>>
+<<walk Mae fast>>
+<<walk>>
+<<walk Mae {$speed > 1}>>
+<<wait 1>>
+===";
+        let diagnostics = Compiler::new()
+            .extend_manifest(walk_manifest())
+            .add_file(File {
+                file_name: "test.yarn".to_string(),
+                source: source.to_string(),
+            })
+            .compile()
+            .unwrap_err()
+            .0;
+
+        assert_eq!(3, diagnostics.len(), "{diagnostics:#?}");
+        assert_eq!(
+            "Argument 2 (\"speed\") of command \"walk\" expects a Number, but received \"fast\"",
+            diagnostics[0].message
+        );
+        assert_eq!(DiagnosticCode::InvalidParameterType, diagnostics[0].code);
+        assert_eq!(
+            "Command \"walk\" expects 1 to 2 arguments, but received 0",
+            diagnostics[1].message
+        );
+        assert_eq!(DiagnosticCode::WrongParameterCount, diagnostics[1].code);
+        assert_eq!(
+            "Argument 2 (\"speed\") of command \"walk\" expects a Number, but received an expression of type Bool",
+            diagnostics[2].message
+        );
+    }
+
+    #[test]
+    fn warns_about_unknown_commands_and_deprecated_functions() {
+        let source = "title: test
+---
+<<wlk Mae>>
+<<if dice(6) > 3>>
+    Hello
+<<endif>>
+===";
+        let result = Compiler::new()
+            .extend_manifest(walk_manifest())
+            .add_file(File {
+                file_name: "test.yarn".to_string(),
+                source: source.to_string(),
+            })
+            .compile()
+            .unwrap();
+
+        assert_eq!(2, result.warnings.len(), "{:#?}", result.warnings);
+        let unknown_command = &result.warnings[0];
+        assert_eq!(DiagnosticCode::UnknownCommand, unknown_command.code);
+        assert!(unknown_command.fixes[0]
+            .apply(source)
+            .contains("<<walk Mae>>"));
+        let deprecation = &result.warnings[1];
+        assert_eq!(DiagnosticCode::DeprecatedUsage, deprecation.code);
+        assert_eq!(
+            "Function \"dice\" is deprecated: Use roll instead",
+            deprecation.message
         );
     }
 }
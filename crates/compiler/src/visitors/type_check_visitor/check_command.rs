@@ -0,0 +1,203 @@
+use crate::listeners::closest_match;
+use crate::prelude::generated::yarnspinnerparser::*;
+use crate::prelude::*;
+use crate::visitors::*;
+use antlr_rust::parser_rule_context::ParserRuleContext;
+use antlr_rust::token::Token;
+use antlr_rust::tree::Tree;
+use std::collections::HashMap;
+use yarnspinner_core::types::*;
+
+/// Commands that are handled by the dialogue runner itself and thus need no declaration.
+const BUILT_IN_COMMANDS: [&str; 2] = ["wait", "stop"];
+
+/// A whitespace separated part of a command, e.g. `Mae`, `"Mae Borowski"` or `{$speed}`.
+#[derive(Debug, Clone, PartialEq)]
+enum CommandArgument {
+    Text(String),
+    Expression(Option<Type>),
+    /// Text and expressions without whitespace between them, e.g. `room_{$number}`.
+    Interpolated,
+}
+
+impl<'input> TypeCheckVisitor<'input> {
+    /// Checks a command against its declaration in the [`Manifest`].
+    /// Does nothing if the manifest declares no commands at all, as commands are then registered at runtime only.
+    ///
+    /// `expression_types` maps the start token index of each expression in the command to its type.
+    pub(super) fn check_command(
+        &mut self,
+        ctx: &Command_formatted_textContext<'input>,
+        expression_types: &HashMap<isize, Option<Type>>,
+    ) {
+        if self.manifest.commands.is_empty() {
+            return;
+        }
+        let arguments = split_command(ctx, expression_types);
+        let Some((CommandArgument::Text(name), arguments)) = arguments.split_first() else {
+            // The name of the command is only known at runtime
+            return;
+        };
+
+        let Some(command) = self.manifest.command(name).cloned() else {
+            if !BUILT_IN_COMMANDS.contains(&name.as_str()) {
+                self.report_unknown_command(ctx, name);
+            }
+            return;
+        };
+
+        if let Some(deprecation) = &command.deprecation {
+            let message = format!("Command \"{name}\" is deprecated: {deprecation}");
+            let diagnostic = Diagnostic::new(DiagnosticCode::DeprecatedUsage, message)
+                .with_severity(DiagnosticSeverity::Warning)
+                .with_file_name(&self.file.name)
+                .with_parser_context(ctx, self.file.tokens());
+            self.diagnostics.push(diagnostic);
+        }
+
+        let (minimum, maximum) = command.argument_count();
+        if arguments.len() < minimum || maximum.is_some_and(|maximum| arguments.len() > maximum) {
+            let expected = match maximum {
+                Some(maximum) if maximum == minimum => pluralize_arguments(minimum),
+                Some(maximum) => format!("{minimum} to {maximum} arguments"),
+                None => format!("at least {}", pluralize_arguments(minimum)),
+            };
+            let message = format!(
+                "Command \"{name}\" expects {expected}, but received {}",
+                arguments.len()
+            );
+            let diagnostic = Diagnostic::new(DiagnosticCode::WrongParameterCount, message)
+                .with_counts(minimum, arguments.len())
+                .with_file_name(&self.file.name)
+                .with_parser_context(ctx, self.file.tokens());
+            self.diagnostics.push(diagnostic);
+            return;
+        }
+
+        for (index, argument) in arguments.iter().enumerate() {
+            let Some(parameter) = command.parameter_for_argument(index) else {
+                continue;
+            };
+            let received = match argument {
+                CommandArgument::Text(text) if !accepts_text(&parameter.r#type, text) => {
+                    format!("\"{text}\"")
+                }
+                CommandArgument::Expression(Some(r#type))
+                    if parameter.r#type != Type::Any && parameter.r#type != *r#type =>
+                {
+                    format!("an expression of type {}", r#type.format())
+                }
+                _ => continue,
+            };
+            let message = format!(
+                "Argument {} (\"{}\") of command \"{name}\" expects a {}, but received {received}",
+                index + 1,
+                parameter.name,
+                parameter.r#type.format(),
+            );
+            let diagnostic = Diagnostic::new(DiagnosticCode::InvalidParameterType, message)
+                .with_expected_types([parameter.r#type.format()])
+                .with_file_name(&self.file.name)
+                .with_parser_context(ctx, self.file.tokens());
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    fn report_unknown_command(&mut self, ctx: &Command_formatted_textContext<'input>, name: &str) {
+        let known_commands = self
+            .manifest
+            .commands
+            .iter()
+            .map(|command| command.name.as_str());
+        let fix = closest_match(name, known_commands).map(|suggestion| {
+            // The name is at the very start of the command, after any whitespace
+            let token = ctx.start();
+            let leading_whitespace = token
+                .get_text()
+                .chars()
+                .take_while(|c| c.is_whitespace())
+                .count();
+            let start = Position {
+                line: token.get_line_as_usize().saturating_sub(1),
+                character: token.get_column_as_usize() + leading_whitespace,
+            };
+            let end = Position {
+                character: start.character + name.chars().count(),
+                ..start
+            };
+            DiagnosticFix::did_you_mean(suggestion, start..end)
+        });
+        let message = format!("Command \"{name}\" is not declared in the manifest");
+        let diagnostic = Diagnostic::new(DiagnosticCode::UnknownCommand, message)
+            .with_severity(DiagnosticSeverity::Warning)
+            .with_file_name(&self.file.name)
+            .with_parser_context(ctx, self.file.tokens())
+            .with_fix(fix);
+        self.diagnostics.push(diagnostic);
+    }
+}
+
+/// Splits a command into its whitespace separated arguments the same way the dialogue runner does,
+/// keeping text in double quotes together.
+fn split_command(
+    ctx: &Command_formatted_textContext,
+    expression_types: &HashMap<isize, Option<Type>>,
+) -> Vec<CommandArgument> {
+    let mut arguments = Vec::new();
+    let mut current: Option<CommandArgument> = None;
+    let mut is_in_quotes = false;
+    for child in ctx.get_children() {
+        if child.get_child_count() > 0 {
+            // An expression
+            let r#type = expression_types
+                .get(&child.start().get_token_index())
+                .cloned()
+                .flatten();
+            current = Some(match current {
+                None => CommandArgument::Expression(r#type),
+                Some(_) => CommandArgument::Interpolated,
+            });
+            continue;
+        }
+        let text = child.get_text();
+        if text == "{" || text == "}" {
+            continue;
+        }
+        for character in text.chars() {
+            if character == '"' {
+                is_in_quotes = !is_in_quotes;
+            } else if character.is_whitespace() && !is_in_quotes {
+                arguments.extend(current.take());
+                continue;
+            }
+            current = Some(match current {
+                None => CommandArgument::Text(character.to_string()),
+                Some(CommandArgument::Text(mut text)) => {
+                    text.push(character);
+                    CommandArgument::Text(text)
+                }
+                Some(_) => CommandArgument::Interpolated,
+            });
+        }
+    }
+    arguments.extend(current);
+    arguments
+}
+
+/// Whether a literal argument can be converted to the given type by the dialogue runner.
+fn accepts_text(r#type: &Type, text: &str) -> bool {
+    let is_quoted = text.len() >= 2 && text.starts_with('"') && text.ends_with('"');
+    match r#type {
+        Type::Number => !is_quoted && text.parse::<f32>().is_ok(),
+        Type::Boolean => !is_quoted && (text == "true" || text == "false"),
+        _ => true,
+    }
+}
+
+fn pluralize_arguments(count: usize) -> String {
+    if count == 1 {
+        "1 argument".to_owned()
+    } else {
+        format!("{count} arguments")
+    }
+}
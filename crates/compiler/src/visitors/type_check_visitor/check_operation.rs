@@ -93,7 +93,7 @@ impl<'input> TypeCheckVisitor<'input> {
                         "Type of expression \"{}\" can't be determined without more context (the compiler thinks it could be {type_names}). Use a type cast on at least one of the terms (e.g. the string(), number(), bool() functions)",
                         context.get_text_with_whitespace(self.file.tokens()),
                     );
-                        let diagnostic = Diagnostic::from_message(message)
+                        let diagnostic = Diagnostic::new(DiagnosticCode::UndeterminedType, message)
                             .with_file_name(&self.file.name)
                             .with_parser_context(context, self.file.tokens());
                         self.diagnostics.push(diagnostic);
@@ -105,7 +105,7 @@ impl<'input> TypeCheckVisitor<'input> {
                         "Type of expression \"{}\" can't be determined without more context. Use a type cast on at least one of the terms (e.g. the string(), number(), bool() functions)",
                         context.get_text_with_whitespace(self.file.tokens()),
                     );
-                        let diagnostic = Diagnostic::from_message(message)
+                        let diagnostic = Diagnostic::new(DiagnosticCode::UndeterminedType, message)
                             .with_file_name(&self.file.name)
                             .with_parser_context(context, self.file.tokens());
                         self.diagnostics.push(diagnostic);
@@ -250,12 +250,20 @@ impl<'input> TypeCheckVisitor<'input> {
                     .with_range(undefined_variable_context.range())
                     .with_implicit();
                 self.new_declarations.push(decl);
+                self.check_for_misspelled_variable(&undefined_variable_context);
             } else {
                 // If we can't produce this, then we can't generate the
                 // declaration.
-                let diagnostic = Diagnostic::from_message(
+                let diagnostic = Diagnostic::new(
+                    DiagnosticCode::UndeclaredVariable,
                     format_cannot_determine_variable_type_error(&var_name),
                 )
+                .with_variable(var_name.clone())
+                .with_fix(self.misspelled_variable_fix(
+                    &var_name,
+                    undefined_variable_context.range(),
+                    true,
+                ))
                 .with_file_name(&self.file.name)
                 .with_parser_context(undefined_variable_context.as_ref(), self.file.tokens());
                 self.diagnostics.push(diagnostic);
@@ -279,7 +287,9 @@ impl<'input> TypeCheckVisitor<'input> {
                 .join(", ");
             let message =
                 format!("All terms of {operation_description} must be the same, not {type_list}");
-            let diagnostic = Diagnostic::from_message(message)
+            let diagnostic = Diagnostic::new(DiagnosticCode::MixedOperandTypes, message)
+                .with_operator(operation_description)
+                .with_actual_types(term_types.iter().map(|t| t.format()))
                 .with_file_name(&self.file.name)
                 .with_parser_context(context, self.file.tokens());
             self.diagnostics.push(diagnostic);
@@ -314,7 +324,7 @@ impl<'input> TypeCheckVisitor<'input> {
                     "{} has no implementation defined for {operation_description}",
                     expression_type.format(),
                 );
-                let diagnostic = Diagnostic::from_message(message)
+                let diagnostic = Diagnostic::new(DiagnosticCode::UnsupportedOperator, message)
                     .with_file_name(&self.file.name)
                     .with_parser_context(context, self.file.tokens());
                 self.diagnostics.push(diagnostic);
@@ -348,7 +358,10 @@ impl<'input> TypeCheckVisitor<'input> {
             let message = format!(
                 "Terms of '{operation_description}' must be {permitted_types_list}, not {type_list}",
             );
-            let diagnostic = Diagnostic::from_message(message)
+            let diagnostic = Diagnostic::new(DiagnosticCode::InvalidOperandType, message)
+                .with_operator(operation_description)
+                .with_expected_types(permitted_types.iter().map(|t| t.format()))
+                .with_actual_types(term_types.iter().map(|t| t.format()))
                 .with_file_name(&self.file.name)
                 .with_parser_context(context, self.file.tokens());
             self.diagnostics.push(diagnostic);
@@ -374,7 +387,7 @@ impl<'input> TypeCheckVisitor<'input> {
                 expression_type.format()
             );
             self.diagnostics.push(
-                Diagnostic::from_message(message)
+                Diagnostic::new(DiagnosticCode::UnsupportedOperator, message)
                     .with_file_name(&self.file.name)
                     .with_parser_context(context, self.file.tokens()),
             );
@@ -0,0 +1,176 @@
+//! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Compiler/DebugInfo.cs>
+
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Contains debug information for a node in a Yarn file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct DebugInfo {
+    /// The file that this DebugInfo was produced from.
+    pub file_name: String,
+
+    /// The node that this DebugInfo was produced from.
+    pub node_name: String,
+
+    /// The mapping of instruction numbers to line and character
+    /// information in the file indicated by `file_name`.
+    pub line_positions: HashMap<usize, Option<Position>>,
+
+    /// The mapping of instruction numbers to the source in the file
+    /// indicated by `file_name` that produced them.
+    /// The start of each source is the corresponding entry of `line_positions`.
+    pub instruction_sources: HashMap<usize, Option<InstructionSource>>,
+}
+
+impl DebugInfo {
+    /// Gets a [`LineInfo`] object that describes the specified instruction
+    /// at the index `instruction_number`.
+    ///
+    /// # Arguments
+    ///
+    /// * `instruction_number` - The index of the instruction to retrieve
+    ///  information for.
+    ///
+    /// # Returns
+    ///
+    /// A [`LineInfo`] object that describes the position of the instruction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `instruction_number` is greater than the
+    /// number of instructions present in the node.
+    ///
+    /// # See also
+    ///
+    /// * [`DebugInfo::try_get_line_info`] for the fallible version.
+    pub fn get_line_info(&self, instruction_number: usize) -> LineInfo {
+        self.try_get_line_info(instruction_number)
+            .expect("instruction_number out of range")
+    }
+
+    /// Fallible version of [`DebugInfo::get_line_info`].
+    pub fn try_get_line_info(&self, instruction_number: usize) -> Option<LineInfo> {
+        self.instruction_sources
+            .get(&instruction_number)
+            .map(|source| LineInfo {
+                file_name: self.file_name.clone(),
+                node_name: self.node_name.clone(),
+                position: source.as_ref().map(|source| source.range.start),
+                source: source.clone(),
+            })
+    }
+
+    /// Gets the indices of all instructions whose source contains `position`, in ascending order.
+    /// Instructions produced by an empty range, such as the implicit `Stop` at the end of a node, are found at their start.
+    ///
+    /// This is the reverse of [`DebugInfo::try_get_line_info`] and can be used to e.g. place breakpoints or attribute coverage.
+    #[must_use]
+    pub fn instructions_at(&self, position: Position) -> Vec<usize> {
+        self.find_instructions(|source| source.contains(position))
+    }
+
+    /// Gets the indices of all instructions whose source starts on the zero-indexed `line`, in ascending order.
+    #[must_use]
+    pub fn instructions_on_line(&self, line: usize) -> Vec<usize> {
+        self.find_instructions(|source| source.range.start.line == line)
+    }
+
+    fn find_instructions(&self, predicate: impl Fn(&InstructionSource) -> bool) -> Vec<usize> {
+        let mut instructions: Vec<_> = self
+            .instruction_sources
+            .iter()
+            .filter(|(_, source)| source.as_ref().is_some_and(&predicate))
+            .map(|(instruction_number, _)| *instruction_number)
+            .collect();
+        instructions.sort_unstable();
+        instructions
+    }
+}
+
+/// The source that an instruction was produced from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct InstructionSource {
+    /// The zero-indexed range of the token or expression that produced the instruction.
+    pub range: Range<Position>,
+
+    /// The kind of statement the instruction was produced for.
+    pub construct: SourceConstruct,
+}
+
+impl InstructionSource {
+    /// Whether `position` lies within [`InstructionSource::range`].
+    /// An empty range contains only its start.
+    #[must_use]
+    pub fn contains(&self, position: Position) -> bool {
+        self.range.contains(&position) || self.range.start == position
+    }
+}
+
+/// The kind of statement an instruction was produced for, see [`InstructionSource::construct`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash, Default))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub enum SourceConstruct {
+    /// Bookkeeping of the node itself, such as visit tracking and the `Stop` at its end.
+    #[default]
+    Node,
+    /// A line of dialogue, including its inline expressions.
+    Line,
+    /// A shortcut option (`-> Option`), including its condition and inline expressions.
+    ShortcutOption,
+    /// The condition of an `<<if>>`, `<<elseif>>` or `<<else>>` clause.
+    Condition,
+    /// A `<<set>>` statement.
+    Set,
+    /// A `<<call>>` statement.
+    Call,
+    /// A command such as `<<wait 2>>` or `<<stop>>`.
+    Command,
+    /// A `<<jump>>` statement.
+    Jump,
+}
+
+/// Contains positional information about an instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct LineInfo {
+    /// The file name of the source that this instruction was produced from.
+    pub file_name: String,
+
+    /// The node name of the source that this instruction was produced from.
+    pub node_name: String,
+
+    /// The zero-indexed position in `file_name` that contains the
+    /// statement or expression that this line was produced from.
+    /// This is the start of [`LineInfo::source`].
+    pub position: Option<Position>,
+
+    /// The source that this instruction was produced from.
+    pub source: Option<InstructionSource>,
+}
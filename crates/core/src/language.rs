@@ -0,0 +1,80 @@
+#[cfg(any(feature = "bevy", feature = "serde"))]
+use crate::prelude::*;
+use core::fmt::Display;
+use icu_locid::subtags::Variants;
+use icu_locid::LanguageIdentifier;
+
+/// IETF BCP 47 code.
+/// The default is "en-US".
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct Language(pub(crate) LanguageIdentifier);
+impl Language {
+    /// Creates a new `Language` from a string. Panics if the string is not a valid IETF BCP 47 code.
+    pub fn new(language: impl Into<String>) -> Self {
+        let language = language.into();
+        Self(language.parse().unwrap())
+    }
+
+    /// Returns the languages to look for text in when text for this language is missing, starting with this language itself.
+    /// The chain is derived by truncating subtags as described in [RFC 4647, section 3.4](https://www.rfc-editor.org/rfc/rfc4647#section-3.4):
+    /// first the variants are dropped, then the region and finally the script.
+    ///
+    /// The base language is not part of the chain, as it is always the last resort.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use yarnspinner_core::prelude::*;
+    /// assert_eq!(
+    ///     vec![Language::new("pt-BR"), Language::new("pt")],
+    ///     Language::new("pt-BR").fallback_chain()
+    /// );
+    /// assert_eq!(
+    ///     vec![
+    ///         Language::new("zh-Hant-TW"),
+    ///         Language::new("zh-Hant"),
+    ///         Language::new("zh")
+    ///     ],
+    ///     Language::new("zh-Hant-TW").fallback_chain()
+    /// );
+    /// ```
+    pub fn fallback_chain(&self) -> Vec<Language> {
+        let mut identifier = self.0.clone();
+        let mut chain = vec![self.clone()];
+        loop {
+            if !identifier.variants.is_empty() {
+                identifier.variants = Variants::new();
+            } else if identifier.region.is_some() {
+                identifier.region = None;
+            } else if identifier.script.is_some() {
+                identifier.script = None;
+            } else {
+                return chain;
+            }
+            chain.push(Self(identifier.clone()));
+        }
+    }
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        "en-US".into()
+    }
+}
+
+impl<T> From<T> for Language
+where
+    String: From<T>,
+{
+    fn from(language: T) -> Self {
+        let language: String = language.into();
+        Self::new(language)
+    }
+}
@@ -6,12 +6,17 @@
 //! - If you wish to write an adapter crate for an engine yourself, use the [`yarnspinner`](https://crates.io/crates/yarnspinner) crate.
 
 #![warn(missing_docs, missing_debug_implementations)]
+mod debug_info;
 mod feature_gates;
 mod generated;
 mod internal_value;
+mod language;
 mod library;
 mod line_id;
+pub mod markup;
+mod number_formatting;
 mod operator;
+mod pluralization;
 mod position;
 pub mod types;
 mod yarn_fn;
@@ -22,14 +27,18 @@ pub mod prelude {
     #[cfg(any(feature = "bevy", feature = "serde"))]
     pub use crate::feature_gates::*;
 
+    pub(crate) use crate::pluralization::*;
     pub use crate::{
+        debug_info::*,
         generated::{
             instruction::OpCode, operand::Value as OperandValue, Header, Instruction,
             InvalidOpCodeError, Node, Operand, Program,
         },
         internal_value::*,
+        language::*,
         library::*,
         line_id::*,
+        number_formatting::*,
         operator::*,
         position::*,
         types::Type,
@@ -0,0 +1,36 @@
+//! Types handling the parsing of Yarn markup.
+//! Yarn markup looks like this:
+//! ```text
+//! Mae: [shout]I'm a cat![/shout]!
+//! Greg: You're a [size=12]cat[/size]!
+//! ```
+//! The parsing extracts the information that "Mae" and "Greg" are characters, that "shout" and "size" are attributes, and that "size" has a value of "12".
+//!
+//! The markup is parsed here rather than in the runtime so that the compiler can validate it without depending on the runtime.
+mod attribute_marker_processor;
+mod line_parser;
+mod markup_parse_error;
+mod markup_serializer;
+mod markup_spans;
+mod markup_validator;
+mod message_format;
+mod parsed_markup;
+mod translation_checker;
+mod word_count;
+
+pub use self::line_parser::{
+    CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY, TRIM_WHITESPACE_PROPERTY,
+};
+pub(crate) use self::{attribute_marker_processor::*, line_parser::*};
+// Used by the runtime to parse the lines it delivers, not part of the public API.
+#[doc(hidden)]
+pub use self::{
+    attribute_marker_processor::{
+        AttributeMarkerProcessor, DialogueTextProcessor, MessageFormatTextProcessor,
+    },
+    line_parser::{normalize, LineParser, END_OF_CHARACTER_MARKER},
+};
+pub use self::{
+    markup_parse_error::*, markup_serializer::*, markup_spans::*, markup_validator::*,
+    message_format::*, parsed_markup::*, translation_checker::*, word_count::*,
+};
@@ -1,23 +1,30 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/YarnSpinner.Markup/IAttributeMarkerProcessor.cs>
 
-pub(crate) use self::{dialogue_text_processor::*, no_markup_text_processor::*};
-use crate::markup::MarkupAttributeMarker;
+pub(crate) use self::no_markup_text_processor::*;
+pub use self::{dialogue_text_processor::*, message_format_text_processor::*};
+use crate::markup::{MarkupAttributeMarker, MarkupParseError};
 use crate::prelude::Language;
 use core::fmt::Debug;
 
 mod dialogue_text_processor;
+mod message_format_text_processor;
 mod no_markup_text_processor;
 
 /// Provides a mechanism for producing replacement text for a marker.
-pub(crate) trait AttributeMarkerProcessor: Debug + Send + Sync {
+pub trait AttributeMarkerProcessor: Debug + Send + Sync {
     /// Produces the replacement text that should be inserted into a parse
     /// result for a given attribute.
     ///
     /// If the marker is an `open` marker, the text from the marker's
     /// position to its corresponding closing marker is provided as a string
     /// property called `contents`.
-    fn replacement_text_for_marker(&self, marker: &MarkupAttributeMarker) -> String;
+    fn replacement_text_for_marker(
+        &self,
+        marker: &MarkupAttributeMarker,
+    ) -> Result<String, MarkupParseError>;
+    /// Sets the language whose plural rules and number formatting are used for the replacement text.
     fn set_language_code(&mut self, language_code: Option<Language>);
+    /// Clones the processor so that the [`LineParser`](crate::markup::LineParser) holding it can be cloned.
     fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor>;
 }
 
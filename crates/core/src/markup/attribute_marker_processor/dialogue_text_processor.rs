@@ -5,13 +5,15 @@ use crate::prelude::*;
 use icu_plurals::PluralCategory;
 use std::collections::HashSet;
 
+/// A markup text processor that implements the `select`, `plural` and `ordinal` markers.
 #[derive(Default, Debug, Clone)]
-pub(crate) struct DialogueTextProcessor {
+pub struct DialogueTextProcessor {
     pub(crate) language_code: Option<Language>,
 }
 
 impl DialogueTextProcessor {
-    pub(crate) fn new() -> Self {
+    /// Creates a processor without a language, which is set through [`LineParser::set_language_code`](crate::markup::LineParser::set_language_code).
+    pub fn new() -> Self {
         Self::default()
     }
 }
@@ -27,7 +29,10 @@ impl AttributeMarkerProcessor for DialogueTextProcessor {
     ///
     /// ## Panics
     /// Panics when the string contains a `plural` or `ordinal` marker, but the specified value cannot be parsed as a number.
-    fn replacement_text_for_marker(&self, marker: &crate::markup::MarkupAttributeMarker) -> String {
+    fn replacement_text_for_marker(
+        &self,
+        marker: &crate::markup::MarkupAttributeMarker,
+    ) -> Result<String, crate::markup::MarkupParseError> {
         let value_prop = marker
             .properties
             .get("value")
@@ -42,7 +47,7 @@ impl AttributeMarkerProcessor for DialogueTextProcessor {
                 .unwrap_or_else(|| panic!("error: no replacement for {value}"));
             let replacement = replacement_prop.to_string();
 
-            return replace_value_placeholders(&replacement, &value);
+            return Ok(replace_value_placeholders(&replacement, &value));
         }
 
         // If it's not "select", then it's "plural" or "ordinal"
@@ -75,7 +80,7 @@ impl AttributeMarkerProcessor for DialogueTextProcessor {
         });
         let input = replacement_value.to_string();
 
-        replace_value_placeholders(&input, &value)
+        Ok(replace_value_placeholders(&input, &value))
     }
 
     fn set_language_code(&mut self, language_code: Option<Language>) {
@@ -110,7 +115,7 @@ fn replace_value_placeholders(text: &str, value: &str) -> String {
         .collect()
 }
 
-fn plural_case_name(plural_case: PluralCategory) -> &'static str {
+pub(crate) fn plural_case_name(plural_case: PluralCategory) -> &'static str {
     match plural_case {
         PluralCategory::Zero => "zero",
         PluralCategory::One => "one",
@@ -0,0 +1,67 @@
+//! Implements the `[message]` marker.
+//!
+//! ## Implementation notes
+//! This has no equivalent in the original C# code.
+
+use crate::markup::{
+    AttributeMarkerProcessor, MarkupAttributeMarker, MarkupParseError, MarkupValue, MessageFormat,
+    REPLACEMENT_MARKER_CONTENTS, TRIM_WHITESPACE_PROPERTY,
+};
+use crate::prelude::Language;
+use std::collections::HashMap;
+
+/// A markup text processor that formats the contents of a `[message]` marker as a [`MessageFormat`] pattern,
+/// using the marker's properties as arguments.
+#[derive(Default, Debug, Clone)]
+pub struct MessageFormatTextProcessor {
+    pub(crate) language_code: Option<Language>,
+}
+
+impl MessageFormatTextProcessor {
+    /// Creates a processor without a language, which is set through [`LineParser::set_language_code`](crate::markup::LineParser::set_language_code).
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AttributeMarkerProcessor for MessageFormatTextProcessor {
+    /// Returns the formatted message, or a [`MarkupParseError::InvalidMessageFormat`] when the pattern is invalid,
+    /// an argument is missing or an argument that needs to be a number is not.
+    fn replacement_text_for_marker(
+        &self,
+        marker: &MarkupAttributeMarker,
+    ) -> Result<String, MarkupParseError> {
+        let Some(MarkupValue::String(pattern)) = marker.properties.get(REPLACEMENT_MARKER_CONTENTS)
+        else {
+            // A self-closing marker has no pattern
+            return Ok(String::new());
+        };
+        let language_code = self.language_code.as_ref().expect(
+            "Dialogue locale code is not set. 'message' markers cannot be called unless one is set.",
+        );
+        let arguments: HashMap<_, _> = marker
+            .properties
+            .iter()
+            .filter(|(name, _)| {
+                name.as_str() != REPLACEMENT_MARKER_CONTENTS
+                    && name.as_str() != TRIM_WHITESPACE_PROPERTY
+            })
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+
+        MessageFormat::parse(pattern)
+            .and_then(|message| message.format(language_code, &arguments))
+            .map_err(|error| MarkupParseError::InvalidMessageFormat {
+                input: pattern.clone(),
+                reason: error.to_string(),
+            })
+    }
+
+    fn set_language_code(&mut self, language_code: Option<Language>) {
+        self.language_code = language_code;
+    }
+
+    fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor> {
+        Box::new(self.clone())
+    }
+}
@@ -9,11 +9,13 @@ use crate::prelude::*;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
 use unicode_normalization::UnicodeNormalization;
 use unicode_segmentation::UnicodeSegmentation;
 
 pub type Result<T> = std::result::Result<T, MarkupParseError>;
 
+/// Parses the markup of a line into its plain text and [`MarkupAttribute`]s.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "bevy", derive(Reflect))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -22,7 +24,7 @@ pub type Result<T> = std::result::Result<T, MarkupParseError>;
     all(feature = "bevy", feature = "serde"),
     reflect(Serialize, Deserialize)
 )]
-pub(crate) struct LineParser {
+pub struct LineParser {
     // ## Implementation notes
     // We don't port `stringReader` because [`BufReader`] is not [`Clone`]
     /// A map for the names of attributes to an object that can generate replacement text for those attributes.
@@ -35,27 +37,49 @@ pub(crate) struct LineParser {
     source_position: usize,
     /// The current position of the string reader in the plain text, measured in text elements.
     position: usize,
+    /// The position in the input at which the marker that was parsed last starts, measured in characters.
+    marker_source_position: usize,
+    /// The open markers of the last parsed line that were never closed and thus did not produce an attribute.
+    #[cfg_attr(feature = "bevy", reflect(ignore))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    unclosed_markers: Vec<MarkupAttributeMarker>,
+    /// Whether `\\` is an escaped backslash, see [`LineParser::with_escaped_backslashes`].
+    escaped_backslashes: bool,
 }
 
 impl Default for LineParser {
     fn default() -> Self {
         Self {
             marker_processors: HashMap::from([(
-                "nomarkup".to_string(),
+                NO_MARKUP_ATTRIBUTE.to_string(),
                 Box::new(NoMarkupTextProcessor::new()) as Box<dyn AttributeMarkerProcessor>,
             )]),
             input: Default::default(),
             source_position: Default::default(),
             position: Default::default(),
+            marker_source_position: Default::default(),
+            unclosed_markers: Default::default(),
+            escaped_backslashes: false,
         }
     }
 }
 
 impl LineParser {
-    pub(crate) fn new() -> Self {
+    /// Creates a parser that has no marker processors registered besides the built-in `nomarkup` marker.
+    pub fn new() -> Self {
         Self::default()
     }
 
+    /// Makes the parser read `\\` as a single backslash, so that a backslash can be written directly in front of a marker.
+    /// This is used for the markup written by [`serialize_markup`](crate::markup::serialize_markup).
+    ///
+    /// ## Implementation notes
+    /// The original only supports escaping brackets, which is why this is not the default.
+    pub(crate) fn with_escaped_backslashes(mut self) -> Self {
+        self.escaped_backslashes = true;
+        self
+    }
+
     /// Registers an object as a marker processor for a given
     /// marker name.
     ///
@@ -64,9 +88,9 @@ impl LineParser {
     /// text. This allows users of the [`LineParser`]
     /// to dynamically replace text in a line. The `nomarkup` tag is
     /// implemented in this way by the [`LineParser`]
-    /// directly; the [`Dialogue`] uses this mechanism
+    /// directly; the `Dialogue` uses this mechanism
     /// to implement the `select`, `plural` and `ordinal` markers.
-    pub(crate) fn register_marker_processor(
+    pub fn register_marker_processor(
         mut self,
         attribute_name: impl Into<String>,
         processor: Box<dyn AttributeMarkerProcessor>,
@@ -86,7 +110,7 @@ impl LineParser {
     /// ## Implementation notes
     ///
     /// The original does not reset the internal `source_position`. This was likely a bug.
-    pub(crate) fn parse_markup(&mut self, input: &str) -> Result<ParsedMarkup> {
+    pub fn parse_markup(&mut self, input: &str) -> Result<ParsedMarkup> {
         if input.is_empty() {
             // We got a null input; return an empty markup parse result
             return Ok(ParsedMarkup::new());
@@ -94,6 +118,8 @@ impl LineParser {
 
         self.input = normalize(input);
         self.source_position = 0;
+        self.marker_source_position = 0;
+        self.unclosed_markers.clear();
 
         let mut text = String::new();
         let mut markers = Vec::new();
@@ -103,9 +129,12 @@ impl LineParser {
         while let Some(character) = self.read_next() {
             match character {
                 '\\' => {
-                    // This may be the start of an escaped bracket ("\[" or "\]"). Peek ahead to see if it is.
+                    // This may be the start of an escaped bracket ("\[" or "\]") or, if enabled, backslash ("\\"). Peek ahead to see if it is.
                     if let Some(next_character) = self.peek_next() {
-                        if next_character == '[' || next_character == ']' {
+                        if next_character == '['
+                            || next_character == ']'
+                            || (self.escaped_backslashes && next_character == '\\')
+                        {
                             // It is! We'll discard this '\', and read the next character as plain text.
                             let character = self.read_next().unwrap();
                             text.push(character);
@@ -210,7 +239,29 @@ impl LineParser {
         Ok(ParsedMarkup { text, attributes })
     }
 
-    pub(crate) fn set_language_code(&mut self, language_code: impl Into<Option<Language>>) {
+    /// Returns the open markers of the line parsed last that were never closed. These markers are silently dropped by [`LineParser::parse_markup`].
+    pub(crate) fn unclosed_markers(&self) -> &[MarkupAttributeMarker] {
+        &self.unclosed_markers
+    }
+
+    /// Returns the range of the input, measured in characters, that caused `error` to be returned by the last call to [`LineParser::parse_markup`].
+    ///
+    /// The range starts at the marker in which the error occurred and ends where the parser gave up.
+    pub(crate) fn error_source_range(&self, error: &MarkupParseError) -> Range<usize> {
+        let input_length = self.input.chars().count();
+        let start = self.marker_source_position.min(input_length);
+        let end = match error {
+            // Reported after all markers were read, so the parser is at the end of the line
+            MarkupParseError::UnmatchedCloseMarker { name, .. } => {
+                start + "[/]".len() + name.chars().count()
+            }
+            _ => self.source_position,
+        };
+        start..end.clamp(start, input_length)
+    }
+
+    /// Passes the language of the lines being parsed on to the registered marker processors.
+    pub fn set_language_code(&mut self, language_code: impl Into<Option<Language>>) {
         let language_code = language_code.into();
         for processor in self.marker_processors.values_mut() {
             processor.set_language_code(language_code.clone());
@@ -222,6 +273,7 @@ impl LineParser {
         // Implementation note: -1 because the original increments `source_position` at the end of the loop in `parse_markup`,
         // while we do it at the beginning instead
         let source_position_at_marker_start = self.source_position - 1;
+        self.marker_source_position = source_position_at_marker_start;
 
         // Implementation note: No need to advance position here,
         // since we do so automatically on every read / parse_character
@@ -357,12 +409,10 @@ impl LineParser {
         }
         // Fetch the text that should be inserted into the string at
         // this point
-        let replacement = self
-            .marker_processors
+        self.marker_processors
             .get(name)
             .unwrap()
-            .replacement_text_for_marker(marker);
-        Ok(replacement)
+            .replacement_text_for_marker(marker)
     }
 
     /// Peeks ahead in the input without consuming any characters, looking for whitespace.
@@ -378,7 +428,7 @@ impl LineParser {
     ///
     /// Returns an `Err` when a close marker is encountered, but no corresponding open marker for it exists.
     fn build_attributes_from_markers(
-        &self,
+        &mut self,
         markers: Vec<MarkupAttributeMarker>,
     ) -> Result<Vec<MarkupAttribute>> {
         let mut unclosed_markers = VecDeque::new();
@@ -396,14 +446,17 @@ impl LineParser {
                     // unclosed stack to find the most recent
                     // marker of the same type to find its pair.
                     assert!(marker.name.is_some());
-                    let matched_open_marker_index = unclosed_markers
+                    let Some(matched_open_marker_index) = unclosed_markers
                         .iter()
                         .position(|open_marker| open_marker.name == marker.name)
-                        .ok_or_else(|| MarkupParseError::UnmatchedCloseMarker {
+                    else {
+                        self.marker_source_position = marker.source_position;
+                        return Err(MarkupParseError::UnmatchedCloseMarker {
                             input: self.input.clone(),
                             name: marker.name.unwrap(),
                             position: marker.position,
-                        })?;
+                        });
+                    };
 
                     // This attribute is now closed, so we can
                     // remove the marker from the unmatched list
@@ -440,6 +493,7 @@ impl LineParser {
             }
         }
 
+        self.unclosed_markers = unclosed_markers.into();
         attributes.sort_by_key(|attribute| attribute.source_position);
         Ok(attributes)
     }
@@ -565,11 +619,12 @@ impl LineParser {
         // parse integers or floats:
         if self.peek_numeric()? {
             // could be an int or a float
-            let integer = self.parse_integer()?;
+            let integer = self.parse_digits()?;
 
             // if there's a decimal separator, this is a float
             if !self.peek_character('.')? {
                 // no decimal separator, so this is an integer
+                let integer: u32 = integer.parse().unwrap();
                 return Ok(integer.into());
             }
 
@@ -577,7 +632,7 @@ impl LineParser {
             self.parse_character('.')?;
 
             // parse the fractional value
-            let fraction = self.parse_integer()?;
+            let fraction = self.parse_digits()?;
             let float: f32 = format!("{integer}.{fraction}").parse().unwrap();
             return Ok(float.into());
         }
@@ -644,13 +699,14 @@ impl LineParser {
         Ok(next.is_ascii_digit())
     }
 
-    /// Parses an (ASCII) integer from the stream.
+    /// Parses a run of (ASCII) digits from the stream without interpreting them,
+    /// so that leading zeros are preserved.
     ///
-    /// This method returns false if the parser has reached the end of
-    /// the line.
-    fn parse_integer(&mut self) -> Result<u32> {
+    /// ## Implementation notes
+    /// The original has a `ParseInteger` method instead, which loses leading zeros in the fractional part of floats, e.g. `0.05` becomes `0.5`.
+    fn parse_digits(&mut self) -> Result<String> {
         self.consume_whitespace()?;
-        let mut integer_string = String::new();
+        let mut digits = String::new();
         loop {
             let next = self
                 .peek_next()
@@ -659,11 +715,10 @@ impl LineParser {
                 })?;
             if next.is_ascii_digit() {
                 self.read_next().unwrap();
-                integer_string.push(next);
+                digits.push(next);
             } else {
-                // end of the integer! parse and return it
-                let integer = integer_string.parse().unwrap();
-                return Ok(integer);
+                // end of the digits
+                return Ok(digits);
             }
         }
     }
@@ -721,10 +776,13 @@ impl LineParser {
 }
 
 /// Returns a new string whose textual value is the same as this string, but whose binary representation is in Unicode normalization form C.
-pub(crate) fn normalize(string: &str) -> String {
+pub fn normalize(string: &str) -> String {
     string.nfc().to_string()
 }
 
+/// The name of the attribute whose contents are not parsed for markup.
+pub(crate) const NO_MARKUP_ATTRIBUTE: &str = "nomarkup";
+
 /// The name of the property in replacement attributes that contains the text of the attribute.
 pub(crate) const REPLACEMENT_MARKER_CONTENTS: &str = "contents";
 
@@ -739,4 +797,4 @@ pub const CHARACTER_ATTRIBUTE_NAME_PROPERTY: &str = "name";
 pub const TRIM_WHITESPACE_PROPERTY: &str = "trimwhitespace";
 
 /// A regular expression that matches a colon followed by optional whitespace.
-static END_OF_CHARACTER_MARKER: Lazy<Regex> = Lazy::new(|| Regex::new(r":\s*").unwrap());
+pub static END_OF_CHARACTER_MARKER: Lazy<Regex> = Lazy::new(|| Regex::new(r":\s*").unwrap());
@@ -49,6 +49,10 @@ pub enum MarkupParseError {
         name: String,
         position: usize,
     },
+    InvalidMessageFormat {
+        input: String,
+        reason: String,
+    },
 }
 
 impl Error for MarkupParseError {}
@@ -79,6 +83,7 @@ impl fmt::Display for MarkupParseError {
                 name,
                 position,
             } => write!(f, "Unterminated marker {name} in line {input} at position {position}"),
+            InvalidMessageFormat { input, reason } => write!(f, "Error while formatting message \"{input}\": {reason}"),
         }
     }
 }
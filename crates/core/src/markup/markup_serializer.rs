@@ -0,0 +1,271 @@
+//! Rendering of text and [`MarkupAttribute`]s back into Yarn markup.
+//!
+//! ## Implementation notes
+//! This has no equivalent in the original C# code, which can only parse markup.
+
+use crate::markup::{
+    sorted_outermost_first, GraphemeBoundaries, MarkupAttribute, MarkupValue, CHARACTER_ATTRIBUTE,
+    CHARACTER_ATTRIBUTE_NAME_PROPERTY, END_OF_CHARACTER_MARKER, NO_MARKUP_ATTRIBUTE,
+    REPLACEMENT_MARKER_CONTENTS, TRIM_WHITESPACE_PROPERTY,
+};
+
+/// Renders `text` and the `attributes` applying to it into canonical Yarn markup,
+/// such that parsing the result with [`ParsedMarkup::parse`](crate::markup::ParsedMarkup::parse) yields the same text and attributes again.
+///
+/// The canonical form is defined as follows:
+/// - `[`, `]` and `\` in the text are escaped with a backslash where they would otherwise be interpreted as markup.
+///   Note that an escaped backslash, `\\`, is only understood by [`ParsedMarkup::parse`](crate::markup::ParsedMarkup::parse),
+///   while the `Dialogue` reads it as two backslashes, just like the original Yarn Spinner.
+/// - Attributes are opened in the order of their position, with enclosing attributes opened first, and closed with a named close marker, e.g. `[/b]`.
+/// - Zero-length attributes are written as self-closing markers, e.g. `[pause/]`.
+/// - Properties are sorted by name. A property with the same name as its attribute is written in the shortcut form, e.g. `[size=12]`.
+/// - Strings are only quoted if they cannot be written as a single word.
+/// - A `character` attribute is left implicit when the text starts with the character's name followed by a colon, as in `Mae: Hi!`.
+/// - If a self-closing marker would swallow the whitespace following it, an additional space is written so that the text is unchanged.
+/// - The text covered by a `nomarkup` attribute is written verbatim and its `contents` property is omitted, as the parser generates it anew.
+///
+/// Some attributes cannot be expressed in Yarn markup and will not survive a round-trip:
+/// - Attributes inside a `nomarkup` attribute are dropped.
+/// - Crossing attributes with the same name, e.g. one `a` covering 0-2 and another `a` covering 1-3, are closed in the wrong order.
+/// - Negative or non-finite [`MarkupValue::Float`]s are written as strings.
+/// - Any colon in the markup of a line without a `character` attribute will make the parser generate one.
+///
+/// ## Example
+/// ```rust
+/// # use yarnspinner_core::markup::*;
+/// let markup = ParsedMarkup::parse("Mae: [wave  size = 2]Hi[ /wave] \\[sic\\]").unwrap();
+/// assert_eq!("Mae: [wave size=2]Hi[/wave] \\[sic\\]", serialize_markup(&markup.text, &markup.attributes));
+/// ```
+pub fn serialize_markup(text: &str, attributes: &[MarkupAttribute]) -> String {
+    if let Some(index) = attributes.iter().position(is_implicit_character_candidate) {
+        let mut explicit_attributes = attributes.to_vec();
+        let character_attribute = explicit_attributes.remove(index);
+        let markup = MarkupWriter::new(text, &explicit_attributes).write();
+        if is_generated_implicitly(&markup, &character_attribute) {
+            return markup;
+        }
+    }
+    MarkupWriter::new(text, attributes).write()
+}
+
+fn is_implicit_character_candidate(attribute: &MarkupAttribute) -> bool {
+    attribute.name == CHARACTER_ATTRIBUTE
+        && attribute.position == 0
+        && attribute.properties.len() == 1
+        && matches!(
+            attribute.property(CHARACTER_ATTRIBUTE_NAME_PROPERTY),
+            Some(MarkupValue::String(_))
+        )
+}
+
+/// Mirrors how the line parser generates a `character` attribute when none is present.
+fn is_generated_implicitly(markup: &str, character_attribute: &MarkupAttribute) -> bool {
+    let Some(match_) = END_OF_CHARACTER_MARKER.find(markup) else {
+        return false;
+    };
+    let name = &markup[..match_.start()];
+    character_attribute.length == match_.end()
+        && character_attribute.property(CHARACTER_ATTRIBUTE_NAME_PROPERTY)
+            == Some(&MarkupValue::String(name.to_owned()))
+}
+
+/// Writes markup while keeping track of the same state the line parser does, so that it knows when escaping or extra whitespace is needed.
+struct MarkupWriter<'a> {
+    text: &'a str,
+    boundaries: GraphemeBoundaries,
+    attributes: Vec<(usize, usize, &'a MarkupAttribute)>,
+    output: String,
+    /// The last character the line parser will have seen outside of escape sequences, which decides whether a marker trims whitespace.
+    last_character: char,
+    /// Whether the line parser will consume the next character if it is whitespace.
+    trim_pending: bool,
+}
+
+impl<'a> MarkupWriter<'a> {
+    fn new(text: &'a str, attributes: &'a [MarkupAttribute]) -> Self {
+        let boundaries = GraphemeBoundaries::new(text);
+        let attributes = sorted_outermost_first(attributes, boundaries.grapheme_count());
+        Self {
+            text,
+            boundaries,
+            attributes,
+            output: String::new(),
+            last_character: 0 as char,
+            trim_pending: false,
+        }
+    }
+
+    fn write(mut self) -> String {
+        let grapheme_count = self.boundaries.grapheme_count();
+        // Indices into `self.attributes` of the attributes that were opened, but not closed yet
+        let mut open_attributes: Vec<usize> = Vec::new();
+        // Positions before this one are covered by a `nomarkup` attribute and written verbatim
+        let mut verbatim_until = 0;
+
+        for position in 0..=grapheme_count {
+            if position < verbatim_until {
+                continue;
+            }
+            let mut still_open = Vec::with_capacity(open_attributes.len());
+            for index in open_attributes.into_iter().rev() {
+                let (_, end, attribute) = self.attributes[index];
+                if end <= position {
+                    self.write_marker(format!("[/{}]", attribute.name), false);
+                } else {
+                    still_open.insert(0, index);
+                }
+            }
+            open_attributes = still_open;
+
+            for index in 0..self.attributes.len() {
+                let (start, end, attribute) = self.attributes[index];
+                if start == position && end == position {
+                    let trims = trims_whitespace(attribute, true);
+                    self.write_marker(format_marker(attribute, true), trims);
+                }
+            }
+
+            for index in 0..self.attributes.len() {
+                let (start, end, attribute) = self.attributes[index];
+                if start != position || end == position {
+                    continue;
+                }
+                let trims = trims_whitespace(attribute, false);
+                self.write_marker(format_marker(attribute, false), trims);
+                open_attributes.push(index);
+                if attribute.name == NO_MARKUP_ATTRIBUTE {
+                    // The parser reads everything up to the close marker as plain text.
+                    let contents = self.boundaries.substring(self.text, start, end);
+                    self.output.push_str(contents);
+                    self.trim_pending = false;
+                    verbatim_until = end;
+                    break;
+                }
+            }
+
+            if position < grapheme_count && position >= verbatim_until {
+                self.write_grapheme(position);
+            }
+        }
+        self.output
+    }
+
+    fn write_marker(&mut self, marker: String, trims_if_preceded_by_whitespace: bool) {
+        let preceded_by_whitespace = self.last_character.is_whitespace();
+        self.output.push_str(&marker);
+        self.trim_pending = preceded_by_whitespace && trims_if_preceded_by_whitespace;
+        self.last_character = '[';
+    }
+
+    fn write_grapheme(&mut self, position: usize) {
+        let grapheme = self.boundaries.substring(self.text, position, position + 1);
+        let next_is_marker = self
+            .attributes
+            .iter()
+            .any(|(start, end, _)| *start == position + 1 || *end == position + 1);
+        let mut characters = grapheme.chars().peekable();
+        while let Some(character) = characters.next() {
+            if self.trim_pending && character.is_whitespace() {
+                // This space is swallowed by the preceding marker, leaving the actual whitespace intact
+                self.output.push(' ');
+            }
+            self.trim_pending = false;
+
+            let next_character = characters.peek().copied().or_else(|| {
+                if next_is_marker {
+                    Some('[')
+                } else {
+                    self.text[self.boundaries.byte_offset(position + 1)..]
+                        .chars()
+                        .next()
+                }
+            });
+            match character {
+                '[' | ']' => {
+                    self.output.push('\\');
+                    self.output.push(character);
+                }
+                '\\' if matches!(next_character, Some('[' | ']' | '\\')) => {
+                    self.output.push_str("\\\\");
+                }
+                _ => {
+                    self.output.push(character);
+                    self.last_character = character;
+                }
+            }
+        }
+    }
+}
+
+/// Mirrors the line parser's rules for trimming a single whitespace after a marker that is preceded by whitespace.
+fn trims_whitespace(attribute: &MarkupAttribute, self_closing: bool) -> bool {
+    match attribute.property(TRIM_WHITESPACE_PROPERTY) {
+        Some(MarkupValue::Bool(trim_whitespace)) => *trim_whitespace,
+        _ => self_closing && attribute.name != NO_MARKUP_ATTRIBUTE,
+    }
+}
+
+fn format_marker(attribute: &MarkupAttribute, self_closing: bool) -> String {
+    let mut marker = format!("[{}", attribute.name);
+    if let Some(value) = attribute.property(&attribute.name) {
+        marker.push('=');
+        marker.push_str(&format_value(value));
+    }
+    let mut properties: Vec<_> = attribute
+        .properties
+        .iter()
+        .filter(|(name, _)| **name != attribute.name)
+        .filter(|(name, _)| {
+            attribute.name != NO_MARKUP_ATTRIBUTE || name.as_str() != REPLACEMENT_MARKER_CONTENTS
+        })
+        .collect();
+    properties.sort_by_key(|(name, _)| name.as_str());
+    for (name, value) in &properties {
+        marker.push_str(&format!(" {name}={}", format_value(value)));
+    }
+    match (
+        self_closing,
+        properties.is_empty() && attribute.property(&attribute.name).is_none(),
+    ) {
+        (false, _) => marker.push(']'),
+        (true, true) => marker.push_str("/]"),
+        (true, false) => marker.push_str(" /]"),
+    }
+    marker
+}
+
+fn format_value(value: &MarkupValue) -> String {
+    match value {
+        MarkupValue::Integer(integer) => integer.to_string(),
+        MarkupValue::Float(float) if float.is_finite() && float.is_sign_positive() => {
+            let float = float.to_string();
+            if float.contains('.') {
+                float
+            } else {
+                format!("{float}.0")
+            }
+        }
+        MarkupValue::Float(float) => quote(&float.to_string()),
+        MarkupValue::Bool(bool) => bool.to_string(),
+        MarkupValue::String(string) if is_single_word(string) => string.clone(),
+        MarkupValue::String(string) => quote(string),
+    }
+}
+
+/// Returns `true` if the line parser would read `string` as an unquoted string.
+fn is_single_word(string: &str) -> bool {
+    let mut characters = string.chars();
+    let Some(first) = characters.next() else {
+        return false;
+    };
+    !first.is_ascii_digit()
+        && (first.is_alphanumeric() || first == '_')
+        && characters.all(|character| character.is_alphanumeric() || character == '_')
+        && string != "true"
+        && string != "false"
+}
+
+fn quote(string: &str) -> String {
+    let escaped = string.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
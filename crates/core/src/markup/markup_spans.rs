@@ -0,0 +1,275 @@
+//! Conversion of the flat, possibly overlapping list of [`MarkupAttribute`]s of a line into structures that are easier to render.
+//!
+//! ## Implementation notes
+//! This has no equivalent in the original C# code. Every renderer ended up re-implementing the splitting of a line into styled runs,
+//! so it lives here instead.
+
+use crate::markup::MarkupAttribute;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A run of text in a marked-up line to which the same set of [`MarkupAttribute`]s apply.
+///
+/// Created by [`markup_spans`] or `Line::spans`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkupSpan {
+    /// The text of this span.
+    pub text: String,
+    /// The position in the plain text where this span begins, measured in text elements (grapheme clusters),
+    /// just like [`MarkupAttribute::position`].
+    pub position: usize,
+    /// The number of text elements in the plain text that this span covers.
+    /// This is `0` for spans created by zero-length attributes, e.g. `[pause/]`.
+    pub length: usize,
+    /// The attributes applying to this span, ordered from the outermost to the innermost attribute.
+    pub attributes: Vec<MarkupAttribute>,
+}
+
+impl MarkupSpan {
+    /// Returns `true` if an attribute with the given name applies to this span.
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| attribute.name == name)
+    }
+
+    /// Gets the innermost attribute with the specified name applying to this span, if present.
+    pub fn attribute(&self, name: &str) -> Option<&MarkupAttribute> {
+        self.attributes
+            .iter()
+            .rev()
+            .find(|attribute| attribute.name == name)
+    }
+}
+
+/// A node in the tree representation of a marked-up line, created by [`markup_tree`] or `Line::markup_tree`.
+///
+/// Attributes that overlap without nesting, e.g. `[a]x[b]y[/a]z[/b]`, are split into multiple [`MarkupNode::Attribute`]s
+/// sharing the same [`MarkupAttribute`] so that every node is properly nested within its parent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkupNode {
+    /// Plain text without any further attributes.
+    Text(String),
+    /// An attribute and the nodes it contains.
+    /// Zero-length attributes, e.g. `[pause/]`, have no children.
+    Attribute {
+        /// The attribute applying to all `children`.
+        attribute: MarkupAttribute,
+        /// The nodes contained in this attribute.
+        children: Vec<MarkupNode>,
+    },
+}
+
+impl MarkupNode {
+    /// Returns the concatenated text of this node and all its children.
+    pub fn text(&self) -> String {
+        match self {
+            MarkupNode::Text(text) => text.clone(),
+            MarkupNode::Attribute { children, .. } => children.iter().map(Self::text).collect(),
+        }
+    }
+}
+
+/// Splits `text` into an ordered sequence of non-overlapping [`MarkupSpan`]s, each carrying the set of `attributes` that apply to it.
+///
+/// Spans are split at grapheme cluster boundaries, which is the unit of [`MarkupAttribute::position`] and [`MarkupAttribute::length`].
+/// Text that is not covered by any attribute is returned as a span without attributes.
+/// Zero-length attributes produce an empty span at their position, so renderers can still react to them.
+///
+/// ## Example
+/// ```rust
+/// # use yarnspinner_core::markup::*;
+/// # use std::collections::HashMap;
+/// # let bold = MarkupAttribute {
+/// #     name: "b".to_owned(),
+/// #     position: 2,
+/// #     length: 4,
+/// #     properties: HashMap::new(),
+/// #     source_position: 2,
+/// # };
+/// // The line "A [b]bold[/b]!" parses into the text "A bold!" with a "b" attribute spanning "bold"
+/// let spans = markup_spans("A bold!", &[bold]);
+/// let texts: Vec<_> = spans.iter().map(|span| span.text.as_str()).collect();
+/// assert_eq!(vec!["A ", "bold", "!"], texts);
+/// assert!(spans[1].has_attribute("b"));
+/// assert!(spans[2].attributes.is_empty());
+/// ```
+pub fn markup_spans(text: &str, attributes: &[MarkupAttribute]) -> Vec<MarkupSpan> {
+    let boundaries = GraphemeBoundaries::new(text);
+    let attributes = sorted_outermost_first(attributes, boundaries.grapheme_count());
+
+    let mut split_points: Vec<_> = attributes
+        .iter()
+        .flat_map(|(start, end, _)| [*start, *end])
+        .chain([0, boundaries.grapheme_count()])
+        .collect();
+    split_points.sort_unstable();
+    split_points.dedup();
+
+    let mut spans = Vec::new();
+    for (index, &start) in split_points.iter().enumerate() {
+        // Zero-length attributes sit between two runs of text
+        spans.extend(
+            attributes
+                .iter()
+                .filter(|(attr_start, attr_end, _)| *attr_start == start && *attr_end == start)
+                .map(|(_, _, attribute)| MarkupSpan {
+                    text: String::new(),
+                    position: start,
+                    length: 0,
+                    attributes: attributes
+                        .iter()
+                        .filter(|(other_start, other_end, _)| {
+                            *other_start <= start && *other_end > start
+                        })
+                        .map(|(_, _, attribute)| (*attribute).clone())
+                        .chain(std::iter::once((*attribute).clone()))
+                        .collect(),
+                }),
+        );
+
+        let Some(&end) = split_points.get(index + 1) else {
+            break;
+        };
+        let applying_attributes = attributes
+            .iter()
+            .filter(|(attr_start, attr_end, _)| *attr_start <= start && *attr_end >= end)
+            .map(|(_, _, attribute)| (*attribute).clone())
+            .collect();
+        spans.push(MarkupSpan {
+            text: boundaries.substring(text, start, end).to_owned(),
+            position: start,
+            length: end - start,
+            attributes: applying_attributes,
+        });
+    }
+    spans
+}
+
+/// Converts `text` and its `attributes` into a tree of properly nested [`MarkupNode`]s.
+///
+/// The tree is built from the spans returned by [`markup_spans`], so it shares the same grapheme cluster handling.
+/// Attributes that cross the boundary of an enclosing attribute are split into multiple nodes.
+///
+/// ## Example
+/// ```rust
+/// # use yarnspinner_core::markup::*;
+/// # use std::collections::HashMap;
+/// # let bold = MarkupAttribute {
+/// #     name: "b".to_owned(),
+/// #     position: 2,
+/// #     length: 4,
+/// #     properties: HashMap::new(),
+/// #     source_position: 2,
+/// # };
+/// // The line "A [b]bold[/b]!" parses into the text "A bold!" with a "b" attribute spanning "bold"
+/// let tree = markup_tree("A bold!", &[bold.clone()]);
+/// assert_eq!(
+///     vec![
+///         MarkupNode::Text("A ".to_owned()),
+///         MarkupNode::Attribute {
+///             attribute: bold,
+///             children: vec![MarkupNode::Text("bold".to_owned())],
+///         },
+///         MarkupNode::Text("!".to_owned()),
+///     ],
+///     tree
+/// );
+/// ```
+pub fn markup_tree(text: &str, attributes: &[MarkupAttribute]) -> Vec<MarkupNode> {
+    let mut root = Vec::new();
+    // The attributes of the currently open nodes, outermost first
+    let mut open_attributes: Vec<MarkupAttribute> = Vec::new();
+    // The children collected for each open node, parallel to `open_attributes`
+    let mut open_children: Vec<Vec<MarkupNode>> = Vec::new();
+
+    for span in markup_spans(text, attributes) {
+        let shared_prefix_length = open_attributes
+            .iter()
+            .zip(span.attributes.iter())
+            .take_while(|(open, applying)| open == applying)
+            .count();
+
+        while open_attributes.len() > shared_prefix_length {
+            close_innermost_node(&mut root, &mut open_attributes, &mut open_children);
+        }
+        for attribute in &span.attributes[shared_prefix_length..] {
+            open_attributes.push(attribute.clone());
+            open_children.push(Vec::new());
+        }
+
+        if span.length == 0 {
+            // A zero-length attribute is always the innermost attribute of its span
+            close_innermost_node(&mut root, &mut open_attributes, &mut open_children);
+        } else {
+            open_children
+                .last_mut()
+                .unwrap_or(&mut root)
+                .push(MarkupNode::Text(span.text));
+        }
+    }
+    while !open_attributes.is_empty() {
+        close_innermost_node(&mut root, &mut open_attributes, &mut open_children);
+    }
+    root
+}
+
+fn close_innermost_node(
+    root: &mut Vec<MarkupNode>,
+    open_attributes: &mut Vec<MarkupAttribute>,
+    open_children: &mut Vec<Vec<MarkupNode>>,
+) {
+    let attribute = open_attributes.pop().unwrap();
+    let children = open_children.pop().unwrap();
+    let node = MarkupNode::Attribute {
+        attribute,
+        children,
+    };
+    open_children.last_mut().unwrap_or(root).push(node);
+}
+
+/// Returns the attributes together with their start and end positions clamped to the text,
+/// sorted so that enclosing attributes come before the attributes they enclose.
+pub(crate) fn sorted_outermost_first(
+    attributes: &[MarkupAttribute],
+    grapheme_count: usize,
+) -> Vec<(usize, usize, &MarkupAttribute)> {
+    let mut attributes: Vec<_> = attributes
+        .iter()
+        .map(|attribute| {
+            let start = attribute.position.min(grapheme_count);
+            let end = (attribute.position + attribute.length).min(grapheme_count);
+            (start, end, attribute)
+        })
+        .collect();
+    // Stable sort, so that attributes covering the same range keep the order in which they appear in the source
+    attributes.sort_by(|(start_a, end_a, _), (start_b, end_b, _)| {
+        start_a.cmp(start_b).then_with(|| end_b.cmp(end_a))
+    });
+    attributes
+}
+
+/// Maps positions measured in grapheme clusters to byte offsets.
+pub(crate) struct GraphemeBoundaries(Vec<usize>);
+
+impl GraphemeBoundaries {
+    pub(crate) fn new(text: &str) -> Self {
+        let boundaries = text
+            .grapheme_indices(true)
+            .map(|(index, _)| index)
+            .chain(std::iter::once(text.len()))
+            .collect();
+        Self(boundaries)
+    }
+
+    pub(crate) fn grapheme_count(&self) -> usize {
+        self.0.len() - 1
+    }
+
+    pub(crate) fn substring<'a>(&self, text: &'a str, start: usize, end: usize) -> &'a str {
+        &text[self.0[start]..self.0[end]]
+    }
+
+    pub(crate) fn byte_offset(&self, position: usize) -> usize {
+        self.0[position]
+    }
+}
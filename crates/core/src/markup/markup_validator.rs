@@ -0,0 +1,300 @@
+//! Ahead-of-time checks of Yarn markup, so that problems surface before a line is delivered.
+//!
+//! ## Implementation notes
+//! This has no equivalent in the original C# code, which only reports markup errors when a line is run.
+
+use crate::markup::{
+    LineParser, MarkupAttribute, MarkupParseError, MarkupValue, MessageFormat, MessageFormatError,
+    NoMarkupTextProcessor, ParsedMarkup, CHARACTER_ATTRIBUTE, NO_MARKUP_ATTRIBUTE,
+    REPLACEMENT_MARKER_CONTENTS,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+/// The names of the markers that have a special meaning to Yarn Spinner and are always known to a [`MarkupValidator`].
+pub const BUILT_IN_MARKERS: &[&str] = &[
+    CHARACTER_ATTRIBUTE,
+    NO_MARKUP_ATTRIBUTE,
+    "select",
+    "plural",
+    "ordinal",
+    "message",
+];
+
+/// Matches the substitution placeholders of a line as stored in a string table, e.g. `{0}` or `{0:0.00}`.
+pub(crate) static SUBSTITUTION_PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{(\d+)(?::[^{}\[\]]+)?\}").unwrap());
+
+/// Checks lines of marked-up text for problems without running them through a `Dialogue`.
+///
+/// The validator parses markup the same way the `Dialogue` does, and additionally reports:
+/// - Open markers that are never closed, which the parser silently drops.
+/// - `select`, `plural` and `ordinal` markers without the properties they need, which cause a panic at runtime.
+/// - `message` markers with an invalid [`MessageFormat`] pattern or without a property for every argument of the pattern.
+/// - Markers whose name is not known, if the known marker names were passed with [`MarkupValidator::with_known_markers`].
+///
+/// Substitution placeholders such as `{0}` are replaced by digits of the same length before parsing,
+/// so a line can be validated straight from a string table or strings file.
+///
+/// ## Example
+/// ```rust
+/// # use yarnspinner_core::markup::*;
+/// let validator = MarkupValidator::new().with_known_markers(["wave"]);
+/// let issues = validator.validate("Mae: [wave]Hi[/wave], [whave]{0}[/whave]!");
+/// assert_eq!(1, issues.len());
+/// assert!(!issues[0].is_error());
+/// assert_eq!(22..28, issues[0].source_range());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarkupValidator {
+    known_markers: Option<HashSet<String>>,
+}
+
+impl MarkupValidator {
+    /// Creates a new [`MarkupValidator`] that accepts markers of any name.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds names of markers that are used by the game, in addition to the [`BUILT_IN_MARKERS`].
+    /// Once called, markers with any other name are reported as [`MarkupIssue::UnknownMarker`].
+    pub fn with_known_markers(
+        mut self,
+        names: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.known_markers
+            .get_or_insert_with(Default::default)
+            .extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Returns `true` if a marker named `name` is allowed.
+    pub fn is_known_marker(&self, name: &str) -> bool {
+        let Some(known_markers) = &self.known_markers else {
+            return true;
+        };
+        BUILT_IN_MARKERS.contains(&name) || known_markers.contains(name)
+    }
+
+    /// Validates a single line of marked-up text and returns the issues found, ordered by their position.
+    pub fn validate(&self, line: &str) -> Vec<MarkupIssue> {
+        let mut line_parser = validation_line_parser();
+        let markup = match parse_for_validation(&mut line_parser, line) {
+            Ok(markup) => markup,
+            Err(error) => {
+                let source_range = line_parser.error_source_range(&error);
+                return vec![MarkupIssue::ParseError {
+                    error,
+                    source_range,
+                }];
+            }
+        };
+
+        let mut issues: Vec<_> = line_parser
+            .unclosed_markers()
+            .iter()
+            .filter_map(|marker| {
+                let name = marker.name.clone()?;
+                Some(MarkupIssue::UnclosedMarker {
+                    source_range: marker_name_range(marker.source_position, &name),
+                    name,
+                })
+            })
+            .collect();
+        for attribute in &markup.attributes {
+            if !self.is_known_marker(&attribute.name) {
+                issues.push(MarkupIssue::UnknownMarker {
+                    name: attribute.name.clone(),
+                    source_range: marker_name_range(attribute.source_position, &attribute.name),
+                });
+            }
+            if let Some(property) = missing_replacement_property(attribute) {
+                issues.push(MarkupIssue::MissingProperty {
+                    name: attribute.name.clone(),
+                    property: property.to_owned(),
+                    source_range: marker_name_range(attribute.source_position, &attribute.name),
+                });
+            }
+            if attribute.name == "message" {
+                issues.extend(validate_message(attribute));
+            }
+        }
+        issues.sort_by_key(|issue| issue.source_range().start);
+        issues
+    }
+}
+
+/// Creates a [`LineParser`] that keeps the replacement markers as attributes.
+/// The replacement markers need to read their contents the same way they do at runtime.
+/// Their replacement text is irrelevant for validation.
+pub(crate) fn validation_line_parser() -> LineParser {
+    LineParser::new()
+        .register_marker_processor("select", Box::new(NoMarkupTextProcessor::new()))
+        .register_marker_processor("plural", Box::new(NoMarkupTextProcessor::new()))
+        .register_marker_processor("ordinal", Box::new(NoMarkupTextProcessor::new()))
+        .register_marker_processor("message", Box::new(NoMarkupTextProcessor::new()))
+}
+
+/// Parses a line as stored in a string table, replacing its substitution placeholders by digits of the same length first.
+pub(crate) fn parse_for_validation(
+    line_parser: &mut LineParser,
+    line: &str,
+) -> Result<ParsedMarkup, MarkupParseError> {
+    let input = SUBSTITUTION_PLACEHOLDER
+        .replace_all(line, |captures: &regex::Captures| {
+            "0".repeat(captures[0].len())
+        })
+        .into_owned();
+    line_parser.parse_markup(&input)
+}
+
+/// Returns the range of the opening bracket and the name of a marker.
+fn marker_name_range(source_position: usize, name: &str) -> Range<usize> {
+    source_position..source_position + "[".len() + name.chars().count()
+}
+
+fn missing_replacement_property(attribute: &MarkupAttribute) -> Option<&'static str> {
+    match attribute.name.as_str() {
+        "select" | "plural" | "ordinal" if attribute.property("value").is_none() => Some("value"),
+        "plural" | "ordinal" if attribute.property("other").is_none() => Some("other"),
+        "plural" | "ordinal" => match attribute.property("value") {
+            Some(MarkupValue::String(value)) if value.parse::<f32>().is_err() => Some("value"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn validate_message(attribute: &MarkupAttribute) -> Vec<MarkupIssue> {
+    let Some(MarkupValue::String(pattern)) = attribute.property(REPLACEMENT_MARKER_CONTENTS) else {
+        return Vec::new();
+    };
+    let source_range = marker_name_range(attribute.source_position, &attribute.name);
+    match MessageFormat::parse(pattern) {
+        Ok(message) => message
+            .argument_names()
+            .into_iter()
+            .filter(|argument| attribute.property(argument).is_none())
+            .map(|argument| MarkupIssue::MissingProperty {
+                name: attribute.name.clone(),
+                property: argument.to_owned(),
+                source_range: source_range.clone(),
+            })
+            .collect(),
+        Err(error) => vec![MarkupIssue::InvalidMessageFormat {
+            error,
+            source_range,
+        }],
+    }
+}
+
+/// A problem in a line of marked-up text, as found by [`MarkupValidator::validate`].
+///
+/// All ranges are measured in characters of the validated line.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub enum MarkupIssue {
+    /// The line cannot be parsed, so running it will fail.
+    ParseError {
+        /// The error the parser returned.
+        error: MarkupParseError,
+        /// The range from the start of the offending marker to the point where the parser gave up.
+        source_range: Range<usize>,
+    },
+    /// An open marker is never closed, so it has no effect.
+    UnclosedMarker {
+        /// The name of the marker.
+        name: String,
+        /// The range of the marker's opening bracket and name.
+        source_range: Range<usize>,
+    },
+    /// A `select`, `plural`, `ordinal` or `message` marker lacks a property it needs
+    /// or has a non-numeric `value` where a number is expected.
+    /// Running the line will panic.
+    MissingProperty {
+        /// The name of the marker.
+        name: String,
+        /// The name of the missing or invalid property.
+        property: String,
+        /// The range of the marker's opening bracket and name.
+        source_range: Range<usize>,
+    },
+    /// The contents of a `message` marker are not a valid [`MessageFormat`] pattern.
+    /// Running the line will panic.
+    InvalidMessageFormat {
+        /// The error returned by [`MessageFormat::parse`].
+        error: MessageFormatError,
+        /// The range of the marker's opening bracket and name.
+        source_range: Range<usize>,
+    },
+    /// A marker is not among the known marker names, which usually indicates a typo.
+    UnknownMarker {
+        /// The name of the marker.
+        name: String,
+        /// The range of the marker's opening bracket and name.
+        source_range: Range<usize>,
+    },
+}
+
+impl MarkupIssue {
+    /// The range of the validated line that this issue refers to, measured in characters.
+    pub fn source_range(&self) -> Range<usize> {
+        match self {
+            MarkupIssue::ParseError { source_range, .. }
+            | MarkupIssue::UnclosedMarker { source_range, .. }
+            | MarkupIssue::MissingProperty { source_range, .. }
+            | MarkupIssue::InvalidMessageFormat { source_range, .. }
+            | MarkupIssue::UnknownMarker { source_range, .. } => source_range.clone(),
+        }
+    }
+
+    /// Returns `true` if this issue makes the line fail at runtime.
+    /// Other issues only indicate a likely mistake.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            MarkupIssue::ParseError { .. }
+                | MarkupIssue::MissingProperty { .. }
+                | MarkupIssue::InvalidMessageFormat { .. }
+        )
+    }
+}
+
+impl Error for MarkupIssue {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MarkupIssue::ParseError { error, .. } => Some(error),
+            MarkupIssue::InvalidMessageFormat { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MarkupIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use MarkupIssue::*;
+        match self {
+            ParseError { error, .. } => write!(f, "Invalid markup: {error}"),
+            UnclosedMarker { name, .. } => write!(
+                f,
+                "Marker [{name}] is never closed and has no effect. Close it with [/{name}] or [/]"
+            ),
+            MissingProperty { name, property, .. }
+                if (name == "plural" || name == "ordinal") && property == "value" =>
+            {
+                write!(
+                    f,
+                    "Marker [{name}] needs a property \"value\" that is a number"
+                )
+            }
+            MissingProperty { name, property, .. } => {
+                write!(f, "Marker [{name}] needs a property \"{property}\"")
+            }
+            InvalidMessageFormat { error, .. } => write!(f, "Invalid [message] marker: {error}"),
+            UnknownMarker { name, .. } => write!(f, "Unknown marker [{name}]"),
+        }
+    }
+}
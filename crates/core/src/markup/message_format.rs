@@ -0,0 +1,606 @@
+//! A subset of [ICU MessageFormat](https://unicode-org.github.io/icu/userguide/format_parse/messages/) for text that needs
+//! grammatical agreement beyond what the `select`, `plural` and `ordinal` markers can express.
+//!
+//! ## Implementation notes
+//! This has no equivalent in the original C# code. Plural categories are determined the same way as for the `plural` and `ordinal` markers.
+
+use crate::markup::MarkupValue;
+use crate::prelude::{Language, NumberFormatter, NumberStyle, Pluralization};
+use icu_plurals::PluralCategory;
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+/// A parsed message pattern in the ICU MessageFormat syntax.
+///
+/// The following constructs are supported:
+/// - `{name}` inserts the argument `name`. Numbers are formatted according to the language.
+/// - `{name, number}`, `{name, number, integer}` and `{name, number, percent}` insert the argument `name` as a formatted number.
+/// - `{name, select, female {...} male {...} other {...}}` chooses a message by the value of `name`.
+/// - `{name, plural, =0 {...} one {...} other {...}}` chooses a message by the cardinal plural category of `name`.
+///   Cases starting with `=` match the exact value and take precedence over the plural categories.
+///   An optional `offset:1` right after `plural,` is subtracted from the value before determining its category.
+///   Inside the cases, `#` is replaced by the formatted value minus the offset.
+/// - `{name, selectordinal, one {#st} two {#nd} few {#rd} other {#th}}` works like `plural`, but uses ordinal plural categories.
+/// - Messages can be nested arbitrarily, e.g. a `select` inside a `plural` case.
+///
+/// `select`, `plural` and `selectordinal` must always have an `other` case.
+/// Literal braces are written by quoting them with apostrophes, e.g. `'{'`, and `''` is a literal apostrophe.
+/// A backslash in front of a brace is ignored, so that the pattern can be copied verbatim from a Yarn script, where braces need to be escaped.
+///
+/// In a line, a message is written as the contents of a `message` marker, whose properties are the arguments:
+/// ```text
+/// [message gender={$gender} count={$cats}]\{gender, select, female \{She has\} other \{They have\}\} \{count, plural, one \{a cat\} other \{# cats\}\}[/message]
+/// ```
+///
+/// ## Example
+/// ```rust
+/// # use yarnspinner_core::markup::*;
+/// # use yarnspinner_core::prelude::*;
+/// # use std::collections::HashMap;
+/// let message = MessageFormat::parse(
+///     "{count, plural, =0 {Nobody came} one {{host, select, female {She} other {They}} came alone} other {{host} came with # friends}}",
+/// )
+/// .unwrap();
+/// let arguments = HashMap::from([
+///     ("host".to_owned(), MarkupValue::String("Mae".to_owned())),
+///     ("count".to_owned(), MarkupValue::Integer(1200)),
+/// ]);
+/// let text = message.format(&Language::new("en"), &arguments).unwrap();
+/// assert_eq!("Mae came with 1,200 friends", text);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageFormat {
+    parts: Vec<MessagePart>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum MessagePart {
+    Text(String),
+    /// `{name}` if `style` is [`None`], otherwise `{name, number, style}`
+    Argument {
+        name: String,
+        style: Option<NumberStyle>,
+    },
+    /// `#` inside a plural case
+    PluralValue,
+    Select {
+        name: String,
+        cases: Vec<(String, Vec<MessagePart>)>,
+    },
+    Plural {
+        name: String,
+        ordinal: bool,
+        offset: f32,
+        cases: Vec<(PluralKey, Vec<MessagePart>)>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PluralKey {
+    Exact(f32),
+    Category(PluralCategory),
+}
+
+impl MessageFormat {
+    /// Parses a message pattern.
+    pub fn parse(pattern: &str) -> Result<Self, MessageFormatError> {
+        // Braces escaped for Yarn scripts are regular braces here
+        let pattern = pattern.replace("\\{", "{").replace("\\}", "}");
+        let mut parser = PatternParser {
+            characters: pattern.chars().collect(),
+            pattern: &pattern,
+            position: 0,
+        };
+        let parts = parser.parse_message(false, false)?;
+        Ok(Self { parts })
+    }
+
+    /// Formats the message for `language` using the passed arguments.
+    ///
+    /// Returns an error if an argument used by the message is missing, or if an argument that needs to be a number is not.
+    /// Strings containing a number are accepted as numbers, just like for the `plural` and `ordinal` markers.
+    pub fn format(
+        &self,
+        language: &Language,
+        arguments: &HashMap<String, MarkupValue>,
+    ) -> Result<String, MessageFormatError> {
+        let formatter = MessageFormatter {
+            language,
+            arguments,
+            number_formatter: OnceCell::new(),
+        };
+        let mut output = String::new();
+        formatter.write(&self.parts, None, &mut output)?;
+        Ok(output)
+    }
+
+    /// Returns the names of all arguments used by this message, sorted alphabetically.
+    pub fn argument_names(&self) -> Vec<&str> {
+        fn collect<'a>(parts: &'a [MessagePart], names: &mut HashSet<&'a str>) {
+            for part in parts {
+                match part {
+                    MessagePart::Text(_) | MessagePart::PluralValue => {}
+                    MessagePart::Argument { name, .. } => {
+                        names.insert(name);
+                    }
+                    MessagePart::Select { name, cases } => {
+                        names.insert(name);
+                        for (_, case) in cases {
+                            collect(case, names);
+                        }
+                    }
+                    MessagePart::Plural { name, cases, .. } => {
+                        names.insert(name);
+                        for (_, case) in cases {
+                            collect(case, names);
+                        }
+                    }
+                }
+            }
+        }
+        let mut names = HashSet::new();
+        collect(&self.parts, &mut names);
+        let mut names: Vec<_> = names.into_iter().collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+struct MessageFormatter<'a> {
+    language: &'a Language,
+    arguments: &'a HashMap<String, MarkupValue>,
+    number_formatter: OnceCell<NumberFormatter>,
+}
+
+impl MessageFormatter<'_> {
+    fn write(
+        &self,
+        parts: &[MessagePart],
+        plural_value: Option<f32>,
+        output: &mut String,
+    ) -> Result<(), MessageFormatError> {
+        for part in parts {
+            match part {
+                MessagePart::Text(text) => output.push_str(text),
+                MessagePart::PluralValue => match plural_value {
+                    Some(value) => {
+                        output.push_str(&self.format_number(value, NumberStyle::Decimal))
+                    }
+                    None => output.push('#'),
+                },
+                MessagePart::Argument { name, style: None } => match self.argument(name)? {
+                    MarkupValue::Integer(value) => {
+                        output.push_str(&self.format_number(*value as f32, NumberStyle::Decimal))
+                    }
+                    MarkupValue::Float(value) => {
+                        output.push_str(&self.format_number(*value, NumberStyle::Decimal))
+                    }
+                    value => output.push_str(&value.to_string()),
+                },
+                MessagePart::Argument {
+                    name,
+                    style: Some(style),
+                } => {
+                    let value = self.number(name)?;
+                    output.push_str(&self.format_number(value, *style));
+                }
+                MessagePart::Select { name, cases } => {
+                    let value = self.argument(name)?.to_string();
+                    let case = find_case(cases, |key| *key == value)
+                        .or_else(|| find_case(cases, |key| key == "other"))
+                        .unwrap();
+                    self.write(case, plural_value, output)?;
+                }
+                MessagePart::Plural {
+                    name,
+                    ordinal,
+                    offset,
+                    cases,
+                } => {
+                    let value = self.number(name)?;
+                    let offset_value = value - offset;
+                    let case = find_case(cases, |key| *key == PluralKey::Exact(value))
+                        .or_else(|| {
+                            // I would love to cache this, but `icu_plural::PluralRules` is not `Send`, see `DialogueTextProcessor`
+                            let pluralization = Pluralization::new(self.language.clone());
+                            let category = if *ordinal {
+                                pluralization.get_ordinal_plural_case(offset_value)
+                            } else {
+                                pluralization.get_cardinal_plural_case(offset_value)
+                            };
+                            find_case(cases, |key| *key == PluralKey::Category(category))
+                        })
+                        .or_else(|| {
+                            find_case(cases, |key| {
+                                *key == PluralKey::Category(PluralCategory::Other)
+                            })
+                        })
+                        .unwrap();
+                    self.write(case, Some(offset_value), output)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn argument(&self, name: &str) -> Result<&MarkupValue, MessageFormatError> {
+        self.arguments
+            .get(name)
+            .ok_or_else(|| MessageFormatError::MissingArgument {
+                name: name.to_owned(),
+            })
+    }
+
+    fn number(&self, name: &str) -> Result<f32, MessageFormatError> {
+        let value = self.argument(name)?;
+        let number = match value {
+            MarkupValue::Integer(value) => Some(*value as f32),
+            MarkupValue::Float(value) => Some(*value),
+            MarkupValue::String(value) => value.parse().ok(),
+            MarkupValue::Bool(_) => None,
+        };
+        number.ok_or_else(|| MessageFormatError::NotANumber {
+            name: name.to_owned(),
+            value: value.to_string(),
+        })
+    }
+
+    fn format_number(&self, value: f32, style: NumberStyle) -> String {
+        self.number_formatter
+            .get_or_init(|| NumberFormatter::new(self.language.clone()))
+            .format(value, style)
+    }
+}
+
+fn find_case<K>(
+    cases: &[(K, Vec<MessagePart>)],
+    predicate: impl Fn(&K) -> bool,
+) -> Option<&[MessagePart]> {
+    cases
+        .iter()
+        .find(|(key, _)| predicate(key))
+        .map(|(_, case)| case.as_slice())
+}
+
+struct PatternParser<'a> {
+    pattern: &'a str,
+    characters: Vec<char>,
+    position: usize,
+}
+
+impl PatternParser<'_> {
+    /// Parses text and arguments up to the end of the pattern or, if `nested`, up to the `}` closing the current case.
+    fn parse_message(
+        &mut self,
+        nested: bool,
+        in_plural: bool,
+    ) -> Result<Vec<MessagePart>, MessageFormatError> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        loop {
+            let Some(character) = self.peek() else {
+                if nested {
+                    return Err(self.error("expected `}` to close the case"));
+                }
+                break;
+            };
+            match character {
+                '}' if nested => break,
+                '}' => return Err(self.error("found `}` without a matching `{`")),
+                '{' => {
+                    flush_text(&mut text, &mut parts);
+                    self.position += 1;
+                    parts.push(self.parse_argument(in_plural)?);
+                }
+                '#' if in_plural => {
+                    flush_text(&mut text, &mut parts);
+                    self.position += 1;
+                    parts.push(MessagePart::PluralValue);
+                }
+                '\'' => self.parse_apostrophe(in_plural, &mut text),
+                _ => {
+                    text.push(character);
+                    self.position += 1;
+                }
+            }
+        }
+        flush_text(&mut text, &mut parts);
+        Ok(parts)
+    }
+
+    /// Applies ICU's quoting rules: `''` is an apostrophe, and an apostrophe in front of a special character
+    /// starts a quoted literal that lasts until the next single apostrophe. Any other apostrophe is just an apostrophe.
+    fn parse_apostrophe(&mut self, in_plural: bool, text: &mut String) {
+        self.position += 1;
+        match self.peek() {
+            Some('\'') => {
+                text.push('\'');
+                self.position += 1;
+            }
+            Some('{' | '}') => self.parse_quoted_literal(text),
+            Some('#') if in_plural => self.parse_quoted_literal(text),
+            _ => text.push('\''),
+        }
+    }
+
+    fn parse_quoted_literal(&mut self, text: &mut String) {
+        while let Some(character) = self.peek() {
+            self.position += 1;
+            if character != '\'' {
+                text.push(character);
+            } else if self.peek() == Some('\'') {
+                text.push('\'');
+                self.position += 1;
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Parses an argument after its opening `{`, up to and including its closing `}`.
+    fn parse_argument(&mut self, in_plural: bool) -> Result<MessagePart, MessageFormatError> {
+        self.skip_whitespace();
+        let name = self.read_identifier("expected an argument name")?;
+        self.skip_whitespace();
+        if self.eat('}') {
+            return Ok(MessagePart::Argument { name, style: None });
+        }
+        self.expect(',')?;
+        self.skip_whitespace();
+        let type_position = self.position;
+        let type_ = self.read_identifier("expected an argument type")?;
+        self.skip_whitespace();
+        match type_.as_str() {
+            "number" => {
+                let style = if self.eat(',') {
+                    self.skip_whitespace();
+                    let style_position = self.position;
+                    let style = match self.read_identifier("expected a number style")?.as_str() {
+                        "integer" => NumberStyle::Integer,
+                        "percent" => NumberStyle::Percent,
+                        _ => {
+                            return Err(self.error_at(
+                                style_position,
+                                "unknown number style, expected `integer` or `percent`",
+                            ))
+                        }
+                    };
+                    self.skip_whitespace();
+                    style
+                } else {
+                    NumberStyle::Decimal
+                };
+                self.expect('}')?;
+                Ok(MessagePart::Argument {
+                    name,
+                    style: Some(style),
+                })
+            }
+            "select" => {
+                self.expect(',')?;
+                let cases = self
+                    .parse_cases(in_plural)?
+                    .into_iter()
+                    .map(|(_, key, case)| (key, case))
+                    .collect();
+                Ok(MessagePart::Select { name, cases })
+            }
+            "plural" | "selectordinal" => {
+                self.expect(',')?;
+                self.skip_whitespace();
+                let offset = self.parse_offset()?;
+                let cases = self
+                    .parse_cases(true)?
+                    .into_iter()
+                    .map(|(position, key, case)| Ok((self.parse_plural_key(position, &key)?, case)))
+                    .collect::<Result<_, _>>()?;
+                Ok(MessagePart::Plural {
+                    name,
+                    ordinal: type_ == "selectordinal",
+                    offset,
+                    cases,
+                })
+            }
+            _ => Err(self.error_at(
+                type_position,
+                "unknown argument type, expected `number`, `select`, `plural` or `selectordinal`",
+            )),
+        }
+    }
+
+    fn parse_offset(&mut self) -> Result<f32, MessageFormatError> {
+        const OFFSET: &str = "offset:";
+        let has_offset = self.characters[self.position..]
+            .iter()
+            .take(OFFSET.len())
+            .copied()
+            .eq(OFFSET.chars());
+        if !has_offset {
+            return Ok(0.0);
+        }
+        self.position += OFFSET.len();
+        self.skip_whitespace();
+        let offset_position = self.position;
+        let offset = self.read_while(|character| character.is_ascii_digit() || character == '.');
+        offset
+            .parse()
+            .map_err(|_| self.error_at(offset_position, "expected a number after `offset:`"))
+    }
+
+    /// Parses the cases of a `select`, `plural` or `selectordinal` argument up to and including the argument's closing `}`.
+    /// Returns the position of each key, the key and the case's message.
+    fn parse_cases(
+        &mut self,
+        in_plural: bool,
+    ) -> Result<Vec<(usize, String, Vec<MessagePart>)>, MessageFormatError> {
+        let start_position = self.position;
+        let mut cases: Vec<(usize, String, Vec<MessagePart>)> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat('}') {
+                break;
+            }
+            let key_position = self.position;
+            let key = self.read_while(|character| {
+                !character.is_whitespace() && character != '{' && character != '}'
+            });
+            if key.is_empty() {
+                return Err(self.error("expected a case"));
+            }
+            if cases.iter().any(|(_, existing, _)| *existing == key) {
+                return Err(self.error_at(key_position, "duplicate case"));
+            }
+            self.skip_whitespace();
+            self.expect('{')?;
+            let message = self.parse_message(true, in_plural)?;
+            self.expect('}')?;
+            cases.push((key_position, key, message));
+        }
+        if !cases.iter().any(|(_, key, _)| key == "other") {
+            return Err(self.error_at(start_position, "missing an `other` case"));
+        }
+        Ok(cases)
+    }
+
+    fn parse_plural_key(
+        &self,
+        position: usize,
+        key: &str,
+    ) -> Result<PluralKey, MessageFormatError> {
+        if let Some(value) = key.strip_prefix('=') {
+            return value
+                .parse()
+                .map(PluralKey::Exact)
+                .map_err(|_| self.error_at(position, "expected a number after `=`"));
+        }
+        let category = match key {
+            "zero" => PluralCategory::Zero,
+            "one" => PluralCategory::One,
+            "two" => PluralCategory::Two,
+            "few" => PluralCategory::Few,
+            "many" => PluralCategory::Many,
+            "other" => PluralCategory::Other,
+            _ => {
+                return Err(self.error_at(
+                    position,
+                    "unknown plural case, expected `zero`, `one`, `two`, `few`, `many`, `other` or an exact value like `=0`",
+                ))
+            }
+        };
+        Ok(PluralKey::Category(category))
+    }
+
+    fn read_identifier(&mut self, error: &str) -> Result<String, MessageFormatError> {
+        let identifier =
+            self.read_while(|character| character.is_alphanumeric() || character == '_');
+        if identifier.is_empty() {
+            Err(self.error(error))
+        } else {
+            Ok(identifier)
+        }
+    }
+
+    fn read_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let mut result = String::new();
+        while let Some(character) = self.peek().filter(|character| predicate(*character)) {
+            result.push(character);
+            self.position += 1;
+        }
+        result
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.read_while(char::is_whitespace);
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.characters.get(self.position).copied()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        let found = self.peek() == Some(expected);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), MessageFormatError> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{expected}`")))
+        }
+    }
+
+    fn error(&self, reason: &str) -> MessageFormatError {
+        self.error_at(self.position, reason)
+    }
+
+    fn error_at(&self, position: usize, reason: &str) -> MessageFormatError {
+        MessageFormatError::Syntax {
+            pattern: self.pattern.to_owned(),
+            position,
+            reason: reason.to_owned(),
+        }
+    }
+}
+
+fn flush_text(text: &mut String, parts: &mut Vec<MessagePart>) {
+    if !text.is_empty() {
+        parts.push(MessagePart::Text(std::mem::take(text)));
+    }
+}
+
+/// An error returned by [`MessageFormat::parse`] or [`MessageFormat::format`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MessageFormatError {
+    /// The pattern is not valid.
+    Syntax {
+        /// The pattern that was parsed, with backslashes in front of braces removed.
+        pattern: String,
+        /// The position in `pattern` where the error was found, measured in characters.
+        position: usize,
+        /// A description of what went wrong.
+        reason: String,
+    },
+    /// The message uses an argument that was not passed.
+    MissingArgument {
+        /// The name of the argument.
+        name: String,
+    },
+    /// An argument that needs to be a number is not.
+    NotANumber {
+        /// The name of the argument.
+        name: String,
+        /// The value that was passed.
+        value: String,
+    },
+}
+
+impl Error for MessageFormatError {}
+
+impl fmt::Display for MessageFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use MessageFormatError::*;
+        match self {
+            Syntax {
+                pattern,
+                position,
+                reason,
+            } => write!(
+                f,
+                "Invalid message format at position {position} of \"{pattern}\": {reason}"
+            ),
+            MissingArgument { name } => {
+                write!(f, "The message needs an argument \"{name}\"")
+            }
+            NotANumber { name, value } => write!(
+                f,
+                "The argument \"{name}\" of the message must be a number, but is \"{value}\""
+            ),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/YarnSpinner.Markup/MarkupParseResult.cs>
+
+pub use self::{markup_attribute::*, markup_value::*};
+// Part of the `AttributeMarkerProcessor` interface used by the runtime, not part of the public API.
+#[doc(hidden)]
+pub use self::markup_attribute_marker::MarkupAttributeMarker;
+pub(crate) use self::tag_type::*;
+use crate::markup::{serialize_markup, LineParser, MarkupParseError};
+use std::fmt::Debug;
+
+mod markup_attribute;
+mod markup_attribute_marker;
+mod markup_value;
+mod tag_type;
+
+/// The result of parsing a line of marked-up text.
+///
+/// Most of the time, you do not create instances of this struct yourself, as the `Dialogue` parses the markup of every line
+/// it delivers into a `Line`. Tooling that needs to inspect or rewrite markup outside of a running dialogue can use
+/// [`ParsedMarkup::parse`] and [`ParsedMarkup::to_markup`] instead.
+///
+/// ## Implementation Notes
+/// - This is called `MarkupParseResult` in the original C# code, but was renamed because [`Result`] already carries meaning in Rust.
+/// - The API for inspecting the attributes has been merged with `Line`.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct ParsedMarkup {
+    /// The original text, with all parsed markers removed.
+    pub text: String,
+    /// The list of [`MarkupAttribute`] in this parse result.
+    pub attributes: Vec<MarkupAttribute>,
+}
+
+impl ParsedMarkup {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new [`ParsedMarkup`] from plain text and the attributes applying to it.
+    pub fn from_parts(text: impl Into<String>, attributes: Vec<MarkupAttribute>) -> Self {
+        Self {
+            text: text.into(),
+            attributes,
+        }
+    }
+
+    /// Parses a line of marked-up text.
+    ///
+    /// Unlike the parsing done by the `Dialogue`, the `select`, `plural` and `ordinal` markers are not evaluated,
+    /// but kept as regular attributes. This way, the result can be turned back into the same markup with [`ParsedMarkup::to_markup`].
+    /// The `nomarkup` marker is still processed.
+    ///
+    /// Also unlike the `Dialogue`, `\\` is read as a single backslash, which [`ParsedMarkup::to_markup`] uses
+    /// to write a backslash directly in front of a marker.
+    pub fn parse(input: &str) -> Result<Self, MarkupParseError> {
+        LineParser::new()
+            .with_escaped_backslashes()
+            .parse_markup(input)
+    }
+
+    /// Renders this parse result back into canonical Yarn markup. See [`serialize_markup`] for details.
+    pub fn to_markup(&self) -> String {
+        serialize_markup(&self.text, &self.attributes)
+    }
+}
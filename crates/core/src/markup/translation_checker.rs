@@ -0,0 +1,572 @@
+//! Checks that translated lines are consistent with the lines they were translated from.
+//!
+//! ## Implementation notes
+//! This has no equivalent in the original C# code.
+
+use crate::markup::markup_validator::{
+    parse_for_validation, validation_line_parser, SUBSTITUTION_PLACEHOLDER,
+};
+use crate::markup::{
+    plural_case_name, MarkupAttribute, MarkupIssue, MarkupValidator, ParsedMarkup,
+    CHARACTER_ATTRIBUTE,
+};
+use crate::prelude::{Language, LineId, Pluralization};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+
+/// Compares translated lines with the base language lines they were translated from and reports inconsistencies
+/// that would otherwise only show up when the line is reached in-game:
+/// - Substitution placeholders such as `{0}` that were dropped, duplicated or renumbered.
+/// - Markup markers that were dropped, added, renamed or nested differently.
+/// - A character name prefix such as `Mae: ` that was dropped or added.
+/// - `plural` and `ordinal` markers that lack a case for one of the plural categories of the translation's language.
+///
+/// Additionally, the markup of every translation is checked with a [`MarkupValidator`].
+///
+/// ## Example
+/// ```rust
+/// # use yarnspinner_core::markup::*;
+/// # use yarnspinner_core::prelude::*;
+/// let checker = TranslationChecker::new();
+/// let issues = checker.check(
+///     "Mae: You have [b]{0}[/b] [plural value={0} one=apple other=apples /]!",
+///     "Mae: Masz [b]jabłka[/b] [plural value={0} one=jabłko other=jabłek /]!",
+///     &Language::new("pl"),
+/// );
+/// assert_eq!(
+///     vec![
+///         TranslationIssue::PlaceholderCountMismatch {
+///             index: 0,
+///             expected: 2,
+///             actual: 1,
+///         },
+///         TranslationIssue::MissingPluralCategory {
+///             name: "plural".to_owned(),
+///             category: "few".to_owned(),
+///         },
+///         TranslationIssue::MissingPluralCategory {
+///             name: "plural".to_owned(),
+///             category: "many".to_owned(),
+///         },
+///     ],
+///     issues
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TranslationChecker {
+    markup_validator: MarkupValidator,
+}
+
+impl TranslationChecker {
+    /// Creates a new [`TranslationChecker`] that validates markup with a default [`MarkupValidator`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the [`MarkupValidator`] used to validate the markup of the translations, e.g. to report unknown markers.
+    pub fn with_markup_validator(mut self, markup_validator: MarkupValidator) -> Self {
+        self.markup_validator = markup_validator;
+        self
+    }
+
+    /// Checks a single translated line against the base language line it was translated from.
+    /// Both lines are expected as they are stored in a string table, i.e. with substitution placeholders.
+    pub fn check(
+        &self,
+        base_text: &str,
+        translated_text: &str,
+        language: &Language,
+    ) -> Vec<TranslationIssue> {
+        let mut issues: Vec<_> = self
+            .markup_validator
+            .validate(translated_text)
+            .into_iter()
+            .map(TranslationIssue::InvalidMarkup)
+            .collect();
+        issues.extend(compare_placeholders(base_text, translated_text));
+
+        let mut line_parser = validation_line_parser();
+        let (Ok(base), Ok(translation)) = (
+            parse_for_validation(&mut line_parser, base_text),
+            parse_for_validation(&mut line_parser, translated_text),
+        ) else {
+            // Broken base lines are reported by the compiler and broken translations by the markup validator
+            return issues;
+        };
+        match (has_character_name(&base), has_character_name(&translation)) {
+            (true, false) => issues.push(TranslationIssue::MissingCharacterName),
+            (false, true) => issues.push(TranslationIssue::UnexpectedCharacterName),
+            _ => {}
+        }
+        let marker_issues = compare_markers(&base, &translation);
+        if marker_issues.is_empty() {
+            issues.extend(compare_nesting(&base, &translation));
+        }
+        issues.extend(marker_issues);
+        issues.extend(check_plural_categories(&translation, language));
+        issues
+    }
+
+    /// Checks all lines of a translation's string table against the base language string table.
+    /// Lines that are missing in either table are skipped, as they are simply untranslated or obsolete.
+    pub fn check_string_table(
+        &self,
+        base_string_table: &HashMap<LineId, String>,
+        translated_string_table: &HashMap<LineId, String>,
+        language: &Language,
+    ) -> TranslationReport {
+        let mut entries: Vec<_> = translated_string_table
+            .iter()
+            .filter_map(|(id, translated_text)| {
+                let base_text = base_string_table.get(id)?;
+                let issues = self.check(base_text, translated_text, language);
+                (!issues.is_empty()).then(|| TranslationReportEntry {
+                    line_id: id.clone(),
+                    language: language.clone(),
+                    issues,
+                })
+            })
+            .collect();
+        entries.sort_by(|lhs, rhs| lhs.line_id.0.cmp(&rhs.line_id.0));
+        TranslationReport { entries }
+    }
+}
+
+fn compare_placeholders(base_text: &str, translated_text: &str) -> Vec<TranslationIssue> {
+    let count = |text: &str| {
+        let mut counts = BTreeMap::<usize, usize>::new();
+        for captures in SUBSTITUTION_PLACEHOLDER.captures_iter(text) {
+            if let Ok(index) = captures[1].parse() {
+                *counts.entry(index).or_default() += 1;
+            }
+        }
+        counts
+    };
+    compare_counts(count(base_text), count(translated_text))
+        .map(
+            |(index, expected, actual)| TranslationIssue::PlaceholderCountMismatch {
+                index,
+                expected,
+                actual,
+            },
+        )
+        .collect()
+}
+
+fn has_character_name(markup: &ParsedMarkup) -> bool {
+    markup
+        .attributes
+        .iter()
+        .any(|attribute| attribute.name == CHARACTER_ATTRIBUTE)
+}
+
+/// The attributes of a line, without the character attribute, which is checked separately.
+fn markers(markup: &ParsedMarkup) -> impl Iterator<Item = &MarkupAttribute> {
+    markup
+        .attributes
+        .iter()
+        .filter(|attribute| attribute.name != CHARACTER_ATTRIBUTE)
+}
+
+fn compare_markers(base: &ParsedMarkup, translation: &ParsedMarkup) -> Vec<TranslationIssue> {
+    let count = |markup| {
+        let mut counts = BTreeMap::<String, usize>::new();
+        for attribute in markers(markup) {
+            *counts.entry(attribute.name.clone()).or_default() += 1;
+        }
+        counts
+    };
+    compare_counts(count(base), count(translation))
+        .map(
+            |(name, expected, actual)| TranslationIssue::MarkerCountMismatch {
+                name,
+                expected,
+                actual,
+            },
+        )
+        .collect()
+}
+
+fn compare_nesting(base: &ParsedMarkup, translation: &ParsedMarkup) -> Vec<TranslationIssue> {
+    let parents = |markup| {
+        let mut parents = BTreeMap::<String, Vec<Option<String>>>::new();
+        for attribute in markers(markup) {
+            let parent = parent_of(markup, attribute).map(|parent| parent.name.clone());
+            parents
+                .entry(attribute.name.clone())
+                .or_default()
+                .push(parent);
+        }
+        parents.values_mut().for_each(|parents| parents.sort());
+        parents
+    };
+    let base_parents = parents(base);
+    let mut translation_parents = parents(translation);
+    base_parents
+        .into_iter()
+        .filter_map(|(name, expected_parents)| {
+            let actual_parents = translation_parents.remove(&name).unwrap_or_default();
+            (expected_parents != actual_parents).then_some(
+                TranslationIssue::MarkerNestingMismatch {
+                    name,
+                    expected_parents,
+                    actual_parents,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Returns the innermost marker that contains `attribute`. Markers covering the same text are nested in the order they were opened.
+fn parent_of<'a>(
+    markup: &'a ParsedMarkup,
+    attribute: &MarkupAttribute,
+) -> Option<&'a MarkupAttribute> {
+    let end = |attribute: &MarkupAttribute| attribute.position + attribute.length;
+    markers(markup)
+        .filter(|candidate| {
+            candidate.source_position < attribute.source_position
+                && candidate.position <= attribute.position
+                && end(attribute) <= end(candidate)
+        })
+        .min_by_key(|candidate| (candidate.length, usize::MAX - candidate.source_position))
+}
+
+fn check_plural_categories(
+    translation: &ParsedMarkup,
+    language: &Language,
+) -> Vec<TranslationIssue> {
+    let plural_markers: Vec<_> = translation
+        .attributes
+        .iter()
+        .filter(|attribute| attribute.name == "plural" || attribute.name == "ordinal")
+        .collect();
+    if plural_markers.is_empty() {
+        return Vec::new();
+    }
+    let pluralization = Pluralization::new(language.clone());
+    let mut issues = Vec::new();
+    for attribute in plural_markers {
+        let categories: Vec<_> = if attribute.name == "plural" {
+            pluralization.cardinal_categories().collect()
+        } else {
+            pluralization.ordinal_categories().collect()
+        };
+        issues.extend(
+            categories
+                .into_iter()
+                .map(plural_case_name)
+                .filter(|category| attribute.property(category).is_none())
+                .map(|category| TranslationIssue::MissingPluralCategory {
+                    name: attribute.name.clone(),
+                    category: category.to_owned(),
+                }),
+        );
+    }
+    issues
+}
+
+/// Returns the keys whose counts differ, together with the expected and actual count.
+fn compare_counts<K: Ord>(
+    expected: BTreeMap<K, usize>,
+    mut actual: BTreeMap<K, usize>,
+) -> impl Iterator<Item = (K, usize, usize)> {
+    let mut differences: Vec<_> = expected
+        .into_iter()
+        .map(|(key, expected)| {
+            let actual = actual.remove(&key).unwrap_or_default();
+            (key, expected, actual)
+        })
+        .collect();
+    differences.extend(actual.into_iter().map(|(key, actual)| (key, 0, actual)));
+    differences.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+    differences
+        .into_iter()
+        .filter(|(_, expected, actual)| expected != actual)
+}
+
+/// An inconsistency between a translated line and its base language line, as found by [`TranslationChecker::check`].
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub enum TranslationIssue {
+    /// The markup of the translation has a problem on its own, as found by [`MarkupValidator::validate`].
+    InvalidMarkup(MarkupIssue),
+    /// A substitution placeholder appears a different number of times than in the base line.
+    /// If it is missing, the value will not be shown, and if it is unexpected, it will show the wrong value or none at all.
+    PlaceholderCountMismatch {
+        /// The index of the placeholder, e.g. `0` for `{0}`.
+        index: usize,
+        /// How often the placeholder appears in the base line.
+        expected: usize,
+        /// How often the placeholder appears in the translation.
+        actual: usize,
+    },
+    /// A markup marker appears a different number of times than in the base line, which usually means it was dropped or renamed.
+    MarkerCountMismatch {
+        /// The name of the marker.
+        name: String,
+        /// How often the marker appears in the base line.
+        expected: usize,
+        /// How often the marker appears in the translation.
+        actual: usize,
+    },
+    /// A markup marker is nested inside different markers than in the base line.
+    MarkerNestingMismatch {
+        /// The name of the marker.
+        name: String,
+        /// The names of the markers directly containing each occurrence of the marker in the base line, sorted. `None` stands for the top level.
+        expected_parents: Vec<Option<String>>,
+        /// The names of the markers directly containing each occurrence of the marker in the translation, sorted. `None` stands for the top level.
+        actual_parents: Vec<Option<String>>,
+    },
+    /// The base line starts with a character name, e.g. `Mae: `, but the translation does not.
+    MissingCharacterName,
+    /// The translation starts with a character name, e.g. `Mae: `, but the base line does not.
+    /// This is often caused by a colon that was introduced by the translation.
+    UnexpectedCharacterName,
+    /// A `plural` or `ordinal` marker has no case for a plural category used by the translation's language, so the `other` case is shown instead.
+    MissingPluralCategory {
+        /// The name of the marker, i.e. `plural` or `ordinal`.
+        name: String,
+        /// The name of the missing category, e.g. `few`.
+        category: String,
+    },
+}
+
+impl TranslationIssue {
+    /// Returns `true` if this issue makes the translated line fail at runtime or show wrong text.
+    /// Other issues only indicate a likely mistake, e.g. a marker that was deliberately moved.
+    pub fn is_error(&self) -> bool {
+        match self {
+            TranslationIssue::InvalidMarkup(issue) => issue.is_error(),
+            TranslationIssue::MarkerNestingMismatch { .. }
+            | TranslationIssue::UnexpectedCharacterName => false,
+            _ => true,
+        }
+    }
+}
+
+impl Error for TranslationIssue {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TranslationIssue::InvalidMarkup(issue) => Some(issue),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TranslationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TranslationIssue::*;
+        let format_parents = |parents: &[Option<String>]| {
+            parents
+                .iter()
+                .map(|parent| match parent {
+                    Some(parent) => format!("[{parent}]"),
+                    None => "the top level".to_owned(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            InvalidMarkup(issue) => issue.fmt(f),
+            PlaceholderCountMismatch {
+                index, actual: 0, ..
+            } => write!(f, "Placeholder {{{index}}} is missing"),
+            PlaceholderCountMismatch {
+                index, expected: 0, ..
+            } => write!(f, "Placeholder {{{index}}} does not exist in the base language"),
+            PlaceholderCountMismatch {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Placeholder {{{index}}} appears {actual} times, but {expected} times in the base language"
+            ),
+            MarkerCountMismatch { name, actual: 0, .. } => write!(f, "Marker [{name}] is missing"),
+            MarkerCountMismatch {
+                name, expected: 0, ..
+            } => write!(f, "Marker [{name}] does not exist in the base language"),
+            MarkerCountMismatch {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Marker [{name}] appears {actual} times, but {expected} times in the base language"
+            ),
+            MarkerNestingMismatch {
+                name,
+                expected_parents,
+                actual_parents,
+            } => write!(
+                f,
+                "Marker [{name}] is inside {}, but inside {} in the base language",
+                format_parents(actual_parents),
+                format_parents(expected_parents)
+            ),
+            MissingCharacterName => write!(f, "The character name is missing"),
+            UnexpectedCharacterName => write!(
+                f,
+                "The line starts with a character name, but the base language does not. Escape colons that are not part of a character name"
+            ),
+            MissingPluralCategory { name, category } => {
+                write!(f, "Marker [{name}] has no case for the plural category \"{category}\"")
+            }
+        }
+    }
+}
+
+/// The issues found by [`TranslationChecker::check_string_table`], grouped by line.
+///
+/// The [`Display`](fmt::Display) implementation lists one issue per line of output, which is suitable for logs and CI.
+/// Use [`TranslationReport::has_errors`] to decide whether a CI job should fail.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TranslationReport {
+    /// The lines with issues, sorted by their ID.
+    pub entries: Vec<TranslationReportEntry>,
+}
+
+/// The issues of a single translated line in a [`TranslationReport`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct TranslationReportEntry {
+    /// The ID of the line.
+    pub line_id: LineId,
+    /// The language of the translation.
+    pub language: Language,
+    /// The issues found in the translation.
+    pub issues: Vec<TranslationIssue>,
+}
+
+impl TranslationReport {
+    /// Returns `true` if no issues were found.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns `true` if any issue is an error, see [`TranslationIssue::is_error`].
+    pub fn has_errors(&self) -> bool {
+        self.issues().any(|(_, issue)| issue.is_error())
+    }
+
+    /// Iterates over all issues together with the entry they belong to.
+    pub fn issues(&self) -> impl Iterator<Item = (&TranslationReportEntry, &TranslationIssue)> {
+        self.entries
+            .iter()
+            .flat_map(|entry| entry.issues.iter().map(move |issue| (entry, issue)))
+    }
+
+    /// Adds the entries of another report, e.g. for another language.
+    pub fn extend(&mut self, other: TranslationReport) {
+        self.entries.extend(other.entries);
+    }
+}
+
+impl fmt::Display for TranslationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (entry, issue) in self.issues() {
+            let severity = if issue.is_error() { "error" } else { "warning" };
+            writeln!(
+                f,
+                "{severity}: {} ({}): {issue}",
+                entry.line_id, entry.language
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(base_text: &str, translated_text: &str) -> Vec<TranslationIssue> {
+        TranslationChecker::new().check(base_text, translated_text, &Language::new("en"))
+    }
+
+    #[test]
+    fn accepts_consistent_translation() {
+        let issues = check(
+            "Mae: I have [b]{0}[/b] {1:0.00} [plural value={0} one=apple other=apples /]!",
+            r#"Mae: [plural value={0} one="Apfel" other="Äpfel" /] {1:0.00}, ich habe [b]{0}[/b]!"#,
+        );
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+
+    #[test]
+    fn reports_character_name_changes() {
+        assert_eq!(
+            vec![TranslationIssue::MissingCharacterName],
+            check("Mae: Hi", "Hallo")
+        );
+        assert_eq!(
+            vec![TranslationIssue::UnexpectedCharacterName],
+            check("Note to self", "Notiz: an mich")
+        );
+    }
+
+    #[test]
+    fn reports_marker_count_mismatches() {
+        assert_eq!(
+            vec![
+                TranslationIssue::MarkerCountMismatch {
+                    name: "b".to_owned(),
+                    expected: 1,
+                    actual: 0,
+                },
+                TranslationIssue::MarkerCountMismatch {
+                    name: "i".to_owned(),
+                    expected: 0,
+                    actual: 1,
+                },
+            ],
+            check("A [b]bold[/b] move", "Ein [i]kühner[/i] Zug")
+        );
+    }
+
+    #[test]
+    fn reports_nesting_mismatches() {
+        assert_eq!(
+            vec![
+                TranslationIssue::MarkerNestingMismatch {
+                    name: "b".to_owned(),
+                    expected_parents: vec![Some("wave".to_owned())],
+                    actual_parents: vec![None],
+                },
+                TranslationIssue::MarkerNestingMismatch {
+                    name: "wave".to_owned(),
+                    expected_parents: vec![None],
+                    actual_parents: vec![Some("b".to_owned())],
+                },
+            ],
+            check(
+                "[wave]Hello [b]there[/b][/wave]",
+                "[b]Hallo [wave]du[/wave][/b]"
+            )
+        );
+    }
+
+    #[test]
+    fn reports_translation_issues_per_line() {
+        let base = HashMap::from([
+            (LineId::from("line:1"), "Mae: Hi {0}".to_owned()),
+            (LineId::from("line:2"), "Bye".to_owned()),
+            (LineId::from("line:3"), "Untranslated".to_owned()),
+        ]);
+        let translation = HashMap::from([
+            (LineId::from("line:1"), "Mae: Hallo".to_owned()),
+            (LineId::from("line:2"), "Tschüss [wave]".to_owned()),
+        ]);
+
+        let report =
+            TranslationChecker::new().check_string_table(&base, &translation, &Language::new("de"));
+
+        assert!(report.has_errors());
+        assert_eq!(
+            "error: line:1 (de): Placeholder {0} is missing\n\
+             warning: line:2 (de): Marker [wave] is never closed and has no effect. Close it with [/wave] or [/]\n",
+            report.to_string()
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Word counts of lines for budgeting translation and voice-over work.
+//!
+//! ## Implementation notes
+//! This has no equivalent in the original C# code.
+
+use crate::markup::{ParsedMarkup, CHARACTER_ATTRIBUTE};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Counts the words in the text of a line as it is stored in a string table.
+///
+/// Markup markers, substitution placeholders such as `{0}` and the `Name:` prefix designating the character are not counted.
+/// Words are found with Unicode word segmentation, so languages that do not separate words with spaces are counted as well.
+/// Text with invalid markup is counted as it is, without the character name being recognised.
+///
+/// ## Example
+/// ```rust
+/// # use yarnspinner_core::markup::*;
+/// assert_eq!(6, count_words("Mae: You owe me {0} [b]shiny[/b] coins, Greg."));
+/// ```
+pub fn count_words(text: &str) -> usize {
+    let text = match ParsedMarkup::parse(text) {
+        Ok(markup) => without_character_name(markup),
+        Err(_) => text.to_owned(),
+    };
+    let mut in_placeholder = false;
+    let text: String = text
+        .chars()
+        .map(|character| match character {
+            '{' => {
+                in_placeholder = true;
+                ' '
+            }
+            '}' if in_placeholder => {
+                in_placeholder = false;
+                ' '
+            }
+            _ if in_placeholder => ' ',
+            _ => character,
+        })
+        .collect();
+    text.unicode_words().count()
+}
+
+fn without_character_name(markup: ParsedMarkup) -> String {
+    let Some(character_attribute) = markup
+        .attributes
+        .iter()
+        .find(|attribute| attribute.name == CHARACTER_ATTRIBUTE)
+    else {
+        return markup.text;
+    };
+    let name =
+        character_attribute.position..character_attribute.position + character_attribute.length;
+    // Attribute positions are counted in text elements, not bytes
+    markup
+        .text
+        .graphemes(true)
+        .enumerate()
+        .filter(|(index, _)| !name.contains(index))
+        .map(|(_, grapheme)| grapheme)
+        .collect()
+}
@@ -0,0 +1,199 @@
+//! Locale-aware formatting of numbers that end up in the text of a line.
+//!
+//! ## Implementation notes
+//! This has no equivalent in the original C# code, which formats numbers with the invariant culture.
+
+use crate::prelude::Language;
+use fixed_decimal::{DoublePrecision, FixedDecimal};
+use icu_decimal::options::{FixedDecimalFormatterOptions, GroupingStrategy};
+use icu_decimal::FixedDecimalFormatter;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// How a number is presented in the text of a line.
+///
+/// In a Yarn script, the style of an interpolated number is chosen with a format specifier after a colon,
+/// e.g. `{$gold:0.00}`, `{$ratio:percent}` or `{$count:integer}`. The specifiers are parsed with [`NumberStyle::from_str`].
+/// Numbers without a specifier use [`NumberStyle::Decimal`].
+///
+/// All styles use the decimal separator, grouping separator and digits of the `Dialogue`'s language.
+///
+/// ## Example
+/// ```rust
+/// # use yarnspinner_core::prelude::*;
+/// let style: NumberStyle = "#,##0.0#".parse().unwrap();
+/// assert_eq!(
+///     NumberStyle::Pattern {
+///         grouping: true,
+///         min_fraction_digits: 1,
+///         max_fraction_digits: 2,
+///     },
+///     style
+/// );
+/// assert_eq!(NumberStyle::Percent, "percent".parse().unwrap());
+/// assert!("0.00 gold".parse::<NumberStyle>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NumberStyle {
+    /// Grouped and with up to three fraction digits, like ICU's default `#,##0.###` pattern.
+    #[default]
+    Decimal,
+    /// Grouped and rounded to the nearest integer. Written as `integer`.
+    Integer,
+    /// Multiplied by 100, grouped, rounded to the nearest integer and followed by a percent sign. Written as `percent`.
+    ///
+    /// ## Implementation notes
+    /// The percent sign is always appended directly after the number, as ICU4X 1.x has no stable percent formatter.
+    /// Languages that place it differently, e.g. German (`25 %`) or Turkish (`%25`), still get `25%`.
+    Percent,
+    /// A subset of the ICU decimal patterns: `0` for the integer digits, optionally preceded by `#,##` to group them,
+    /// and optionally followed by a `.`, zeros for the fraction digits that are always shown,
+    /// and number signs for fraction digits that are only shown if they are not zero.
+    /// For example, `#,##0.0#` shows `1234.5` as `1,234.5` and `2` as `2.0`.
+    Pattern {
+        /// Whether the integer digits are grouped, e.g. `1,234` instead of `1234`.
+        grouping: bool,
+        /// The number of fraction digits that are always shown.
+        min_fraction_digits: u8,
+        /// The maximum number of fraction digits. The number is rounded half to even to this precision.
+        max_fraction_digits: u8,
+    },
+}
+
+/// The most fraction digits a [`NumberStyle::Pattern`] can have.
+const MAX_FRACTION_DIGITS: usize = 9;
+
+static NUMBER_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(#,##)?0(?:\.(0*)(#*))?$").unwrap());
+
+impl FromStr for NumberStyle {
+    type Err = ParseNumberStyleError;
+
+    fn from_str(specifier: &str) -> Result<Self, Self::Err> {
+        let error = || ParseNumberStyleError(specifier.to_owned());
+        match specifier {
+            "integer" => return Ok(NumberStyle::Integer),
+            "percent" => return Ok(NumberStyle::Percent),
+            _ => {}
+        }
+        let captures = NUMBER_PATTERN.captures(specifier).ok_or_else(error)?;
+        let zeros = captures.get(2).map_or(0, |zeros| zeros.len());
+        let hashes = captures.get(3).map_or(0, |hashes| hashes.len());
+        if zeros + hashes > MAX_FRACTION_DIGITS {
+            return Err(error());
+        }
+        Ok(NumberStyle::Pattern {
+            grouping: captures.get(1).is_some(),
+            min_fraction_digits: zeros as u8,
+            max_fraction_digits: (zeros + hashes) as u8,
+        })
+    }
+}
+
+impl fmt::Display for NumberStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumberStyle::Decimal => write!(f, "#,##0.###"),
+            NumberStyle::Integer => write!(f, "integer"),
+            NumberStyle::Percent => write!(f, "percent"),
+            NumberStyle::Pattern {
+                grouping,
+                min_fraction_digits,
+                max_fraction_digits,
+            } => {
+                if *grouping {
+                    write!(f, "#,##")?;
+                }
+                write!(f, "0")?;
+                if *max_fraction_digits > 0 {
+                    let zeros = usize::from(*min_fraction_digits);
+                    let hashes =
+                        usize::from(max_fraction_digits.saturating_sub(*min_fraction_digits));
+                    write!(f, ".{}{}", "0".repeat(zeros), "#".repeat(hashes))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The error returned when parsing an invalid format specifier into a [`NumberStyle`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseNumberStyleError(pub String);
+
+impl Error for ParseNumberStyleError {}
+
+impl fmt::Display for ParseNumberStyleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid number format \"{}\". Expected \"integer\", \"percent\" or a pattern like \"0.00\", \"0.0#\" or \"#,##0\"",
+            self.0
+        )
+    }
+}
+
+// Used by the runtime to format inline expressions, not part of the public API.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct NumberFormatter {
+    grouped: FixedDecimalFormatter,
+    ungrouped: FixedDecimalFormatter,
+}
+
+impl NumberFormatter {
+    pub fn new(language: impl Into<Language>) -> Self {
+        let language = language.into();
+        let locale = language.0.into();
+        let formatter = |grouping_strategy| {
+            let mut options = FixedDecimalFormatterOptions::default();
+            options.grouping_strategy = grouping_strategy;
+            FixedDecimalFormatter::try_new(&locale, options).unwrap()
+        };
+        Self {
+            grouped: formatter(GroupingStrategy::Auto),
+            ungrouped: formatter(GroupingStrategy::Never),
+        }
+    }
+
+    /// Formats `value` using the decimal separator, grouping separator and digits of the language.
+    pub fn format(&self, value: f32, style: NumberStyle) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+        let (value, grouping, min_fraction_digits, max_fraction_digits) = match style {
+            NumberStyle::Decimal => (value as f64, true, 0, 3),
+            NumberStyle::Integer => (value as f64, true, 0, 0),
+            NumberStyle::Percent => (value as f64 * 100.0, true, 0, 0),
+            NumberStyle::Pattern {
+                grouping,
+                min_fraction_digits,
+                max_fraction_digits,
+            } => (
+                value as f64,
+                grouping,
+                min_fraction_digits,
+                max_fraction_digits,
+            ),
+        };
+        let mut decimal = FixedDecimal::try_from_f64(value, DoublePrecision::Floating).unwrap();
+        // Rounding also gets rid of the noise introduced by converting from `f32`, e.g. `0.30000001`
+        decimal.half_even(-i16::from(max_fraction_digits));
+        decimal.trim_end();
+        decimal.pad_end(-i16::from(min_fraction_digits));
+        let formatter = if grouping {
+            &self.grouped
+        } else {
+            &self.ungrouped
+        };
+        let formatted = formatter.format_to_string(&decimal);
+        // See the implementation notes on `NumberStyle::Percent`
+        match style {
+            NumberStyle::Percent => format!("{formatted}%"),
+            _ => formatted,
+        }
+    }
+}
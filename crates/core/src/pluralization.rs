@@ -30,6 +30,16 @@ impl Pluralization {
         let value = get_into_plural_operand(value);
         self.ordinal_rules.category_for(value)
     }
+
+    /// The cardinal plural categories used by the language. These always include [`PluralCategory::Other`].
+    pub(crate) fn cardinal_categories(&self) -> impl Iterator<Item = PluralCategory> + '_ {
+        self.cardinal_rules.categories()
+    }
+
+    /// The ordinal plural categories used by the language. These always include [`PluralCategory::Other`].
+    pub(crate) fn ordinal_categories(&self) -> impl Iterator<Item = PluralCategory> + '_ {
+        self.ordinal_rules.categories()
+    }
 }
 
 fn get_into_plural_operand(value: f32) -> PluralOperands {
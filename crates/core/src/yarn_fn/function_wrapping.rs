@@ -356,6 +356,25 @@ mod tests {
         accept_yarn_fn(f);
     }
 
+    #[test]
+    fn reports_flattened_parameter_infos() {
+        let mut parameters = Vec::new();
+        <(&str, (f32, Option<(bool, YarnValue)>))>::parameter_infos(&mut parameters);
+        let parameters: Vec<_> = parameters
+            .into_iter()
+            .map(|parameter| (parameter.r#type, parameter.is_optional))
+            .collect();
+        assert_eq!(
+            vec![
+                (Type::String, false),
+                (Type::Number, false),
+                (Type::Boolean, true),
+                (Type::Any, true),
+            ],
+            parameters
+        );
+    }
+
     fn accept_yarn_fn<Marker>(_: impl YarnFn<Marker>) {}
 
     fn apply_yarn_fn<T, Marker>(f: T, input: Vec<YarnValue>) -> T::Out
@@ -4,7 +4,7 @@
 
 use super::optionality::{AllowedOptionalityChain, Optional, Optionality, Required};
 use crate::prelude::*;
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::borrow::Borrow;
 use std::fmt::{Debug, Display};
 use std::iter::Peekable;
@@ -65,6 +65,28 @@ pub trait YarnFnParam {
 
     #[doc(hidden)]
     fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a>;
+
+    /// Appends the signature of every Yarn value this parameter takes to `parameters`, in order.
+    /// Tuples are flattened, so `(String, Option<f32>)` appends a required string and an optional number.
+    fn parameter_infos(parameters: &mut Vec<YarnFnParameterInfo>);
+}
+
+/// The signature of a single value passed from Yarn to a [`YarnFnParam`], as reported by [`YarnFnParam::parameter_infos`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YarnFnParameterInfo {
+    /// The Yarn type the value is converted from. [`Type::Any`] for [`YarnValue`].
+    pub r#type: Type,
+    /// Whether the value may be omitted, i.e. the parameter is an [`Option`].
+    pub is_optional: bool,
+}
+
+impl YarnFnParameterInfo {
+    fn required<T: 'static>() -> Self {
+        Self {
+            r#type: Type::try_from(TypeId::of::<T>()).unwrap_or_default(),
+            is_optional: false,
+        }
+    }
 }
 
 /// Shorthand way of accessing the associated type [`YarnFnParam::Item`] for a given [`YarnFnParam`].
@@ -81,6 +103,14 @@ impl<T: YarnFnParam> YarnFnParam for Option<T> {
             None
         }
     }
+
+    fn parameter_infos(parameters: &mut Vec<YarnFnParameterInfo>) {
+        let start = parameters.len();
+        T::parameter_infos(parameters);
+        for parameter in &mut parameters[start..] {
+            parameter.is_optional = true;
+        }
+    }
 }
 
 macro_rules! impl_yarn_fn_param_tuple {
@@ -97,6 +127,11 @@ macro_rules! impl_yarn_fn_param_tuple {
             fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
                ($($param::retrieve(iter),)*)
             }
+
+            #[allow(unused_variables)] // for n = 0 tuples
+            fn parameter_infos(parameters: &mut Vec<YarnFnParameterInfo>) {
+                $($param::parameter_infos(parameters);)*
+            }
         }
     };
 }
@@ -130,6 +165,10 @@ where
             phantom_data: PhantomData,
         }
     }
+
+    fn parameter_infos(parameters: &mut Vec<YarnFnParameterInfo>) {
+        parameters.push(YarnFnParameterInfo::required::<T>());
+    }
 }
 
 /// For types like `String`, of which a reference to `&str` and not `&String`.
@@ -165,6 +204,10 @@ where
             phantom_data: PhantomData,
         }
     }
+
+    fn parameter_infos(parameters: &mut Vec<YarnFnParameterInfo>) {
+        parameters.push(YarnFnParameterInfo::required::<T>());
+    }
 }
 
 struct ResOwned<T>
@@ -190,6 +233,10 @@ where
         let value = *converted.downcast::<T>().unwrap();
         ResOwned { value }
     }
+
+    fn parameter_infos(parameters: &mut Vec<YarnFnParameterInfo>) {
+        parameters.push(YarnFnParameterInfo::required::<T>());
+    }
 }
 
 macro_rules! impl_yarn_fn_param {
@@ -211,6 +258,10 @@ macro_rules! impl_yarn_fn_param_inner {
             fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
                 ResRef::<$referenced>::retrieve(iter).value
             }
+
+            fn parameter_infos(parameters: &mut Vec<YarnFnParameterInfo>) {
+                ResRef::<$referenced>::parameter_infos(parameters);
+            }
         }
 
         impl YarnFnParam for $referenced {
@@ -220,6 +271,10 @@ macro_rules! impl_yarn_fn_param_inner {
             fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
                 ResOwned::<$referenced>::retrieve(iter).value
             }
+
+            fn parameter_infos(parameters: &mut Vec<YarnFnParameterInfo>) {
+                ResOwned::<$referenced>::parameter_infos(parameters);
+            }
         }
     };
     ($referenced:ty => $owned:ty: YarnFnParam) => {
@@ -230,6 +285,10 @@ macro_rules! impl_yarn_fn_param_inner {
             fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
                 ResRefBorrow::<$owned, $referenced>::retrieve(iter).value
             }
+
+            fn parameter_infos(parameters: &mut Vec<YarnFnParameterInfo>) {
+                ResRefBorrow::<$owned, $referenced>::parameter_infos(parameters);
+            }
         }
 
         impl YarnFnParam for &$owned {
@@ -239,6 +298,10 @@ macro_rules! impl_yarn_fn_param_inner {
             fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
                 ResRef::<$owned>::retrieve(iter).value
             }
+
+            fn parameter_infos(parameters: &mut Vec<YarnFnParameterInfo>) {
+                ResRef::<$owned>::parameter_infos(parameters);
+            }
         }
 
         impl YarnFnParam for $owned {
@@ -248,6 +311,10 @@ macro_rules! impl_yarn_fn_param_inner {
             fn retrieve<'a>(iter: &mut YarnValueWrapperIter<'a>) -> Self::Item<'a> {
                 ResOwned::<$owned>::retrieve(iter).value
             }
+
+            fn parameter_infos(parameters: &mut Vec<YarnFnParameterInfo>) {
+                ResOwned::<$owned>::parameter_infos(parameters);
+            }
         }
     };
 }
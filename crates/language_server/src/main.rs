@@ -0,0 +1,28 @@
+//! A language server for Yarn Spinner, built on [`yarnspinner_compiler`].
+//!
+//! Provides diagnostics, go-to-definition for nodes and variables, completion, hover, document symbols and semantic highlighting
+//! for `.yarn` files. All Yarn files in the workspace are compiled together, just like they would be in a game.
+//!
+//! The server communicates over stdio, so any editor with LSP support can start it by running `yarn-language-server`.
+//! Commands registered by the game are not known to the compiler, but can be passed for completion as initialization options:
+//! ```json
+//! { "commands": ["fade_in", "play_sound"] }
+//! ```
+
+use lsp_server::Connection;
+use server::{Result, Server};
+
+mod server;
+mod text;
+mod workspace;
+
+fn main() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = serde_json::to_value(server::capabilities())?;
+    let params = connection.initialize(capabilities)?;
+    let mut server = Server::new(serde_json::from_value(params)?);
+    server.run(&connection)?;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
@@ -0,0 +1,580 @@
+//! Handles the requests and notifications sent by the editor.
+
+use crate::text::{
+    find_nodes, from_lsp_position, line_prefix, to_lsp_position, to_lsp_range, word_at,
+};
+use crate::workspace::{parse_uri, uri_to_path, UriFileResolver, Workspace};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types as lsp;
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+    Notification as NotificationTrait, PublishDiagnostics,
+};
+use lsp_types::request::{
+    Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, Request as RequestTrait,
+    SemanticTokensFullRequest,
+};
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use yarnspinner_compiler::prelude::*;
+use yarnspinner_core::prelude::*;
+use yarnspinner_core::types::FunctionType;
+
+pub(crate) type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+const KEYWORDS: &[&str] = &[
+    "if", "elseif", "else", "endif", "set", "declare", "jump", "call",
+];
+
+/// The commands that every dialogue runner understands.
+const BUILT_IN_COMMANDS: &[&str] = &["wait", "stop"];
+
+const TOKEN_TYPES: &[lsp::SemanticTokenType] = &[
+    lsp::SemanticTokenType::KEYWORD,
+    lsp::SemanticTokenType::VARIABLE,
+    lsp::SemanticTokenType::FUNCTION,
+    lsp::SemanticTokenType::NUMBER,
+    lsp::SemanticTokenType::STRING,
+    lsp::SemanticTokenType::OPERATOR,
+    lsp::SemanticTokenType::COMMENT,
+    lsp::SemanticTokenType::MACRO,
+    lsp::SemanticTokenType::PROPERTY,
+    lsp::SemanticTokenType::NAMESPACE,
+    lsp::SemanticTokenType::DECORATOR,
+];
+
+pub(crate) fn capabilities() -> lsp::ServerCapabilities {
+    lsp::ServerCapabilities {
+        text_document_sync: Some(lsp::TextDocumentSyncCapability::Kind(
+            lsp::TextDocumentSyncKind::FULL,
+        )),
+        definition_provider: Some(lsp::OneOf::Left(true)),
+        hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+        document_symbol_provider: Some(lsp::OneOf::Left(true)),
+        completion_provider: Some(lsp::CompletionOptions {
+            trigger_characters: Some(vec!["$".to_owned(), "<".to_owned()]),
+            ..Default::default()
+        }),
+        semantic_tokens_provider: Some(
+            lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                lsp::SemanticTokensOptions {
+                    legend: lsp::SemanticTokensLegend {
+                        token_types: TOKEN_TYPES.to_vec(),
+                        token_modifiers: vec![],
+                    },
+                    full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+                    ..Default::default()
+                },
+            ),
+        ),
+        ..Default::default()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Server {
+    workspace: Workspace,
+    /// The names of the commands the game registered, passed by the editor as `initializationOptions.commands`.
+    commands: Vec<String>,
+    /// The last compilation without errors. Used for the declarations, so that completion keeps working while the user is typing.
+    compilation: Option<Compilation>,
+    /// The documents that diagnostics were published for, so they can be cleared once the issues are fixed.
+    documents_with_diagnostics: HashSet<String>,
+}
+
+impl Server {
+    pub(crate) fn new(params: lsp::InitializeParams) -> Self {
+        // `root_uri` is still sent by editors that don't support workspace folders
+        #[allow(deprecated)]
+        let roots = params
+            .workspace_folders
+            .map(|folders| folders.into_iter().map(|folder| folder.uri).collect())
+            .or_else(|| params.root_uri.map(|uri| vec![uri]))
+            .unwrap_or_default();
+        let mut workspace = Workspace::default();
+        for root in roots.iter().filter_map(uri_to_path) {
+            workspace.scan(&root);
+        }
+        let commands = params
+            .initialization_options
+            .as_ref()
+            .and_then(|options| options.get("commands"))
+            .and_then(|commands| commands.as_array())
+            .map(|commands| {
+                commands
+                    .iter()
+                    .filter_map(|command| command.as_str())
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            workspace,
+            commands,
+            compilation: None,
+            documents_with_diagnostics: HashSet::new(),
+        }
+    }
+
+    pub(crate) fn run(&mut self, connection: &Connection) -> Result<()> {
+        self.compile(connection)?;
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.handle_request(request);
+                    connection.sender.send(Message::Response(response))?;
+                }
+                Message::Notification(notification) => {
+                    self.handle_notification(notification, connection)?
+                }
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        let Request { id, method, params } = request;
+        let result = match method.as_str() {
+            GotoDefinition::METHOD => serde_json::from_value(params)
+                .map(|params| self.definition(params))
+                .and_then(serde_json::to_value),
+            Completion::METHOD => serde_json::from_value(params)
+                .map(|params| self.completion(params))
+                .and_then(serde_json::to_value),
+            HoverRequest::METHOD => serde_json::from_value(params)
+                .map(|params| self.hover(params))
+                .and_then(serde_json::to_value),
+            DocumentSymbolRequest::METHOD => serde_json::from_value(params)
+                .map(|params| self.document_symbols(params))
+                .and_then(serde_json::to_value),
+            SemanticTokensFullRequest::METHOD => serde_json::from_value(params)
+                .map(|params| self.semantic_tokens(params))
+                .and_then(serde_json::to_value),
+            _ => {
+                return Response::new_err(
+                    id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("Unsupported request: {method}"),
+                )
+            }
+        };
+        match result {
+            Ok(result) => Response::new_ok(id, result),
+            Err(error) => Response::new_err(id, ErrorCode::InvalidParams as i32, error.to_string()),
+        }
+    }
+
+    fn handle_notification(
+        &mut self,
+        notification: Notification,
+        connection: &Connection,
+    ) -> Result<()> {
+        let Notification { method, params } = notification;
+        match method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp::DidOpenTextDocumentParams = serde_json::from_value(params)?;
+                self.workspace
+                    .open(&params.text_document.uri, params.text_document.text);
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: lsp::DidChangeTextDocumentParams = serde_json::from_value(params)?;
+                // We only support full syncing, so the last change contains the whole document
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.workspace
+                        .change(&params.text_document.uri, change.text);
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp::DidCloseTextDocumentParams = serde_json::from_value(params)?;
+                self.workspace.close(&params.text_document.uri);
+            }
+            _ => return Ok(()),
+        }
+        self.compile(connection)
+    }
+
+    /// Compiles all documents together, since nodes and variables may be shared between files, and publishes the resulting diagnostics.
+    fn compile(&mut self, connection: &Connection) -> Result<()> {
+        let files: Vec<_> = self
+            .workspace
+            .iter()
+            .map(|(uri, text)| File {
+                file_name: uri.to_owned(),
+                source: text.to_owned(),
+            })
+            .collect();
+        // The compiler should never panic, but if it does, the editor should keep its language server
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            Compiler::new()
+                .add_files(files)
+                .with_file_resolver(UriFileResolver)
+                .compile()
+        }));
+        let diagnostics = match result {
+            Ok(Ok(compilation)) => {
+                let warnings = compilation.warnings.clone();
+                self.compilation = Some(compilation);
+                warnings
+            }
+            Ok(Err(CompilerError(errors))) => errors,
+            Err(_) => {
+                eprintln!("The Yarn Spinner compiler panicked, skipping diagnostics");
+                return Ok(());
+            }
+        };
+
+        let mut diagnostics_by_document: BTreeMap<_, _> = self
+            .workspace
+            .iter()
+            .map(|(uri, text)| (uri, (text, Vec::new())))
+            .collect();
+        for diagnostic in diagnostics {
+            let Some(file_name) = diagnostic.file_name.as_deref() else {
+                continue;
+            };
+            let Some((text, diagnostics)) = diagnostics_by_document.get_mut(file_name) else {
+                continue;
+            };
+            let range = diagnostic
+                .range
+                .as_ref()
+                .map(|range| to_lsp_range(text, range))
+                .unwrap_or_default();
+            let severity = match diagnostic.severity {
+                DiagnosticSeverity::Error => lsp::DiagnosticSeverity::ERROR,
+                DiagnosticSeverity::Warning => lsp::DiagnosticSeverity::WARNING,
+            };
+            diagnostics.push(lsp::Diagnostic {
+                range,
+                severity: Some(severity),
+                code: Some(lsp::NumberOrString::String(diagnostic.code.to_string())),
+                source: Some("yarnspinner".to_owned()),
+                message: diagnostic.message,
+                ..Default::default()
+            });
+        }
+
+        let mut documents_with_diagnostics = HashSet::new();
+        let publish = |uri: &str, diagnostics: Vec<lsp::Diagnostic>| {
+            let params = lsp::PublishDiagnosticsParams {
+                uri: parse_uri(uri),
+                diagnostics,
+                version: None,
+            };
+            let notification = Notification::new(PublishDiagnostics::METHOD.to_owned(), params);
+            connection.sender.send(Message::Notification(notification))
+        };
+        for (uri, (_text, diagnostics)) in diagnostics_by_document {
+            if !diagnostics.is_empty() {
+                documents_with_diagnostics.insert(uri.to_owned());
+            } else if !self.documents_with_diagnostics.contains(uri) {
+                continue;
+            }
+            publish(uri, diagnostics)?;
+        }
+        // Clear diagnostics of documents that were removed from the workspace
+        for uri in self.documents_with_diagnostics.iter() {
+            if self.workspace.get(&parse_uri(uri)).is_none() {
+                publish(uri, Vec::new())?;
+            }
+        }
+        self.documents_with_diagnostics = documents_with_diagnostics;
+        Ok(())
+    }
+
+    fn definition(&self, params: lsp::GotoDefinitionParams) -> Option<lsp::GotoDefinitionResponse> {
+        let params = params.text_document_position_params;
+        let text = self.workspace.get(&params.text_document.uri)?;
+        let word = word_at(text, &from_lsp_position(text, params.position))?;
+        let location = if word.starts_with('$') {
+            let declaration = self.declaration(&word)?;
+            let DeclarationSource::File(file_name) = &declaration.source_file_name else {
+                return None;
+            };
+            let uri = parse_uri(file_name);
+            let range = to_lsp_range(self.workspace.get(&uri)?, declaration.range.as_ref()?);
+            lsp::Location { uri, range }
+        } else {
+            self.workspace.iter().find_map(|(uri, text)| {
+                let node = find_nodes(text)
+                    .into_iter()
+                    .find(|node| node.name == word)?;
+                Some(lsp::Location {
+                    uri: parse_uri(uri),
+                    range: to_lsp_range(text, &node.title_range),
+                })
+            })?
+        };
+        Some(lsp::GotoDefinitionResponse::Scalar(location))
+    }
+
+    fn completion(&self, params: lsp::CompletionParams) -> Option<lsp::CompletionResponse> {
+        let params = params.text_document_position;
+        let text = self.workspace.get(&params.text_document.uri)?;
+        let position = from_lsp_position(text, params.position);
+        let prefix = line_prefix(text, &position);
+
+        let typed_word: String = {
+            let mut word: Vec<_> = prefix
+                .chars()
+                .rev()
+                .take_while(|&c| c.is_alphanumeric() || matches!(c, '_' | '.' | '$'))
+                .collect();
+            word.reverse();
+            word.into_iter().collect()
+        };
+        let open_command = prefix
+            .rsplit_once("<<")
+            .map(|(_, command)| command.trim_start())
+            .filter(|command| !command.contains(">>"));
+        let is_in_expression = prefix
+            .rsplit_once('{')
+            .is_some_and(|(_, expression)| !expression.contains('}'));
+
+        let items = if typed_word.starts_with('$') {
+            self.variable_completions()
+        } else if let Some(command) = open_command {
+            if command.starts_with("jump ") {
+                self.node_completions()
+            } else if !command.contains(char::is_whitespace) {
+                self.command_completions()
+            } else {
+                self.function_completions()
+            }
+        } else if is_in_expression {
+            self.function_completions()
+        } else {
+            return None;
+        };
+
+        // Replace what the user typed so far, as editors don't consider `$` part of a word
+        let start = Position {
+            line: position.line,
+            character: position.character - typed_word.chars().count(),
+        };
+        let range = lsp::Range::new(
+            to_lsp_position(text, &start),
+            to_lsp_position(text, &position),
+        );
+        let items = items
+            .into_iter()
+            .map(|item| lsp::CompletionItem {
+                text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+                    range,
+                    new_text: item.label.clone(),
+                })),
+                ..item
+            })
+            .collect();
+        Some(lsp::CompletionResponse::Array(items))
+    }
+
+    fn variable_completions(&self) -> Vec<lsp::CompletionItem> {
+        self.declarations()
+            .filter(|declaration| declaration.name.starts_with('$'))
+            .filter(|declaration| !declaration.name.starts_with("$Yarn.Internal"))
+            .map(|declaration| lsp::CompletionItem {
+                label: declaration.name.clone(),
+                kind: Some(lsp::CompletionItemKind::VARIABLE),
+                detail: Some(declaration.r#type.to_string()),
+                documentation: declaration
+                    .description
+                    .clone()
+                    .map(lsp::Documentation::String),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn node_completions(&self) -> Vec<lsp::CompletionItem> {
+        self.workspace
+            .iter()
+            .flat_map(|(_uri, text)| find_nodes(text))
+            .map(|node| lsp::CompletionItem {
+                label: node.name,
+                kind: Some(lsp::CompletionItemKind::MODULE),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn command_completions(&self) -> Vec<lsp::CompletionItem> {
+        let keywords = KEYWORDS.iter().map(|keyword| lsp::CompletionItem {
+            label: keyword.to_string(),
+            kind: Some(lsp::CompletionItemKind::KEYWORD),
+            ..Default::default()
+        });
+        let commands = BUILT_IN_COMMANDS
+            .iter()
+            .map(|command| command.to_string())
+            .chain(self.commands.iter().cloned())
+            .map(|command| lsp::CompletionItem {
+                label: command,
+                kind: Some(lsp::CompletionItemKind::FUNCTION),
+                detail: Some("command".to_owned()),
+                ..Default::default()
+            });
+        keywords.chain(commands).collect()
+    }
+
+    fn function_completions(&self) -> Vec<lsp::CompletionItem> {
+        self.functions()
+            .into_iter()
+            .map(|(name, function_type)| lsp::CompletionItem {
+                label: name,
+                kind: Some(lsp::CompletionItemKind::FUNCTION),
+                detail: Some(function_type.to_string()),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn hover(&self, params: lsp::HoverParams) -> Option<lsp::Hover> {
+        let params = params.text_document_position_params;
+        let text = self.workspace.get(&params.text_document.uri)?;
+        let word = word_at(text, &from_lsp_position(text, params.position))?;
+        let (r#type, description) = match self.declaration(&word) {
+            Some(declaration) => (
+                declaration.r#type.to_string(),
+                declaration.description.clone(),
+            ),
+            None => {
+                let (_name, function_type) = self
+                    .functions()
+                    .into_iter()
+                    .find(|(name, _)| *name == word)?;
+                (function_type.to_string(), None)
+            }
+        };
+        let mut value = format!("```\n{word}: {type}\n```");
+        if let Some(description) = description {
+            value.push_str("\n\n");
+            value.push_str(&description);
+        }
+        Some(lsp::Hover {
+            contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                kind: lsp::MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        })
+    }
+
+    fn document_symbols(
+        &self,
+        params: lsp::DocumentSymbolParams,
+    ) -> Option<lsp::DocumentSymbolResponse> {
+        let text = self.workspace.get(&params.text_document.uri)?;
+        #[allow(deprecated)] // `DocumentSymbol::deprecated` has no default
+        let symbols = find_nodes(text)
+            .into_iter()
+            .map(|node| lsp::DocumentSymbol {
+                name: node.name,
+                detail: None,
+                kind: lsp::SymbolKind::NAMESPACE,
+                tags: None,
+                deprecated: None,
+                range: to_lsp_range(text, &node.range),
+                selection_range: to_lsp_range(text, &node.title_range),
+                children: None,
+            })
+            .collect();
+        Some(lsp::DocumentSymbolResponse::Nested(symbols))
+    }
+
+    fn semantic_tokens(
+        &self,
+        params: lsp::SemanticTokensParams,
+    ) -> Option<lsp::SemanticTokensResult> {
+        let text = self.workspace.get(&params.text_document.uri)?;
+        let mut data = Vec::new();
+        let mut previous = lsp::Position::new(0, 0);
+        for token in Compiler::tokenize(text) {
+            let token_type = match token.kind {
+                SyntaxTokenKind::Keyword | SyntaxTokenKind::BodyDelimiter => {
+                    lsp::SemanticTokenType::KEYWORD
+                }
+                SyntaxTokenKind::Variable => lsp::SemanticTokenType::VARIABLE,
+                SyntaxTokenKind::Function => lsp::SemanticTokenType::FUNCTION,
+                SyntaxTokenKind::Number => lsp::SemanticTokenType::NUMBER,
+                SyntaxTokenKind::String | SyntaxTokenKind::HeaderValue => {
+                    lsp::SemanticTokenType::STRING
+                }
+                SyntaxTokenKind::Operator => lsp::SemanticTokenType::OPERATOR,
+                SyntaxTokenKind::Comment => lsp::SemanticTokenType::COMMENT,
+                SyntaxTokenKind::Command => lsp::SemanticTokenType::MACRO,
+                SyntaxTokenKind::HeaderKey => lsp::SemanticTokenType::PROPERTY,
+                SyntaxTokenKind::Identifier => lsp::SemanticTokenType::NAMESPACE,
+                SyntaxTokenKind::Hashtag => lsp::SemanticTokenType::DECORATOR,
+                // Left to the editor's default highlighting
+                SyntaxTokenKind::Text | SyntaxTokenKind::Punctuation | SyntaxTokenKind::Error => {
+                    continue
+                }
+            };
+            let start = to_lsp_position(text, &token.range.start);
+            let end = to_lsp_position(text, &token.range.end);
+            let delta_line = start.line - previous.line;
+            let delta_start = if delta_line == 0 {
+                start.character - previous.character
+            } else {
+                start.character
+            };
+            data.push(lsp::SemanticToken {
+                delta_line,
+                delta_start,
+                length: end.character - start.character,
+                token_type: TOKEN_TYPES
+                    .iter()
+                    .position(|legend_type| *legend_type == token_type)
+                    .unwrap() as u32,
+                token_modifiers_bitset: 0,
+            });
+            previous = start;
+        }
+        Some(lsp::SemanticTokensResult::Tokens(lsp::SemanticTokens {
+            result_id: None,
+            data,
+        }))
+    }
+
+    fn declarations(&self) -> impl Iterator<Item = &Declaration> {
+        self.compilation
+            .iter()
+            .flat_map(|compilation| compilation.declarations.iter())
+    }
+
+    fn declaration(&self, name: &str) -> Option<&Declaration> {
+        self.declarations()
+            .find(|declaration| declaration.name == name)
+    }
+
+    /// The functions that can be called from Yarn, which includes the functions the game registered if the workspace compiled at least once.
+    /// Otherwise, these are the functions of the standard library.
+    fn functions(&self) -> Vec<(String, Type)> {
+        if self.compilation.is_some() {
+            return self
+                .declarations()
+                .filter(|declaration| matches!(declaration.r#type, Type::Function(_)))
+                .map(|declaration| (declaration.name.clone(), declaration.r#type.clone()))
+                .collect();
+        }
+        Library::standard_library()
+            .iter()
+            // Operators such as `Number.Add` are not called by name
+            .filter(|(name, _function)| !name.contains('.'))
+            .map(|(name, function)| {
+                let mut function_type = FunctionType::default();
+                for parameter in function.parameter_types() {
+                    function_type.add_parameter(Type::try_from(parameter).ok());
+                }
+                function_type.set_return_type(Type::try_from(function.return_type()).ok());
+                (name.to_owned(), Type::Function(function_type))
+            })
+            .collect()
+    }
+}
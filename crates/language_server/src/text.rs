@@ -0,0 +1,181 @@
+//! Helpers for working with the text of Yarn documents without compiling them.
+
+use lsp_types as lsp;
+use yarnspinner_core::prelude::Position;
+
+/// Converts a compiler [`Position`], which counts unicode code points, into an LSP position, which counts UTF-16 code units.
+pub(crate) fn to_lsp_position(text: &str, position: &Position) -> lsp::Position {
+    let character = text
+        .lines()
+        .nth(position.line)
+        .map(|line| {
+            line.chars()
+                .take(position.character)
+                .map(char::len_utf16)
+                .sum::<usize>()
+        })
+        .unwrap_or(position.character);
+    lsp::Position::new(position.line as u32, character as u32)
+}
+
+/// Converts a range of compiler [`Position`]s into an LSP range. See [`to_lsp_position`].
+pub(crate) fn to_lsp_range(text: &str, range: &std::ops::Range<Position>) -> lsp::Range {
+    lsp::Range::new(
+        to_lsp_position(text, &range.start),
+        to_lsp_position(text, &range.end),
+    )
+}
+
+/// Converts an LSP position into a compiler [`Position`]. See [`to_lsp_position`].
+pub(crate) fn from_lsp_position(text: &str, position: lsp::Position) -> Position {
+    let line = position.line as usize;
+    let character = text
+        .lines()
+        .nth(line)
+        .map(|line| {
+            let mut utf16_offset = 0;
+            line.chars()
+                .take_while(|c| {
+                    utf16_offset += c.len_utf16();
+                    utf16_offset <= position.character as usize
+                })
+                .count()
+        })
+        .unwrap_or_default();
+    Position { line, character }
+}
+
+/// Returns the text of the given line up to the given position, i.e. what the user has typed so far.
+pub(crate) fn line_prefix(text: &str, position: &Position) -> String {
+    text.lines()
+        .nth(position.line)
+        .map(|line| line.chars().take(position.character).collect())
+        .unwrap_or_default()
+}
+
+/// Returns the identifier under the given position, including a leading `$` for variables.
+pub(crate) fn word_at(text: &str, position: &Position) -> Option<String> {
+    let is_word_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '.' | '$');
+    let line: Vec<char> = text.lines().nth(position.line)?.chars().collect();
+    let mut start = position.character.min(line.len());
+    while start > 0 && is_word_char(line[start - 1]) {
+        start -= 1;
+    }
+    let mut end = position.character.min(line.len());
+    while end < line.len() && is_word_char(line[end]) {
+        end += 1;
+    }
+    (start < end).then(|| line[start..end].iter().collect())
+}
+
+/// A node as declared in the source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NodeDefinition {
+    pub(crate) name: String,
+    /// The range of the node's name in its `title` header.
+    pub(crate) title_range: std::ops::Range<Position>,
+    /// The range of the whole node, from its first header to its closing `===`.
+    pub(crate) range: std::ops::Range<Position>,
+}
+
+/// Finds all nodes in a Yarn document by looking at their `title` headers.
+/// This works even when the document does not compile, e.g. while the user is typing.
+pub(crate) fn find_nodes(text: &str) -> Vec<NodeDefinition> {
+    let mut nodes = Vec::new();
+    let mut is_in_body = false;
+    let mut node_start = None;
+    let mut title = None;
+    for (line_number, line) in text.lines().enumerate() {
+        let trimmed = line.trim();
+        if is_in_body {
+            if trimmed == "===" {
+                is_in_body = false;
+                if let (Some(start), Some((name, title_range))) = (node_start.take(), title.take())
+                {
+                    nodes.push(NodeDefinition {
+                        name,
+                        title_range,
+                        range: start..Position {
+                            line: line_number,
+                            character: line.chars().count(),
+                        },
+                    });
+                }
+            }
+            continue;
+        }
+        if trimmed == "---" {
+            is_in_body = true;
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+        node_start.get_or_insert(Position {
+            line: line_number,
+            character: 0,
+        });
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if key.trim() != "title" {
+            continue;
+        }
+        let name = value.trim();
+        let start =
+            key.chars().count() + 1 + value.chars().take_while(|c| c.is_whitespace()).count();
+        title = Some((
+            name.to_owned(),
+            Position {
+                line: line_number,
+                character: start,
+            }..Position {
+                line: line_number,
+                character: start + name.chars().count(),
+            },
+        ));
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_nodes_by_title() {
+        let text =
+            "title: Start\ntags: a\n---\nHello\n===\n\n// comment\ntitle:  Second\n---\n===\n";
+        let nodes = find_nodes(text);
+
+        assert_eq!(2, nodes.len());
+        assert_eq!("Start", nodes[0].name);
+        assert_eq!(
+            Position {
+                line: 0,
+                character: 7
+            },
+            nodes[0].title_range.start
+        );
+        assert_eq!(4, nodes[0].range.end.line);
+        assert_eq!("Second", nodes[1].name);
+        assert_eq!(7, nodes[1].range.start.line);
+        assert_eq!(8, nodes[1].title_range.start.character);
+        assert_eq!(14, nodes[1].title_range.end.character);
+    }
+
+    #[test]
+    fn converts_positions_to_utf16() {
+        let text = "title: Start\n---\n🦀 says $hi\n===\n";
+        let position = Position {
+            line: 2,
+            character: 7,
+        };
+
+        let lsp_position = to_lsp_position(text, &position);
+
+        assert_eq!(lsp::Position::new(2, 8), lsp_position);
+        assert_eq!(position, from_lsp_position(text, lsp_position));
+        assert_eq!(Some("$hi".to_owned()), word_at(text, &position));
+    }
+}
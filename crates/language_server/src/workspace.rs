@@ -0,0 +1,176 @@
+//! The Yarn files the server knows about, either read from disk or opened in the editor.
+
+use lsp_types::Uri;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use yarnspinner_compiler::prelude::{File, FileResolver};
+
+/// All Yarn files of the workspace, keyed by their URI.
+/// The URI is also used as the file name when compiling, so diagnostics and declarations can be mapped back to documents.
+#[derive(Debug, Default)]
+pub(crate) struct Workspace {
+    documents: BTreeMap<String, String>,
+}
+
+impl Workspace {
+    /// Reads all `.yarn` files below the given directory. Documents that are already open in the editor are kept as they are.
+    pub(crate) fn scan(&mut self, root: &Path) {
+        for path in find_yarn_files(root) {
+            let Ok(text) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            self.documents.entry(path_to_uri(&path)).or_insert(text);
+        }
+    }
+
+    pub(crate) fn open(&mut self, uri: &Uri, text: String) {
+        self.documents.insert(normalize(uri), text);
+    }
+
+    pub(crate) fn change(&mut self, uri: &Uri, text: String) {
+        self.open(uri, text);
+    }
+
+    /// Hands the document back to the file system, which is now the source of truth for its contents.
+    pub(crate) fn close(&mut self, uri: &Uri) {
+        let text = uri_to_path(uri).and_then(|path| std::fs::read_to_string(path).ok());
+        match text {
+            Some(text) => {
+                self.documents.insert(normalize(uri), text);
+            }
+            None => {
+                self.documents.remove(&normalize(uri));
+            }
+        }
+    }
+
+    pub(crate) fn get(&self, uri: &Uri) -> Option<&str> {
+        self.documents.get(&normalize(uri)).map(String::as_str)
+    }
+
+    /// Returns the URI and text of every document.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.documents
+            .iter()
+            .map(|(uri, text)| (uri.as_str(), text.as_str()))
+    }
+}
+
+/// Reads included files that are not part of the workspace from disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct UriFileResolver;
+
+impl FileResolver for UriFileResolver {
+    fn resolve(&self, path: &str, _including_file: &File) -> std::io::Result<File> {
+        // `File::include_path` collapses the slashes after the scheme of the URI
+        let uri = path.replacen("file:/", "file:///", 1);
+        let file_path = Uri::from_str(&uri)
+            .ok()
+            .as_ref()
+            .and_then(uri_to_path)
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "only files on disk can be included",
+                )
+            })?;
+        let source = std::fs::read_to_string(file_path)?;
+        Ok(File {
+            file_name: uri,
+            source,
+        })
+    }
+}
+
+fn find_yarn_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut directories = vec![root.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        let Ok(entries) = std::fs::read_dir(&directory) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if path.is_dir() && !is_hidden && entry.file_name() != "target" {
+                directories.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == "yarn")
+            {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+/// Editors differ in which characters of a file URI they percent-encode, e.g. VS Code sends `file:///c%3A/dialogue`.
+/// File URIs are thus compared by the path they point to.
+pub(crate) fn normalize(uri: &Uri) -> String {
+    match uri_to_path(uri) {
+        Some(path) => path_to_uri(&path),
+        None => uri.to_string(),
+    }
+}
+
+/// Parses a URI that is known to be valid, such as one received from the client.
+pub(crate) fn parse_uri(uri: &str) -> Uri {
+    Uri::from_str(uri).expect("Documents are only stored under valid URIs")
+}
+
+pub(crate) fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        // Windows paths such as `C:/dialogue` become `file:///C:/dialogue`
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
+
+pub(crate) fn uri_to_path(uri: &Uri) -> Option<PathBuf> {
+    let path = uri.as_str().strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut input = path.bytes();
+    while let Some(byte) = input.next() {
+        if byte == b'%' {
+            let hex = [input.next()?, input.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // `/C:/dialogue` is a Windows path
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => &path[1..],
+        _ => path.as_str(),
+    };
+    Some(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_paths_and_uris() {
+        let path = Path::new("/home/yarn/my dialogue/Start.yarn");
+
+        let uri = path_to_uri(path);
+
+        assert_eq!("file:///home/yarn/my%20dialogue/Start.yarn", uri);
+        assert_eq!(Some(path.to_path_buf()), uri_to_path(&parse_uri(&uri)));
+    }
+}
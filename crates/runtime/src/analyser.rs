@@ -1,10 +1,11 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/Analyser.cs>
 
 pub(crate) use self::default_analysers::*;
-pub use self::{context::*, diagnosis::*};
+pub use self::{analysis_options::*, context::*, diagnosis::*};
 use std::fmt::Debug;
 use yarnspinner_core::prelude::*;
 
+mod analysis_options;
 mod context;
 pub(crate) mod default_analysers;
 mod diagnosis;
@@ -15,6 +16,13 @@ pub trait CompiledProgramAnalyser: Debug {
     /// Reads data from the provided program that is later used in [`CompiledProgramAnalyser::collect_diagnoses`].
     fn diagnose(&mut self, program: &Program);
 
+    /// Like [`CompiledProgramAnalyser::diagnose`], but additionally receives the [`AnalysisOptions`] of the [`Context`],
+    /// such as the entry nodes of the dialogue and the [`DebugInfo`] used to attach source locations to diagnoses.
+    /// This is the method called by [`Dialogue::analyse`](crate::prelude::Dialogue). The default implementation ignores the options.
+    fn diagnose_with_options(&mut self, program: &Program, _options: &AnalysisOptions) {
+        self.diagnose(program);
+    }
+
     /// Takes the data collected by [`CompiledProgramAnalyser::diagnose`], analyzes it and returns the resulting [`Diagnosis`] instances.
     ///
     /// ## Implementation note
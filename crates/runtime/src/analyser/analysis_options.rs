@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use yarnspinner_core::prelude::*;
+
+/// Information about the analysed programs that is not part of a [`Program`] itself.
+/// Set on a [`Context`](crate::prelude::Context) with [`Context::with_entry_nodes`](crate::prelude::Context::with_entry_nodes)
+/// and [`Context::with_debug_info`](crate::prelude::Context::with_debug_info), and passed to [`CompiledProgramAnalyser::diagnose_with_options`](crate::prelude::CompiledProgramAnalyser::diagnose_with_options).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AnalysisOptions {
+    /// The nodes the dialogue may start at. Empty by default.
+    pub entry_nodes: Vec<String>,
+    /// The [`DebugInfo`] of the nodes of the analysed programs, keyed by node name. Empty by default.
+    pub debug_info: HashMap<String, DebugInfo>,
+}
+
+impl AnalysisOptions {
+    /// Returns the source location of the instruction at `instruction_number` in the given node, if known.
+    #[must_use]
+    pub fn line_info(&self, node_name: &str, instruction_number: usize) -> Option<LineInfo> {
+        let line_info = self
+            .debug_info
+            .get(node_name)?
+            .try_get_line_info(instruction_number)?;
+        line_info.position.is_some().then_some(line_info)
+    }
+
+    /// Returns the source location of the earliest instruction of the given node, if known.
+    #[must_use]
+    pub fn node_line_info(&self, node_name: &str) -> Option<LineInfo> {
+        let debug_info = self.debug_info.get(node_name)?;
+        let source = debug_info
+            .instruction_sources
+            .values()
+            .flatten()
+            .min_by_key(|source| source.range.start)?;
+        Some(LineInfo {
+            file_name: debug_info.file_name.clone(),
+            node_name: node_name.to_owned(),
+            position: Some(source.range.start),
+            source: Some(source.clone()),
+        })
+    }
+}
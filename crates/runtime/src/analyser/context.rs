@@ -7,20 +7,23 @@ use yarnspinner_core::prelude::*;
 /// A structure that holds several [`CompiledProgramAnalyser`]s which are used to analyse one or more compiled Yarn programs with [`Dialogue::analyse`].
 /// To get the analysis results, call [`Context::finish_analysis`] afterwards.
 #[derive(Debug)]
-pub struct Context(Vec<Box<dyn CompiledProgramAnalyser>>);
+pub struct Context {
+    analysers: Vec<Box<dyn CompiledProgramAnalyser>>,
+    options: AnalysisOptions,
+}
 
 impl IntoIterator for Context {
     type Item = Box<dyn CompiledProgramAnalyser>;
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.analysers.into_iter()
     }
 }
 
 impl Extend<Box<dyn CompiledProgramAnalyser>> for Context {
     fn extend<T: IntoIterator<Item = Box<dyn CompiledProgramAnalyser>>>(&mut self, iter: T) {
-        self.0.extend(iter);
+        self.analysers.extend(iter);
     }
 }
 
@@ -28,32 +31,77 @@ impl Context {
     /// Creates a new empty [`Context`] with no analysers.
     #[must_use]
     pub fn empty() -> Self {
-        Self(Vec::new())
+        Self {
+            analysers: Vec::new(),
+            options: AnalysisOptions::default(),
+        }
     }
 
     /// Sets up a [`Context`] with the default analysers. These are:
     /// - Variable Lister: Adds a [`DiagnosisSeverity::Note`] diagnosis for each variable in the program.
     /// - Unused Variable Checker: Adds a [`DiagnosisSeverity::Warning`] diagnosis for each unused variable in the program.
+    ///
+    /// More checks can be enabled with [`Context::with_content_analysers`].
     #[must_use]
     pub fn default_analysers() -> Self {
-        let mut context = Self::empty();
-        for analyser in default_analysers() {
-            context = context.add_analyser(analyser);
-        }
-        context
+        Self::empty().extend_with(default_analysers())
+    }
+
+    /// Adds the analysers that look for content that can never be seen by the player. These are:
+    /// - Unreachable Node Checker: Adds a [`DiagnosisSeverity::Warning`] diagnosis for each node that cannot be reached from the
+    ///   entry nodes set with [`Context::with_entry_nodes`]. Reports nothing if no entry nodes are set.
+    /// - Dead Code Checker: Adds a [`DiagnosisSeverity::Warning`] diagnosis for content following an unconditional `<<jump>>` or `<<stop>>`
+    ///   and for options whose condition is the constant `false`.
+    /// - Unassigned Variable Checker: Adds a [`DiagnosisSeverity::Warning`] diagnosis for each variable that is read from, but never assigned anywhere.
+    #[must_use]
+    pub fn with_content_analysers(self) -> Self {
+        self.extend_with(content_analysers())
     }
 
     /// Adds an analyser to the [`Context`].
     #[must_use]
     pub fn add_analyser(mut self, analyser: Box<dyn CompiledProgramAnalyser>) -> Self {
-        self.0.push(analyser);
+        self.analysers.push(analyser);
+        self
+    }
+
+    /// Sets the nodes the dialogue may start at. Nodes that cannot be reached from any of them are reported by the analysers added with
+    /// [`Context::with_content_analysers`]. By default, no entry nodes are set.
+    #[must_use]
+    pub fn with_entry_nodes(
+        mut self,
+        entry_nodes: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.options.entry_nodes = entry_nodes.into_iter().map(Into::into).collect();
         self
     }
 
+    /// Adds the [`DebugInfo`] of the analysed programs, keyed by node name, as found in the `debug_info` of a compilation.
+    /// This lets the analysers report the file and line that caused a [`Diagnosis`] instead of just the node name.
+    #[must_use]
+    pub fn with_debug_info(
+        mut self,
+        debug_info: impl IntoIterator<Item = (String, DebugInfo)>,
+    ) -> Self {
+        self.options.debug_info.extend(debug_info);
+        self
+    }
+
+    fn extend_with(mut self, analysers: Vec<Box<dyn CompiledProgramAnalyser>>) -> Self {
+        self.extend(analysers);
+        self
+    }
+
+    /// Returns the [`AnalysisOptions`] passed to the analysers.
+    #[must_use]
+    pub fn options(&self) -> &AnalysisOptions {
+        &self.options
+    }
+
     /// Collects the diagnoses from all analysers in the [`Context`] that were previously used with [`Dialogue::analyse`].
     #[must_use]
     pub fn finish_analysis(&self) -> Vec<Diagnosis> {
-        self.0
+        self.analysers
             .iter()
             .flat_map(|analyser| analyser.collect_diagnoses())
             .collect()
@@ -62,8 +110,8 @@ impl Context {
     /// ## Implementation notes
     /// Corresponds to the original `AddProgramToAnalysis`
     pub(crate) fn diagnose_program(&mut self, program: &Program) {
-        for analyser in &mut self.0 {
-            analyser.diagnose(program);
+        for analyser in &mut self.analysers {
+            analyser.diagnose_with_options(program, &self.options);
         }
     }
 }
@@ -1,6 +1,12 @@
-use self::{unused_variable_checker::*, variable_lister::*};
+use self::{
+    dead_code_checker::*, unassigned_variable_checker::*, unreachable_node_checker::*,
+    unused_variable_checker::*, variable_lister::*,
+};
 use crate::prelude::*;
 
+mod dead_code_checker;
+mod unassigned_variable_checker;
+mod unreachable_node_checker;
 mod unused_variable_checker;
 mod variable_lister;
 
@@ -12,3 +18,19 @@ macro_rules! boxes {
 pub(crate) fn default_analysers() -> Vec<Box<dyn CompiledProgramAnalyser>> {
     boxes![VariableLister, UnusedVariableChecker]
 }
+
+pub(crate) fn content_analysers() -> Vec<Box<dyn CompiledProgramAnalyser>> {
+    boxes![
+        UnreachableNodeChecker,
+        DeadCodeChecker,
+        UnassignedVariableChecker
+    ]
+}
+
+/// Attaches the source location to the diagnosis if it is known, and the node name otherwise.
+fn locate(diagnosis: Diagnosis, node_name: &str, line_info: Option<LineInfo>) -> Diagnosis {
+    match line_info {
+        Some(line_info) => diagnosis.with_line_info(line_info),
+        None => diagnosis.with_node_name(node_name),
+    }
+}
@@ -0,0 +1,186 @@
+use super::locate;
+use crate::prelude::*;
+use std::collections::HashSet;
+use yarnspinner_core::prelude::*;
+
+/// Reports content that can never run: statements following an unconditional `<<jump>>` or `<<stop>>`,
+/// and options whose condition is the constant `false`, e.g. `-> Never shown <<if false>>`.
+///
+/// ## Implementation note
+/// Analysers work on the compiled [`Program`], so constant conditions are detected in the bytecode rather than with the compiler's `ConstantValueVisitor`.
+/// Like that visitor, only literal values are recognized, not expressions that happen to be constant.
+#[derive(Debug, Default)]
+pub(crate) struct DeadCodeChecker {
+    diagnoses: Vec<Diagnosis>,
+}
+
+impl DeadCodeChecker {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CompiledProgramAnalyser for DeadCodeChecker {
+    fn diagnose(&mut self, program: &Program) {
+        self.diagnose_with_options(program, &AnalysisOptions::default());
+    }
+
+    fn diagnose_with_options(&mut self, program: &Program, options: &AnalysisOptions) {
+        let mut node_names: Vec<_> = program.nodes.keys().collect();
+        node_names.sort();
+        for node_name in node_names {
+            let node = &program.nodes[node_name];
+            self.diagnose_unreachable_code(node, options);
+            self.diagnose_false_option_conditions(node, options);
+        }
+    }
+
+    fn collect_diagnoses(&self) -> Vec<Diagnosis> {
+        self.diagnoses.clone()
+    }
+}
+
+impl DeadCodeChecker {
+    fn diagnose_unreachable_code(&mut self, node: &Node, options: &AnalysisOptions) {
+        let reachable = reachable_instructions(node);
+        let mut index = 0;
+        while index < node.instructions.len() {
+            if reachable.contains(&index) {
+                index += 1;
+                continue;
+            }
+            let start = index;
+            while index < node.instructions.len() && !reachable.contains(&index) {
+                index += 1;
+            }
+            // The compiler generates bookkeeping instructions after jumps, e.g. to leave an `<<if>>` statement,
+            // so only runs of unreachable instructions that contain actual content are reported.
+            let is_content = |instruction: &Instruction| {
+                matches!(
+                    instruction.opcode(),
+                    OpCode::RunLine
+                        | OpCode::RunCommand
+                        | OpCode::AddOption
+                        | OpCode::StoreVariable
+                        | OpCode::RunNode
+                )
+            };
+            if !node.instructions[start..index].iter().any(is_content) {
+                continue;
+            }
+            let cause = node.instructions[..start]
+                .iter()
+                .enumerate()
+                .rev()
+                .find(|(index, _)| reachable.contains(index))
+                .map(|(_, instruction)| match instruction.opcode() {
+                    OpCode::Stop => "a <<stop>>",
+                    _ => "a <<jump>>",
+                })
+                .unwrap_or("a <<jump>> or <<stop>>");
+            let line_info = (start..index).find_map(|index| options.line_info(&node.name, index));
+            let diagnosis = Diagnosis::new(
+                DiagnosisSeverity::Warning,
+                format!("This content can never run because it follows {cause}"),
+            );
+            self.diagnoses
+                .push(locate(diagnosis, &node.name, line_info));
+        }
+    }
+
+    fn diagnose_false_option_conditions(&mut self, node: &Node, options: &AnalysisOptions) {
+        fn pop(stack: &mut Vec<Constant>, count: usize) {
+            stack.truncate(stack.len().saturating_sub(count));
+        }
+        let mut stack: Vec<Constant> = Vec::new();
+        for (index, instruction) in node.instructions.iter().enumerate() {
+            match instruction.opcode() {
+                OpCode::PushBool => stack.push(Constant::Bool(instruction.read_operand(0))),
+                OpCode::PushFloat => stack.push(Constant::Number(instruction.read_operand(0))),
+                OpCode::PushString
+                | OpCode::PushNull
+                | OpCode::PushVariable
+                | OpCode::ShowOptions => stack.push(Constant::Unknown),
+                OpCode::CallFunc => {
+                    let parameter_count = match stack.pop() {
+                        Some(Constant::Number(count)) => count as usize,
+                        _ => 0,
+                    };
+                    pop(&mut stack, parameter_count);
+                    stack.push(Constant::Unknown);
+                }
+                OpCode::Pop | OpCode::RunNode => pop(&mut stack, 1),
+                OpCode::RunLine | OpCode::RunCommand => {
+                    pop(&mut stack, instruction.read_operand(1));
+                }
+                OpCode::AddOption => {
+                    pop(&mut stack, instruction.read_operand(2));
+                    let has_condition: bool = instruction.read_operand(3);
+                    if !has_condition {
+                        continue;
+                    }
+                    if let Some(Constant::Bool(false)) = stack.pop() {
+                        let diagnosis = Diagnosis::new(
+                            DiagnosisSeverity::Warning,
+                            "This option can never be shown because its condition is always false"
+                                .to_owned(),
+                        );
+                        let line_info = options.line_info(&node.name, index);
+                        self.diagnoses
+                            .push(locate(diagnosis, &node.name, line_info));
+                    }
+                }
+                OpCode::JumpTo
+                | OpCode::Jump
+                | OpCode::JumpIfFalse
+                | OpCode::StoreVariable
+                | OpCode::Stop => {}
+            }
+        }
+    }
+}
+
+/// A value on the stack while evaluating a node's instructions in order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Constant {
+    Bool(bool),
+    Number(f32),
+    Unknown,
+}
+
+/// Follows the control flow of a node from its first instruction.
+fn reachable_instructions(node: &Node) -> HashSet<usize> {
+    let label = |instruction: &Instruction, index: usize| {
+        let label: String = instruction.read_operand(index);
+        node.labels.get(&label).map(|&index| index as usize)
+    };
+    // Selecting an option jumps to its destination
+    let option_destinations: Vec<_> = node
+        .instructions
+        .iter()
+        .filter(|instruction| instruction.opcode() == OpCode::AddOption)
+        .filter_map(|instruction| label(instruction, 1))
+        .collect();
+
+    let mut reachable = HashSet::new();
+    let mut queue = vec![0];
+    while let Some(index) = queue.pop() {
+        let Some(instruction) = node.instructions.get(index) else {
+            continue;
+        };
+        if !reachable.insert(index) {
+            continue;
+        }
+        match instruction.opcode() {
+            OpCode::JumpTo => queue.extend(label(instruction, 0)),
+            OpCode::Jump => queue.extend(&option_destinations),
+            OpCode::JumpIfFalse => {
+                queue.push(index + 1);
+                queue.extend(label(instruction, 0));
+            }
+            OpCode::RunNode | OpCode::Stop => {}
+            _ => queue.push(index + 1),
+        }
+    }
+    reachable
+}
@@ -0,0 +1,65 @@
+use super::locate;
+use crate::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use yarnspinner_core::prelude::*;
+
+/// Reports variables that are read from, but never assigned with `<<set>>` in any node.
+/// Such variables always have their default value unless the game sets them in the [`VariableStorage`].
+///
+/// ## Implementation note
+/// The default values of variables are not considered writes, because the compiler also registers them
+/// for variables that were never declared, so a [`Program`] cannot tell the two apart.
+#[derive(Debug, Default)]
+pub(crate) struct UnassignedVariableChecker {
+    /// The first read of every variable, keyed by variable name.
+    read_variables: BTreeMap<String, (String, Option<LineInfo>)>,
+    written_variables: HashSet<String>,
+}
+
+impl UnassignedVariableChecker {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CompiledProgramAnalyser for UnassignedVariableChecker {
+    fn diagnose(&mut self, program: &Program) {
+        self.diagnose_with_options(program, &AnalysisOptions::default());
+    }
+
+    fn diagnose_with_options(&mut self, program: &Program, options: &AnalysisOptions) {
+        let mut node_names: Vec<_> = program.nodes.keys().collect();
+        node_names.sort();
+        for node_name in node_names {
+            let node = &program.nodes[node_name];
+            for (index, instruction) in node.instructions.iter().enumerate() {
+                match instruction.opcode() {
+                    OpCode::PushVariable => {
+                        let variable: String = instruction.read_operand(0);
+                        self.read_variables.entry(variable).or_insert_with(|| {
+                            (node_name.clone(), options.line_info(node_name, index))
+                        });
+                    }
+                    OpCode::StoreVariable => {
+                        self.written_variables.insert(instruction.read_operand(0));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn collect_diagnoses(&self) -> Vec<Diagnosis> {
+        self.read_variables
+            .iter()
+            .filter(|(variable, _)| !self.written_variables.contains(*variable))
+            .map(|(variable, (node_name, line_info))| {
+                let diagnosis = Diagnosis::new(
+                    DiagnosisSeverity::Warning,
+                    format!("Variable {variable} is read from, but never assigned"),
+                );
+                locate(diagnosis, node_name, line_info.clone())
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,79 @@
+use super::locate;
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+use yarnspinner_core::prelude::*;
+
+/// Reports nodes that cannot be reached from [`AnalysisOptions::entry_nodes`] through `<<jump>>`s.
+/// Nothing is reported if no entry nodes are set or none of them exist, since the dialogue is then evidently started elsewhere.
+/// Jumps to expressions such as `<<jump {$next_node}>>` cannot be followed, so nodes only reached that way are reported as well.
+#[derive(Debug, Default)]
+pub(crate) struct UnreachableNodeChecker {
+    entry_nodes: Vec<String>,
+    jumps: HashMap<String, HashSet<String>>,
+    locations: HashMap<String, Option<LineInfo>>,
+}
+
+impl UnreachableNodeChecker {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CompiledProgramAnalyser for UnreachableNodeChecker {
+    fn diagnose(&mut self, program: &Program) {
+        self.diagnose_with_options(program, &AnalysisOptions::default());
+    }
+
+    fn diagnose_with_options(&mut self, program: &Program, options: &AnalysisOptions) {
+        self.entry_nodes.clone_from(&options.entry_nodes);
+        for (node_name, node) in &program.nodes {
+            // Jumps to node names are compiled to pushing the name followed by running the node
+            let targets = node
+                .instructions
+                .windows(2)
+                .filter(|instructions| {
+                    instructions[0].opcode() == OpCode::PushString
+                        && instructions[1].opcode() == OpCode::RunNode
+                })
+                .map(|instructions| instructions[0].read_operand(0))
+                .collect();
+            self.jumps.insert(node_name.clone(), targets);
+            self.locations
+                .insert(node_name.clone(), options.node_line_info(node_name));
+        }
+    }
+
+    fn collect_diagnoses(&self) -> Vec<Diagnosis> {
+        if !self
+            .entry_nodes
+            .iter()
+            .any(|node| self.jumps.contains_key(node))
+        {
+            return Vec::new();
+        }
+        let mut reachable = HashSet::new();
+        let mut queue: VecDeque<&String> = self.entry_nodes.iter().collect();
+        while let Some(node) = queue.pop_front() {
+            if reachable.insert(node) {
+                queue.extend(self.jumps.get(node).into_iter().flatten());
+            }
+        }
+        let mut unreachable: Vec<_> = self
+            .jumps
+            .keys()
+            .filter(|node| !reachable.contains(node))
+            .collect();
+        unreachable.sort();
+        let entry_nodes = self.entry_nodes.join(", ");
+        unreachable
+            .into_iter()
+            .map(|node| {
+                let diagnosis = Diagnosis::new(
+                    DiagnosisSeverity::Warning,
+                    format!("Node {node} can never be reached from the entry nodes {entry_nodes}"),
+                );
+                locate(diagnosis, node, self.locations[node].clone())
+            })
+            .collect()
+    }
+}
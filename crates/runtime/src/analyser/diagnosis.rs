@@ -1,10 +1,9 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/Analyser.cs>,
 //! which was split into multiple files.
 
-#[cfg(any(feature = "bevy", feature = "serde"))]
-use crate::prelude::*;
 use core::fmt::{Display, Formatter};
 use std::iter;
+use yarnspinner_core::prelude::*;
 
 /// A result of analysing a compiled Yarn program with [`Dialogue::analyse`]. Created by the [`CompiledProgramAnalyser`]s used in the given [`Context`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -23,6 +22,8 @@ pub struct Diagnosis {
     pub message: String,
     /// The name of the node that caused the diagnosis, if any.
     pub node_name: Option<String>,
+    /// The name of the Yarn file that caused the diagnosis, if any.
+    pub file_name: Option<String>,
     /// The 1-indexed line number of the node that caused the diagnosis, if any.
     pub line: Option<usize>,
     /// The 1-indexed column number, i.e. the character index in the line, of the node that caused the diagnosis, if any.
@@ -65,6 +66,7 @@ impl Diagnosis {
             severity,
             message,
             node_name: Default::default(),
+            file_name: Default::default(),
             line: Default::default(),
             column: Default::default(),
         }
@@ -77,6 +79,28 @@ impl Diagnosis {
         self
     }
 
+    /// Sets the name of the Yarn file the diagnosis is associated with. By default, this is `None`.
+    #[must_use]
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Sets the node name, file name, line and column the diagnosis is associated with from the given [`LineInfo`],
+    /// e.g. as returned by [`AnalysisOptions::line_info`](crate::prelude::AnalysisOptions::line_info).
+    #[must_use]
+    pub fn with_line_info(self, line_info: LineInfo) -> Self {
+        let diagnosis = self
+            .with_node_name(line_info.node_name)
+            .with_file_name(line_info.file_name);
+        match line_info.position {
+            Some(position) => diagnosis
+                .with_line(position.line + 1)
+                .with_column(position.character + 1),
+            None => diagnosis,
+        }
+    }
+
     /// Sets the 1-indexed line the diagnosis is associated with. By default, this is `None`.
     #[must_use]
     pub fn with_line(mut self, line: usize) -> Self {
@@ -105,7 +129,12 @@ impl Display for Diagnosis {
             let column = self.column.map(|c| format!(":{c}")).unwrap_or_default();
             format!("{line}{column}")
         });
-        let message = [Some(severity), self.node_name.clone(), line]
+        let location = match (&self.file_name, line) {
+            (Some(file_name), Some(line)) => Some(format!("{file_name}:{line}")),
+            (Some(file_name), None) => Some(file_name.clone()),
+            (None, line) => line,
+        };
+        let message = [Some(severity), self.node_name.clone(), location]
             .into_iter()
             .take_while(|o| o.is_some())
             .flatten()
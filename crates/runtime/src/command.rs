@@ -6,6 +6,10 @@
 use crate::markup::normalize;
 #[cfg(any(feature = "bevy", feature = "serde"))]
 use crate::prelude::*;
+use core::fmt;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use yarnspinner_core::prelude::YarnValue;
 
 /// A custom command found in a Yarn file within the `<<` and `>>` characters.
@@ -61,6 +65,34 @@ impl Command {
     }
 }
 
+/// Parses the text of a command the way the dialogue does before emitting it, e.g. `set_sprite ship "happy"`.
+impl FromStr for Command {
+    type Err = ParseCommandError;
+
+    fn from_str(input: &str) -> std::result::Result<Self, Self::Err> {
+        if input.trim().is_empty() {
+            return Err(ParseCommandError(input.to_owned()));
+        }
+        Ok(Self::parse(input.to_owned()))
+    }
+}
+
+/// The error returned when parsing a [`Command`] from text that is composed entirely of whitespace.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseCommandError(pub String);
+
+impl Error for ParseCommandError {}
+
+impl Display for ParseCommandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to parse the command \"{}\" because it is composed entirely of whitespace",
+            self.0
+        )
+    }
+}
+
 /// Splits input into a number of non-empty sub-strings, separated
 /// by whitespace, and grouping double-quoted strings into a single
 /// sub-string.
@@ -238,4 +270,13 @@ mod tests {
             assert_eq!(expected_command, parsed_command);
         }
     }
+
+    #[test]
+    fn parsing_whitespace_fails() {
+        assert_eq!(
+            Err(ParseCommandError(" \t".to_owned())),
+            " \t".parse::<Command>()
+        );
+        assert_eq!(Ok(Command::parse("wait 1".to_owned())), "wait 1".parse());
+    }
 }
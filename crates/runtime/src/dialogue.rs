@@ -1,6 +1,8 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/Dialogue.cs>
 
-use crate::markup::{DialogueTextProcessor, LineParser, MarkupParseError};
+use crate::markup::{
+    DialogueTextProcessor, LineParser, MarkupParseError, MessageFormatTextProcessor,
+};
 use crate::prelude::*;
 use log::error;
 use std::collections::HashMap;
@@ -107,7 +109,8 @@ impl Dialogue {
         let line_parser = LineParser::new()
             .register_marker_processor("select", dialogue_text_processor.clone())
             .register_marker_processor("plural", dialogue_text_processor.clone())
-            .register_marker_processor("ordinal", dialogue_text_processor);
+            .register_marker_processor("ordinal", dialogue_text_processor)
+            .register_marker_processor("message", Box::new(MessageFormatTextProcessor::new()));
 
         Self {
             vm: VirtualMachine::new(library, variable_storage, line_parser, text_provider),
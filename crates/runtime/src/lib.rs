@@ -11,10 +11,9 @@ mod command;
 mod dialogue;
 mod dialogue_option;
 mod events;
-mod language;
 mod line;
 pub mod markup;
-mod pluralization;
+mod pseudo_localization;
 mod text_provider;
 mod variable_storage;
 mod virtual_machine;
@@ -23,18 +22,19 @@ pub use dialogue::Result;
 
 pub mod prelude {
     //! Everything you need to get starting using the Yarn Spinner runtime.
+    pub(crate) use crate::virtual_machine::*;
     pub use crate::{
         analyser::*,
         command::*,
         dialogue::{Dialogue, DialogueError},
         dialogue_option::*,
         events::*,
-        language::*,
         line::*,
         markup::MarkupParseError,
+        pseudo_localization::*,
         text_provider::*,
         variable_storage::*,
     };
-    pub(crate) use crate::{pluralization::*, virtual_machine::*};
     pub(crate) use yarnspinner_core::prelude::*;
+    pub use yarnspinner_core::prelude::{Language, NumberStyle, ParseNumberStyleError};
 }
@@ -4,7 +4,8 @@
 //! Introduced `LineId` newtype for better type safety
 
 use crate::markup::{
-    MarkupAttribute, MarkupValue, CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY,
+    markup_spans, markup_tree, MarkupAttribute, MarkupNode, MarkupSpan, MarkupValue,
+    CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY,
 };
 use crate::prelude::*;
 
@@ -37,6 +38,12 @@ pub struct Line {
     pub text: String,
     /// The list of [`MarkupAttribute`] in this parse result.
     pub attributes: Vec<MarkupAttribute>,
+    /// The language the text was actually taken from, as reported by [`TextProvider::get_text_with_language`].
+    /// `None` means the base language, i.e. the language the Yarn files are written in.
+    /// If this differs from [`Dialogue::language_code`], the line is not translated into the current language yet.
+    /// Code that builds a [`Line`] with a struct literal, e.g. in tests, can set this to `None`.
+    #[cfg_attr(feature = "bevy", reflect(ignore))]
+    pub language: Option<Language>,
 }
 
 impl Line {
@@ -67,6 +74,7 @@ impl Line {
     /// #        properties: HashMap::from([("name".to_owned(), "Alice".into())]),
     /// #        source_position: 0,
     /// #    }],
+    /// #    language: None,
     /// # };
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!(Some("Alice"), line.character_name());
@@ -82,6 +90,7 @@ impl Line {
     /// #    id: "line".into(),
     /// #    text: "Great, thanks".to_owned(),
     /// #    attributes: vec![],
+    /// #    language: None,
     /// # };
     /// assert_eq!("Great, thanks", line.text);
     /// assert!(line.character_name().is_none());
@@ -119,6 +128,7 @@ impl Line {
     /// #        properties: HashMap::from([("name".to_owned(), "Alice".into())]),
     /// #        source_position: 0,
     /// #    }],
+    /// #    language: None,
     /// # };
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!("Hello! How are you today?", &line.text_without_character_name());
@@ -134,6 +144,7 @@ impl Line {
     /// #    id: "line".into(),
     /// #    text: "Great, thanks".to_owned(),
     /// #    attributes: vec![],
+    /// #    language: None,
     /// # };
     /// assert_eq!("Great, thanks", line.text);
     /// assert_eq!("Great, thanks", &line.text_without_character_name());
@@ -156,6 +167,20 @@ impl Line {
         &self.text[attribute.position..attribute.position + attribute.length]
     }
 
+    /// Splits [`Line::text`] into an ordered sequence of non-overlapping [`MarkupSpan`]s, each carrying the attributes that apply to it.
+    ///
+    /// See [`markup_spans`] for details.
+    pub fn spans(&self) -> Vec<MarkupSpan> {
+        markup_spans(&self.text, &self.attributes)
+    }
+
+    /// Converts [`Line::text`] and [`Line::attributes`] into a tree of properly nested [`MarkupNode`]s.
+    ///
+    /// See [`markup_tree`] for details.
+    pub fn markup_tree(&self) -> Vec<MarkupNode> {
+        markup_tree(&self.text, &self.attributes)
+    }
+
     /// Deletes an attribute from this markup.
     /// This method deletes the range of text covered by `attribute_to_delete`,
     /// and updates the other attributes in this markup as follows:
@@ -200,6 +225,7 @@ impl Line {
                 id: self.id.clone(),
                 text: self.text.to_string(),
                 attributes,
+                language: self.language.clone(),
             };
         }
         let deletion_start = attribute_to_delete.position;
@@ -275,6 +301,7 @@ impl Line {
             id: self.id.clone(),
             text: edited_substring,
             attributes,
+            language: self.language.clone(),
         }
     }
 }
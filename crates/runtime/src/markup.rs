@@ -5,22 +5,16 @@
 //! Greg: You're a [size=12]cat[/size]!
 //! ```
 //! The parsing extracts the information that "Mae" and "Greg" are characters, that "shout" and "size" are attributes, and that "size" has a value of "12".
-mod attribute_marker_processor;
-mod line_parser;
-mod markup_parse_error;
-mod parsed_markup;
-
-pub use self::line_parser::{
-    CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY, TRIM_WHITESPACE_PROPERTY,
-};
-pub(crate) use self::{attribute_marker_processor::*, line_parser::*};
-pub use self::{markup_parse_error::*, parsed_markup::*};
+pub use yarnspinner_core::markup::*;
 
 #[cfg(test)]
 mod tests {
     //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Tests/MarkupTests.cs>
     use super::*;
     use crate::prelude::{Language, Line};
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+    use unicode_segmentation::UnicodeSegmentation;
 
     #[test]
     fn test_markup_parsing() {
@@ -359,6 +353,585 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_spans_split_overlapping_attributes() {
+        let line = "A [a]B [b]C[/a] D[/b] E";
+        let markup = line_parser().parse_markup(line).unwrap();
+
+        let spans = markup_spans(&markup.text, &markup.attributes);
+        let spans: Vec<_> = spans
+            .iter()
+            .map(|span| {
+                let names: Vec<_> = span.attributes.iter().map(|a| a.name.as_str()).collect();
+                (span.text.as_str(), names)
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("A ", vec![]),
+                ("B ", vec!["a"]),
+                ("C", vec!["a", "b"]),
+                (" D", vec!["b"]),
+                (" E", vec![]),
+            ],
+            spans
+        );
+    }
+
+    #[test]
+    fn test_spans_respect_grapheme_clusters() {
+        let line = "👨‍👩‍👧 [a]👍🏽x[/a]";
+        let markup = line_parser().parse_markup(line).unwrap();
+
+        let spans = markup_spans(&markup.text, &markup.attributes);
+
+        assert_eq!(2, spans.len());
+        assert_eq!("👨‍👩‍👧 ", spans[0].text);
+        assert_eq!(0, spans[0].position);
+        assert_eq!(2, spans[0].length);
+        assert_eq!("👍🏽x", spans[1].text);
+        assert_eq!(2, spans[1].position);
+        assert_eq!(2, spans[1].length);
+        assert!(spans[1].has_attribute("a"));
+    }
+
+    #[test]
+    fn test_spans_include_zero_length_attributes() {
+        let line = "[a]A [p/]B[/a]";
+        let markup = line_parser().parse_markup(line).unwrap();
+
+        let spans = markup_spans(&markup.text, &markup.attributes);
+
+        assert_eq!(3, spans.len());
+        assert_eq!("A ", spans[0].text);
+        assert_eq!("", spans[1].text);
+        assert_eq!(0, spans[1].length);
+        let names: Vec<_> = spans[1]
+            .attributes
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(vec!["a", "p"], names);
+        assert_eq!("B", spans[2].text);
+    }
+
+    #[test]
+    fn test_zero_length_attributes_at_the_start_of_an_attribute_are_enclosed() {
+        let line = "[a][p/]B[/a]";
+        let markup = line_parser().parse_markup(line).unwrap();
+
+        let spans = markup_spans(&markup.text, &markup.attributes);
+        let names: Vec<_> = spans[0]
+            .attributes
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(vec!["a", "p"], names);
+
+        let tree = markup_tree(&markup.text, &markup.attributes);
+        assert_eq!(1, tree.len());
+        let MarkupNode::Attribute { children, .. } = &tree[0] else {
+            panic!("Expected an attribute node, got {:?}", tree[0]);
+        };
+        assert!(
+            matches!(&children[0], MarkupNode::Attribute { attribute, .. } if attribute.name == "p")
+        );
+    }
+
+    #[test]
+    fn test_tree_splits_crossing_attributes() {
+        let line = "[a]x[b]y[/a]z[/b]";
+        let markup = line_parser().parse_markup(line).unwrap();
+        let a = markup.get_attribute("a").unwrap();
+        let b = markup.get_attribute("b").unwrap();
+
+        let tree = markup_tree(&markup.text, &markup.attributes);
+
+        assert_eq!(
+            vec![
+                MarkupNode::Attribute {
+                    attribute: a,
+                    children: vec![
+                        MarkupNode::Text("x".to_owned()),
+                        MarkupNode::Attribute {
+                            attribute: b.clone(),
+                            children: vec![MarkupNode::Text("y".to_owned())],
+                        },
+                    ],
+                },
+                MarkupNode::Attribute {
+                    attribute: b,
+                    children: vec![MarkupNode::Text("z".to_owned())],
+                },
+            ],
+            tree
+        );
+    }
+
+    #[test]
+    fn test_tree_nests_zero_length_attributes() {
+        let line = "[a]A [p/]B[/a]";
+        let markup = line_parser().parse_markup(line).unwrap();
+
+        let tree = markup_tree(&markup.text, &markup.attributes);
+
+        assert_eq!(1, tree.len());
+        let MarkupNode::Attribute {
+            attribute,
+            children,
+        } = &tree[0]
+        else {
+            panic!("Expected an attribute node, got {:?}", tree[0]);
+        };
+        assert_eq!("a", attribute.name);
+        assert_eq!(3, children.len());
+        assert!(
+            matches!(&children[1], MarkupNode::Attribute { attribute, children } if attribute.name == "p" && children.is_empty())
+        );
+        assert_eq!("A B", tree[0].text());
+    }
+
+    #[test]
+    fn test_serialising_canonicalises_markup() {
+        for (input, expected) in [
+            ("A [b]B[/b]", "A [b]B[/b]"),
+            (
+                "[ a  p1 = 1 p2=\"two words\" ]x[/]",
+                "[a p1=1 p2=\"two words\"]x[/a]",
+            ),
+            ("[a=1]s[/a]", "[a=1]s[/a]"),
+            ("[a p=0.05/]", "[a p=0.05 /]"),
+            ("A [a/] B", "A [a/]B"),
+            (
+                "A [a trimwhitespace=false/] B",
+                "A [a trimwhitespace=false /] B",
+            ),
+            (r"\[b\] \\", r"\[b\] \"),
+            ("Mae: Wow!", "Mae: Wow!"),
+            (
+                "S [nomarkup][a]S;][/a][/nomarkup]",
+                "S [nomarkup][a]S;][/a][/nomarkup]",
+            ),
+        ] {
+            let markup = ParsedMarkup::parse(input).unwrap();
+
+            assert_eq!(expected, markup.to_markup(), "input: {input}");
+            assert_eq!(
+                markup,
+                ParsedMarkup::parse(expected)
+                    .unwrap()
+                    .with_source_positions_of(&markup)
+            );
+        }
+    }
+
+    #[test]
+    fn test_serialising_preserves_whitespace_after_self_closing_markers() {
+        let markup = ParsedMarkup::from_parts(
+            "A  B",
+            vec![MarkupAttribute {
+                name: "a".to_owned(),
+                position: 2,
+                length: 0,
+                properties: HashMap::new(),
+                source_position: 0,
+            }],
+        );
+
+        let serialised = markup.to_markup();
+
+        assert_eq!("A [a/]  B", serialised);
+        assert_eq!("A  B", ParsedMarkup::parse(&serialised).unwrap().text);
+    }
+
+    #[test]
+    fn test_serialising_escapes_backslash_before_marker() {
+        let markup = ParsedMarkup::from_parts(
+            r"x\y",
+            vec![MarkupAttribute {
+                name: "a".to_owned(),
+                position: 2,
+                length: 1,
+                properties: HashMap::new(),
+                source_position: 0,
+            }],
+        );
+
+        let serialised = markup.to_markup();
+
+        assert_eq!(r"x\\[a]y[/a]", serialised);
+        let parsed = ParsedMarkup::parse(&serialised).unwrap();
+        assert_eq!(r"x\y", parsed.text);
+        assert_eq!(2, parsed.attributes[0].position);
+    }
+
+    #[test]
+    fn test_dialogue_does_not_escape_backslashes() {
+        let line = r"[a]x\\y[/a] \\\[";
+
+        let markup = line_parser().parse_markup(line).unwrap();
+        assert_eq!(r"x\\y \\[", markup.text);
+        assert_eq!(4, markup.attributes[0].length);
+
+        let markup = ParsedMarkup::parse(line).unwrap();
+        assert_eq!(r"x\y \[", markup.text);
+        assert_eq!(3, markup.attributes[0].length);
+    }
+
+    #[test]
+    fn test_serialising_quotes_strings_that_are_not_single_words() {
+        for (value, expected) in [
+            (MarkupValue::from("word"), "[a p=word /]"),
+            ("two words".into(), "[a p=\"two words\" /]"),
+            ("true".into(), "[a p=\"true\" /]"),
+            ("1st".into(), "[a p=\"1st\" /]"),
+            ("".into(), "[a p=\"\" /]"),
+            ("say \"hi\\\"".into(), r#"[a p="say \"hi\\\"" /]"#),
+            (1.0.into(), "[a p=1.0 /]"),
+            ((-1.5).into(), "[a p=\"-1.5\" /]"),
+        ] {
+            let markup = ParsedMarkup::from_parts(
+                "",
+                vec![MarkupAttribute {
+                    name: "a".to_owned(),
+                    position: 0,
+                    length: 0,
+                    properties: HashMap::from([("p".to_owned(), value)]),
+                    source_position: 0,
+                }],
+            );
+
+            assert_eq!(expected, markup.to_markup());
+        }
+    }
+
+    #[test]
+    fn test_validating_reports_parse_errors_with_source_range() {
+        let issues = MarkupValidator::new().validate(r#"Hi [a p="x\z"] there"#);
+
+        assert_eq!(1, issues.len());
+        assert!(issues[0].is_error());
+        assert!(matches!(
+            issues[0],
+            MarkupIssue::ParseError {
+                error: MarkupParseError::InvalidEscapeSequence { .. },
+                ..
+            }
+        ));
+        assert_eq!(3..12, issues[0].source_range());
+    }
+
+    #[test]
+    fn test_validating_reports_unmatched_close_markers() {
+        let issues = MarkupValidator::new().validate("[a]x[/a] [/b]");
+
+        assert_eq!(1, issues.len());
+        assert_eq!(9..13, issues[0].source_range());
+    }
+
+    #[test]
+    fn test_validating_reports_unclosed_and_unknown_markers() {
+        let validator = MarkupValidator::new().with_known_markers(["b"]);
+        let issues = validator.validate("[b]bold [shout]{0}[/shout] [b]unclosed");
+
+        assert_eq!(
+            vec![
+                MarkupIssue::UnclosedMarker {
+                    name: "b".to_owned(),
+                    source_range: 0..2,
+                },
+                MarkupIssue::UnknownMarker {
+                    name: "shout".to_owned(),
+                    source_range: 8..14,
+                },
+                MarkupIssue::UnclosedMarker {
+                    name: "b".to_owned(),
+                    source_range: 27..29,
+                },
+            ],
+            issues
+        );
+        assert!(issues.iter().all(|issue| !issue.is_error()));
+    }
+
+    #[test]
+    fn test_validating_reports_incomplete_replacement_markers() {
+        let validator = MarkupValidator::new();
+
+        assert!(validator
+            .validate("[plural value={0} one=\"a pie\" other=\"% pies\" /]")
+            .is_empty());
+        assert!(validator
+            .validate("[select value={0} m=he f=she /] waves")
+            .is_empty());
+
+        let issues = validator.validate("I have [plural value={0} one=\"a pie\" /]");
+        assert_eq!(
+            vec![MarkupIssue::MissingProperty {
+                name: "plural".to_owned(),
+                property: "other".to_owned(),
+                source_range: 7..14,
+            }],
+            issues
+        );
+        assert!(issues[0].is_error());
+
+        let issues = validator.validate("[ordinal value=first other=\"%th\" /]");
+        assert!(issues[0].is_error());
+    }
+
+    #[test]
+    fn test_message_format_nests_select_in_plural() {
+        for (count, expected) in [(1, "She has a cat"), (3, "She has 3 cats")] {
+            let line = format!(
+                "[message gender=female count={count}]{{count, plural, \
+                one {{{{gender, select, female {{She has}} other {{They have}}}} a cat}} \
+                other {{{{gender, select, female {{She has}} other {{They have}}}} # cats}}}}[/message]"
+            );
+
+            let mut line_parser = line_parser();
+            line_parser.set_language_code(Language::from("en"));
+            let markup = line_parser.parse_markup(&line).unwrap();
+
+            assert_eq!(expected, markup.text);
+        }
+    }
+
+    #[test]
+    fn test_message_format_exact_cases_and_offsets() {
+        let message = MessageFormat::parse(
+            "{guests, plural, offset:1 =0 {Nobody came} =1 {{host} came} \
+            one {{host} and one other came} other {{host} and # others came}}",
+        )
+        .unwrap();
+        for (guests, expected) in [
+            (0, "Nobody came"),
+            (1, "Mae came"),
+            (2, "Mae and one other came"),
+            (5, "Mae and 4 others came"),
+        ] {
+            let arguments = HashMap::from([
+                ("guests".to_owned(), MarkupValue::Integer(guests)),
+                ("host".to_owned(), MarkupValue::String("Mae".to_owned())),
+            ]);
+            let text = message.format(&Language::from("en"), &arguments).unwrap();
+            assert_eq!(expected, text);
+        }
+    }
+
+    #[test]
+    fn test_message_format_formats_numbers() {
+        let line = "[message gold=1234.5 ratio=0.25]\
+            {gold, number} Gold, {gold, number, integer}, {ratio, number, percent}[/message]";
+        let mut line_parser = line_parser();
+        line_parser.set_language_code(Language::from("de"));
+        let markup = line_parser.parse_markup(line).unwrap();
+        assert_eq!("1.234,5 Gold, 1.234, 25%", markup.text);
+
+        let message = MessageFormat::parse(
+            "{place, selectordinal, one {#st} two {#nd} few {#rd} other {#th}}",
+        )
+        .unwrap();
+        let arguments = HashMap::from([("place".to_owned(), MarkupValue::Integer(22))]);
+        let text = message.format(&Language::from("en"), &arguments).unwrap();
+        assert_eq!("22nd", text);
+    }
+
+    #[test]
+    fn test_message_format_quoting_and_errors() {
+        let message = MessageFormat::parse("'{'literal'}' it''s, \\{name\\}").unwrap();
+        let arguments = HashMap::from([("name".to_owned(), MarkupValue::Bool(true))]);
+        let text = message.format(&Language::from("en"), &arguments).unwrap();
+        assert_eq!("{literal} it's, true", text);
+
+        assert!(matches!(
+            MessageFormat::parse("{count, plural, one {# cat}}"),
+            Err(MessageFormatError::Syntax { position: 16, .. })
+        ));
+        assert!(matches!(
+            MessageFormat::parse("{count, plural, some {x} other {y}}"),
+            Err(MessageFormatError::Syntax { position: 16, .. })
+        ));
+        assert!(matches!(
+            MessageFormat::parse("{count, plural, other {# cats}"),
+            Err(MessageFormatError::Syntax { .. })
+        ));
+
+        let message = MessageFormat::parse("{count, plural, other {# cats}}").unwrap();
+        let arguments = HashMap::from([("count".to_owned(), MarkupValue::Bool(true))]);
+        assert_eq!(
+            Err(MessageFormatError::NotANumber {
+                name: "count".to_owned(),
+                value: "true".to_owned(),
+            }),
+            message.format(&Language::from("en"), &arguments)
+        );
+        assert_eq!(
+            Err(MessageFormatError::MissingArgument {
+                name: "count".to_owned(),
+            }),
+            message.format(&Language::from("en"), &HashMap::new())
+        );
+
+        let mut line_parser = line_parser();
+        line_parser.set_language_code(Language::from("en"));
+        assert!(matches!(
+            line_parser.parse_markup("[message]{count, plural, one {# cat}}[/message]"),
+            Err(MarkupParseError::InvalidMessageFormat { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validating_message_markers() {
+        let validator = MarkupValidator::new();
+
+        assert!(validator
+            .validate("[message count={0}]{count, plural, one {# cat} other {# cats}}[/message]")
+            .is_empty());
+
+        let issues =
+            validator.validate("A [message count={0}]{count, plural, one {# cat}}[/message]");
+        assert!(matches!(
+            issues.as_slice(),
+            [MarkupIssue::InvalidMessageFormat {
+                source_range,
+                ..
+            }] if *source_range == (2..10)
+        ));
+        assert!(issues[0].is_error());
+
+        let issues = validator.validate("[message]{name} waves[/message]");
+        assert_eq!(
+            vec![MarkupIssue::MissingProperty {
+                name: "message".to_owned(),
+                property: "name".to_owned(),
+                source_range: 0..8,
+            }],
+            issues
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_serialising_round_trips((text, attributes) in text_with_attributes()) {
+            let serialised = serialize_markup(&text, &attributes);
+            let parsed = ParsedMarkup::parse(&serialised).unwrap();
+
+            prop_assert_eq!(&text, &parsed.text);
+            prop_assert_eq!(normalized(&attributes), normalized(&parsed.attributes));
+
+            // parse -> serialise -> parse is the identity
+            let reserialised = parsed.to_markup();
+            prop_assert_eq!(&serialised, &reserialised);
+            prop_assert_eq!(parsed, ParsedMarkup::parse(&reserialised).unwrap());
+        }
+    }
+
+    fn text_with_attributes() -> impl Strategy<Value = (String, Vec<MarkupAttribute>)> {
+        let characters = vec![
+            'a',
+            'b',
+            'Z',
+            '1',
+            '_',
+            ' ',
+            ' ',
+            '\u{3000}',
+            'é',
+            'ß',
+            '👍',
+            '\u{1F3FD}',
+            '[',
+            ']',
+            '\\',
+            '"',
+            '=',
+            '/',
+        ];
+        prop::collection::vec(prop::sample::select(characters), 0..16)
+            .prop_map(|characters| characters.into_iter().collect::<String>())
+            .prop_flat_map(|text| {
+                let grapheme_count = text.graphemes(true).count();
+                let attribute = (0..=grapheme_count, 0..=grapheme_count, properties());
+                let attributes = prop::collection::vec(attribute, 0..4);
+                (Just(text), attributes)
+            })
+            .prop_map(|(text, ranges)| {
+                // Distinct names, as crossing attributes with the same name cannot be expressed
+                let names = ["a", "b", "wave", "ü"];
+                let attributes = ranges
+                    .into_iter()
+                    .zip(names)
+                    .map(|((a, b, mut properties), name)| {
+                        if let Some(shortcut) = properties.remove("shortcut") {
+                            properties.insert(name.to_owned(), shortcut);
+                        }
+                        MarkupAttribute {
+                            name: name.to_owned(),
+                            position: a.min(b),
+                            length: a.max(b) - a.min(b),
+                            properties,
+                            source_position: 0,
+                        }
+                    })
+                    .collect();
+                (text, attributes)
+            })
+    }
+
+    fn properties() -> impl Strategy<Value = HashMap<String, MarkupValue>> {
+        let value = prop_oneof![
+            any::<u32>().prop_map(MarkupValue::Integer),
+            (0.0_f32..1e6).prop_map(MarkupValue::Float),
+            any::<bool>().prop_map(MarkupValue::Bool),
+            "[a-zA-Z0-9 _\"\\\\\\]\\[]{0,6}".prop_map(MarkupValue::String),
+        ];
+        let name = prop::sample::select(vec!["p", "q", "size", "shortcut"]);
+        let trim_whitespace = prop::option::of(any::<bool>());
+        (
+            prop::collection::hash_map(name, value, 0..3),
+            trim_whitespace,
+        )
+            .prop_map(|(properties, trim_whitespace)| {
+                properties
+                    .into_iter()
+                    .map(|(name, value)| (name.to_owned(), value))
+                    .chain(
+                        trim_whitespace
+                            .map(|trim| (TRIM_WHITESPACE_PROPERTY.to_owned(), trim.into())),
+                    )
+                    .collect()
+            })
+    }
+
+    fn normalized(attributes: &[MarkupAttribute]) -> Vec<MarkupAttribute> {
+        let mut attributes: Vec<_> = attributes
+            .iter()
+            .cloned()
+            .map(|attribute| MarkupAttribute {
+                source_position: 0,
+                ..attribute
+            })
+            .collect();
+        attributes
+            .sort_by(|a, b| (a.position, a.length, &a.name).cmp(&(b.position, b.length, &b.name)));
+        attributes
+    }
+
+    trait WithSourcePositions {
+        fn with_source_positions_of(self, other: &ParsedMarkup) -> Self;
+    }
+
+    impl WithSourcePositions for ParsedMarkup {
+        fn with_source_positions_of(mut self, other: &ParsedMarkup) -> Self {
+            for (attribute, other) in self.attributes.iter_mut().zip(&other.attributes) {
+                attribute.source_position = other.source_position;
+            }
+            self
+        }
+    }
+
     fn line_parser() -> LineParser {
         let dialogue_text_processor = Box::new(DialogueTextProcessor::new());
 
@@ -366,6 +939,7 @@ mod tests {
             .register_marker_processor("select", dialogue_text_processor.clone())
             .register_marker_processor("plural", dialogue_text_processor.clone())
             .register_marker_processor("ordinal", dialogue_text_processor)
+            .register_marker_processor("message", Box::new(MessageFormatTextProcessor::new()))
     }
 
     trait ParsedMarkupAsLine {
@@ -381,10 +955,7 @@ mod tests {
 
         fn delete_range(&self, attribute_to_delete: &MarkupAttribute) -> ParsedMarkup {
             let line = self.as_line().delete_range(attribute_to_delete);
-            ParsedMarkup {
-                text: line.text,
-                attributes: line.attributes,
-            }
+            ParsedMarkup::from_parts(line.text, line.attributes)
         }
     }
 
@@ -394,6 +965,7 @@ mod tests {
                 id: "test".into(),
                 text: self.text.clone(),
                 attributes: self.attributes.clone(),
+                language: None,
             }
         }
     }
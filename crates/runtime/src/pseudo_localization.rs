@@ -0,0 +1,349 @@
+//! Pseudo-localisation of lines, used to find layout and localisation problems before the text is actually translated.
+//!
+//! ## Implementation notes
+//! This has no equivalent in the original C# code.
+
+use crate::markup::END_OF_CHARACTER_MARKER;
+use crate::prelude::{Language, TextProvider};
+use std::any::Any;
+use std::fmt::Debug;
+use yarnspinner_core::prelude::*;
+
+/// Settings for turning text into pseudo-localised text, which looks translated while staying readable.
+/// This makes it easy to spot text that is cut off because it is too long, text that does not come from the string table,
+/// and characters that the font cannot display.
+///
+/// Markup markers such as `[b]`, substitution placeholders such as `{0}`, escaped characters and the character name prefix of a line (e.g. `Mae: `)
+/// are left untouched, so that the result can still be parsed like the original line.
+///
+/// ## Example
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// let pseudo_localization = PseudoLocalization::default();
+/// assert_eq!(
+///     "Mae: ⟦Ĥéļļö, [b]{0}[/b]!~~~⟧",
+///     pseudo_localization.apply("Mae: Hello, [b]{0}[/b]!")
+/// );
+///
+/// let mirrored = PseudoLocalization::default()
+///     .with_accents(false)
+///     .with_brackets(false)
+///     .with_padding_percentage(0)
+///     .with_mirroring(true);
+/// assert_eq!("Mae: ereht olleH", mirrored.apply("Mae: Hello there"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct PseudoLocalization {
+    /// By how many percent the text of a line is lengthened with padding characters to simulate languages that need more space.
+    /// Markup and placeholders are not counted. Defaults to 30.
+    pub padding_percentage: u32,
+    /// Whether to replace ASCII letters by accented look-alikes, e.g. `Hello` by `Ĥéļļö`. Defaults to `true`.
+    pub accents: bool,
+    /// Whether to surround the text of a line by `⟦` and `⟧`, which shows where text is cut off or was concatenated from multiple lines. Defaults to `true`.
+    pub brackets: bool,
+    /// Whether to reverse the characters of the text between markup markers and placeholders, simulating right-to-left text. Defaults to `false`.
+    pub mirror: bool,
+}
+
+impl Default for PseudoLocalization {
+    fn default() -> Self {
+        Self {
+            padding_percentage: 30,
+            accents: true,
+            brackets: true,
+            mirror: false,
+        }
+    }
+}
+
+const ASCII_LETTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const ACCENTED_LETTERS: &str = "àƀçđéƒĝĥîĵķļɱñöþǫŕšţûṽŵẋýžÅƁÇĐÉƑĜĤÎĴĶĻṀÑÖÞǪŔŠŢÛṼŴẊÝŽ";
+const PADDING: &str = "~";
+const OPENING_BRACKET: char = '⟦';
+const CLOSING_BRACKET: char = '⟧';
+
+impl PseudoLocalization {
+    /// Creates new [`PseudoLocalization`] settings with the default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets [`PseudoLocalization::padding_percentage`].
+    pub fn with_padding_percentage(mut self, padding_percentage: u32) -> Self {
+        self.padding_percentage = padding_percentage;
+        self
+    }
+
+    /// Sets [`PseudoLocalization::accents`].
+    pub fn with_accents(mut self, accents: bool) -> Self {
+        self.accents = accents;
+        self
+    }
+
+    /// Sets [`PseudoLocalization::brackets`].
+    pub fn with_brackets(mut self, brackets: bool) -> Self {
+        self.brackets = brackets;
+        self
+    }
+
+    /// Sets [`PseudoLocalization::mirror`].
+    pub fn with_mirroring(mut self, mirror: bool) -> Self {
+        self.mirror = mirror;
+        self
+    }
+
+    /// Pseudo-localises the raw text of a line, i.e. the text as it appears in a string table, before substitutions and markup are processed.
+    pub fn apply(&self, text: &str) -> String {
+        let mut segments = segments(text);
+        let character_name_prefix = match segments.first_mut() {
+            Some(Segment::Text(first)) => {
+                let end = END_OF_CHARACTER_MARKER
+                    .find(first)
+                    .map(|marker| marker.end());
+                end.map(|end| {
+                    let rest = first.split_off(end);
+                    std::mem::replace(first, rest)
+                })
+            }
+            _ => None,
+        };
+
+        let mut result = character_name_prefix.unwrap_or_default();
+        if self.brackets {
+            result.push(OPENING_BRACKET);
+        }
+        let mut text_length = 0;
+        for segment in segments {
+            match segment {
+                Segment::Protected(protected) => result.push_str(&protected),
+                Segment::Text(text) => {
+                    text_length += text.chars().filter(|c| !c.is_whitespace()).count();
+                    let mut characters: Vec<_> = text.chars().map(|c| self.accent(c)).collect();
+                    if self.mirror {
+                        characters.reverse();
+                    }
+                    result.extend(characters);
+                }
+            }
+        }
+        let padding = (text_length * self.padding_percentage as usize).div_ceil(100);
+        result.push_str(&PADDING.repeat(padding));
+        if self.brackets {
+            result.push(CLOSING_BRACKET);
+        }
+        result
+    }
+
+    fn accent(&self, character: char) -> char {
+        if !self.accents {
+            return character;
+        }
+        ASCII_LETTERS
+            .chars()
+            .position(|letter| letter == character)
+            .and_then(|index| ACCENTED_LETTERS.chars().nth(index))
+            .unwrap_or(character)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// Text that must be kept as is, like markup markers, placeholders and escaped characters.
+    Protected(String),
+    /// Text that is shown to the player.
+    Text(String),
+}
+
+/// Splits raw line text into text shown to the player and text that needs to be kept as is.
+fn segments(text: &str) -> Vec<Segment> {
+    let characters: Vec<_> = text.chars().collect();
+    let mut segments = Vec::new();
+    let mut current_text = String::new();
+    let mut index = 0;
+    while index < characters.len() {
+        let protected_end = match characters[index] {
+            '\\' if index + 1 < characters.len() => Some(index + 2),
+            '[' => find_end_of_marker(&characters, index),
+            '{' => characters[index..]
+                .iter()
+                .position(|&c| c == '}')
+                .map(|offset| index + offset + 1),
+            _ => None,
+        };
+        match protected_end {
+            Some(end) => {
+                if !current_text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut current_text)));
+                }
+                segments.push(Segment::Protected(characters[index..end].iter().collect()));
+                index = end;
+            }
+            None => {
+                current_text.push(characters[index]);
+                index += 1;
+            }
+        }
+    }
+    if !current_text.is_empty() {
+        segments.push(Segment::Text(current_text));
+    }
+    segments
+}
+
+/// Returns the index after the `]` closing the markup marker starting at `start`, skipping over quoted property values.
+fn find_end_of_marker(characters: &[char], start: usize) -> Option<usize> {
+    let mut in_string = false;
+    let mut index = start + 1;
+    while index < characters.len() {
+        match characters[index] {
+            '\\' if in_string => index += 1,
+            '"' => in_string = !in_string,
+            ']' if !in_string => return Some(index + 1),
+            _ => {}
+        }
+        index += 1;
+    }
+    None
+}
+
+/// A [`TextProvider`] that wraps another [`TextProvider`] and pseudo-localises all text it provides according to its [`PseudoLocalization`].
+/// Use this to review the layout of your game with text that behaves like a translation before sending the strings to translators.
+///
+/// ## Example
+/// ```rust
+/// # use std::collections::HashMap;
+/// # use yarnspinner_core::prelude::*;
+/// # use yarnspinner_runtime::prelude::*;
+/// let mut string_table_text_provider = StringTableTextProvider::new();
+/// string_table_text_provider.extend_base_language(HashMap::from([(
+///     LineId::from("line:1"),
+///     "Mae: I have {0} [b]apples[/b].".to_owned(),
+/// )]));
+/// let text_provider = PseudoLocalizedTextProvider::new(string_table_text_provider)
+///     .with_pseudo_localization(PseudoLocalization::new().with_padding_percentage(0));
+///
+/// assert_eq!(
+///     Some("Mae: ⟦Î ĥàṽé {0} [b]àþþļéš[/b].⟧".to_owned()),
+///     text_provider.get_text(&"line:1".into())
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct PseudoLocalizedTextProvider<T> {
+    text_provider: T,
+    pseudo_localization: PseudoLocalization,
+}
+
+impl<T> PseudoLocalizedTextProvider<T> {
+    /// Wraps the given [`TextProvider`] using the default [`PseudoLocalization`].
+    pub fn new(text_provider: T) -> Self {
+        Self {
+            text_provider,
+            pseudo_localization: PseudoLocalization::default(),
+        }
+    }
+
+    /// Sets the [`PseudoLocalization`] applied to all text.
+    pub fn with_pseudo_localization(mut self, pseudo_localization: PseudoLocalization) -> Self {
+        self.pseudo_localization = pseudo_localization;
+        self
+    }
+
+    /// Returns the [`PseudoLocalization`] applied to all text.
+    pub fn pseudo_localization(&self) -> &PseudoLocalization {
+        &self.pseudo_localization
+    }
+
+    /// Returns the [`PseudoLocalization`] applied to all text mutably.
+    pub fn pseudo_localization_mut(&mut self) -> &mut PseudoLocalization {
+        &mut self.pseudo_localization
+    }
+
+    /// Returns the wrapped [`TextProvider`].
+    pub fn inner(&self) -> &T {
+        &self.text_provider
+    }
+
+    /// Returns the wrapped [`TextProvider`] mutably.
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.text_provider
+    }
+
+    /// Unwraps the wrapped [`TextProvider`].
+    pub fn into_inner(self) -> T {
+        self.text_provider
+    }
+}
+
+impl<T> TextProvider for PseudoLocalizedTextProvider<T>
+where
+    T: TextProvider + Clone + 'static,
+{
+    fn clone_shallow(&self) -> Box<dyn TextProvider> {
+        Box::new(self.clone())
+    }
+
+    fn accept_line_hints(&mut self, line_ids: &[LineId]) {
+        self.text_provider.accept_line_hints(line_ids)
+    }
+
+    fn get_text(&self, id: &LineId) -> Option<String> {
+        self.text_provider
+            .get_text(id)
+            .map(|text| self.pseudo_localization.apply(&text))
+    }
+
+    fn get_text_with_language(&self, id: &LineId) -> Option<(String, Option<Language>)> {
+        self.text_provider
+            .get_text_with_language(id)
+            .map(|(text, language)| (self.pseudo_localization.apply(&text), language))
+    }
+
+    fn set_language(&mut self, language: Option<Language>) {
+        self.text_provider.set_language(language)
+    }
+
+    fn get_language(&self) -> Option<Language> {
+        self.text_provider.get_language()
+    }
+
+    fn are_lines_available(&self) -> bool {
+        self.text_provider.are_lines_available()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markup::{LineParser, MarkupAttribute};
+
+    #[test]
+    fn pseudo_localized_lines_keep_their_markup() {
+        let pseudo_localization = PseudoLocalization::default().with_mirroring(true);
+        let line = r#"Mae: [wave speed="fast [!]"]Hi[/wave] \[not markup\] {0}[pause/]"#;
+
+        let pseudo_localized = pseudo_localization.apply(line);
+
+        assert_eq!(
+            r#"Mae: ⟦[wave speed="fast [!]"]îĤ[/wave] \[þûķŕàɱ ţöñ\] {0}[pause/]~~~~⟧"#,
+            pseudo_localized
+        );
+        let original = LineParser::new().parse_markup(line).unwrap();
+        let parsed = LineParser::new().parse_markup(&pseudo_localized).unwrap();
+        let names = |attributes: &[MarkupAttribute]| {
+            attributes
+                .iter()
+                .map(|attribute| attribute.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&original.attributes), names(&parsed.attributes));
+    }
+}
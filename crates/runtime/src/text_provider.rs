@@ -1,13 +1,12 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/Dialogue.cs>, which we split off into multiple files
 use crate::prelude::Language;
-use log::error;
 use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use yarnspinner_core::prelude::*;
 
 /// A trait for providing text to a [`Dialogue`](crate::prelude::Dialogue). The default implementation is [`StringTableTextProvider`], which keeps the
-/// text for the base language, i.e. the language the Yarn files are written in, and the text for the currently selected translation in memory.
+/// text for the base language, i.e. the language the Yarn files are written in, and the text for any number of translations in memory.
 ///
 /// ## Implementation notes
 ///
@@ -21,6 +20,14 @@ pub trait TextProvider: Debug + Send + Sync {
     fn accept_line_hints(&mut self, line_ids: &[LineId]);
     /// Returns the text for the given [`LineId`]. Will only be called if [`TextProvider::are_lines_available`] returns `true`.
     fn get_text(&self, id: &LineId) -> Option<String>;
+    /// Returns the text for the given [`LineId`] together with the language it was actually taken from, which is `None` for the base language.
+    /// This differs from [`TextProvider::get_language`] when the text for the current language is missing and a fallback was used instead.
+    /// Will only be called if [`TextProvider::are_lines_available`] returns `true`.
+    ///
+    /// The default implementation assumes that all text is in the current language.
+    fn get_text_with_language(&self, id: &LineId) -> Option<(String, Option<Language>)> {
+        self.get_text(id).map(|text| (text, self.get_language()))
+    }
     /// Sets the current language. If `None` is passed, the base language will be used.
     fn set_language(&mut self, language: Option<Language>);
     /// Returns the current language. If `None` is returned, the base language is used.
@@ -45,11 +52,40 @@ impl Clone for Box<dyn TextProvider> {
 pub type StringTable = HashMap<LineId, String>;
 
 /// A basic implementation of [`TextProvider`] which keeps the text for the base language,
-/// i.e. the language the Yarn files are written in, and the text for the currently selected translation in memory.
+/// i.e. the language the Yarn files are written in, and the text for any number of translations in memory.
+///
+/// When a line is missing in the current language, the languages of its fallback chain are tried in order before falling back to the base language.
+/// See [`StringTableTextProvider::fallback_chain`] for how the chain is determined.
+/// The language that actually served a line is reported by [`TextProvider::get_text_with_language`] and ends up in [`Line::language`](crate::prelude::Line::language).
+///
+/// ## Example
+/// ```rust
+/// # use std::collections::HashMap;
+/// # use yarnspinner_core::prelude::*;
+/// # use yarnspinner_runtime::prelude::*;
+/// let mut text_provider = StringTableTextProvider::new();
+/// text_provider.extend_base_language(HashMap::from([
+///     (LineId::from("line:1"), "Hello".to_owned()),
+///     (LineId::from("line:2"), "Goodbye".to_owned()),
+/// ]));
+/// text_provider.extend_translation("pt", HashMap::from([(LineId::from("line:1"), "Olá".to_owned())]));
+/// text_provider.set_language(Some("pt-BR".into()));
+///
+/// assert!(text_provider.are_lines_available());
+/// assert_eq!(
+///     Some(("Olá".to_owned(), Some(Language::new("pt")))),
+///     text_provider.get_text_with_language(&"line:1".into())
+/// );
+/// assert_eq!(
+///     Some(("Goodbye".to_owned(), None)),
+///     text_provider.get_text_with_language(&"line:2".into())
+/// );
+/// ```
 #[derive(Debug, Clone, Default)]
 pub struct StringTableTextProvider {
     base_language_table: StringTable,
-    translation_table: Option<(Language, StringTable)>,
+    translation_tables: HashMap<Language, StringTable>,
+    fallback_chains: HashMap<Language, Vec<Language>>,
     /// Set to `None` to select base language.
     translation_language: Option<Language>,
 }
@@ -65,20 +101,40 @@ impl StringTableTextProvider {
         self.base_language_table.extend(string_table);
     }
 
-    /// Adds strings for the a specific language. If this is not the language used selected by [`TextProvider::set_language`], the strings will be ignored.
+    /// Adds strings for a specific language. Strings for other languages that were added before are kept,
+    /// so that they can be used as fallbacks.
     pub fn extend_translation(
         &mut self,
         language: impl Into<Language>,
         string_table: HashMap<LineId, String>,
     ) {
-        let language = language.into();
-        if let Some((current_language, translation_table)) = self.translation_table.as_mut() {
-            if language == *current_language {
-                translation_table.extend(string_table);
-                return;
-            }
+        self.translation_tables
+            .entry(language.into())
+            .or_default()
+            .extend(string_table);
+    }
+
+    /// Sets the languages that are tried, in order, when a line is missing in `language`, before falling back to the base language.
+    /// This overrides the chain derived from `language` by [`Language::fallback_chain`], e.g. to let `pt-BR` fall back to `pt-PT`.
+    pub fn set_fallback_chain(
+        &mut self,
+        language: impl Into<Language>,
+        fallbacks: impl IntoIterator<Item = impl Into<Language>>,
+    ) {
+        let fallbacks = fallbacks.into_iter().map(Into::into).collect();
+        self.fallback_chains.insert(language.into(), fallbacks);
+    }
+
+    /// Returns the languages that are searched for the text of a line when `language` is selected, in order.
+    /// The chain starts with `language` itself and is followed by the fallbacks set with [`StringTableTextProvider::set_fallback_chain`], if any,
+    /// or the chain derived by [`Language::fallback_chain`] otherwise. The base language is always tried last and is not part of the chain.
+    pub fn fallback_chain(&self, language: &Language) -> Vec<Language> {
+        match self.fallback_chains.get(language) {
+            Some(fallbacks) => std::iter::once(language.clone())
+                .chain(fallbacks.iter().cloned())
+                .collect(),
+            None => language.fallback_chain(),
         }
-        self.translation_table.replace((language, string_table));
     }
 }
 
@@ -92,19 +148,24 @@ impl TextProvider for StringTableTextProvider {
     }
 
     fn get_text(&self, id: &LineId) -> Option<String> {
+        self.get_text_with_language(id).map(|(text, _)| text)
+    }
+
+    fn get_text_with_language(&self, id: &LineId) -> Option<(String, Option<Language>)> {
         if let Some(language) = self.translation_language.as_ref() {
-            if let Some((registered_language, translation_table)) = self.translation_table.as_ref()
-            {
-                if registered_language != language {
-                    error!("Didn't find language {language} in translations, falling back to base language.");
-                } else if let Some(line) = translation_table.get(id) {
-                    return Some(line.clone());
-                } else {
-                    error!("No translation found for line {id} in language {language}, falling back to base language.");
-                }
+            let translation = self
+                .fallback_chain(language)
+                .into_iter()
+                .find_map(|language| {
+                    let text = self.translation_tables.get(&language)?.get(id)?.clone();
+                    Some((text, Some(language)))
+                });
+            if translation.is_some() {
+                return translation;
             }
         }
-        self.base_language_table.get(id).cloned()
+        let text = self.base_language_table.get(id).cloned()?;
+        Some((text, None))
     }
 
     fn set_language(&mut self, language_code: Option<Language>) {
@@ -119,11 +180,9 @@ impl TextProvider for StringTableTextProvider {
         let Some(language) = self.translation_language.as_ref() else {
             return !self.base_language_table.is_empty();
         };
-        let translation_language = self
-            .translation_table
-            .as_ref()
-            .map(|(language, _)| language);
-        translation_language == Some(language)
+        self.fallback_chain(language)
+            .iter()
+            .any(|language| self.translation_tables.contains_key(language))
     }
 
     fn as_any(&self) -> &dyn Any {
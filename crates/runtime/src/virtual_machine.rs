@@ -8,6 +8,9 @@ use crate::markup::{LineParser, ParsedMarkup};
 use crate::prelude::*;
 use crate::Result;
 use log::*;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::cell::OnceCell;
 use std::fmt::Debug;
 use yarnspinner_core::prelude::OpCode;
 use yarnspinner_core::prelude::*;
@@ -219,7 +222,10 @@ impl VirtualMachine {
         Ok(std::mem::take(&mut self.batched_events))
     }
 
-    pub(crate) fn parse_markup(&mut self, line: &str) -> crate::markup::Result<ParsedMarkup> {
+    pub(crate) fn parse_markup(
+        &mut self,
+        line: &str,
+    ) -> std::result::Result<ParsedMarkup, MarkupParseError> {
         self.line_parser.parse_markup(line)
     }
 
@@ -331,7 +337,7 @@ impl VirtualMachine {
                     .into_iter()
                     .enumerate()
                     .fold(command_text, |command_text, (i, substitution)| {
-                        command_text.replace(&format!("{{{i}}}"), &substitution)
+                        command_text.replace(&format!("{{{i}}}"), &String::from(substitution))
                     });
                 let command = Command::parse(command_text);
 
@@ -554,14 +560,16 @@ impl VirtualMachine {
         Ok(())
     }
 
-    fn prepare_line(&mut self, string_id: LineId, substitutions: &[String]) -> Result<Line> {
-        let line_text = self.text_provider.get_text(&string_id).ok_or_else(|| {
-            DialogueError::LineProviderError {
+    fn prepare_line(&mut self, string_id: LineId, substitutions: &[YarnValue]) -> Result<Line> {
+        let (line_text, language) = self
+            .text_provider
+            .get_text_with_language(&string_id)
+            .ok_or_else(|| DialogueError::LineProviderError {
                 id: string_id.clone(),
                 language_code: self.language_code.clone(),
-            }
-        })?;
-        let substituted_text = expand_substitutions(&line_text, substitutions);
+            })?;
+        let substituted_text =
+            expand_substitutions(&line_text, substitutions, self.language_code.as_ref());
         let markup = self
             .parse_markup(&substituted_text)
             .map_err(DialogueError::MarkupParseError)?;
@@ -569,6 +577,7 @@ impl VirtualMachine {
             id: string_id,
             text: markup.text,
             attributes: markup.attributes,
+            language,
         };
         Ok(line)
     }
@@ -602,7 +611,7 @@ impl VirtualMachine {
         &mut self,
         instruction: &Instruction,
         index: usize,
-    ) -> Vec<String> {
+    ) -> Vec<YarnValue> {
         let expression_count: usize = instruction.operands[index].clone().try_into().unwrap();
         let mut values: Vec<_> = (0..expression_count)
             .rev()
@@ -628,12 +637,119 @@ fn assert_up_to_date_compiler(predicate: bool) {
 /// If `test` contains a substitution marker whose
 /// index is not present in `substitutions`, it is
 /// ignored.
+///
+/// ## Implementation notes
+///
+/// In contrast to the original, numbers are formatted according to `language`, e.g. `1.234,5` in German.
+/// A substitution marker can carry a format specifier that is parsed into a [`NumberStyle`], e.g. `{0:0.00}`.
+/// If no language is set, numbers without a format specifier are written as before and the others use the root locale.
+/// Substitutions inside markup markers, e.g. `[plural value={0} ...]`, are never localized,
+/// because the markup parser needs to read them back as numbers.
 #[must_use]
-fn expand_substitutions(text: &str, substitutions: &[String]) -> String {
-    substitutions
-        .iter()
-        .enumerate()
-        .fold(text.to_owned(), |text, (i, substitution)| {
-            text.replace(&format!("{{{i}}}",), substitution)
-        })
+fn expand_substitutions(
+    text: &str,
+    substitutions: &[YarnValue],
+    language: Option<&Language>,
+) -> String {
+    let number_formatter = OnceCell::new();
+    let format_number = |value: f32, style: Option<NumberStyle>| {
+        if language.is_none() && style.is_none() {
+            return value.to_string();
+        }
+        number_formatter
+            .get_or_init(|| {
+                NumberFormatter::new(language.cloned().unwrap_or_else(|| Language::new("und")))
+            })
+            .format(value, style.unwrap_or_default())
+    };
+
+    let mut result = String::with_capacity(text.len());
+    let mut last_end = 0;
+    let mut in_marker = false;
+    for captures in SUBSTITUTION_MARKER.captures_iter(text) {
+        let marker = captures.get(0).unwrap();
+        let preceding_text = &text[last_end..marker.start()];
+        in_marker = is_in_markup_marker_after(preceding_text, in_marker);
+        result.push_str(preceding_text);
+        last_end = marker.end();
+
+        let substitution = captures[1]
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| substitutions.get(index));
+        let substituted = match substitution {
+            None => marker.as_str().to_owned(),
+            Some(YarnValue::Number(value)) if !in_marker => {
+                let style = captures
+                    .get(2)
+                    .and_then(|specifier| specifier.as_str().parse().ok());
+                format_number(*value, style)
+            }
+            Some(value) => String::from(value),
+        };
+        result.push_str(&substituted);
+    }
+    result.push_str(&text[last_end..]);
+    result
+}
+
+/// Matches a substitution marker, e.g. `{0}`, with an optional format specifier, e.g. `{0:0.00}`.
+static SUBSTITUTION_MARKER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{(\d+)(?::([^{}]+))?\}").unwrap());
+
+/// Returns whether a markup marker is open after `text`, given whether one was open before it.
+fn is_in_markup_marker_after(text: &str, mut in_marker: bool) -> bool {
+    let mut characters = text.chars();
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => {
+                characters.next();
+            }
+            '[' => in_marker = true,
+            ']' => in_marker = false,
+            _ => {}
+        }
+    }
+    in_marker
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitutions_format_numbers_for_the_language() {
+        let substitutions = [
+            YarnValue::Number(1234.5),
+            YarnValue::Number(0.1 + 0.2),
+            YarnValue::String("Mae".to_owned()),
+        ];
+        let text =
+            "{2} has {0} gold, {0:0.00} to be exact, and {1:percent} of it is {0:integer} {3}";
+
+        let english = expand_substitutions(text, &substitutions, Some(&Language::new("en")));
+        assert_eq!(
+            "Mae has 1,234.5 gold, 1234.50 to be exact, and 30% of it is 1,234 {3}",
+            english
+        );
+        let german = expand_substitutions(text, &substitutions, Some(&Language::new("de")));
+        assert_eq!(
+            "Mae has 1.234,5 gold, 1234,50 to be exact, and 30% of it is 1.234 {3}",
+            german
+        );
+        let unset = expand_substitutions("{0} or {1}", &substitutions, None);
+        assert_eq!("1234.5 or 0.3", unset);
+    }
+
+    #[test]
+    fn substitutions_in_markup_markers_are_not_localized() {
+        let substitutions = [YarnValue::Number(1234.5)];
+        let text = "[plural value={0} one=\"a coin\" other=\"% coins\" /] \\[{0}\\]";
+
+        let result = expand_substitutions(text, &substitutions, Some(&Language::new("de")));
+        assert_eq!(
+            "[plural value=1234.5 one=\"a coin\" other=\"% coins\" /] \\[1.234,5\\]",
+            result
+        );
+    }
 }
@@ -29,9 +29,9 @@ pub mod core {
     //! Core types and traits that are used by both the compiler and runtime.
     pub use yarnspinner_core::prelude::{
         optionality, yarn_fn_type, yarn_library, Header, Instruction,
-        IntoYarnValueFromNonYarnValue, InvalidOpCodeError, Library, LineId, Node, Position,
-        Program, Type, UntypedYarnFn, YarnFn, YarnFnParam, YarnFnParamItem, YarnValue,
-        YarnValueCastError, YarnValueWrapper, YarnValueWrapperIter,
+        IntoYarnValueFromNonYarnValue, InvalidOpCodeError, Library, LineId, Node, OpCode, Position,
+        Program, Type, UntypedYarnFn, YarnFn, YarnFnParam, YarnFnParamItem, YarnFnParameterInfo,
+        YarnValue, YarnValueCastError, YarnValueWrapper, YarnValueWrapperIter,
     };
 }
 pub mod compiler {
@@ -43,7 +43,10 @@ pub mod compiler {
 pub mod runtime {
     //! Types and traits used by the runtime, in particular the [`Dialogue`] struct.
     pub use yarnspinner_runtime::markup::{
-        MarkupAttribute, MarkupParseError, MarkupValue, CHARACTER_ATTRIBUTE,
+        count_words, markup_spans, markup_tree, serialize_markup, MarkupAttribute, MarkupIssue,
+        MarkupNode, MarkupParseError, MarkupSpan, MarkupValidator, MarkupValue, MessageFormat,
+        MessageFormatError, ParsedMarkup, TranslationChecker, TranslationIssue, TranslationReport,
+        TranslationReportEntry, BUILT_IN_MARKERS, CHARACTER_ATTRIBUTE,
         CHARACTER_ATTRIBUTE_NAME_PROPERTY, TRIM_WHITESPACE_PROPERTY,
     };
     pub use yarnspinner_runtime::prelude::*;
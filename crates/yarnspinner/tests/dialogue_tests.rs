@@ -89,6 +89,64 @@ fn test_analysis_has_no_false_positives() {
     assert!(diagnoses.is_empty());
 }
 
+#[test]
+fn test_analysis_finds_dead_content() {
+    let test_base = TestBase::new();
+    let file = File {
+        file_name: "dead_content.yarn".to_string(),
+        source: "title: Start
+---
+-> Run <<if false>>
+    <<jump Start>>
+-> Stay
+<<jump End>>
+This line never runs.
+===
+title: End
+---
+<<if $has_key>>
+    You open the door.
+<<endif>>
+===
+title: Lost
+---
+Nobody ever comes here.
+==="
+        .to_string(),
+    };
+    let result = Compiler::new()
+        .add_file(file)
+        .extend_library(test_base.dialogue.library().clone())
+        .compile()
+        .unwrap();
+    let mut context = Context::default_analysers()
+        .with_content_analysers()
+        .with_entry_nodes(["Start"])
+        .with_debug_info(result.debug_info.clone());
+    test_base
+        .with_compilation(result)
+        .dialogue
+        .analyse(&mut context);
+
+    let diagnoses: Vec<_> = context
+        .finish_analysis()
+        .into_iter()
+        .filter(|d| d.severity == DiagnosisSeverity::Warning)
+        .map(|d| d.to_string())
+        .collect();
+    println!("{diagnoses:#?}");
+
+    assert_eq!(
+        vec![
+            "WARNING: Lost: dead_content.yarn:17:1: Node Lost can never be reached from the entry nodes Start",
+            "WARNING: Start: dead_content.yarn:7:1: This content can never run because it follows a <<jump>>",
+            "WARNING: Start: dead_content.yarn:3:4: This option can never be shown because its condition is always false",
+            "WARNING: End: dead_content.yarn:11:6: Variable $has_key is read from, but never assigned",
+        ],
+        diagnoses
+    );
+}
+
 #[test]
 fn test_missing_node() {
     let path = test_data_path().join("TestCases").join("Smileys.yarn");
@@ -18,6 +18,97 @@ fn test_malformed_if_statement() {
         .contains("Expected an <<endif>> to match the <<if>> statement on line 3")));
 }
 
+#[test]
+fn test_malformed_if_statement_can_be_fixed() {
+    let mut compiler = Compiler::from_test_source("<<if true>>\nOne");
+    let result = compiler.compile().unwrap_err();
+
+    let fix = result
+        .0
+        .iter()
+        .filter(|d| d.code == DiagnosticCode::MissingEndif)
+        .flat_map(|d| &d.fixes)
+        .next()
+        .expect("Expected a fix for the missing <<endif>>");
+    assert_eq!("<<endif>>\n", fix.replacement);
+    let fixed_source = fix.apply(&compiler.files[0].source);
+    compiler.files[0].source = fixed_source;
+    compiler.compile().unwrap();
+}
+
+#[test]
+fn test_reports_all_independent_syntax_errors() {
+    let result = Compiler::new()
+        .add_file(File {
+            file_name: "test.yarn".to_owned(),
+            source: "title: Start
+---
+<<set test = 1>>
+This line is fine
+<<if someFunction(>><<endif>>
+===
+title: Valid
+---
+<<set $gold to \"five\" + 1>>
+==="
+            .to_owned(),
+        })
+        .compile()
+        .unwrap_err();
+
+    println!("{}", result);
+    assert!(result
+        .0
+        .iter()
+        .any(|d| d.message == "Variable names need to start with a $"));
+    assert!(result.0.iter().any(|d| d
+        .message
+        .contains("Unexpected \">>\" while reading a function call")));
+
+    // The node without syntax errors is still type checked, the one with them is not
+    let semantic_errors: Vec<_> = result
+        .0
+        .iter()
+        .filter(|d| d.code != DiagnosticCode::SyntaxError)
+        .collect();
+    assert!(semantic_errors
+        .iter()
+        .any(|d| d.code == DiagnosticCode::MixedOperandTypes));
+    assert!(semantic_errors
+        .iter()
+        .all(|d| d.range.as_ref().unwrap().start.line == 8));
+}
+
+#[test]
+fn test_declarations_in_nodes_with_syntax_errors_are_kept() {
+    let result = Compiler::new()
+        .add_file(File {
+            file_name: "test.yarn".to_owned(),
+            source: "title: Start
+---
+<<declare $gold = 0>>
+<<if someFunction(>><<endif>>
+===
+title: Shop
+---
+You have {$gold} coins.
+==="
+            .to_owned(),
+        })
+        .compile()
+        .unwrap_err();
+
+    println!("{}", result);
+    assert!(result
+        .0
+        .iter()
+        .any(|d| d.code == DiagnosticCode::SyntaxError));
+    assert!(result
+        .0
+        .iter()
+        .all(|d| d.code == DiagnosticCode::SyntaxError));
+}
+
 #[test]
 fn test_extraneous_else() {
     let result = Compiler::from_test_source(
@@ -272,6 +272,121 @@ before 🧑🏾‍❤️‍💋‍🧑🏻after #line:abc130 // with a comment
     assert_eq!(visited_ids.len(), compilation.string_table.len());
 }
 
+#[test]
+fn test_line_tag_strategies_are_applied() {
+    let file = File {
+        file_name: "Strategies.yarn".to_owned(),
+        source: "title: Start
+---
+A line with a tag. #line:Start_001
+A line without a tag.
+A line without a tag.
+===
+"
+        .to_owned(),
+    };
+
+    let sequential = Compiler::add_tags_to_lines_with_strategy(
+        file.clone(),
+        vec![LineId("line:Start_001".to_owned())],
+        &SequentialLineIds,
+    )
+    .unwrap()
+    .unwrap();
+    assert!(sequential.contains("A line without a tag. #line:Start_002"));
+    assert!(sequential.contains("A line without a tag. #line:Start_003"));
+
+    let content_hash =
+        Compiler::add_tags_to_lines_with_strategy(file.clone(), Vec::new(), &ContentHashLineIds)
+            .unwrap()
+            .unwrap();
+    let content_hash_again =
+        Compiler::add_tags_to_lines_with_strategy(file.clone(), Vec::new(), &ContentHashLineIds)
+            .unwrap()
+            .unwrap();
+    assert_eq!(content_hash, content_hash_again);
+
+    // The identical lines must still receive different tags
+    let compilation = Compiler::new()
+        .add_file(File {
+            source: content_hash,
+            ..file
+        })
+        .compile()
+        .unwrap();
+    assert_eq!(3, compilation.string_table.len());
+}
+
+#[derive(Debug)]
+struct IncludedFiles(Vec<File>);
+
+impl FileResolver for IncludedFiles {
+    fn resolve(&self, path: &str, _including_file: &File) -> std::io::Result<File> {
+        self.0
+            .iter()
+            .find(|file| file.file_name == path)
+            .cloned()
+            .ok_or_else(|| std::io::ErrorKind::NotFound.into())
+    }
+}
+
+#[test]
+fn test_included_files_are_compiled() {
+    let chapter = File {
+        file_name: "chapters/one.yarn".to_owned(),
+        source: "#include:../common.yarn
+
+title: Start
+---
+<<jump Helper>>
+===
+"
+        .to_owned(),
+    };
+    let common = File {
+        file_name: "common.yarn".to_owned(),
+        source: "title: Helper
+---
+<<declare $visits = 0>>
+Hello from the helper
+===
+"
+        .to_owned(),
+    };
+
+    let compilation = Compiler::new()
+        .add_file(chapter.clone())
+        .with_file_resolver(IncludedFiles(vec![common]))
+        .compile()
+        .unwrap();
+
+    let program = compilation.program.unwrap();
+    assert!(program.nodes.contains_key("Helper"));
+    assert!(compilation
+        .declarations
+        .iter()
+        .any(|declaration| declaration.name == "$visits"));
+
+    let result = Compiler::new()
+        .add_file(chapter)
+        .with_file_resolver(IncludedFiles(Vec::new()))
+        .compile();
+    let codes: Vec<_> = result
+        .unwrap_err()
+        .0
+        .iter()
+        .map(|diagnostic| diagnostic.code)
+        .collect();
+    // The jump into the file that cannot be included fails as well
+    assert_eq!(
+        vec![
+            DiagnosticCode::UnresolvedInclude,
+            DiagnosticCode::UnknownNode
+        ],
+        codes
+    );
+}
+
 #[test]
 fn test_debug_output_is_produced() {
     let file = File {
@@ -292,3 +407,59 @@ fn test_debug_output_is_produced() {
     assert_eq!(2, first_line_info.position.unwrap().line);
     assert_eq!(0, first_line_info.position.unwrap().character);
 }
+
+#[test]
+fn test_debug_output_maps_source_ranges_to_instructions() {
+    let file = File {
+        file_name: "input".to_owned(),
+        source: create_test_node_with_name(
+            "<<set $gold to 1 + 2>>\nYou have {$gold} gold.",
+            "DebugTesting",
+        ),
+    };
+    let result = Compiler::new().add_file(file).compile().unwrap();
+    let debug_info = result.debug_info.values().next().unwrap();
+
+    // The addition is attributed to the whole `1 + 2`, not just one of its tokens
+    let addition = debug_info.instructions_at(Position {
+        line: 2,
+        character: 17,
+    });
+    assert!(!addition.is_empty());
+    for instruction in addition {
+        let source = debug_info.get_line_info(instruction).source.unwrap();
+        assert_eq!(SourceConstruct::Set, source.construct);
+        assert_eq!(
+            Position {
+                line: 2,
+                character: 15
+            }..Position {
+                line: 2,
+                character: 20
+            },
+            source.range
+        );
+    }
+
+    // Both the inline expression and the line itself contain the variable
+    let line = debug_info.instructions_at(Position {
+        line: 3,
+        character: 11,
+    });
+    assert!(line.len() >= 2);
+    assert!(line.iter().all(|&instruction| {
+        debug_info
+            .get_line_info(instruction)
+            .source
+            .unwrap()
+            .construct
+            == SourceConstruct::Line
+    }));
+
+    let last_instruction = debug_info.instruction_sources.len() - 1;
+    let stop = debug_info.get_line_info(last_instruction).source.unwrap();
+    assert_eq!(SourceConstruct::Node, stop.construct);
+    assert!(debug_info
+        .instructions_on_line(stop.range.start.line)
+        .contains(&last_instruction));
+}